@@ -1,14 +1,22 @@
-use crate::assessment::{question::Question, scale::Scale, v01::assessment::AssessmentV01};
+use crate::assessment::{
+    question::{Answer, Question},
+    scale::Scale,
+    v01::assessment::AssessmentV01,
+};
 use futures::StreamExt;
 use hikari_utils::id_map::id_map;
-use hikari_utils::loader::{Filter, Loader, LoaderTrait, error::LoadingError};
+use hikari_utils::loader::{
+    Filter, Loader, LoaderTrait,
+    error::{LoadingError, ParseError},
+};
 use indexmap::IndexMap;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use utoipa::ToSchema;
 
+pub mod condition;
 pub mod error;
 pub mod question;
 pub mod scale;
@@ -46,6 +54,63 @@ impl From<AssessmentV01> for Assessment {
     }
 }
 
+impl Assessment {
+    /// Returns the IDs of the questions which are shown given the answers.
+    ///
+    /// Questions are evaluated in order and only answers of shown questions are considered, so answers which were
+    /// given before an earlier answer changed don't affect the conditions.
+    #[must_use]
+    pub fn visible_questions(&self, answers: &HashMap<&str, Answer>) -> HashSet<&str> {
+        let mut visible_answers = HashMap::new();
+        let mut visible = HashSet::new();
+        for question in self.questions.values() {
+            if question
+                .show_if
+                .as_ref()
+                .is_none_or(|condition| condition.evaluate(&visible_answers))
+            {
+                visible.insert(question.id.as_str());
+                if let Some(answer) = answers.get(question.id.as_str()) {
+                    visible_answers.insert(question.id.as_str(), answer.clone());
+                }
+            }
+        }
+        visible
+    }
+
+    /// Checks that the conditions of all questions only depend on previous questions
+    pub fn validate_conditions(&self) -> Result<(), String> {
+        let mut previous = HashSet::new();
+        for question in self.questions.values() {
+            if let Some(condition) = &question.show_if
+                && let Some(unknown) = condition.questions().into_iter().find(|id| !previous.contains(id))
+            {
+                return Err(format!(
+                    "condition of question \"{}\" in assessment \"{}\" depends on \"{unknown}\" which is not a previous question",
+                    question.id, self.assessment_id
+                ));
+            }
+            previous.insert(question.id.as_str());
+        }
+        Ok(())
+    }
+
+    /// Checks that the norms of all scales can be used to calculate norm scores
+    pub fn validate_scales(&self) -> Result<(), String> {
+        for scale in self.scales.values() {
+            if let Some(norms) = &scale.norms
+                && !(norms.sd.is_finite() && norms.sd > 0.0)
+            {
+                return Err(format!(
+                    "norms of scale \"{}\" in assessment \"{}\" need a positive standard deviation",
+                    scale.id, self.assessment_id
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct AssessmentConfig {
     pub assessments: IndexMap<String, Assessment>,
@@ -75,6 +140,8 @@ pub async fn load(loader: Loader) -> Result<AssessmentConfig, LoadingError> {
     while let Some(Ok(file)) = stream.next().await {
         let VersionConfig::V01 { assessment } = yaml_serde::from_slice::<VersionConfig>(&file.content)?;
         let assessment: Assessment = assessment.into();
+        assessment.validate_conditions().map_err(ParseError::Other)?;
+        assessment.validate_scales().map_err(ParseError::Other)?;
         res.insert(assessment.assessment_id.clone(), assessment);
     }
     tracing::debug!(?res, "loaded assessment configuration");
@@ -94,4 +161,92 @@ mod tests {
         let assessment: Assessment = assessment.into();
         assert_eq!(assessment.questions.len(), 1);
     }
+
+    #[test]
+    fn test_visible_questions() {
+        let VersionConfig::V01 { assessment } = yaml_serde::from_str::<VersionConfig>(
+            r#"
+version: "0.1"
+assessment:
+  id: branching
+  title: Branching
+  questions:
+    - id: smoker
+      title: Do you smoke?
+      type: select
+      body: {}
+    - id: cigarettes
+      title: How many cigarettes per day?
+      type: scale
+      body:
+        min: 1
+        max: 5
+      show-if:
+        answer:
+          question: smoker
+          equals: true
+    - id: quit
+      title: Do you want to quit?
+      type: select
+      body: {}
+      show-if:
+        answer:
+          question: cigarettes
+          min: 3
+"#,
+        )
+        .unwrap();
+        let assessment: Assessment = assessment.into();
+        assert!(assessment.validate_conditions().is_ok());
+
+        let visible = assessment.visible_questions(&HashMap::from([("smoker", Answer::Bool(false))]));
+        assert_eq!(visible, HashSet::from(["smoker"]));
+
+        let answers = HashMap::from([("smoker", Answer::Bool(true)), ("cigarettes", Answer::Scale(4))]);
+        assert_eq!(
+            assessment.visible_questions(&answers),
+            HashSet::from(["smoker", "cigarettes", "quit"])
+        );
+
+        // The answer of a skipped question doesn't show the questions depending on it
+        let answers = HashMap::from([("smoker", Answer::Bool(false)), ("cigarettes", Answer::Scale(4))]);
+        assert_eq!(assessment.visible_questions(&answers), HashSet::from(["smoker"]));
+    }
+
+    #[test]
+    fn test_conditions_on_later_questions_are_invalid() {
+        let mut assessment: Assessment = {
+            let assessment_file = read_to_string("test_configs/test.assessment.yaml").unwrap();
+            let VersionConfig::V01 { assessment } = yaml_serde::from_str::<VersionConfig>(&assessment_file).unwrap();
+            assessment.into()
+        };
+        for question in assessment.questions.values_mut() {
+            question.show_if = Some(condition::ShowIf::Answer {
+                question: question.id.clone(),
+                equals: None,
+                one_of: vec![],
+                min: Some(1),
+                max: None,
+            });
+        }
+        assert!(assessment.validate_conditions().is_err());
+    }
+
+    #[test]
+    fn test_norms_need_positive_sd() {
+        let mut assessment: Assessment = {
+            let assessment_file = read_to_string("test_configs/test.assessment.yaml").unwrap();
+            let VersionConfig::V01 { assessment } = yaml_serde::from_str::<VersionConfig>(&assessment_file).unwrap();
+            assessment.into()
+        };
+        assert!(assessment.validate_scales().is_ok());
+
+        let scale = assessment.scales.get_index_mut(0).unwrap().1;
+        scale.norms = Some(scale::Norms {
+            mean: 4.0,
+            sd: 0.0,
+            percentiles: vec![],
+        });
+        assert!(assessment.validate_scales().is_err());
+    }
 }
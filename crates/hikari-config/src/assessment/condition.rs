@@ -0,0 +1,78 @@
+use crate::assessment::question::Answer;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+/// Condition on the answers of previous questions which decides if a question is shown
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub enum ShowIf {
+    /// # Shows the question if the answer of a previous question meets all given constraints
+    Answer {
+        /// # ID of the previous question
+        question: String,
+        /// # The answer has to be equal to this value
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        equals: Option<Answer>,
+        /// # The answer has to be one of these values
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        one_of: Vec<Answer>,
+        /// # Minimum value of a scale answer (inclusive)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<u8>,
+        /// # Maximum value of a scale answer (inclusive)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<u8>,
+    },
+    /// # Shows the question if all nested conditions are met
+    #[schema(no_recursion)]
+    All(Vec<ShowIf>),
+    /// # Shows the question if any nested condition is met
+    #[schema(no_recursion)]
+    Any(Vec<ShowIf>),
+    /// # Shows the question if the nested condition is not met
+    #[schema(no_recursion)]
+    Not(Box<ShowIf>),
+}
+
+impl ShowIf {
+    /// Evaluates the condition against the given answers. Unanswered questions never meet a condition.
+    #[must_use]
+    pub fn evaluate(&self, answers: &HashMap<&str, Answer>) -> bool {
+        match self {
+            Self::Answer {
+                question,
+                equals,
+                one_of,
+                min,
+                max,
+            } => {
+                let Some(answer) = answers.get(question.as_str()) else {
+                    return false;
+                };
+                let scale_value = match answer {
+                    Answer::Scale(value) => Some(*value),
+                    Answer::Bool(_) | Answer::Text(_) => None,
+                };
+                equals.as_ref().is_none_or(|equals| equals == answer)
+                    && (one_of.is_empty() || one_of.contains(answer))
+                    && min.is_none_or(|min| scale_value.is_some_and(|value| value >= min))
+                    && max.is_none_or(|max| scale_value.is_some_and(|value| value <= max))
+            }
+            Self::All(conditions) => conditions.iter().all(|condition| condition.evaluate(answers)),
+            Self::Any(conditions) => conditions.iter().any(|condition| condition.evaluate(answers)),
+            Self::Not(condition) => !condition.evaluate(answers),
+        }
+    }
+
+    /// IDs of all questions the condition depends on
+    #[must_use]
+    pub fn questions(&self) -> Vec<&str> {
+        match self {
+            Self::Answer { question, .. } => vec![question.as_str()],
+            Self::All(conditions) | Self::Any(conditions) => conditions.iter().flat_map(Self::questions).collect(),
+            Self::Not(condition) => condition.questions(),
+        }
+    }
+}
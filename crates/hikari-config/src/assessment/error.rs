@@ -8,4 +8,6 @@ pub enum ValidationError {
     AnswerOutOfRange { min: u8, max: u8, value: u8 },
     #[error("invalid option: {value}")]
     InvalidOption { value: String },
+    #[error("couldn't parse stored answer value {value}")]
+    InvalidStoredValue { value: String },
 }
@@ -1,3 +1,4 @@
+use crate::assessment::condition::ShowIf;
 use crate::assessment::error::ValidationError;
 use heck::ToSnakeCase;
 use hikari_utils::id_map::ItemId;
@@ -6,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use strum::IntoStaticStr;
 use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, JsonSchema)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema, JsonSchema)]
 #[serde(untagged)]
 pub enum Answer {
     Scale(u8),
@@ -27,8 +28,32 @@ pub struct Question {
     /// # Body of the question
     /// Contains the detailed content and type of the question.
     pub body: QuestionBody,
+    /// # Condition to show the question
+    /// The question is skipped if the condition on the answers of previous questions is not met.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_if: Option<ShowIf>,
     #[serde(skip_serializing)]
     pub answer: Option<Answer>,
+    /// Set when loading an assessment session if the question is skipped given the current answers
+    #[serde(default, skip_deserializing, skip_serializing_if = "std::ops::Not::not")]
+    #[schemars(skip)]
+    pub skipped: bool,
+}
+
+impl Question {
+    /// Parses an answer stored as string
+    pub fn parse_answer(&self, data: &str) -> Result<Answer, ValidationError> {
+        let invalid = || ValidationError::InvalidStoredValue { value: data.to_owned() };
+        Ok(match self.body {
+            QuestionBody::Scale(_) => Answer::Scale(data.parse().map_err(|_| invalid())?),
+            QuestionBody::Textfield(_) | QuestionBody::Textarea(_) | QuestionBody::MultiChoice(_) => {
+                Answer::Text(data.to_owned())
+            }
+            QuestionBody::Select(_) | QuestionBody::SingleChoice(_) => {
+                Answer::Bool(data.parse().map_err(|_| invalid())?)
+            }
+        })
+    }
 }
 
 impl ItemId for Question {
@@ -101,6 +126,16 @@ pub enum AnswerValue {
     SmallInt { value: u8 },
 }
 
+impl From<AnswerValue> for Answer {
+    fn from(value: AnswerValue) -> Self {
+        match value {
+            AnswerValue::Bool { value } => Self::Bool(value),
+            AnswerValue::Text { value } => Self::Text(value),
+            AnswerValue::SmallInt { value } => Self::Scale(value),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, IntoStaticStr, ToSchema, JsonSchema)]
 #[serde(tag = "type", content = "body")]
 #[serde(deny_unknown_fields, rename_all = "kebab-case")]
@@ -164,7 +199,9 @@ mod tests {
                 hint_min: None,
                 hint_max: None,
             }),
+            show_if: None,
             answer: None,
+            skipped: false,
         };
         let value = AnswerValue::Bool { value: true };
         let Err(ValidationError::InvalidAnswerType {
@@ -41,6 +41,80 @@ pub struct Scale {
     /// # Items in the scale
     /// A list of items that make up the scale.
     pub items: Vec<Item>,
+    #[serde(default)]
+    /// # Maximum number of missing items
+    /// Up to this many unanswered items are prorated from the answered ones. Any further missing item is an error.
+    pub max_missing: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// # Interpretation bands
+    /// The first band which contains the score is returned together with the scale value.
+    pub bands: Vec<Band>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// # Norm table
+    /// Reference values to convert the raw value into z-, T-scores and percentiles.
+    pub norms: Option<Norms>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// # Reliability coefficient
+    /// Reliability of the scale (e.g. Cronbach's alpha or test-retest) which is used together with the standard
+    /// deviation of the norms to decide whether a change between pre- and post-assessment is reliable.
+    pub reliability: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Band {
+    /// # Label of the band
+    pub label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// # Lower cutoff (inclusive)
+    pub min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// # Upper cutoff (exclusive)
+    pub max: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// # Feedback text shown to the user
+    pub feedback: Option<String>,
+    #[serde(default)]
+    /// # Score the cutoffs refer to
+    pub score: BandScore,
+}
+
+impl Band {
+    #[must_use]
+    pub fn contains(&self, score: f64) -> bool {
+        self.min.is_none_or(|min| score >= min) && self.max.is_none_or(|max| score < max)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, ToSchema, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub enum BandScore {
+    #[default]
+    Raw,
+    Z,
+    T,
+    Percentile,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Norms {
+    /// # Mean of the raw value in the norm sample
+    pub mean: f64,
+    /// # Standard deviation of the raw value in the norm sample
+    pub sd: f64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// # Percentile table
+    /// Raw values with their percentile rank, values in between are interpolated. Without a table percentiles are
+    /// derived from the normal distribution.
+    pub percentiles: Vec<PercentileRank>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct PercentileRank {
+    pub value: f64,
+    pub percentile: f64,
 }
 
 impl ItemId for Scale {
@@ -1,6 +1,7 @@
 use crate::global::{
-    access::AccessConfig, frontend::FrontendConfig, journal::JournalConfig, modules::ModuleConfig,
-    onboarding::OnboardingConfig, user::UserConfig, v01::config::GlobalConfigV01,
+    access::AccessConfig, crisis::CrisisConfig, frontend::FrontendConfig, journal::JournalConfig, llm::LlmStepConfig,
+    modules::ModuleConfig, onboarding::OnboardingConfig, roles::RolesConfig, user::UserConfig,
+    v01::config::GlobalConfigV01,
 };
 use hikari_utils::loader::{Loader, LoaderTrait, error::LoadingError};
 use schemars::JsonSchema;
@@ -8,10 +9,13 @@ use serde_derive::Deserialize;
 use std::fmt::Debug;
 
 pub mod access;
+pub mod crisis;
 pub mod frontend;
 pub mod journal;
+pub mod llm;
 pub mod modules;
 pub mod onboarding;
+pub mod roles;
 pub mod user;
 pub mod v01;
 
@@ -39,10 +43,18 @@ pub struct GlobalConfig {
     pub user: UserConfig,
     pub journal: JournalConfig,
     pub access: Vec<AccessConfig>,
+    pub roles: RolesConfig,
+    pub llm: LlmStepConfig,
+    pub crisis: CrisisConfig,
 }
 
 impl From<GlobalConfigV01> for GlobalConfig {
     fn from(value: GlobalConfigV01) -> Self {
+        let mut llm = value.llm;
+        // The hotlines are configured once, for the crisis detection and the escalation of the moderation
+        llm.moderation
+            .crisis_message
+            .get_or_insert_with(|| value.crisis.resources_text());
         Self {
             onboarding: value.onboarding,
             frontend: value.frontend.into(),
@@ -51,6 +63,9 @@ impl From<GlobalConfigV01> for GlobalConfig {
             user: value.config,
             journal: value.journal,
             access: value.access,
+            roles: value.roles,
+            llm,
+            crisis: value.crisis,
         }
     }
 }
@@ -88,6 +103,21 @@ impl GlobalConfig {
     pub fn access(&self) -> &Vec<AccessConfig> {
         &self.access
     }
+
+    #[must_use]
+    pub fn roles(&self) -> &RolesConfig {
+        &self.roles
+    }
+
+    #[must_use]
+    pub fn llm(&self) -> &LlmStepConfig {
+        &self.llm
+    }
+
+    #[must_use]
+    pub fn crisis(&self) -> &CrisisConfig {
+        &self.crisis
+    }
 }
 
 pub async fn load(loader: Loader) -> Result<GlobalConfig, LoadingError> {
@@ -100,6 +130,7 @@ pub async fn load(loader: Loader) -> Result<GlobalConfig, LoadingError> {
 
 #[cfg(test)]
 mod tests {
+    use crate::global::access::{GroupAccess, StratificationAttribute};
     use crate::global::modules::ModuleGroup;
     use std::fs::read_to_string;
 
@@ -118,6 +149,35 @@ mod tests {
         assert!(!config.approvals.is_empty());
         assert!(!config.user.allowed_keys.is_empty());
         assert!(!config.journal.focus.is_empty());
+        assert_eq!(config.roles.instructor, vec!["lecturers".to_string()]);
+        assert_eq!(config.roles.beta, vec!["beta".to_string()]);
+        assert!(config.roles.is_role_group("operators"));
+        assert!(!config.roles.is_role_group("wi"));
+        assert_eq!(config.llm.defaults.timeout, Some(120));
+        assert_eq!(config.llm.defaults.max_retries, Some(2));
+        assert!(config.llm.learner_memory.enabled);
+        assert_eq!(config.llm.learner_memory.max_per_user, Some(200));
+        assert!(config.llm.moderation.enabled);
+        assert!(config.llm.moderation.check_input);
+        assert!(!config.llm.moderation.check_output);
+        assert!(config.crisis.checks_chat());
+        assert_eq!(config.crisis.retention_days, Some(30));
+        assert_eq!(config.crisis.hotlines[0].name, "TelefonSeelsorge");
+        assert_eq!(
+            config.llm.moderation.crisis_message,
+            Some(config.crisis.resources_text())
+        );
+        assert!(matches!(
+            &config.llm.moderation.provider,
+            llm::ModerationProvider::Classifier { model: Some(model), .. } if model == "gpt-4.1-mini"
+        ));
+
+        let study = config.access.iter().find(|access| access.token == "study").unwrap();
+        assert!(matches!(
+            study.groups.as_slice(),
+            [GroupAccess::Random { random, block_size: Some(4), stratify_by }]
+                if random.len() == 2 && stratify_by == &[StratificationAttribute::Semester]
+        ));
     }
 
     #[test]
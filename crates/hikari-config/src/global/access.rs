@@ -32,5 +32,24 @@ pub enum GroupAccess {
     /// # Single group to add
     Single(String),
     /// # One of multiple groups to add (randomly chosen)
-    Random { random: Vec<String> },
+    Random {
+        random: Vec<String>,
+        /// # Size of the randomization blocks
+        /// If set the groups are assigned in permuted blocks of this size, so after every block each group was
+        /// assigned equally often. Must be a multiple of the number of groups.
+        #[serde(default, rename = "block-size")]
+        block_size: Option<u32>,
+        /// # User attributes to stratify the randomization on
+        /// Every combination of attribute values gets its own allocation sequence.
+        /// Implies block randomization with one block per group if no block size is set.
+        #[serde(default, rename = "stratify-by")]
+        stratify_by: Vec<StratificationAttribute>,
+    },
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum StratificationAttribute {
+    Subject,
+    Semester,
 }
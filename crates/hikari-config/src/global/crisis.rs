@@ -0,0 +1,203 @@
+use crate::module::llm_agent::LlmService;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use url::Url;
+use utoipa::ToSchema;
+
+const fn default_true() -> bool {
+    true
+}
+
+fn default_keywords() -> Vec<String> {
+    [
+        "suizid",
+        "selbstmord",
+        "umbringen",
+        "mir das leben nehmen",
+        "nicht mehr leben",
+        "will sterben",
+        "ritzen",
+        "selbstverletzung",
+        "keinen sinn mehr",
+    ]
+    .into_iter()
+    .map(ToOwned::to_owned)
+    .collect()
+}
+
+fn default_message() -> String {
+    "Es klingt so, als ginge es dir gerade nicht gut. Du musst damit nicht allein bleiben. \
+     Hier findest du rund um die Uhr Unterstützung:"
+        .to_string()
+}
+
+fn default_hotlines() -> Vec<CrisisHotline> {
+    vec![
+        CrisisHotline {
+            name: "TelefonSeelsorge".to_string(),
+            phone: Some("0800 111 0 111".to_string()),
+            url: Some("https://online.telefonseelsorge.de".to_string()),
+            availability: Some("rund um die Uhr, kostenlos und anonym".to_string()),
+        },
+        CrisisHotline {
+            name: "Notruf".to_string(),
+            phone: Some("112".to_string()),
+            url: None,
+            availability: Some("bei akuter Gefahr".to_string()),
+        },
+    ]
+}
+
+#[derive(Deserialize, Clone, Debug, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct CrisisConfig {
+    /// # Detect signals of acute distress
+    #[serde(default)]
+    pub enabled: bool,
+    /// # Check new journal content
+    #[serde(default = "default_true")]
+    pub check_journal: bool,
+    /// # Check incoming chat messages
+    #[serde(default = "default_true")]
+    pub check_chat: bool,
+    /// # Keywords which indicate acute distress
+    /// Matched case-insensitively at the start of a word, multiple words have to appear in order
+    #[serde(default = "default_keywords")]
+    pub keywords: Vec<String>,
+    /// # LLM classifier for texts without keywords
+    /// The text is sent to the configured service
+    pub classifier: Option<CrisisClassifierConfig>,
+    /// # Message shown above the hotlines
+    #[serde(default = "default_message")]
+    pub message: String,
+    /// # Hotlines shown to the user
+    #[serde(default = "default_hotlines")]
+    pub hotlines: Vec<CrisisHotline>,
+    /// # Webhook notified on detections
+    pub webhook: Option<CrisisWebhookConfig>,
+    /// # Number of days the events are kept
+    /// Older events are removed whenever a new event is logged. Events are kept forever if unset.
+    pub retention_days: Option<u32>,
+}
+
+impl Default for CrisisConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            check_journal: true,
+            check_chat: true,
+            keywords: default_keywords(),
+            classifier: None,
+            message: default_message(),
+            hotlines: default_hotlines(),
+            webhook: None,
+            retention_days: None,
+        }
+    }
+}
+
+impl CrisisConfig {
+    #[must_use]
+    pub fn checks_journal(&self) -> bool {
+        self.enabled && self.check_journal
+    }
+
+    #[must_use]
+    pub fn checks_chat(&self) -> bool {
+        self.enabled && self.check_chat
+    }
+
+    /// The message followed by one line per hotline, for replies which can only contain text
+    #[must_use]
+    pub fn resources_text(&self) -> String {
+        let mut text = self.message.clone();
+        for hotline in &self.hotlines {
+            text.push_str("\n- ");
+            text.push_str(&hotline.name);
+            let details: Vec<&str> = [&hotline.phone, &hotline.url, &hotline.availability]
+                .into_iter()
+                .filter_map(Option::as_deref)
+                .collect();
+            if !details.is_empty() {
+                text.push_str(": ");
+                text.push_str(&details.join(", "));
+            }
+        }
+        text
+    }
+}
+
+#[derive(Deserialize, Clone, Debug, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct CrisisClassifierConfig {
+    pub service: Option<LlmService>,
+    pub model: Option<String>,
+    /// # Instructions for the classifier
+    pub prompt: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, ToSchema, Debug, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct CrisisHotline {
+    /// # Name of the service
+    pub name: String,
+    /// # Phone number
+    pub phone: Option<String>,
+    /// # Website, e.g. for chat or mail counseling
+    pub url: Option<String>,
+    /// # When and how the service is available
+    pub availability: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Debug, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct CrisisWebhookConfig {
+    /// # Endpoint of the counselor which receives a POST request per event
+    /// The request never contains the text which triggered the detection
+    pub url: Url,
+    /// # Bearer token sent with the request
+    pub token: Option<String>,
+    /// # Send the id of the user
+    /// Without it the counselor only learns that an event happened, an admin can look it up with the event id at
+    /// `/api/v0/admin/crisis-events/{id}`
+    #[serde(default)]
+    pub include_user: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_crisis_config() {
+        let config: CrisisConfig = yaml_serde::from_str(
+            r"
+enabled: true
+check-chat: false
+keywords: [suizid]
+webhook:
+  url: https://example.com/hook
+hotlines:
+  - name: Beratung
+    phone: 0123 456
+",
+        )
+        .unwrap();
+
+        assert!(config.checks_journal());
+        assert!(!config.checks_chat());
+        assert_eq!(config.keywords, vec!["suizid"]);
+        assert!(!config.webhook.as_ref().unwrap().include_user);
+        assert_eq!(config.hotlines.len(), 1);
+        assert_eq!(config.message, default_message());
+
+        assert_eq!(
+            config.resources_text(),
+            format!("{}\n- Beratung: 0123 456", default_message())
+        );
+
+        let config = CrisisConfig::default();
+        assert!(!config.checks_journal());
+        assert!(!config.hotlines.is_empty());
+    }
+}
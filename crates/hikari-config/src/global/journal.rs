@@ -18,4 +18,31 @@ pub struct JournalConfig {
     /// # List of focus which are available in the journal
     /// Focusses assign tags and icons to journal entries
     pub focus: Vec<JournalFocusEntry>,
+    #[serde(default)]
+    /// # Defaults and limits of the journal insights
+    pub insights: JournalInsightsConfig,
+}
+
+#[derive(Serialize, Deserialize, Clone, ToSchema, Debug, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case", default)]
+pub struct JournalInsightsConfig {
+    /// # Number of days insights cover if no range is requested
+    pub default_window_days: u32,
+    /// # Maximum number of days a single insights request may cover
+    pub max_window_days: u32,
+    /// # Number of periods averaged for the mood trend
+    pub moving_average_periods: u32,
+    /// # Number of summaries a topic has to appear in to count as recurring
+    pub min_topic_occurrences: u32,
+}
+
+impl Default for JournalInsightsConfig {
+    fn default() -> Self {
+        Self {
+            default_window_days: 90,
+            max_window_days: 366,
+            moving_average_periods: 4,
+            min_topic_occurrences: 2,
+        }
+    }
 }
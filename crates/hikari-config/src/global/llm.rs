@@ -0,0 +1,254 @@
+use crate::module::llm_agent::LlmService;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Debug, Default, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct LlmStepConfig {
+    /// # Defaults for the LLM calls of agent steps
+    /// Every step can override them in its model configuration
+    #[serde(default)]
+    pub defaults: LlmCallDefaults,
+    /// # Long-term memory of learners across sessions
+    #[serde(default)]
+    pub learner_memory: LearnerMemoryConfig,
+    /// # Moderation of the messages of students and agents
+    #[serde(default)]
+    pub moderation: ModerationConfig,
+}
+
+#[derive(Deserialize, Clone, Debug, Default, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct LlmCallDefaults {
+    /// # Total timeout of a single attempt in seconds
+    pub timeout: Option<u64>,
+    /// # Timeout until the first token is received in seconds
+    /// For calls which are not streamed this limits every http request of an attempt
+    pub first_token_timeout: Option<u64>,
+    /// # Number of retries after a failed attempt
+    pub max_retries: Option<u32>,
+    /// # Delay before the first retry in seconds, doubled for every further retry
+    pub backoff: Option<f64>,
+}
+
+#[derive(Deserialize, Clone, Debug, Default, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct LearnerMemoryConfig {
+    /// # Store and recall facts about learners
+    /// Without it, the memorize steps of agents store nothing and the recall steps find nothing. Facts are only stored
+    /// for users who opted in.
+    #[serde(default)]
+    pub enabled: bool,
+    /// # Maximum number of memories per user
+    /// The oldest memories are removed first
+    pub max_per_user: Option<u64>,
+}
+
+const fn default_true() -> bool {
+    true
+}
+
+fn default_block_message() -> String {
+    "Darauf kann ich leider nicht eingehen. Lass uns gerne über etwas anderes sprechen.".to_string()
+}
+
+#[derive(Deserialize, Clone, Debug, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ModerationConfig {
+    /// # Check messages before they are processed or shown
+    #[serde(default)]
+    pub enabled: bool,
+    /// # Check the messages of students
+    #[serde(default = "default_true")]
+    pub check_input: bool,
+    /// # Check the messages generated by agents
+    /// The messages are only shown once they are complete and checked, so they are not streamed
+    #[serde(default = "default_true")]
+    pub check_output: bool,
+    /// # How messages are classified
+    #[serde(default)]
+    pub provider: ModerationProvider,
+    /// # Actions for flagged categories
+    /// The first rule with a matching category is applied
+    #[serde(default)]
+    pub rules: Vec<GuardrailRule>,
+    /// # Action if no rule matches
+    #[serde(default)]
+    pub default_action: GuardrailAction,
+    /// # Apply the default action if the moderation fails
+    /// Otherwise messages pass unchecked if the provider is unavailable
+    #[serde(default)]
+    pub fail_closed: bool,
+    /// # Message shown instead of blocked messages
+    #[serde(default = "default_block_message")]
+    pub block_message: String,
+    /// # Message with crisis resources shown on escalation
+    /// Defaults to the message and the hotlines of the crisis configuration
+    pub crisis_message: Option<String>,
+}
+
+impl Default for ModerationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            check_input: true,
+            check_output: true,
+            provider: ModerationProvider::default(),
+            rules: vec![],
+            default_action: GuardrailAction::default(),
+            fail_closed: false,
+            block_message: default_block_message(),
+            crisis_message: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub enum ModerationProvider {
+    /// # Moderation endpoint of the service
+    Endpoint {
+        service: Option<LlmService>,
+        /// # Moderation model, e.g. `omni-moderation-latest`
+        model: Option<String>,
+    },
+    /// # Chat model which classifies the messages
+    Classifier {
+        service: Option<LlmService>,
+        model: Option<String>,
+        /// # Instructions for the classifier
+        /// Replaces the default instructions, which cover self-harm, violence, sexual content, hate and harassment
+        prompt: Option<String>,
+    },
+}
+
+impl Default for ModerationProvider {
+    fn default() -> Self {
+        Self::Endpoint {
+            service: None,
+            model: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct GuardrailRule {
+    /// # Categories the rule applies to
+    /// A category also matches its subcategories, e.g. `self-harm` matches `self-harm/intent`
+    pub categories: Vec<String>,
+    pub action: GuardrailAction,
+}
+
+#[derive(Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub enum GuardrailAction {
+    /// # Replaces the message with the block message
+    #[default]
+    Block,
+    /// # Replaces the message with the given text
+    Replace(String),
+    /// # Replaces the message with the crisis message
+    Escalate,
+    /// # Lets the message pass, it is only recorded for review
+    Flag,
+}
+
+impl GuardrailAction {
+    /// Name of the action as it is recorded
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            GuardrailAction::Block => "block",
+            GuardrailAction::Replace(_) => "replace",
+            GuardrailAction::Escalate => "escalate",
+            GuardrailAction::Flag => "flag",
+        }
+    }
+}
+
+impl GuardrailRule {
+    #[must_use]
+    pub fn matches(&self, category: &str) -> bool {
+        self.categories.iter().any(|rule| {
+            category == rule
+                || category
+                    .strip_prefix(rule.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+    }
+}
+
+impl ModerationConfig {
+    #[must_use]
+    pub fn checks_input(&self) -> bool {
+        self.enabled && self.check_input
+    }
+
+    #[must_use]
+    pub fn checks_output(&self) -> bool {
+        self.enabled && self.check_output
+    }
+
+    /// Action for the flagged categories, the first matching rule wins
+    #[must_use]
+    pub fn action_for(&self, categories: &[String]) -> &GuardrailAction {
+        self.rules
+            .iter()
+            .find(|rule| categories.iter().any(|category| rule.matches(category)))
+            .map_or(&self.default_action, |rule| &rule.action)
+    }
+
+    /// Text shown instead of the message, `None` if the message passes
+    #[must_use]
+    pub fn replacement<'a>(&'a self, action: &'a GuardrailAction) -> Option<&'a str> {
+        match action {
+            GuardrailAction::Block => Some(&self.block_message),
+            GuardrailAction::Replace(text) => Some(text),
+            GuardrailAction::Escalate => Some(self.crisis_message.as_deref().unwrap_or(&self.block_message)),
+            GuardrailAction::Flag => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_for_categories() {
+        let config: ModerationConfig = yaml_serde::from_str(
+            r"
+enabled: true
+rules:
+  - categories: [self-harm]
+    action: escalate
+  - categories: [harassment, violence]
+    action:
+      replace: Bitte bleib freundlich.
+  - categories: [sexual/minors]
+    action: block
+default-action: flag
+crisis-message: Hier findest du Hilfe.
+",
+        )
+        .unwrap();
+
+        assert!(config.check_output);
+        assert_eq!(
+            config.action_for(&["violence".to_string(), "self-harm/intent".to_string()]),
+            &GuardrailAction::Escalate
+        );
+        assert_eq!(
+            config.action_for(&["harassment/threatening".to_string()]),
+            &GuardrailAction::Replace("Bitte bleib freundlich.".to_string())
+        );
+        assert_eq!(config.action_for(&["self-harmony".to_string()]), &GuardrailAction::Flag);
+        assert_eq!(config.action_for(&[]), &GuardrailAction::Flag);
+        assert_eq!(
+            config.replacement(config.action_for(&["self-harm".to_string()])),
+            Some("Hier findest du Hilfe.")
+        );
+        assert_eq!(config.replacement(&GuardrailAction::Flag), None);
+    }
+}
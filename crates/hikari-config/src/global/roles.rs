@@ -0,0 +1,39 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Debug, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case", default)]
+pub struct RolesConfig {
+    /// # Groups which grant access to beta features
+    pub beta: Vec<String>,
+    /// # Groups which grant the instructor role
+    /// Instructors can curate content and inspect the progress of users
+    pub instructor: Vec<String>,
+    /// # Groups which grant the admin role
+    /// Admins have all instructor permissions and can additionally grant groups to users
+    pub admin: Vec<String>,
+    /// # Groups which revoke access to the journal
+    pub no_journal: Vec<String>,
+}
+
+impl RolesConfig {
+    /// Checks if the group is mapped to a role and therefore may not be granted manually
+    #[must_use]
+    pub fn is_role_group(&self, group: &str) -> bool {
+        [&self.beta, &self.instructor, &self.admin, &self.no_journal]
+            .into_iter()
+            .flatten()
+            .any(|role_group| role_group == group)
+    }
+}
+
+impl Default for RolesConfig {
+    fn default() -> Self {
+        Self {
+            beta: vec!["beta".to_owned()],
+            instructor: vec!["instructor".to_owned()],
+            admin: vec![],
+            no_journal: vec!["no-journal".to_owned()],
+        }
+    }
+}
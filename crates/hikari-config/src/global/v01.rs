@@ -1,7 +1,10 @@
 pub(crate) mod access;
 pub(crate) mod config;
+pub(crate) mod crisis;
 pub(crate) mod frontend;
 pub(crate) mod journal;
+pub(crate) mod llm;
 pub(crate) mod modules;
 pub(crate) mod onboarding;
+pub(crate) mod roles;
 pub(crate) mod user;
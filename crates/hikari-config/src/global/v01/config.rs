@@ -4,8 +4,9 @@ use serde::Deserialize;
 use crate::global::{
     ApprovalConfigEntry,
     v01::{
-        access::AccessConfigV01, frontend::FrontendConfigV01, journal::JournalConfigV01, modules::ModuleConfigV01,
-        onboarding::OnboardingConfigV01, user::UserConfigV01,
+        access::AccessConfigV01, crisis::CrisisConfigV01, frontend::FrontendConfigV01, journal::JournalConfigV01,
+        llm::LlmConfigV01, modules::ModuleConfigV01, onboarding::OnboardingConfigV01, roles::RolesConfigV01,
+        user::UserConfigV01,
     },
 };
 
@@ -34,4 +35,16 @@ pub struct GlobalConfigV01 {
     /// Defines which tokens are associated with which groups
     /// Tokens can be used to add groups to the user; groups define permissions for modules
     pub(crate) access: Vec<AccessConfigV01>,
+    #[serde(default)]
+    /// # Role configuration
+    /// Maps the groups of the identity provider to roles
+    pub(crate) roles: RolesConfigV01,
+    #[serde(default)]
+    /// # LLM configuration
+    /// Defines the defaults of the LLM calls of agents
+    pub(crate) llm: LlmConfigV01,
+    #[serde(default)]
+    /// # Crisis detection
+    /// Detects signals of acute distress in journal entries and chats and shows hotlines
+    pub(crate) crisis: CrisisConfigV01,
 }
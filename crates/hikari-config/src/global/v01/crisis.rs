@@ -0,0 +1,3 @@
+use crate::global::crisis::CrisisConfig;
+
+pub(crate) type CrisisConfigV01 = CrisisConfig;
@@ -0,0 +1,3 @@
+use crate::global::llm::LlmStepConfig;
+
+pub(crate) type LlmConfigV01 = LlmStepConfig;
@@ -0,0 +1,3 @@
+use crate::global::roles::RolesConfig;
+
+pub(crate) type RolesConfigV01 = RolesConfig;
@@ -3,7 +3,7 @@ use std::collections::{HashMap, HashSet};
 use crate::documents::collection::DocumentCollection;
 use crate::generic::{Metadata, Theme};
 use crate::module::assessment::ModuleAssessment;
-use crate::module::content::Content;
+use crate::module::content::{Content, GradingRubric};
 use crate::module::error::ModuleError;
 use crate::module::llm_agent::{LlmAgent, LlmService};
 use crate::module::session::Session;
@@ -66,6 +66,7 @@ pub struct Module<'a> {
     pub custom: Option<HashMap<String, yaml_serde::Value>>,
     pub self_learning: bool,
     pub quizzable: bool,
+    pub quiz_rubric: GradingRubric,
 }
 
 impl Module<'_> {
@@ -116,6 +117,7 @@ impl Module<'_> {
             groups_blacklist: module.groups_blacklist,
             self_learning: module.self_learning.enabled,
             quizzable: module.quizzable,
+            quiz_rubric: module.quiz_rubric,
             custom: module.custom,
         })
     }
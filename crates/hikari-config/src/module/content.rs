@@ -111,6 +111,18 @@ pub enum QuestionBloomLevel {
     Create,
 }
 
+/// Rule for grading closed question formats like multiple choice, cloze, ordering or matching questions.
+#[derive(Debug, Deserialize, Serialize, ToSchema, Clone, Copy, PartialEq, Eq, Hash, JsonSchema, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum GradingRubric {
+    /// Only completely correct answers get the full grade, everything else is graded with zero
+    AllOrNothing,
+    /// Every correct part of the answer is credited proportionally.
+    /// For multiple choice questions every wrong selection cancels one correct selection
+    #[default]
+    PartialCredit,
+}
+
 impl Display for QuestionBloomLevel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let level_str = match self {
@@ -130,6 +142,28 @@ pub struct ContentExamOption {
     pub is_correct: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ContentExamPair {
+    /// # Left side of the pair
+    pub left: String,
+    /// # Right side of the pair which belongs to the left side
+    pub right: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ContentExamNumeric {
+    /// # The correct value
+    pub value: f64,
+    #[serde(default)]
+    /// # Maximum absolute deviation from the value which is still graded as correct
+    pub tolerance: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// # Unit of the value which is shown next to the input
+    pub unit: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, JsonSchema)]
 #[serde(deny_unknown_fields, rename_all = "kebab-case")]
 pub struct ContentExam {
@@ -144,4 +178,59 @@ pub struct ContentExam {
     /// # Options for multiple-choice questions
     /// Only if the question is multiple choice
     pub options: Vec<ContentExamOption>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// # Solutions for cloze questions
+    /// One solution per `{{gap}}` marker in the question text, in the same order
+    pub gaps: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// # Items of an ordering question in the correct order
+    /// The items are shuffled before they are shown to the user
+    pub sequence: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// # Pairs of a matching question
+    /// The right sides are shuffled before they are shown to the user
+    pub pairs: Vec<ContentExamPair>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// # Solution for numeric questions
+    pub numeric: Option<ContentExamNumeric>,
+}
+
+impl ContentExam {
+    /// Textual representation of the solution, used as reference when grading answers.
+    #[must_use]
+    pub fn solution_text(&self) -> String {
+        if let Some(solution) = &self.solution {
+            return solution.clone();
+        }
+        if !self.options.is_empty() {
+            return self
+                .options
+                .iter()
+                .filter(|opt| opt.is_correct)
+                .map(|opt| opt.option.clone())
+                .collect::<Vec<_>>()
+                .join(", ");
+        }
+        if !self.gaps.is_empty() {
+            return self.gaps.join(", ");
+        }
+        if !self.sequence.is_empty() {
+            return self.sequence.join(" → ");
+        }
+        if !self.pairs.is_empty() {
+            return self
+                .pairs
+                .iter()
+                .map(|pair| format!("{} = {}", pair.left, pair.right))
+                .collect::<Vec<_>>()
+                .join(", ");
+        }
+        if let Some(numeric) = &self.numeric {
+            return match &numeric.unit {
+                Some(unit) => format!("{} {unit}", numeric.value),
+                None => numeric.value.to_string(),
+            };
+        }
+        String::new()
+    }
 }
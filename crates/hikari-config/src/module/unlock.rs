@@ -24,6 +24,15 @@ pub enum UnlockTriggerTimeFormat {
     Utc(DateTime<Utc>),
 }
 
+/// Only used for the json schema of session ids, which can be given as a single id or as a list
+#[derive(JsonSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+enum SessionIds {
+    One(String),
+    Many(Vec<String>),
+}
+
 fn deserialize_string_to_vec<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
 where
     D: Deserializer<'de>,
@@ -34,7 +43,14 @@ where
     let value = Value::deserialize(deserializer)?;
     match value {
         Value::String(s) => Ok(vec![s]),
-        _ => Err(Error::custom("Expected string value")),
+        Value::Array(values) if !values.is_empty() => values
+            .into_iter()
+            .map(|value| match value {
+                Value::String(s) => Ok(s),
+                _ => Err(Error::custom("Expected string value")),
+            })
+            .collect(),
+        _ => Err(Error::custom("Expected string value or non-empty list of strings")),
     }
 }
 
@@ -44,15 +60,52 @@ where
 pub enum UnlockTrigger {
     /// # Unlock after a specific time
     Time { after: UnlockTriggerTimeFormat },
-    /// # Unlock after completion of specific sessions in the same module
+    /// # Unlock after completion of specific sessions
     Completion {
         #[serde(deserialize_with = "deserialize_string_to_vec")]
-        #[schemars(with = "String")]
+        #[schemars(with = "SessionIds")]
         /// # Sesssion ID of the sessions which need to be completed to unlock
+        ///
+        /// If multiple sessions are given, all of them need to be completed
         after: Vec<String>,
+        /// # Module of the sessions, defaults to the module of the locked session
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        module: Option<String>,
         /// # Optional wait time after completion before unlocking
         wait: Option<UnlockTriggerWait>,
     },
+    /// # Unlock if a scale of the last finished pre-assessment lies within a range
+    PreAssessment {
+        /// # Module of the pre-assessment, defaults to the module of the locked session
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        module: Option<String>,
+        /// # ID of the scale
+        scale: String,
+        /// # Minimum value of the scale (inclusive)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<f64>,
+        /// # Maximum value of the scale (inclusive)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<f64>,
+    },
+    /// # Unlock after reaching a quiz score on a topic
+    Quiz {
+        /// # Module of the topic, defaults to the module of the locked session
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        module: Option<String>,
+        /// # Topic of the quiz
+        topic: String,
+        /// # Minimum score (inclusive) the user needs to reach on the topic
+        min_score: f64,
+    },
+    /// # Unlock for members of a group
+    Group { group: String },
+    /// # Unlock if all nested triggers are met
+    #[schema(no_recursion)]
+    All(Vec<UnlockTrigger>),
+    /// # Unlock if any nested trigger is met
+    #[schema(no_recursion)]
+    Any(Vec<UnlockTrigger>),
 }
 
 #[derive(Serialize, Deserialize, ToSchema, JsonSchema)]
@@ -129,4 +182,44 @@ mod tests {
             panic!("Deserialize UTC failed");
         }
     }
+
+    #[test]
+    fn test_session_unlock_trigger_completion_list() {
+        let trigger: UnlockTrigger =
+            serde_json::from_str(r#"{"completion":{"after":["a","b"],"module":"other"}}"#).unwrap();
+        let UnlockTrigger::Completion { after, module, wait } = trigger else {
+            panic!("Deserialize completion failed");
+        };
+        assert_eq!(after, vec!["a".to_owned(), "b".to_owned()]);
+        assert_eq!(module.as_deref(), Some("other"));
+        assert!(wait.is_none());
+
+        let trigger: UnlockTrigger = serde_json::from_str(r#"{"completion":{"after":"a"}}"#).unwrap();
+        assert!(matches!(trigger, UnlockTrigger::Completion { after, .. } if after == vec!["a".to_owned()]));
+
+        assert!(serde_json::from_str::<UnlockTrigger>(r#"{"completion":{"after":[]}}"#).is_err());
+    }
+
+    #[test]
+    fn test_session_unlock_trigger_nested() {
+        let unlock: Unlock = yaml_serde::from_str(
+            r"
+triggers:
+  - any:
+      - group: pilot
+      - all:
+          - pre-assessment:
+              scale: motivation
+              min: 3
+          - quiz:
+              topic: basics
+              min-score: 0.8
+",
+        )
+        .unwrap();
+        let [UnlockTrigger::Any(any)] = unlock.triggers.as_slice() else {
+            panic!("Deserialize nested triggers failed");
+        };
+        assert!(matches!(any.as_slice(), [UnlockTrigger::Group { .. }, UnlockTrigger::All(all)] if all.len() == 2));
+    }
 }
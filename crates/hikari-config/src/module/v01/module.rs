@@ -4,6 +4,7 @@ use crate::{
     generic::{Metadata, Theme},
     module::{
         ModuleCategory,
+        content::GradingRubric,
         v01::{assessment::ModuleAssessmentV01, content::ContentV01, feature::FeatureV01, session::SessionV01},
     },
 };
@@ -44,6 +45,10 @@ pub struct ModuleV01 {
     /// Quizzes simulate an exam about the module content.
     pub(crate) quizzable: bool,
     #[serde(default)]
+    /// # Grading rubric for closed quiz questions
+    /// Multiple choice, cloze, ordering and matching questions are graded without an LLM using this rubric
+    pub(crate) quiz_rubric: GradingRubric,
+    #[serde(default)]
     /// # Whether the module is hidden from the frontend
     pub(crate) hidden: bool,
     #[serde(default)]
@@ -0,0 +1,267 @@
+use chrono::Utc;
+use hikari_config::global::access::StratificationAttribute;
+use hikari_db::allocation::{Mutation, Query};
+use hikari_model::admin::{AllocationReport, GroupAllocation};
+use hikari_model::user::User;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use sea_orm::{ConnectionTrait, DbErr};
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Retries if other requests take the free positions of a sequence at the same time
+const MAX_CLAIM_ATTEMPTS: usize = 10;
+const UNKNOWN_STRATUM_VALUE: &str = "unknown";
+
+#[derive(Debug, Error)]
+pub enum AllocationError {
+    #[error(transparent)]
+    DbError(#[from] DbErr),
+
+    #[error("No groups to select")]
+    NoGroups,
+
+    #[error("Block size {block_size} is not a multiple of the number of groups ({groups})")]
+    InvalidBlockSize { block_size: u32, groups: usize },
+
+    #[error("Could not allocate a group after {MAX_CLAIM_ATTEMPTS} attempts")]
+    Contention,
+}
+
+/// A random group assignment which uses a persisted allocation sequence
+pub struct BlockRandomization<'a> {
+    /// The access token the user redeemed
+    pub token: &'a str,
+    /// Index of the assignment within the groups of the token
+    pub slot: i32,
+    pub groups: &'a [String],
+    pub block_size: Option<u32>,
+    pub stratify_by: &'a [StratificationAttribute],
+}
+
+impl BlockRandomization<'_> {
+    fn block_size(&self) -> Result<i32, AllocationError> {
+        let block_size = self
+            .block_size
+            .unwrap_or_else(|| u32::try_from(self.groups.len()).unwrap_or(u32::MAX));
+        self.check_block_size(block_size)
+    }
+
+    /// Makes sure every block contains each group equally often
+    fn check_block_size(&self, block_size: u32) -> Result<i32, AllocationError> {
+        if self.groups.is_empty() {
+            return Err(AllocationError::NoGroups);
+        }
+        let invalid = || AllocationError::InvalidBlockSize {
+            block_size,
+            groups: self.groups.len(),
+        };
+        let groups = u32::try_from(self.groups.len()).map_err(|_| invalid())?;
+        if block_size == 0 || block_size % groups != 0 {
+            return Err(invalid());
+        }
+        i32::try_from(block_size).map_err(|_| invalid())
+    }
+
+    /// Allocates the next position of the sequence of the user's stratum and returns its group.
+    ///
+    /// Blocks are generated from the seed of the sequence when they are needed and persisted, so every assignment can
+    /// be reproduced even if the random number generator changes.
+    pub async fn allocate<C: ConnectionTrait>(&self, conn: &C, user: &User) -> Result<String, AllocationError> {
+        let block_size = self.block_size()?;
+        let stratum = stratum(self.stratify_by, user);
+        let sequence =
+            Mutation::get_or_create_sequence(conn, self.token, self.slot, &stratum, rand::random(), block_size).await?;
+        // An existing sequence keeps its block size, which no longer fits if groups were added or removed since
+        self.check_block_size(u32::try_from(sequence.block_size).unwrap_or_default())?;
+
+        for _ in 0..MAX_CLAIM_ATTEMPTS {
+            let Some(free) = Query::next_free(conn, sequence.id).await? else {
+                let length = Query::length(conn, sequence.id).await?;
+                let block = generate_block(
+                    self.groups,
+                    sequence.block_size,
+                    sequence.seed,
+                    length / sequence.block_size,
+                );
+                Mutation::insert_block(conn, sequence.id, length, block).await?;
+                continue;
+            };
+            if Mutation::claim(conn, sequence.id, free.position, user.id, Utc::now().naive_utc()).await? {
+                tracing::debug!(
+                    user_id = %user.id,
+                    token = self.token,
+                    stratum,
+                    position = free.position,
+                    group = free.group_name,
+                    "allocated group"
+                );
+                return Ok(free.group_name);
+            }
+        }
+        Err(AllocationError::Contention)
+    }
+}
+
+/// Identifies the stratum of the user, e.g. `semester=3;subject=Informatics`.
+#[must_use]
+pub fn stratum(stratify_by: &[StratificationAttribute], user: &User) -> String {
+    let mut attributes: Vec<_> = stratify_by
+        .iter()
+        .map(|attribute| match attribute {
+            StratificationAttribute::Subject => (
+                "subject",
+                user.subject.as_deref().map_or_else(
+                    || UNKNOWN_STRATUM_VALUE.to_owned(),
+                    |subject| subject.trim().to_lowercase(),
+                ),
+            ),
+            StratificationAttribute::Semester => (
+                "semester",
+                user.semester
+                    .map_or_else(|| UNKNOWN_STRATUM_VALUE.to_owned(), |semester| semester.to_string()),
+            ),
+        })
+        .collect();
+    attributes.sort_unstable();
+    attributes.dedup();
+    attributes
+        .into_iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Generates the `block`th block of a sequence: every group `block_size / groups.len()` times in random order.
+#[must_use]
+pub fn generate_block(groups: &[String], block_size: i32, seed: i64, block: i32) -> Vec<String> {
+    let repetitions = usize::try_from(block_size).unwrap_or_default() / groups.len().max(1);
+    let mut assignments: Vec<String> = groups
+        .iter()
+        .flat_map(|group| std::iter::repeat_n(group.clone(), repetitions))
+        .collect();
+    let mut rng = StdRng::seed_from_u64(seed.cast_unsigned() ^ u64::from(block.cast_unsigned()));
+    assignments.shuffle(&mut rng);
+    assignments
+}
+
+/// Summarizes the allocation sequences, optionally only the ones of one access token.
+pub async fn report<C: ConnectionTrait>(conn: &C, token: Option<&str>) -> Result<Vec<AllocationReport>, DbErr> {
+    let sequences = Query::sequences(conn, token).await?;
+    let mut allocations: HashMap<_, Vec<_>> = HashMap::new();
+    for allocation in Query::allocations(conn, sequences.iter().map(|sequence| sequence.id).collect()).await? {
+        allocations.entry(allocation.sequence_id).or_default().push(allocation);
+    }
+
+    Ok(sequences
+        .into_iter()
+        .map(|sequence| {
+            let positions = allocations.remove(&sequence.id).unwrap_or_default();
+            let mut groups: Vec<GroupAllocation> = vec![];
+            let mut allocated = 0;
+            let mut remaining_in_block = 0;
+            for position in positions {
+                if position.allocated_at.is_none() {
+                    remaining_in_block += 1;
+                    continue;
+                }
+                allocated += 1;
+                match groups.iter_mut().find(|group| group.group == position.group_name) {
+                    Some(group) => group.allocated += 1,
+                    None => groups.push(GroupAllocation {
+                        group: position.group_name,
+                        allocated: 1,
+                    }),
+                }
+            }
+            AllocationReport {
+                token: sequence.token,
+                slot: sequence.slot,
+                stratum: sequence.stratum,
+                block_size: sequence.block_size,
+                allocated,
+                remaining_in_block,
+                groups,
+                created_at: sequence.created_at,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn groups() -> Vec<String> {
+        vec!["control".to_owned(), "treatment".to_owned()]
+    }
+
+    #[test]
+    fn test_blocks_are_balanced_and_reproducible() {
+        let groups = groups();
+        for block in 0..10 {
+            let assignments = generate_block(&groups, 6, 42, block);
+            assert_eq!(assignments.len(), 6);
+            assert_eq!(assignments.iter().filter(|group| *group == "control").count(), 3);
+            assert_eq!(assignments, generate_block(&groups, 6, 42, block));
+        }
+    }
+
+    #[test]
+    fn test_invalid_block_size() {
+        let groups = groups();
+        let randomization = BlockRandomization {
+            token: "study",
+            slot: 0,
+            groups: &groups,
+            block_size: Some(3),
+            stratify_by: &[],
+        };
+        assert!(matches!(
+            randomization.block_size(),
+            Err(AllocationError::InvalidBlockSize {
+                block_size: 3,
+                groups: 2
+            })
+        ));
+
+        let randomization = BlockRandomization {
+            block_size: None,
+            ..randomization
+        };
+        assert_eq!(randomization.block_size().unwrap(), 2);
+
+        // A sequence created with 4 groups can't be continued with 3
+        let groups = vec!["a".to_owned(), "b".to_owned(), "c".to_owned()];
+        let randomization = BlockRandomization {
+            groups: &groups,
+            ..randomization
+        };
+        assert!(matches!(
+            randomization.check_block_size(4),
+            Err(AllocationError::InvalidBlockSize {
+                block_size: 4,
+                groups: 3
+            })
+        ));
+        assert!(randomization.check_block_size(0).is_err());
+    }
+
+    #[test]
+    fn test_stratum() {
+        let user = User {
+            subject: Some(" Informatics".to_owned()),
+            semester: None,
+            ..Default::default()
+        };
+        assert_eq!(stratum(&[], &user), "");
+        assert_eq!(
+            stratum(
+                &[StratificationAttribute::Subject, StratificationAttribute::Semester],
+                &user
+            ),
+            "semester=unknown;subject=informatics"
+        );
+    }
+}
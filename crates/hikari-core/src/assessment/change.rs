@@ -0,0 +1,337 @@
+use crate::assessment::scale::{ScaleError, build_scale_values};
+use futures::future::try_join;
+use hikari_config::assessment::{Assessment, AssessmentConfig};
+use hikari_entity::assessment::answer::Model as Answer;
+use hikari_entity::assessment::session::{AssessmentStatus, Model as AssessmentSession};
+use hikari_model::assessment::change::{CohortAssessmentChange, CohortScaleChange, ReliableChange, ScaleChange};
+use hikari_model::assessment::scales::ItemValue;
+use num_traits::ToPrimitive;
+use sea_orm::prelude::Uuid;
+use sea_orm::{ConnectionTrait, DbErr};
+use std::collections::HashMap;
+use std::error::Error;
+use thiserror::Error;
+
+/// Changes with an absolute reliable change index above this value are unlikely (p < 0.05) to be measurement error
+const RELIABLE_CHANGE_THRESHOLD: f64 = 1.96;
+const COHORT_PAGE_SIZE: u64 = 500;
+
+#[derive(Debug, Error)]
+pub enum ChangeError {
+    #[error(transparent)]
+    DbError(#[from] DbErr),
+
+    #[error(transparent)]
+    Scale(#[from] ScaleError),
+
+    #[error("assessment \"{0}\" is not configured")]
+    AssessmentNotConfigured(String),
+}
+
+/// Computes the per-scale change between the finished pre- and post-assessment of a module.
+///
+/// Returns `None` if the user has not finished both assessments yet.
+pub async fn load_user_change<C: ConnectionTrait>(
+    conn: &C,
+    user_id: Uuid,
+    module_id: &str,
+    config: &AssessmentConfig,
+) -> Result<Option<Vec<ScaleChange>>, ChangeError> {
+    let (pre, post) = try_join(
+        hikari_db::module::assessment::Query::load_pre_assessment(conn, user_id, module_id),
+        hikari_db::module::assessment::Query::load_post_assessment(conn, user_id, module_id),
+    )
+    .await?;
+    let (Some((pre, _)), Some((post, _))) = (pre, post) else {
+        return Ok(None);
+    };
+    if pre.status != AssessmentStatus::Finished || post.status != AssessmentStatus::Finished {
+        return Ok(None);
+    }
+    let (pre_assessment, pre_values) = load_scale_values(conn, &pre, config).await?;
+    let (_, post_values) = load_scale_values(conn, &post, config).await?;
+    Ok(Some(compare(pre_assessment, &pre_values, &post_values)))
+}
+
+async fn load_scale_values<'a, C: ConnectionTrait>(
+    conn: &C,
+    session: &AssessmentSession,
+    config: &'a AssessmentConfig,
+) -> Result<(&'a Assessment, Vec<ItemValue>), ChangeError> {
+    let answers = hikari_db::assessment::answer::Query::load_answers(conn, session.id).await?;
+    scale_values(session, &answers, config)
+}
+
+fn scale_values<'a>(
+    session: &AssessmentSession,
+    answers: &[Answer],
+    config: &'a AssessmentConfig,
+) -> Result<(&'a Assessment, Vec<ItemValue>), ChangeError> {
+    let assessment = config
+        .get(&session.assessment)
+        .ok_or_else(|| ChangeError::AssessmentNotConfigured(session.assessment.clone()))?;
+    Ok((assessment, build_scale_values(assessment, answers)?))
+}
+
+/// Pairs the scales of both assessments by their id. The reliability and norms are taken from the pre-assessment.
+#[must_use]
+pub fn compare(pre_assessment: &Assessment, pre: &[ItemValue], post: &[ItemValue]) -> Vec<ScaleChange> {
+    let post: HashMap<_, _> = post.iter().map(|value| (value.id.as_str(), value.value)).collect();
+    pre.iter()
+        .filter_map(|pre_value| {
+            let post_value = *post.get(pre_value.id.as_str())?;
+            let difference = post_value - pre_value.value;
+            let rci = pre_assessment.scales.get(&pre_value.id).and_then(|scale| {
+                let reliability = scale.reliability?;
+                let sd = scale.norms.as_ref()?.sd;
+                // Standard error of the difference between two measurements
+                let se_difference = std::f64::consts::SQRT_2 * sd * (1.0 - reliability).sqrt();
+                (se_difference > 0.0).then(|| difference / se_difference)
+            });
+            Some(ScaleChange {
+                id: pre_value.id.clone(),
+                title: pre_value.title.clone(),
+                pre: pre_value.value,
+                post: post_value,
+                difference,
+                rci,
+                reliable_change: rci.map(|rci| {
+                    if rci > RELIABLE_CHANGE_THRESHOLD {
+                        ReliableChange::Increase
+                    } else if rci < -RELIABLE_CHANGE_THRESHOLD {
+                        ReliableChange::Decrease
+                    } else {
+                        ReliableChange::Unchanged
+                    }
+                }),
+            })
+        })
+        .collect()
+}
+
+/// Summarizes the changes of all users in a group who finished both assessments of the module.
+///
+/// Users whose scales can't be calculated are skipped and only counted, so one broken session does not hide the
+/// results of the whole cohort.
+pub async fn load_cohort_change<C: ConnectionTrait>(
+    conn: &C,
+    module_id: &str,
+    group: &str,
+    config: &AssessmentConfig,
+) -> Result<CohortAssessmentChange, ChangeError> {
+    let mut users = 0;
+    let mut skipped = 0;
+    let mut changes = Vec::new();
+    let mut offset = 0;
+    loop {
+        let page = hikari_db::user::Query::get_users_by_group(conn, group, COHORT_PAGE_SIZE, offset).await?;
+        let page_len = page.len();
+        users += page_len;
+        let user_ids = page.into_iter().map(|(user, _, _)| user.id).collect();
+        for (user_id, change) in load_page_changes(conn, user_ids, module_id, config).await? {
+            match change {
+                Ok(change) => changes.push(change),
+                Err(error) => {
+                    tracing::warn!(error = &error as &dyn Error, %user_id, module_id, "skipping user in cohort change");
+                    skipped += 1;
+                }
+            }
+        }
+        if page_len < usize::try_from(COHORT_PAGE_SIZE).unwrap_or(usize::MAX) {
+            break;
+        }
+        offset += COHORT_PAGE_SIZE;
+    }
+    Ok(CohortAssessmentChange {
+        module: module_id.to_owned(),
+        group: group.to_owned(),
+        users,
+        participants: changes.len(),
+        skipped,
+        scales: summarize(&changes),
+    })
+}
+
+/// Computes the changes of all users who finished both assessments, loading the module assessments, sessions and
+/// answers of all users at once.
+async fn load_page_changes<C: ConnectionTrait>(
+    conn: &C,
+    user_ids: Vec<Uuid>,
+    module_id: &str,
+    config: &AssessmentConfig,
+) -> Result<Vec<(Uuid, Result<Vec<ScaleChange>, ChangeError>)>, DbErr> {
+    let linked: Vec<_> = hikari_db::module::assessment::Query::get_for_module_users(conn, user_ids, module_id)
+        .await?
+        .into_iter()
+        .filter_map(|assessment| Some((assessment.user_id, assessment.last_pre?, assessment.last_post?)))
+        .collect();
+    if linked.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let session_ids = linked.iter().flat_map(|&(_, pre, post)| [pre, post]).collect();
+    let sessions: HashMap<_, _> = hikari_db::assessment::session::Query::load_sessions_by_ids(conn, session_ids)
+        .await?
+        .into_iter()
+        .filter(|session| session.status == AssessmentStatus::Finished)
+        .map(|session| (session.id, session))
+        .collect();
+    let mut answers: HashMap<Uuid, Vec<Answer>> = HashMap::new();
+    for answer in
+        hikari_db::assessment::answer::Query::load_answers_of_sessions(conn, sessions.keys().copied().collect()).await?
+    {
+        answers.entry(answer.assessment_session_id).or_default().push(answer);
+    }
+    let session_values = |session: &AssessmentSession| {
+        let answers = answers.get(&session.id).map_or(&[][..], Vec::as_slice);
+        scale_values(session, answers, config)
+    };
+
+    Ok(linked
+        .into_iter()
+        .filter_map(|(user_id, pre, post)| {
+            let (pre, post) = (sessions.get(&pre)?, sessions.get(&post)?);
+            let change = session_values(pre).and_then(|(pre_assessment, pre_values)| {
+                let (_, post_values) = session_values(post)?;
+                Ok(compare(pre_assessment, &pre_values, &post_values))
+            });
+            Some((user_id, change))
+        })
+        .collect())
+}
+
+/// Aggregates the scale changes of multiple users, scales are kept in the order they first appear
+#[must_use]
+pub fn summarize(changes: &[Vec<ScaleChange>]) -> Vec<CohortScaleChange> {
+    let mut scales: Vec<(&ScaleChange, Vec<&ScaleChange>)> = Vec::new();
+    for change in changes.iter().flatten() {
+        match scales.iter_mut().find(|(first, _)| first.id == change.id) {
+            Some((_, all)) => all.push(change),
+            None => scales.push((change, vec![change])),
+        }
+    }
+    scales
+        .into_iter()
+        .map(|(first, all)| {
+            let count = |reliable_change| {
+                all.iter()
+                    .filter(|change| change.reliable_change == Some(reliable_change))
+                    .count()
+            };
+            let mean_difference = mean(all.iter().map(|change| change.difference));
+            CohortScaleChange {
+                id: first.id.clone(),
+                title: first.title.clone(),
+                participants: all.len(),
+                mean_pre: mean(all.iter().map(|change| change.pre)),
+                mean_post: mean(all.iter().map(|change| change.post)),
+                mean_difference,
+                sd_difference: sample_sd(all.iter().map(|change| change.difference), mean_difference),
+                reliable_increase: count(ReliableChange::Increase),
+                reliable_decrease: count(ReliableChange::Decrease),
+                reliably_unchanged: count(ReliableChange::Unchanged),
+            }
+        })
+        .collect()
+}
+
+fn mean(values: impl ExactSizeIterator<Item = f64>) -> f64 {
+    let len = values.len().to_f64().unwrap_or(f64::NAN);
+    values.sum::<f64>() / len
+}
+
+fn sample_sd(values: impl ExactSizeIterator<Item = f64>, mean: f64) -> Option<f64> {
+    let degrees_of_freedom = values.len().checked_sub(1).filter(|df| *df > 0)?.to_f64()?;
+    let squares: f64 = values.map(|value| (value - mean).powi(2)).sum();
+    Some((squares / degrees_of_freedom).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hikari_config::assessment::scale::{Mode, Norms, Scale, ScaleBody};
+    use indexmap::IndexMap;
+
+    fn value(id: &str, value: f64) -> ItemValue {
+        ItemValue {
+            id: id.to_owned(),
+            title: id.to_owned(),
+            value,
+            prorated: 0,
+            norm: None,
+            band: None,
+        }
+    }
+
+    fn assessment(reliability: Option<f64>) -> Assessment {
+        Assessment {
+            assessment_id: "assessment".to_owned(),
+            title: "Assessment".to_owned(),
+            questions: IndexMap::new(),
+            scales: IndexMap::from([(
+                "anxiety".to_owned(),
+                Scale {
+                    id: "anxiety".to_owned(),
+                    title: "Anxiety".to_owned(),
+                    body: ScaleBody::Scale { min: 1, max: 7 },
+                    mode: Mode::Average,
+                    items: vec![],
+                    max_missing: 0,
+                    bands: vec![],
+                    norms: Some(Norms {
+                        mean: 4.0,
+                        sd: 1.0,
+                        percentiles: vec![],
+                    }),
+                    reliability,
+                },
+            )]),
+        }
+    }
+
+    #[test]
+    fn test_reliable_change() {
+        // Standard error of the difference: sqrt(2) * 1.0 * sqrt(1 - 0.875) = 0.5
+        let assessment = assessment(Some(0.875));
+        let changes = compare(
+            &assessment,
+            &[value("anxiety", 5.5), value("other", 1.0)],
+            &[value("anxiety", 4.0)],
+        );
+        assert_eq!(changes.len(), 1);
+        assert!((changes[0].difference + 1.5).abs() < 1e-9);
+        assert!((changes[0].rci.unwrap() + 3.0).abs() < 1e-9);
+        assert_eq!(changes[0].reliable_change, Some(ReliableChange::Decrease));
+
+        let changes = compare(&assessment, &[value("anxiety", 4.0)], &[value("anxiety", 4.5)]);
+        assert_eq!(changes[0].reliable_change, Some(ReliableChange::Unchanged));
+
+        let changes = compare(
+            &self::assessment(None),
+            &[value("anxiety", 4.0)],
+            &[value("anxiety", 6.0)],
+        );
+        assert!(changes[0].rci.is_none());
+        assert!(changes[0].reliable_change.is_none());
+    }
+
+    #[test]
+    fn test_summarize() {
+        let assessment = assessment(Some(0.875));
+        let changes = vec![
+            compare(&assessment, &[value("anxiety", 6.0)], &[value("anxiety", 4.0)]),
+            compare(&assessment, &[value("anxiety", 4.0)], &[value("anxiety", 4.0)]),
+        ];
+        let summary = summarize(&changes);
+        assert_eq!(summary.len(), 1);
+        assert_eq!(summary[0].participants, 2);
+        assert!((summary[0].mean_pre - 5.0).abs() < 1e-9);
+        assert!((summary[0].mean_post - 4.0).abs() < 1e-9);
+        assert!((summary[0].mean_difference + 1.0).abs() < 1e-9);
+        assert!((summary[0].sd_difference.unwrap() - std::f64::consts::SQRT_2).abs() < 1e-9);
+        assert_eq!(summary[0].reliable_decrease, 1);
+        assert_eq!(summary[0].reliably_unchanged, 1);
+
+        assert!(summarize(&changes[..1])[0].sd_difference.is_none());
+    }
+}
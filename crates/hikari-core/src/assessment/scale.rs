@@ -0,0 +1,233 @@
+use hikari_config::assessment::Assessment;
+use hikari_config::assessment::question::QuestionBody;
+use hikari_config::assessment::scale::{BandScore, Mode, Norms, Scale};
+use hikari_entity::assessment::answer::Model as Answer;
+use hikari_model::assessment::scales::{ItemValue, NormScore, ScaleBand};
+use num_traits::ToPrimitive;
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ScaleError {
+    #[error("question id \"{0}\" does not exist")]
+    QuestionIdDoesNotExist(String),
+    #[error("Invalid configuration: scale type \"{0}\" not supported")]
+    InvalidScaleType(String),
+    #[error("answer id wasn't found")]
+    AnswerNotFound,
+    #[error("couldn't parse stored answer value {0}")]
+    InvalidValue(String),
+    #[error("no item of scale \"{0}\" was answered")]
+    NoAnsweredItems(String),
+    #[error("{0}")]
+    Other(String),
+}
+
+pub trait Operation {
+    fn evaluate(&self, data: Vec<u8>) -> Result<f64, ScaleError>;
+}
+
+impl Operation for Mode {
+    fn evaluate(&self, data: Vec<u8>) -> Result<f64, ScaleError> {
+        let length = data.len();
+        if length == 0 {
+            return Err(ScaleError::Other("No data to evaluate".to_owned()));
+        }
+        let sum = data.into_iter().map(f64::from).sum();
+        let res = match self {
+            Self::Sum => sum,
+            Self::Average => {
+                sum / length
+                    .to_f64()
+                    .ok_or_else(|| ScaleError::Other("Failed to evaluate average".to_owned()))?
+            }
+        };
+        Ok(res)
+    }
+}
+
+/// Evaluates the scale from the answered items. Sums are scaled up to the number of shown items, so missing items
+/// count with the mean of the answered ones.
+fn prorate(scale: &Scale, values: Vec<u8>, missing: usize) -> Result<f64, ScaleError> {
+    let answered = values.len();
+    let value = scale.mode.evaluate(values)?;
+    Ok(match scale.mode {
+        Mode::Sum if missing > 0 => {
+            let factor = (answered + missing)
+                .to_f64()
+                .zip(answered.to_f64())
+                .map(|(shown, answered)| shown / answered)
+                .ok_or_else(|| ScaleError::Other("Failed to prorate sum".to_owned()))?;
+            value * factor
+        }
+        Mode::Sum | Mode::Average => value,
+    })
+}
+
+fn norm_score(norms: &Norms, value: f64) -> NormScore {
+    let z = (value - norms.mean) / norms.sd;
+    NormScore {
+        z,
+        t: 50.0 + 10.0 * z,
+        percentile: percentile(norms, value, z),
+    }
+}
+
+/// Looks up the percentile rank in the norm table and interpolates linearly between its entries. Without a table
+/// the rank is derived from the normal distribution.
+fn percentile(norms: &Norms, value: f64, z: f64) -> f64 {
+    let mut table: Vec<_> = norms.percentiles.iter().collect();
+    table.sort_by(|a, b| a.value.total_cmp(&b.value));
+    let (Some(first), Some(last)) = (table.first(), table.last()) else {
+        return 100.0 * normal_cdf(z);
+    };
+    if value <= first.value {
+        return first.percentile;
+    }
+    if value >= last.value {
+        return last.percentile;
+    }
+    table
+        .windows(2)
+        .find_map(|window| match window {
+            [lower, upper] if value <= upper.value => {
+                let fraction = (value - lower.value) / (upper.value - lower.value);
+                Some(lower.percentile + fraction * (upper.percentile - lower.percentile))
+            }
+            _ => None,
+        })
+        .unwrap_or(last.percentile)
+}
+
+/// Cumulative distribution function of the standard normal distribution
+fn normal_cdf(z: f64) -> f64 {
+    0.5 * (1.0 + erf(z / std::f64::consts::SQRT_2))
+}
+
+/// Approximation of the error function with a maximum error of 1.5e-7 (Abramowitz and Stegun 7.1.26)
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let polynomial =
+        t * (0.254_829_592 + t * (-0.284_496_736 + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let result = 1.0 - polynomial * (-x * x).exp();
+    if x < 0.0 { -result } else { result }
+}
+
+fn band(scale: &Scale, value: f64) -> Option<ScaleBand> {
+    let norm = scale.norms.as_ref().map(|norms| norm_score(norms, value));
+    scale
+        .bands
+        .iter()
+        .find(|band| {
+            let score = match band.score {
+                BandScore::Raw => Some(value),
+                BandScore::Z => norm.as_ref().map(|norm| norm.z),
+                BandScore::T => norm.as_ref().map(|norm| norm.t),
+                BandScore::Percentile => norm.as_ref().map(|norm| norm.percentile),
+            };
+            score.is_some_and(|score| band.contains(score))
+        })
+        .map(|band| ScaleBand {
+            label: band.label.clone(),
+            feedback: band.feedback.clone(),
+        })
+}
+
+/// Returns the IDs of the questions which are shown given the stored answers of one assessment session.
+pub fn visible_questions<'a>(assessment: &'a Assessment, answers: &[Answer]) -> Result<HashSet<&'a str>, ScaleError> {
+    let answers: HashMap<_, _> = answers
+        .iter()
+        .map(|answer| (answer.question.as_str(), answer))
+        .collect();
+    let parsed: HashMap<_, _> = assessment
+        .questions
+        .values()
+        .filter_map(|question| {
+            answers.get(question.id.as_str()).map(|answer| {
+                question
+                    .parse_answer(&answer.data)
+                    .map(|parsed| (question.id.as_str(), parsed))
+                    .map_err(|_| ScaleError::InvalidValue(answer.data.clone()))
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(assessment.visible_questions(&parsed))
+}
+
+/// Calculates the values of all scales of the assessment from the answers of one assessment session.
+///
+/// Items of questions which were skipped because of their condition are left out. Scales without any shown item
+/// have no value.
+pub fn build_scale_values(assessment: &Assessment, answers: &[Answer]) -> Result<Vec<ItemValue>, ScaleError> {
+    let visible = visible_questions(assessment, answers)?;
+    let answers: HashMap<_, _> = answers
+        .iter()
+        .map(|answer| (answer.question.as_str(), answer))
+        .collect();
+    let is_skipped = |id: &str| assessment.questions.contains_key(id) && !visible.contains(id);
+
+    let result: Result<Vec<_>, ScaleError> = assessment
+        .scales
+        .values()
+        .filter(|scale| !scale.items.iter().all(|item| is_skipped(&item.id)))
+        .map(|scale| {
+            let values: Result<Vec<Option<u8>>, ScaleError> = scale
+                .items
+                .iter()
+                .filter(|item| !is_skipped(&item.id))
+                .map(|item| {
+                    let question = assessment
+                        .questions
+                        .get(item.id.as_str())
+                        .ok_or(ScaleError::QuestionIdDoesNotExist(item.id.clone()))?;
+                    let (min, max) = match &question.body {
+                        QuestionBody::Scale(scale) => (scale.min, scale.max),
+                        scale_type => {
+                            return Err(ScaleError::InvalidScaleType(Into::<&str>::into(scale_type).to_owned()));
+                        }
+                    };
+
+                    answers
+                        .get(item.id.as_str())
+                        .map(|&answer| {
+                            answer
+                                .data
+                                .parse::<u8>()
+                                .map_err(|_| {
+                                    tracing::error!(data = answer.data, "Failed to parse data as u8");
+                                    ScaleError::InvalidValue(answer.data.clone())
+                                })
+                                .map(|val| if item.reverse { max + min - val } else { val })
+                        })
+                        .transpose()
+                })
+                .collect();
+            let values = values?;
+            let missing = values.iter().filter(|value| value.is_none()).count();
+            if missing > usize::try_from(scale.max_missing).unwrap_or(usize::MAX) {
+                return Err(ScaleError::AnswerNotFound);
+            }
+            // Nothing can be prorated from
+            if missing == values.len() {
+                return Err(ScaleError::NoAnsweredItems(scale.id.clone()));
+            }
+            let value = prorate(scale, values.into_iter().flatten().collect(), missing)?;
+            Ok(ItemValue {
+                id: scale.id.clone(),
+                title: scale.title.clone(),
+                value,
+                prorated: missing,
+                norm: scale.norms.as_ref().map(|norms| norm_score(norms, value)),
+                band: band(scale, value),
+            })
+        })
+        .collect();
+    if let Err(error) = &result {
+        tracing::error!(
+            assessment_id = assessment.assessment_id,
+            error = error as &dyn std::error::Error,
+            "failed to build scale values"
+        );
+    }
+    result
+}
@@ -0,0 +1,318 @@
+use crate::crisis::error::CrisisError;
+use crate::llm_config::LlmConfig;
+use crate::openai::error::OpenAiError;
+use crate::openai::{CallConfig, openai_single_tool_call};
+use crate::usage::add_usage;
+use async_openai::types::chat::{ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs};
+use chrono::{Duration as ChronoDuration, NaiveDateTime, Utc};
+use hikari_config::global::crisis::{CrisisClassifierConfig, CrisisConfig, CrisisWebhookConfig};
+use hikari_db::crisis_event;
+use hikari_model::crisis::CrisisResources;
+use schemars::JsonSchema;
+use sea_orm::DatabaseConnection;
+use sea_orm::prelude::Uuid;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::sync::LazyLock;
+use std::time::Duration;
+use tracing::instrument;
+
+pub mod error;
+
+const CLASSIFIER_TIMEOUT: Duration = Duration::from_secs(20);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+/// Gives the notification sent right after the detection time to finish before it is retried
+const WEBHOOK_RETRY_DELAY: ChronoDuration = ChronoDuration::minutes(1);
+const WEBHOOK_RETRY_BATCH_SIZE: u64 = 50;
+
+/// Shared by all deliveries, so the connections to the webhook are reused
+static WEBHOOK_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .expect("failed to create the webhook client")
+});
+
+const DEFAULT_CLASSIFIER_PROMPT: &str = "Du prüfst Texte von Studierenden auf Anzeichen einer akuten Krise, \
+    z. B. Suizidgedanken, Selbstverletzung, Hoffnungslosigkeit oder akute Gefahr für sich oder andere.\n\
+    Alltägliche Sorgen wie Prüfungsstress, schlechter Schlaf oder Traurigkeit sind keine akute Krise.\n\
+    Rufe die Funktion `Krisenerkennung` mit deiner Einschätzung auf.";
+
+/// Where the checked text was written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrisisSource {
+    Journal,
+    Chat,
+}
+
+impl CrisisSource {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CrisisSource::Journal => "journal",
+            CrisisSource::Chat => "chat",
+        }
+    }
+}
+
+/// What detected the signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Detector {
+    Keyword,
+    Classifier,
+}
+
+impl Detector {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Detector::Keyword => "keyword",
+            Detector::Classifier => "classifier",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[schemars(
+    title = "Krisenerkennung",
+    description = "Einschätzung, ob der Text Anzeichen einer akuten Krise enthält."
+)]
+struct CrisisVerdict {
+    /// Ob der Text Anzeichen einer akuten Krise enthält
+    acute_distress: bool,
+}
+
+/// Sent to the counselor webhook. It never contains the text which triggered the detection.
+#[derive(Debug, Serialize)]
+struct WebhookPayload {
+    event_id: Uuid,
+    source: String,
+    detector: String,
+    created_at: NaiveDateTime,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_id: Option<Uuid>,
+}
+
+impl WebhookPayload {
+    fn new(webhook: &CrisisWebhookConfig, event: &hikari_entity::crisis_event::Model) -> Self {
+        Self {
+            event_id: event.id,
+            source: event.source.clone(),
+            detector: event.detector.clone(),
+            created_at: event.created_at,
+            user_id: webhook.include_user.then_some(event.user_id),
+        }
+    }
+}
+
+/// Lowercase words separated by single spaces
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether a keyword appears at the start of a word of the text
+#[must_use]
+pub fn contains_keyword(keywords: &[String], text: &str) -> bool {
+    let text = format!(" {}", normalize(text));
+    keywords
+        .iter()
+        .map(|keyword| normalize(keyword))
+        .filter(|keyword| !keyword.is_empty())
+        .any(|keyword| text.contains(&format!(" {keyword}")))
+}
+
+async fn classify(
+    classifier: &CrisisClassifierConfig,
+    llm_config: &LlmConfig,
+    conn: &DatabaseConnection,
+    user_id: Uuid,
+    text: &str,
+) -> Result<bool, CrisisError> {
+    let messages = vec![
+        ChatCompletionRequestSystemMessageArgs::default()
+            .content(classifier.prompt.as_deref().unwrap_or(DEFAULT_CLASSIFIER_PROMPT))
+            .build()
+            .map_err(OpenAiError::from)?
+            .into(),
+        ChatCompletionRequestUserMessageArgs::default()
+            .content(text)
+            .build()
+            .map_err(OpenAiError::from)?
+            .into(),
+    ];
+    let model = classifier
+        .model
+        .as_deref()
+        .unwrap_or_else(|| llm_config.get_default_model(classifier.service.as_ref()));
+    let (verdict, tokens) = openai_single_tool_call::<CrisisVerdict>(
+        CallConfig::builder()
+            .total_timeout(CLASSIFIER_TIMEOUT)
+            .iteration_timeout(CLASSIFIER_TIMEOUT)
+            .user(user_id.to_string())
+            .build(),
+        llm_config.get_openai_config(classifier.service.as_ref()),
+        Some(0.0),
+        None,
+        model,
+        messages,
+    )
+    .await?;
+    if let Some(tokens) = tokens {
+        add_usage(conn, &user_id, tokens, "crisis_detection").await?;
+    }
+    Ok(verdict.acute_distress)
+}
+
+/// Detects signals of acute distress, the keywords are checked first
+async fn detect(
+    config: &CrisisConfig,
+    llm_config: &LlmConfig,
+    conn: &DatabaseConnection,
+    user_id: Uuid,
+    text: &str,
+) -> Option<Detector> {
+    if contains_keyword(&config.keywords, text) {
+        return Some(Detector::Keyword);
+    }
+    let classifier = config.classifier.as_ref()?;
+    match classify(classifier, llm_config, conn, user_id, text).await {
+        Ok(true) => Some(Detector::Classifier),
+        Ok(false) => None,
+        Err(error) => {
+            tracing::error!(error = &error as &dyn Error, "crisis classifier failed");
+            None
+        }
+    }
+}
+
+async fn notify(
+    webhook: &CrisisWebhookConfig,
+    conn: &DatabaseConnection,
+    event: &hikari_entity::crisis_event::Model,
+) -> Result<(), CrisisError> {
+    let payload = serde_json::to_vec(&WebhookPayload::new(webhook, event))?;
+    let mut request = WEBHOOK_CLIENT
+        .post(webhook.url.clone())
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(payload);
+    if let Some(token) = &webhook.token {
+        request = request.bearer_auth(token);
+    }
+    request.send().await?.error_for_status()?;
+    crisis_event::Mutation::mark_notified(conn, event.id).await?;
+    Ok(())
+}
+
+/// Sends the notifications which failed again. Delivery is at least once, the counselor may receive an event twice
+/// if the webhook accepted it but marking the event failed.
+pub async fn run_webhook_retry(conn: DatabaseConnection, config: CrisisConfig, interval: Duration) {
+    let Some(webhook) = config.webhook else {
+        return;
+    };
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        interval.tick().await;
+        if let Err(error) = retry_notifications(&webhook, &conn).await {
+            tracing::error!(error = &error as &dyn Error, "failed to retry counselor notifications");
+        }
+    }
+}
+
+async fn retry_notifications(webhook: &CrisisWebhookConfig, conn: &DatabaseConnection) -> Result<(), CrisisError> {
+    let before = Utc::now().naive_utc() - WEBHOOK_RETRY_DELAY;
+    let events = crisis_event::Query::find_unnotified(conn, before, WEBHOOK_RETRY_BATCH_SIZE).await?;
+    for event in events {
+        // The remaining events are tried on the next run, the webhook is most likely unavailable
+        notify(webhook, conn, &event).await?;
+        tracing::info!(event_id = %event.id, "notified counselor after retry");
+    }
+    Ok(())
+}
+
+/// Checks the text for signals of acute distress. On a detection the event is logged without the text, the counselor
+/// webhook is notified in the background and the hotlines are returned for the user. Failed notifications are sent
+/// again by [`run_webhook_retry`]. Errors are logged, they never prevent the text from being processed.
+#[instrument(skip(config, llm_config, conn, text), fields(source = source.as_str()))]
+pub async fn check(
+    config: &CrisisConfig,
+    llm_config: &LlmConfig,
+    conn: &DatabaseConnection,
+    user_id: Uuid,
+    source: CrisisSource,
+    reference_id: Option<Uuid>,
+    text: &str,
+) -> Option<CrisisResources> {
+    let detector = detect(config, llm_config, conn, user_id, text).await?;
+    tracing::warn!(detector = detector.as_str(), "crisis signal detected");
+
+    if let Some(days) = config.retention_days {
+        let before = Utc::now().naive_utc() - ChronoDuration::days(i64::from(days));
+        if let Err(error) = crisis_event::Mutation::delete_before(conn, before).await {
+            tracing::error!(error = &error as &dyn Error, "failed to remove expired crisis events");
+        }
+    }
+
+    match crisis_event::Mutation::log(conn, user_id, source.as_str(), reference_id, detector.as_str()).await {
+        Ok(event) => {
+            if let Some(webhook) = config.webhook.clone() {
+                let conn = conn.clone();
+                tokio::spawn(async move {
+                    if let Err(error) = notify(&webhook, &conn, &event).await {
+                        tracing::error!(error = &error as &dyn Error, event_id = %event.id, "failed to notify counselor");
+                    }
+                });
+            }
+        }
+        Err(error) => {
+            tracing::error!(error = &error as &dyn Error, "failed to log crisis event");
+        }
+    }
+
+    Some(CrisisResources::from(config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains_keyword() {
+        let keywords = vec!["Suizid".to_string(), "nicht mehr leben".to_string(), " ".to_string()];
+        assert!(contains_keyword(&keywords, "Ich habe Suizidgedanken."));
+        assert!(contains_keyword(&keywords, "ich will NICHT  mehr\nleben"));
+        assert!(!contains_keyword(&keywords, "Ich will nicht mehr lernen"));
+        assert!(!contains_keyword(&keywords, "Antisuizid"));
+        assert!(!contains_keyword(&[], "Suizid"));
+    }
+
+    #[test]
+    fn test_webhook_payload_privacy() {
+        let event = hikari_entity::crisis_event::Model {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            source: "journal".to_string(),
+            reference_id: Some(Uuid::new_v4()),
+            detector: "keyword".to_string(),
+            created_at: Utc::now().naive_utc(),
+            notified_at: None,
+        };
+        let mut webhook = CrisisWebhookConfig {
+            url: "https://example.com/hook".parse().unwrap(),
+            token: None,
+            include_user: false,
+        };
+
+        let payload = serde_json::to_value(WebhookPayload::new(&webhook, &event)).unwrap();
+        assert!(payload.get("user_id").is_none());
+        assert!(payload.get("reference_id").is_none());
+        assert_eq!(payload["source"], "journal");
+
+        webhook.include_user = true;
+        let payload = serde_json::to_value(WebhookPayload::new(&webhook, &event)).unwrap();
+        assert_eq!(payload["user_id"], event.user_id.to_string());
+    }
+}
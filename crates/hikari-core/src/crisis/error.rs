@@ -0,0 +1,18 @@
+use crate::openai::error::OpenAiError;
+use sea_orm::DbErr;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CrisisError {
+    #[error(transparent)]
+    DbError(#[from] DbErr),
+
+    #[error(transparent)]
+    OpenAi(#[from] OpenAiError),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Webhook(#[from] reqwest::Error),
+}
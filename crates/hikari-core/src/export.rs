@@ -0,0 +1,288 @@
+pub mod error;
+
+use crate::export::error::ExportError;
+use crate::jobs::INSTANCE_ID;
+use async_zip::tokio::write::ZipFileWriter;
+use async_zip::{Compression, ZipEntryBuilder};
+use chrono::{Duration as ChronoDuration, NaiveDateTime, Utc};
+use hikari_db::export::job;
+use hikari_db::export::{ExportDomain, Query};
+use hikari_db::util::FlattenTransactionResultExt;
+use hikari_entity::export_job::{Model as JobModel, Status as JobStatus};
+use hikari_utils::loader::{FileReader, Loader, LoaderTrait};
+use sea_orm::prelude::Uuid;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr, TransactionTrait};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::json;
+use std::error::Error;
+use std::time::Duration;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tracing::instrument;
+use utoipa::ToSchema;
+
+/// How long the archive of an export job can be downloaded.
+const EXPORT_RETENTION: ChronoDuration = ChronoDuration::days(7);
+/// How long a claimed job is reserved for one instance before others may take it over.
+const LEASE_DURATION: ChronoDuration = ChronoDuration::minutes(15);
+/// Jobs which failed this often are not retried automatically.
+const MAX_ATTEMPTS: i32 = 3;
+/// Number of claimable jobs loaded at once by the job processor.
+const CLAIM_BATCH_SIZE: u64 = 10;
+/// Number of queued or running export jobs a user may have at the same time.
+pub const MAX_ACTIVE_JOBS: u64 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportJobStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl From<JobStatus> for ExportJobStatus {
+    fn from(status: JobStatus) -> Self {
+        match status {
+            JobStatus::Pending => Self::Pending,
+            JobStatus::Running => Self::Running,
+            JobStatus::Completed => Self::Completed,
+            JobStatus::Failed => Self::Failed,
+        }
+    }
+}
+
+impl ExportJobStatus {
+    #[must_use]
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed)
+    }
+}
+
+#[derive(Debug, Clone, ToSchema, Serialize, Deserialize)]
+pub struct ExportJob {
+    pub id: Uuid,
+    pub status: ExportJobStatus,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+    /// The archive is deleted after this point
+    pub expires_at: NaiveDateTime,
+}
+
+impl From<JobModel> for ExportJob {
+    fn from(job: JobModel) -> Self {
+        Self {
+            id: job.id,
+            status: job.status.into(),
+            error: job.error,
+            created_at: job.created_at,
+            expires_at: job.expires_at,
+        }
+    }
+}
+
+/// Writes all data stored about the user as ZIP archive with one JSON document per domain.
+///
+/// Every domain is loaded and compressed on its own, so only one domain is held in memory at a time.
+#[instrument(skip(conn, writer))]
+pub async fn write_archive<C, W>(conn: &C, user_id: Uuid, writer: W) -> Result<W, ExportError>
+where
+    C: ConnectionTrait,
+    W: AsyncWrite + Unpin,
+{
+    let mut zip = ZipFileWriter::with_tokio(writer);
+
+    let manifest = json!({
+        "user_id": user_id,
+        "created_at": Utc::now().naive_utc(),
+        "domains": ExportDomain::ALL.map(|domain| format!("{}.json", domain.name())),
+    });
+    write_entry(&mut zip, "manifest.json", &manifest).await?;
+
+    for domain in ExportDomain::ALL {
+        let data = Query::load(conn, user_id, domain).await?;
+        write_entry(&mut zip, &format!("{}.json", domain.name()), &data).await?;
+    }
+
+    Ok(zip.close().await?.into_inner())
+}
+
+async fn write_entry<W: AsyncWrite + Unpin>(
+    zip: &mut ZipFileWriter<W>,
+    name: &str,
+    data: &serde_json::Value,
+) -> Result<(), ExportError> {
+    let content = serde_json::to_vec_pretty(data)?;
+    let entry = ZipEntryBuilder::new(name.to_owned().into(), Compression::Deflate);
+    zip.write_entry_whole(entry, &content).await?;
+    Ok(())
+}
+
+/// Creates an export job which is processed by the next free export job processor.
+///
+/// Fails with [`ExportError::TooManyJobs`] while the user has [`MAX_ACTIVE_JOBS`] queued or running jobs.
+pub async fn request_export(conn: &DatabaseConnection, user_id: Uuid) -> Result<ExportJob, ExportError> {
+    conn.transaction::<_, ExportJob, ExportError>(|txn| {
+        Box::pin(async move {
+            if job::Query::count_active(txn, user_id).await? >= MAX_ACTIVE_JOBS {
+                return Err(ExportError::TooManyJobs);
+            }
+            let expires_at = Utc::now().naive_utc() + EXPORT_RETENTION;
+            Ok(job::Mutation::create(txn, user_id, expires_at).await?.into())
+        })
+    })
+    .await
+    .flatten_res()
+}
+
+pub async fn get_export_job<C: ConnectionTrait>(
+    conn: &C,
+    user_id: Uuid,
+    job_id: Uuid,
+) -> Result<Option<ExportJob>, DbErr> {
+    Ok(job::Query::get_user_job(conn, user_id, job_id).await?.map(Into::into))
+}
+
+/// Opens the archive of a completed export job for download.
+pub async fn open_export_archive<C: ConnectionTrait>(
+    conn: &C,
+    storage: &Loader,
+    user_id: Uuid,
+    job_id: Uuid,
+) -> Result<Option<FileReader>, ExportError> {
+    let Some(job) = job::Query::get_user_job(conn, user_id, job_id).await? else {
+        return Ok(None);
+    };
+    match job.archive_key {
+        Some(key) if job.status == JobStatus::Completed => Ok(Some(storage.open_file(key).await?)),
+        _ => Ok(None),
+    }
+}
+
+/// Claims the job and stores its archive in the export storage.
+///
+/// Returns `false` if the job is finished or already processed by another instance.
+#[instrument(skip(conn, storage), err)]
+pub async fn process_export_job<C: ConnectionTrait>(
+    conn: &C,
+    storage: &Loader,
+    job_id: Uuid,
+) -> Result<bool, ExportError> {
+    let owner = INSTANCE_ID.as_str();
+    let lease_expires_at = (Utc::now() + LEASE_DURATION).naive_utc();
+    let Some(job) = job::Mutation::claim(conn, job_id, owner, lease_expires_at).await? else {
+        tracing::debug!(%job_id, "export job is not claimable");
+        return Ok(false);
+    };
+    tracing::info!(%job_id, user_id = %job.user_id, attempt = job.attempts, "processing export job");
+
+    match store_archive(conn, storage, &job).await {
+        Ok(archive_key) => {
+            if !job::Mutation::complete(conn, job_id, owner, &archive_key).await? {
+                tracing::warn!(%job_id, "export job lease was lost before completion");
+            }
+        }
+        Err(error) => {
+            let retry = job.attempts < MAX_ATTEMPTS && error.is_retryable();
+            tracing::error!(error = &error as &dyn Error, %job_id, retry, "export job failed");
+            job::Mutation::fail(conn, job_id, owner, &error.to_string(), retry).await?;
+        }
+    }
+    Ok(true)
+}
+
+/// Builds the archive in a temporary file, so it is never held in memory, and uploads it to the export storage.
+async fn store_archive<C: ConnectionTrait>(conn: &C, storage: &Loader, job: &JobModel) -> Result<String, ExportError> {
+    let archive_key = archive_key(job);
+    let path = std::env::temp_dir().join(format!("hikari-export-{}.zip", job.id));
+
+    let result: Result<String, ExportError> = async {
+        let file = tokio::fs::File::create(&path).await?;
+        let mut file = write_archive(conn, job.user_id, file).await?;
+        file.flush().await?;
+        storage.store_file(&archive_key, &path).await?;
+        Ok(archive_key)
+    }
+    .await;
+
+    if let Err(error) = tokio::fs::remove_file(&path).await
+        && error.kind() != std::io::ErrorKind::NotFound
+    {
+        tracing::warn!(
+            error = &error as &dyn Error,
+            ?path,
+            "failed to remove temporary export archive"
+        );
+    }
+    result
+}
+
+fn archive_key(job: &JobModel) -> String {
+    format!("{}/{}.zip", job.user_id, job.id)
+}
+
+/// Claims and processes the oldest claimable job.
+///
+/// Returns `false` if there was nothing to do.
+pub async fn process_next_export_job<C: ConnectionTrait>(conn: &C, storage: &Loader) -> Result<bool, ExportError> {
+    let jobs = job::Query::find_claimable(conn, Utc::now().naive_utc(), CLAIM_BATCH_SIZE).await?;
+    for job in jobs {
+        if process_export_job(conn, storage, job.id).await? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Processes export jobs until the task is dropped, waiting `poll_interval` whenever the queue is empty.
+pub async fn run_export_job_processor<C: ConnectionTrait>(conn: C, storage: Loader, poll_interval: Duration) {
+    tracing::info!(instance = %*INSTANCE_ID, "starting export job processor");
+    loop {
+        match process_next_export_job(&conn, &storage).await {
+            Ok(true) => {}
+            Ok(false) => tokio::time::sleep(poll_interval).await,
+            Err(error) => {
+                tracing::error!(error = &error as &dyn Error, "failed to process export jobs");
+                tokio::time::sleep(poll_interval).await;
+            }
+        }
+    }
+}
+
+/// Deletes expired jobs together with their archives and returns how many were removed.
+///
+/// Jobs whose archive could not be deleted are kept, so the deletion is retried on the next run.
+pub async fn delete_expired_exports<C: ConnectionTrait>(
+    conn: &C,
+    storage: &Loader,
+    now: NaiveDateTime,
+) -> Result<u64, ExportError> {
+    let jobs = job::Query::find_expired(conn, now).await?;
+    delete_jobs(conn, storage, jobs).await
+}
+
+/// Deletes all export jobs of the user together with their archives.
+pub async fn delete_user_exports<C: ConnectionTrait>(
+    conn: &C,
+    storage: &Loader,
+    user_id: Uuid,
+) -> Result<u64, ExportError> {
+    let jobs = job::Query::find_by_user(conn, user_id).await?;
+    delete_jobs(conn, storage, jobs).await
+}
+
+async fn delete_jobs<C: ConnectionTrait>(conn: &C, storage: &Loader, jobs: Vec<JobModel>) -> Result<u64, ExportError> {
+    let mut deleted = Vec::with_capacity(jobs.len());
+    for job in jobs {
+        if let Some(key) = &job.archive_key
+            && let Err(error) = storage.delete_file(key).await
+        {
+            tracing::error!(error = &error as &dyn Error, job_id = %job.id, "failed to delete export archive");
+            continue;
+        }
+        deleted.push(job.id);
+    }
+    if deleted.is_empty() {
+        return Ok(0);
+    }
+    Ok(job::Mutation::delete(conn, deleted).await?)
+}
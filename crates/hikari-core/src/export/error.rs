@@ -0,0 +1,33 @@
+use async_zip::error::ZipError;
+use hikari_utils::loader::error::LoadingError;
+use sea_orm::DbErr;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error(transparent)]
+    DbError(#[from] DbErr),
+
+    #[error(transparent)]
+    Zip(#[from] ZipError),
+
+    #[error(transparent)]
+    Serialize(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Storage(#[from] LoadingError),
+
+    #[error("Too many export jobs are running")]
+    TooManyJobs,
+}
+
+impl ExportError {
+    /// Whether another attempt of the job may succeed.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        !matches!(self, Self::Serialize(_) | Self::TooManyJobs)
+    }
+}
@@ -0,0 +1,5 @@
+use sea_orm::prelude::Uuid;
+use std::sync::LazyLock;
+
+/// Identifies this process as owner of job leases, see [`hikari_db::util::lease`].
+pub static INSTANCE_ID: LazyLock<String> = LazyLock::new(|| Uuid::new_v4().to_string());
@@ -1,2 +1,3 @@
 pub mod assistant;
+pub mod insights;
 pub mod summarize;
@@ -0,0 +1,489 @@
+use chrono::{DateTime, Datelike, Days, FixedOffset, Months, NaiveDate, TimeZone};
+use hikari_db::journal::{journal_entry, journal_entry_journal_focus, journal_summary};
+use hikari_entity::journal::journal_entry::Model as JournalEntryModel;
+use hikari_entity::journal::journal_summary::Model as JournalSummaryModel;
+use hikari_entity::journal::journal_topic::Model as JournalTopicModel;
+use hikari_entity::tag::Model as TagModel;
+use hikari_model::journal::insights::{
+    FocusMoodCorrelation, InsightsGranularity, JournalInsights, MoodInsights, MoodTrendPoint, RecurringTopic,
+    StreakInsights,
+};
+use hikari_model_tools::convert::FromDbModel;
+use sea_orm::prelude::Uuid;
+use sea_orm::{ConnectionTrait, DbErr};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+#[derive(Debug, Clone)]
+pub struct InsightsOptions {
+    pub from: DateTime<FixedOffset>,
+    /// End of the window (exclusive). Its offset is used as time zone to assign entries to days.
+    pub to: DateTime<FixedOffset>,
+    pub granularity: InsightsGranularity,
+    pub moving_average_periods: u32,
+    pub min_topic_occurrences: u32,
+}
+
+/// Computes mood, focus, topic and streak insights over the journal of the user.
+pub async fn get_insights<C: ConnectionTrait>(
+    conn: &C,
+    user_id: Uuid,
+    options: &InsightsOptions,
+    today: NaiveDate,
+) -> Result<JournalInsights, DbErr> {
+    let entries =
+        journal_entry::Query::get_user_journal_entries_between(conn, user_id, Some(options.from), Some(options.to))
+            .await?;
+    let focus = journal_entry_journal_focus::Query::get_journal_entries_focus(
+        conn,
+        entries.iter().map(|entry| entry.id).collect(),
+    )
+    .await?;
+    let summaries = journal_summary::Query::get_user_summaries_between(
+        conn,
+        user_id,
+        Some(options.from.naive_utc()),
+        Some(options.to.naive_utc()),
+    )
+    .await?;
+
+    Ok(compute_insights(&entries, focus, &summaries, options, today))
+}
+
+fn compute_insights(
+    entries: &[JournalEntryModel],
+    focus: Vec<(Uuid, TagModel)>,
+    summaries: &[(JournalSummaryModel, Vec<JournalTopicModel>)],
+    options: &InsightsOptions,
+    today: NaiveDate,
+) -> JournalInsights {
+    let offset = *options.to.offset();
+    let moods: Vec<_> = entries
+        .iter()
+        .filter_map(|entry| entry.mood.map(|mood| (entry.created_at.with_timezone(&offset), mood)))
+        .collect();
+
+    let mood = MoodInsights {
+        average: mean(moods.iter().map(|(_, mood)| *mood)),
+        slope_per_week: mood_slope_per_week(&moods),
+        trend: mood_trend(
+            &moods,
+            options.from.with_timezone(&offset).date_naive(),
+            options.to.date_naive(),
+            options.granularity,
+            options.moving_average_periods,
+        ),
+    };
+
+    let days: BTreeSet<_> = entries
+        .iter()
+        .map(|entry| entry.created_at.with_timezone(&offset).date_naive())
+        .collect();
+
+    JournalInsights {
+        from: options.from,
+        to: options.to,
+        granularity: options.granularity,
+        entry_count: u32::try_from(entries.len()).unwrap_or(u32::MAX),
+        mood,
+        focus: focus_correlations(entries, focus),
+        topics: recurring_topics(summaries, offset, options.min_topic_occurrences),
+        streaks: streaks(&days, today),
+    }
+}
+
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+fn mean(values: impl Iterator<Item = f32>) -> Option<f32> {
+    let (sum, count) = values.fold((0.0f64, 0usize), |(sum, count), value| {
+        (sum + f64::from(value), count + 1)
+    });
+    (count > 0).then(|| (sum / count as f64) as f32)
+}
+
+fn period_start(date: NaiveDate, granularity: InsightsGranularity) -> NaiveDate {
+    match granularity {
+        InsightsGranularity::Day => date,
+        InsightsGranularity::Week => date
+            .checked_sub_days(Days::new(u64::from(date.weekday().num_days_from_monday())))
+            .unwrap_or(date),
+        InsightsGranularity::Month => date.with_day(1).unwrap_or(date),
+    }
+}
+
+fn next_period(start: NaiveDate, granularity: InsightsGranularity) -> Option<NaiveDate> {
+    match granularity {
+        InsightsGranularity::Day => start.checked_add_days(Days::new(1)),
+        InsightsGranularity::Week => start.checked_add_days(Days::new(7)),
+        InsightsGranularity::Month => start.checked_add_months(Months::new(1)),
+    }
+}
+
+/// Groups the moods into consecutive periods from `from` to `to`, including periods without entries.
+fn mood_trend(
+    moods: &[(DateTime<FixedOffset>, f32)],
+    from: NaiveDate,
+    to: NaiveDate,
+    granularity: InsightsGranularity,
+    moving_average_periods: u32,
+) -> Vec<MoodTrendPoint> {
+    let mut periods: HashMap<NaiveDate, Vec<f32>> = HashMap::new();
+    for (time, mood) in moods {
+        periods
+            .entry(period_start(time.date_naive(), granularity))
+            .or_default()
+            .push(*mood);
+    }
+
+    let mut buckets = vec![];
+    let mut start = period_start(from, granularity);
+    while start <= to {
+        buckets.push((start, periods.remove(&start).unwrap_or_default()));
+        let Some(next) = next_period(start, granularity) else {
+            break;
+        };
+        start = next;
+    }
+
+    let window = usize::try_from(moving_average_periods.max(1)).unwrap_or(1);
+    buckets
+        .iter()
+        .enumerate()
+        .map(|(index, (period_start, moods))| {
+            let window_start = (index + 1).saturating_sub(window);
+            let moving_average = mean(
+                buckets
+                    .get(window_start..=index)
+                    .unwrap_or_default()
+                    .iter()
+                    .flat_map(|(_, moods)| moods.iter().copied()),
+            );
+            MoodTrendPoint {
+                period_start: *period_start,
+                entries: u32::try_from(moods.len()).unwrap_or(u32::MAX),
+                average: mean(moods.iter().copied()),
+                moving_average,
+            }
+        })
+        .collect()
+}
+
+/// Slope of a least squares fit of the mood over time, scaled to one week.
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+fn mood_slope_per_week(moods: &[(DateTime<FixedOffset>, f32)]) -> Option<f32> {
+    let (first, _) = moods.first()?;
+    let points: Vec<(f64, f64)> = moods
+        .iter()
+        .map(|(time, mood)| {
+            let days = (*time - *first).num_seconds() as f64 / 86_400.0;
+            (days, f64::from(*mood))
+        })
+        .collect();
+    let count = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / count;
+    let covariance: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    if variance <= f64::EPSILON {
+        return None;
+    }
+    Some((covariance / variance * 7.0) as f32)
+}
+
+/// Pearson correlation between having a focus and the mood of an entry.
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+fn focus_correlations(entries: &[JournalEntryModel], focus: Vec<(Uuid, TagModel)>) -> Vec<FocusMoodCorrelation> {
+    let moods: HashMap<Uuid, f32> = entries
+        .iter()
+        .filter_map(|entry| entry.mood.map(|mood| (entry.id, mood)))
+        .collect();
+
+    let mut tags: HashMap<Uuid, TagModel> = HashMap::new();
+    let mut tagged_entries: HashMap<Uuid, BTreeSet<Uuid>> = HashMap::new();
+    for (entry_id, tag) in focus {
+        tagged_entries.entry(tag.id).or_default().insert(entry_id);
+        tags.entry(tag.id).or_insert(tag);
+    }
+
+    let count = moods.len() as f64;
+    let mean_mood = moods.values().map(|mood| f64::from(*mood)).sum::<f64>() / count.max(1.0);
+    let mood_variance: f64 = moods.values().map(|mood| (f64::from(*mood) - mean_mood).powi(2)).sum();
+
+    let mut correlations: Vec<_> = tags
+        .into_values()
+        .map(|tag| {
+            let tagged = tagged_entries.get(&tag.id);
+            let is_tagged = |entry_id: &Uuid| tagged.is_some_and(|tagged| tagged.contains(entry_id));
+            let with: Vec<_> = moods
+                .iter()
+                .filter(|(id, _)| is_tagged(id))
+                .map(|(_, mood)| *mood)
+                .collect();
+            let without = moods.iter().filter(|(id, _)| !is_tagged(id)).map(|(_, mood)| *mood);
+
+            let share = with.len() as f64 / count.max(1.0);
+            let covariance: f64 = moods
+                .iter()
+                .map(|(id, mood)| {
+                    let indicator = if is_tagged(id) { 1.0 } else { 0.0 };
+                    (indicator - share) * (f64::from(*mood) - mean_mood)
+                })
+                .sum();
+            let indicator_variance = count * share * (1.0 - share);
+            let correlation = (indicator_variance > f64::EPSILON && mood_variance > f64::EPSILON)
+                .then(|| (covariance / (indicator_variance * mood_variance).sqrt()) as f32);
+
+            FocusMoodCorrelation {
+                focus: FromDbModel::from_db_model(tag),
+                entries: u32::try_from(with.len()).unwrap_or(u32::MAX),
+                average_mood_with: mean(with.iter().copied()),
+                average_mood_without: mean(without),
+                correlation,
+            }
+        })
+        .collect();
+    correlations.sort_by(|a, b| b.entries.cmp(&a.entries).then_with(|| a.focus.name.cmp(&b.focus.name)));
+    correlations
+}
+
+/// Groups topics of the summaries by their normalized name and keeps those occurring at least `min_occurrences` times.
+fn recurring_topics(
+    summaries: &[(JournalSummaryModel, Vec<JournalTopicModel>)],
+    offset: FixedOffset,
+    min_occurrences: u32,
+) -> Vec<RecurringTopic> {
+    let mut topics: HashMap<String, RecurringTopic> = HashMap::new();
+    // Summaries are sorted by creation time, so later occurrences overwrite the name.
+    for (summary, summary_topics) in summaries {
+        let seen = offset.from_utc_datetime(&summary.created_at);
+        let names: BTreeMap<_, _> = summary_topics
+            .iter()
+            .map(|topic| (topic.topic.trim().to_lowercase(), topic.topic.trim()))
+            .collect();
+        for (key, name) in names {
+            topics
+                .entry(key)
+                .and_modify(|topic| {
+                    topic.occurrences += 1;
+                    name.clone_into(&mut topic.topic);
+                    topic.last_seen = seen;
+                })
+                .or_insert_with(|| RecurringTopic {
+                    topic: name.to_owned(),
+                    occurrences: 1,
+                    first_seen: seen,
+                    last_seen: seen,
+                });
+        }
+    }
+
+    let mut topics: Vec<_> = topics
+        .into_values()
+        .filter(|topic| topic.occurrences >= min_occurrences)
+        .collect();
+    topics.sort_by(|a, b| {
+        b.occurrences
+            .cmp(&a.occurrences)
+            .then_with(|| b.last_seen.cmp(&a.last_seen))
+    });
+    topics
+}
+
+fn streaks(days: &BTreeSet<NaiveDate>, today: NaiveDate) -> StreakInsights {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for day in days {
+        run = match previous.and_then(|previous| previous.succ_opt()) {
+            Some(expected) if expected == *day => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(*day);
+    }
+
+    // The streak is still active if there is an entry today or yesterday.
+    let mut current = 0;
+    let mut day = if days.contains(&today) {
+        Some(today)
+    } else {
+        today.pred_opt()
+    };
+    while let Some(current_day) = day.filter(|day| days.contains(day)) {
+        current += 1;
+        day = current_day.pred_opt();
+    }
+
+    StreakInsights {
+        current,
+        longest,
+        active_days: u32::try_from(days.len()).unwrap_or(u32::MAX),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hikari_entity::tag::Kind;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn time(value: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(value).unwrap()
+    }
+
+    fn entry(created_at: &str, mood: Option<f32>) -> JournalEntryModel {
+        JournalEntryModel {
+            id: Uuid::new_v4(),
+            user_id: Uuid::nil(),
+            title: None,
+            mood,
+            created_at: time(created_at),
+            updated_at: time(created_at),
+        }
+    }
+
+    fn focus(name: &str) -> TagModel {
+        TagModel {
+            id: Uuid::new_v4(),
+            kind: Kind::Focus,
+            user_id: None,
+            name: name.to_owned(),
+            icon: String::new(),
+            hidden: false,
+        }
+    }
+
+    #[test]
+    fn test_period_start() {
+        // 2024-05-15 is a Wednesday
+        assert_eq!(
+            period_start(date("2024-05-15"), InsightsGranularity::Day),
+            date("2024-05-15")
+        );
+        assert_eq!(
+            period_start(date("2024-05-15"), InsightsGranularity::Week),
+            date("2024-05-13")
+        );
+        assert_eq!(
+            period_start(date("2024-05-15"), InsightsGranularity::Month),
+            date("2024-05-01")
+        );
+    }
+
+    #[test]
+    fn test_mood_trend() {
+        let moods = vec![
+            (time("2024-05-13T10:00:00+02:00"), 0.25),
+            (time("2024-05-14T10:00:00+02:00"), 0.75),
+            (time("2024-05-28T10:00:00+02:00"), 1.0),
+        ];
+        let trend = mood_trend(
+            &moods,
+            date("2024-05-13"),
+            date("2024-05-31"),
+            InsightsGranularity::Week,
+            2,
+        );
+        assert_eq!(trend.len(), 3);
+        assert_eq!(trend[0].average, Some(0.5));
+        assert_eq!(trend[0].entries, 2);
+        assert_eq!(trend[1].average, None);
+        assert_eq!(trend[1].moving_average, Some(0.5));
+        assert_eq!(trend[2].period_start, date("2024-05-27"));
+        assert_eq!(trend[2].moving_average, Some(1.0));
+    }
+
+    #[test]
+    fn test_mood_slope() {
+        let moods = vec![
+            (time("2024-05-01T10:00:00+00:00"), 0.0),
+            (time("2024-05-08T10:00:00+00:00"), 0.25),
+            (time("2024-05-15T10:00:00+00:00"), 0.5),
+        ];
+        let slope = mood_slope_per_week(&moods).unwrap();
+        assert!((slope - 0.25).abs() < 1e-6);
+        assert_eq!(mood_slope_per_week(&moods[..1]), None);
+    }
+
+    #[test]
+    fn test_focus_correlation() {
+        let entries = vec![
+            entry("2024-05-01T10:00:00+00:00", Some(1.0)),
+            entry("2024-05-02T10:00:00+00:00", Some(1.0)),
+            entry("2024-05-03T10:00:00+00:00", Some(0.0)),
+            entry("2024-05-04T10:00:00+00:00", Some(0.0)),
+        ];
+        let sport = focus("Sport");
+        let exam = focus("Exam");
+        let focus = vec![
+            (entries[0].id, sport.clone()),
+            (entries[1].id, sport),
+            (entries[2].id, exam.clone()),
+            (entries[3].id, exam),
+        ];
+        let correlations = focus_correlations(&entries, focus);
+        let sport = correlations.iter().find(|c| c.focus.name == "Sport").unwrap();
+        assert_eq!(sport.entries, 2);
+        assert_eq!(sport.average_mood_with, Some(1.0));
+        assert_eq!(sport.average_mood_without, Some(0.0));
+        assert!((sport.correlation.unwrap() - 1.0).abs() < 1e-6);
+        let exam = correlations.iter().find(|c| c.focus.name == "Exam").unwrap();
+        assert!((exam.correlation.unwrap() + 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_recurring_topics() {
+        let summary = |created_at: &str, topics: &[&str]| {
+            let id = Uuid::new_v4();
+            (
+                JournalSummaryModel {
+                    id,
+                    user_id: Uuid::nil(),
+                    key: vec![],
+                    created_at: time(created_at).naive_utc(),
+                    summary: String::new(),
+                },
+                topics
+                    .iter()
+                    .map(|topic| JournalTopicModel {
+                        id: Uuid::new_v4(),
+                        journal_summary_id: id,
+                        topic: (*topic).to_owned(),
+                        summary: String::new(),
+                    })
+                    .collect(),
+            )
+        };
+        let summaries = vec![
+            summary("2024-05-01T10:00:00+00:00", &["stress", "Freunde"]),
+            summary("2024-05-05T10:00:00+00:00", &["Stress ", "Sport"]),
+            summary("2024-05-09T10:00:00+00:00", &["Stress"]),
+        ];
+        let topics = recurring_topics(&summaries, FixedOffset::east_opt(0).unwrap(), 2);
+        assert_eq!(topics.len(), 1);
+        assert_eq!(topics[0].topic, "Stress");
+        assert_eq!(topics[0].occurrences, 3);
+        assert_eq!(topics[0].first_seen, time("2024-05-01T10:00:00+00:00"));
+        assert_eq!(topics[0].last_seen, time("2024-05-09T10:00:00+00:00"));
+    }
+
+    #[test]
+    fn test_streaks() {
+        let days: BTreeSet<_> = ["2024-05-01", "2024-05-02", "2024-05-03", "2024-05-07", "2024-05-08"]
+            .into_iter()
+            .map(date)
+            .collect();
+        let res = streaks(&days, date("2024-05-09"));
+        assert_eq!(
+            res,
+            StreakInsights {
+                current: 2,
+                longest: 3,
+                active_days: 5,
+            }
+        );
+        assert_eq!(streaks(&days, date("2024-05-10")).current, 0);
+        assert_eq!(streaks(&BTreeSet::new(), date("2024-05-10")), StreakInsights::default());
+    }
+}
@@ -1,5 +1,7 @@
 pub mod error;
+pub mod job;
 use crate::journal::summarize::error::SummarizeError;
+use crate::journal::summarize::job::{JobAttempt, SummaryJobStatus};
 use crate::llm_config::LlmConfig;
 use crate::openai::error::OpenAiError;
 use crate::openai::{CallConfig, openai_single_tool_call};
@@ -8,23 +10,19 @@ use async_openai::types::chat::{
     ChatCompletionRequestMessage, ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
 };
 use base64::Engine;
-use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone, Utc};
-use hikari_db::journal;
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone};
 use hikari_db::journal::journal_summary;
 use hikari_model::journal::MetaJournalEntryWithMetaContent;
-use hikari_utils::date::get_day_bounds;
 use schemars::JsonSchema;
 use sea_orm::prelude::Uuid;
 use sea_orm::{ConnectionTrait, TransactionTrait};
 use serde_derive::{Deserialize, Serialize};
 use sha2::Digest;
 use sha2::Sha256;
-use std::collections::HashMap;
 use std::error::Error;
 use std::ops::Add;
-use std::sync::{Arc, LazyLock};
+use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::Mutex;
 use tracing::{Instrument, instrument};
 use utoipa::ToSchema;
 
@@ -103,120 +101,60 @@ pub struct SummaryResponse {
     pub summary: Option<Summary>,
     pub journal_entries: Vec<MetaJournalEntryWithMetaContent>,
 }
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
-struct Key {
-    key: [u8; 32],
-    date: NaiveDateTime,
-}
-
-#[allow(clippy::type_complexity)]
-static LOADING_SUMMARIES: LazyLock<Mutex<HashMap<Key, Arc<Mutex<Option<SummaryResponse>>>>>> =
-    LazyLock::new(|| Mutex::new(HashMap::new()));
-
+/// Number of latest journal entries a summary is created for
+const SUMMARY_ENTRY_LIMIT: u64 = 5;
+/// How long a direct summarize call waits for a job processed by another instance
+const SUMMARY_WAIT_TIMEOUT: Duration = Duration::from_mins(2);
+
+/// Creates a summary and waits for the result.
+///
+/// Summaries are generated through persisted jobs, so concurrent requests for the same entries share one job
+/// regardless of the instance they arrive at. If the job is not processed by another instance yet we process it
+/// ourselves.
 #[instrument(skip_all, err)]
-pub async fn summarize<C: ConnectionTrait + TransactionTrait + Send + Clone + 'static>(
+pub async fn summarize<C: ConnectionTrait + TransactionTrait + Send + Sync + Clone + 'static>(
     conn: C,
     user_id: Uuid,
     llm_config: Arc<LlmConfig>,
     timestamp: Option<DateTime<FixedOffset>>,
 ) -> Result<SummaryResponse, SummarizeError> {
     tracing::info!(%user_id, timestamp = timestamp.as_ref().map_or_else(|| "NONE".to_owned(), DateTime::to_rfc2822), "creating summary");
-    let journal_entries_full =
-        journal::journal_entry::Query::get_user_journal_full(&conn.clone(), user_id, Some(5)).await?;
-
-    if journal_entries_full.is_empty() {
-        return Ok(SummaryResponse {
-            summary: None,
-            journal_entries: vec![],
-        });
-    }
-
-    let entry_ids: Vec<_> = journal_entries_full.iter().map(|(entry, ..)| entry.id).collect();
-
-    let timestamp = timestamp.unwrap_or_else(|| Utc::now().fixed_offset());
-    let (from_date, to_date) = get_day_bounds(timestamp)?;
-    let key = generate_key(&entry_ids);
-    let map_key = Key { key, date: from_date };
-    let str_key = base64::engine::general_purpose::STANDARD.encode(key);
-
-    let running: Option<Arc<Mutex<Option<SummaryResponse>>>> = {
-        let loading = LOADING_SUMMARIES
-            .lock()
-            .instrument(tracing::info_span!("acquire_loading_lock"))
-            .await;
-        loading.get(&map_key).cloned()
-        // Make sure we unlock the hashmap
+    let requested = job::request_summary(&conn, user_id, timestamp).await?;
+    let Some(job_id) = requested.id else {
+        return requested.summary.ok_or(SummarizeError::EmptyResponse);
     };
-    if let Some(running) = running {
-        tracing::debug!(%user_id, key = %str_key, "attaching to running summary");
-        let summary = running
-            .lock()
-            .instrument(tracing::info_span!("acquire_running_lock"))
-            .await;
-        if let Some(summary) = summary.as_ref() {
-            tracing::debug!(%user_id, key = %str_key, "returning shared summary");
-            return Ok(summary.clone());
-        }
-        tracing::debug!(%user_id, key = %str_key, "Got summary lock without a result");
-    }
 
-    // Have to clone the key because we need to move it into the task
-    let str_key_clone = str_key.clone();
-    let summary_task = tokio::task::spawn(
-        async move {
-            let summary = {
-                let loading_mutex = Arc::new(Mutex::new(None));
-                // We lock the mutex before inserting it to make sure we hold the lock until we can insert a result.
-                let mut loading_mutex_value = loading_mutex.lock().await;
-                {
-                    LOADING_SUMMARIES
-                        .lock()
-                        .await
-                        .insert(map_key.clone(), Arc::clone(&loading_mutex));
-                    // Make sure the hashmap is unlocked
-                }
-                let summary = generate_summary(
-                    &conn,
-                    &llm_config,
-                    journal_entries_full,
-                    user_id,
-                    &key,
-                    timestamp,
-                    from_date,
-                    to_date,
-                )
-                .await?;
-                tracing::debug!(%user_id, key = %str_key_clone, "storing summary result in shared map");
-                *loading_mutex_value = Some(summary.clone());
-                // Unlock the loading map value
-                summary
-            };
-            {
-                let mut map_guard = LOADING_SUMMARIES
-                    .lock()
-                    .instrument(tracing::info_span!("acquire_loading_lock"))
-                    .await;
-                map_guard.remove(&map_key);
-                tracing::debug!(%user_id, key = %str_key_clone, remaining = map_guard.len(), "cleaning up shared map");
-                // Make sure the hashmap is unlocked
-            }
-            Result::<_, SummarizeError>::Ok(summary)
-        }
-        .instrument(tracing::info_span!("generate_summary_task")),
-    );
-    let summary = summary_task
-        .await
-        .map_err(|error| {
+    if requested.status == SummaryJobStatus::Pending {
+        // Processing is spawned so the job is not abandoned if the caller goes away.
+        let task_conn = conn.clone();
+        let processing = tokio::task::spawn(
+            async move { job::process_summary_job(&task_conn, &llm_config, job_id).await }
+                .instrument(tracing::info_span!("process_summary_job_task")),
+        );
+        let attempt = processing.await.map_err(|error| {
             tracing::error!(error = &error as &dyn Error, "summary task failed");
             SummarizeError::Other(error.to_string())
-        })?
-        .map_err(|error| {
-            tracing::error!(error = &error as &dyn Error, "failed to generate summary");
-            SummarizeError::Other(error.to_string())
-        })?;
+        })??;
+        // A failed job may be retried in the background, but this request reports the error right away
+        if let JobAttempt::Failed(error) = attempt {
+            return Err(error);
+        }
+    }
 
-    tracing::debug!(%user_id, key = %str_key, "returning summary");
-    Ok(summary)
+    tracing::debug!(%user_id, %job_id, "waiting for summary job");
+    let job = job::wait_for_summary_job(&conn, user_id, job_id, SUMMARY_WAIT_TIMEOUT)
+        .await?
+        .ok_or_else(|| SummarizeError::Other("summary job vanished".to_owned()))?;
+    match job.status {
+        SummaryJobStatus::Completed => job.summary.ok_or(SummarizeError::EmptyResponse),
+        SummaryJobStatus::Failed => Err(SummarizeError::Other(
+            job.error.unwrap_or_else(|| "summary job failed".to_owned()),
+        )),
+        // An attempt failed on another instance, the job waits for its retry
+        SummaryJobStatus::Pending | SummaryJobStatus::Running => {
+            Err(job.error.map_or(SummarizeError::Timeout, SummarizeError::Other))
+        }
+    }
 }
 
 type SummaryData = (
@@ -237,17 +175,20 @@ async fn generate_summary<C: ConnectionTrait + TransactionTrait>(
     timestamp: DateTime<FixedOffset>,
     from_date: NaiveDateTime,
     to_date: NaiveDateTime,
-) -> Result<SummaryResponse, SummarizeError> {
+) -> Result<(Uuid, SummaryResponse), SummarizeError> {
     let str_key = base64::engine::general_purpose::STANDARD.encode(key);
     if let Some((summary, topic_summaries)) =
         journal_summary::Query::find(conn, user_id, key, from_date, to_date).await?
     {
         tracing::debug!(%user_id, timestamp = timestamp.to_rfc2822(), key = %str_key, "returning saved journal summary");
-        return Ok(generate_summary_response(
-            user_id,
-            journal_entries_full.into_iter().map(|(entry, ..)| entry).collect(),
-            summary,
-            topic_summaries,
+        return Ok((
+            summary.id,
+            generate_summary_response(
+                user_id,
+                journal_entries_full.into_iter().map(|(entry, ..)| entry).collect(),
+                summary,
+                topic_summaries,
+            ),
         ));
     }
 
@@ -371,6 +312,7 @@ Verwende Valides JSON als Argumente für den Funktionsaufruf.
         CallConfig::builder()
             .total_timeout(Duration::from_mins(2))
             .iteration_timeout(Duration::from_secs(30))
+            .user(user_id.to_string())
             .build(),
         openai_config,
         None,
@@ -402,11 +344,9 @@ Verwende Valides JSON als Argumente für den Funktionsaufruf.
     )
     .await?;
     tracing::debug!(%user_id, timestamp = timestamp.to_rfc2822(), key = %str_key, "summary created successfully");
-    Ok(generate_summary_response(
-        user_id,
-        journal_entries,
-        summary,
-        topic_summaries,
+    Ok((
+        summary.id,
+        generate_summary_response(user_id, journal_entries, summary, topic_summaries),
     ))
 }
 
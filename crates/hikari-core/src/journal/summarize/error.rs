@@ -31,4 +31,15 @@ pub enum SummarizeError {
 
     #[error("Unexpected response format")]
     UnexpectedResponseFormat,
+
+    #[error("The journal entries changed since the summary was requested")]
+    EntriesChanged,
+}
+
+impl SummarizeError {
+    /// Whether another attempt of the summary job may succeed
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        !matches!(self, SummarizeError::EntriesChanged)
+    }
 }
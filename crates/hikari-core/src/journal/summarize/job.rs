@@ -0,0 +1,311 @@
+use crate::jobs::INSTANCE_ID;
+use crate::journal::summarize::error::SummarizeError;
+use crate::journal::summarize::{
+    SUMMARY_ENTRY_LIMIT, SummaryResponse, generate_key, generate_summary, generate_summary_response,
+};
+use crate::llm_config::LlmConfig;
+use chrono::{DateTime, Duration as ChronoDuration, FixedOffset, TimeZone, Utc};
+use hikari_db::journal;
+use hikari_db::journal::journal_summary;
+use hikari_db::journal::journal_summary_job;
+use hikari_entity::journal::journal_summary_job::{Model as JobModel, Status as JobStatus};
+use hikari_utils::date::get_day_bounds;
+use sea_orm::prelude::Uuid;
+use sea_orm::{ConnectionTrait, TransactionTrait};
+use serde_derive::{Deserialize, Serialize};
+use std::error::Error;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::instrument;
+use utoipa::ToSchema;
+
+/// How long a claimed job is reserved for one instance before others may take it over.
+const LEASE_DURATION: ChronoDuration = ChronoDuration::minutes(5);
+/// Jobs which failed this often are not retried automatically.
+const MAX_ATTEMPTS: i32 = 3;
+/// Number of claimable jobs loaded at once by the job processor.
+const CLAIM_BATCH_SIZE: u64 = 10;
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryJobStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl From<JobStatus> for SummaryJobStatus {
+    fn from(status: JobStatus) -> Self {
+        match status {
+            JobStatus::Pending => Self::Pending,
+            JobStatus::Running => Self::Running,
+            JobStatus::Completed => Self::Completed,
+            JobStatus::Failed => Self::Failed,
+        }
+    }
+}
+
+impl SummaryJobStatus {
+    #[must_use]
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed)
+    }
+}
+
+#[derive(Debug, Clone, ToSchema, Serialize, Deserialize)]
+pub struct SummaryJob {
+    /// Not set if the request could be answered without creating a job
+    pub id: Option<Uuid>,
+    pub status: SummaryJobStatus,
+    pub summary: Option<SummaryResponse>,
+    pub error: Option<String>,
+}
+
+impl SummaryJob {
+    fn finished(summary: SummaryResponse) -> Self {
+        Self {
+            id: None,
+            status: SummaryJobStatus::Completed,
+            summary: Some(summary),
+            error: None,
+        }
+    }
+}
+
+/// Requests a summary of the latest journal entries of the user.
+///
+/// If the summary already exists it is returned right away. Otherwise a job is queued (or the already queued job
+/// for the same entries is reused) which can be processed by any instance.
+#[instrument(skip(conn), err)]
+pub async fn request_summary<C: ConnectionTrait>(
+    conn: &C,
+    user_id: Uuid,
+    timestamp: Option<DateTime<FixedOffset>>,
+) -> Result<SummaryJob, SummarizeError> {
+    let journal_entries_full =
+        journal::journal_entry::Query::get_user_journal_full(conn, user_id, Some(SUMMARY_ENTRY_LIMIT)).await?;
+
+    if journal_entries_full.is_empty() {
+        return Ok(SummaryJob::finished(SummaryResponse {
+            summary: None,
+            journal_entries: vec![],
+        }));
+    }
+
+    let entry_ids: Vec<_> = journal_entries_full.iter().map(|(entry, ..)| entry.id).collect();
+    let timestamp = timestamp.unwrap_or_else(|| Utc::now().fixed_offset());
+    let (from_date, to_date) = get_day_bounds(timestamp)?;
+    let key = generate_key(&entry_ids);
+
+    if let Some((summary, topic_summaries)) =
+        journal_summary::Query::find(conn, user_id, &key, from_date, to_date).await?
+    {
+        tracing::debug!(%user_id, "summary already exists");
+        return Ok(SummaryJob::finished(generate_summary_response(
+            user_id,
+            journal_entries_full.into_iter().map(|(entry, ..)| entry).collect(),
+            summary,
+            topic_summaries,
+        )));
+    }
+
+    let mut job = journal_summary_job::Mutation::get_or_create(
+        conn,
+        user_id,
+        &key,
+        from_date,
+        timestamp.naive_utc(),
+        timestamp.offset().local_minus_utc(),
+    )
+    .await?;
+
+    // A finished job without a summary either failed or its summary was deleted, so we try again.
+    if matches!(job.status, JobStatus::Completed | JobStatus::Failed) {
+        tracing::debug!(%user_id, job_id = %job.id, status = ?job.status, "requeueing summary job");
+        journal_summary_job::Mutation::requeue(conn, job.id).await?;
+        job = journal_summary_job::Query::get_user_job(conn, user_id, job.id)
+            .await?
+            .ok_or_else(|| SummarizeError::Other("summary job vanished".to_owned()))?;
+    }
+
+    job_response(conn, job).await
+}
+
+/// Loads the current state of a summary job of the user.
+pub async fn get_summary_job<C: ConnectionTrait>(
+    conn: &C,
+    user_id: Uuid,
+    job_id: Uuid,
+) -> Result<Option<SummaryJob>, SummarizeError> {
+    match journal_summary_job::Query::get_user_job(conn, user_id, job_id).await? {
+        Some(job) => Ok(Some(job_response(conn, job).await?)),
+        None => Ok(None),
+    }
+}
+
+/// Polls a summary job until it is finished, an attempt failed or `timeout` elapsed and returns its last state.
+pub async fn wait_for_summary_job<C: ConnectionTrait>(
+    conn: &C,
+    user_id: Uuid,
+    job_id: Uuid,
+    timeout: Duration,
+) -> Result<Option<SummaryJob>, SummarizeError> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let Some(job) = get_summary_job(conn, user_id, job_id).await? else {
+            return Ok(None);
+        };
+        let attempt_failed = job.status == SummaryJobStatus::Pending && job.error.is_some();
+        if job.status.is_finished() || attempt_failed || Instant::now() >= deadline {
+            return Ok(Some(job));
+        }
+        tokio::time::sleep(WAIT_POLL_INTERVAL.min(deadline.saturating_duration_since(Instant::now()))).await;
+    }
+}
+
+async fn job_response<C: ConnectionTrait>(conn: &C, job: JobModel) -> Result<SummaryJob, SummarizeError> {
+    let summary = match job.summary_id {
+        Some(summary_id) if job.status == JobStatus::Completed => {
+            match journal_summary::Query::find_by_id(conn, job.user_id, summary_id).await? {
+                Some((summary, topic_summaries)) => {
+                    let journal_entries = journal::journal_entry::Query::get_user_journal_full(
+                        conn,
+                        job.user_id,
+                        Some(SUMMARY_ENTRY_LIMIT),
+                    )
+                    .await?;
+                    Some(generate_summary_response(
+                        job.user_id,
+                        journal_entries.into_iter().map(|(entry, ..)| entry).collect(),
+                        summary,
+                        topic_summaries,
+                    ))
+                }
+                None => None,
+            }
+        }
+        _ => None,
+    };
+
+    Ok(SummaryJob {
+        id: Some(job.id),
+        status: job.status.into(),
+        summary,
+        error: job.error,
+    })
+}
+
+/// Outcome of an attempt to process a summary job
+#[derive(Debug)]
+pub enum JobAttempt {
+    /// The job is finished or already processed by another instance
+    NotClaimed,
+    Completed,
+    /// The attempt failed, the job is queued again if attempts are left
+    Failed(SummarizeError),
+}
+
+/// Claims the job and generates its summary.
+#[instrument(skip(conn, llm_config), err)]
+pub async fn process_summary_job<C: ConnectionTrait + TransactionTrait>(
+    conn: &C,
+    llm_config: &LlmConfig,
+    job_id: Uuid,
+) -> Result<JobAttempt, SummarizeError> {
+    let owner = INSTANCE_ID.as_str();
+    let lease_expires_at = (Utc::now() + LEASE_DURATION).naive_utc();
+    let Some(job) = journal_summary_job::Mutation::claim(conn, job_id, owner, lease_expires_at).await? else {
+        tracing::debug!(%job_id, "summary job is not claimable");
+        return Ok(JobAttempt::NotClaimed);
+    };
+    tracing::info!(%job_id, user_id = %job.user_id, attempt = job.attempts, "processing summary job");
+
+    match run_job(conn, llm_config, &job).await {
+        Ok(summary_id) => {
+            if !journal_summary_job::Mutation::complete(conn, job_id, owner, summary_id).await? {
+                tracing::warn!(%job_id, "summary job lease was lost before completion");
+            }
+            Ok(JobAttempt::Completed)
+        }
+        Err(error) => {
+            let retry = job.attempts < MAX_ATTEMPTS && error.is_retryable();
+            tracing::error!(error = &error as &dyn Error, %job_id, retry, "summary job failed");
+            journal_summary_job::Mutation::fail(conn, job_id, owner, &error.to_string(), retry).await?;
+            Ok(JobAttempt::Failed(error))
+        }
+    }
+}
+
+async fn run_job<C: ConnectionTrait + TransactionTrait>(
+    conn: &C,
+    llm_config: &LlmConfig,
+    job: &JobModel,
+) -> Result<Uuid, SummarizeError> {
+    let offset = FixedOffset::east_opt(job.utc_offset)
+        .ok_or_else(|| SummarizeError::Other(format!("invalid utc offset {}", job.utc_offset)))?;
+    let timestamp = offset.from_utc_datetime(&job.timestamp);
+    let (from_date, to_date) = get_day_bounds(timestamp)?;
+
+    let key = <[u8; 32]>::try_from(job.key.as_slice())
+        .map_err(|_| SummarizeError::Other(format!("invalid key of summary job {}", job.id)))?;
+
+    // The summary is stored under the key of the job, so it has to be created for the same entries. If they changed,
+    // a new request queues a job for the new entries.
+    let journal_entries_full =
+        journal::journal_entry::Query::get_user_journal_full(conn, job.user_id, Some(SUMMARY_ENTRY_LIMIT)).await?;
+    let entry_ids: Vec<_> = journal_entries_full.iter().map(|(entry, ..)| entry.id).collect();
+    if generate_key(&entry_ids) != key {
+        return Err(SummarizeError::EntriesChanged);
+    }
+
+    let (summary_id, _) = generate_summary(
+        conn,
+        llm_config,
+        journal_entries_full,
+        job.user_id,
+        &key,
+        timestamp,
+        from_date,
+        to_date,
+    )
+    .await?;
+    Ok(summary_id)
+}
+
+/// Claims and processes the oldest claimable job.
+///
+/// Returns `false` if there was nothing to do.
+pub async fn process_next_summary_job<C: ConnectionTrait + TransactionTrait>(
+    conn: &C,
+    llm_config: &LlmConfig,
+) -> Result<bool, SummarizeError> {
+    let jobs = journal_summary_job::Query::find_claimable(conn, Utc::now().naive_utc(), CLAIM_BATCH_SIZE).await?;
+    for job in jobs {
+        match process_summary_job(conn, llm_config, job.id).await? {
+            JobAttempt::NotClaimed => {}
+            JobAttempt::Completed | JobAttempt::Failed(_) => return Ok(true),
+        }
+    }
+    Ok(false)
+}
+
+/// Processes summary jobs until the task is dropped, waiting `poll_interval` whenever the queue is empty.
+pub async fn run_summary_job_processor<C: ConnectionTrait + TransactionTrait>(
+    conn: C,
+    llm_config: &LlmConfig,
+    poll_interval: Duration,
+) {
+    tracing::info!(instance = %*INSTANCE_ID, "starting summary job processor");
+    loop {
+        match process_next_summary_job(&conn, llm_config).await {
+            Ok(true) => {}
+            Ok(false) => tokio::time::sleep(poll_interval).await,
+            Err(error) => {
+                tracing::error!(error = &error as &dyn Error, "failed to process summary jobs");
+                tokio::time::sleep(poll_interval).await;
+            }
+        }
+    }
+}
@@ -1,3 +1,8 @@
+pub mod allocation;
+pub mod assessment;
+pub mod crisis;
+pub mod export;
+pub mod jobs;
 pub mod journal;
 pub mod llm_config;
 pub mod openai;
@@ -5,4 +10,5 @@ pub mod pgvector;
 pub mod planner;
 pub mod quiz;
 pub mod status;
+pub mod unlock;
 pub mod usage;
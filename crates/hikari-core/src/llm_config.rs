@@ -1,8 +1,16 @@
 use std::str::FromStr;
 
+use crate::openai::pool::ProviderLimits;
 use async_openai::config::OpenAIConfig;
+use hikari_config::global::llm::{LearnerMemoryConfig, LlmCallDefaults, ModerationConfig};
 use hikari_config::module::llm_agent::LlmService;
 use hikari_utils::args::llm::LlmServices as LlmServiceArgs;
+use std::time::Duration;
+
+const DEFAULT_TRANSCRIPTION_MODEL: &str = "whisper-1";
+const DEFAULT_SPEECH_MODEL: &str = "gpt-4o-mini-tts";
+const DEFAULT_SPEECH_VOICE: &str = "alloy";
+
 #[derive(Debug, Clone)]
 pub struct LlmServiceConfig {
     pub key: Option<String>,
@@ -24,6 +32,16 @@ pub struct LlmConfig {
     pub journaling_config: LlmFeatureConfig,
     pub quiz_config: LlmFeatureConfig,
     pub planner_config: LlmFeatureConfig,
+    pub transcription_config: LlmFeatureConfig,
+    pub speech_config: LlmFeatureConfig,
+    pub speech_voice: String,
+    pub provider_limits: ProviderLimits,
+    /// Defaults for the LLM calls of agent steps from the global config
+    pub step_defaults: LlmCallDefaults,
+    /// Long-term memory of learners from the global config
+    pub learner_memory: LearnerMemoryConfig,
+    /// Moderation of the messages of students and agents from the global config
+    pub moderation: ModerationConfig,
 }
 
 impl From<LlmServiceArgs> for LlmConfig {
@@ -48,6 +66,16 @@ impl From<LlmServiceArgs> for LlmConfig {
             .as_deref()
             .map(|s| LlmService::from_str(s).expect("Invalid planner_service string"));
 
+        let transcription_service = config
+            .transcription_service
+            .as_deref()
+            .map(|s| LlmService::from_str(s).expect("Invalid transcription_service string"));
+
+        let speech_service = config
+            .speech_service
+            .as_deref()
+            .map(|s| LlmService::from_str(s).expect("Invalid speech_service string"));
+
         Self {
             openai: LlmServiceConfig {
                 key: config.openai_key,
@@ -77,6 +105,25 @@ impl From<LlmServiceArgs> for LlmConfig {
                 service: planner_service,
                 model: config.planner_model,
             },
+            transcription_config: LlmFeatureConfig {
+                service: transcription_service,
+                model: config.transcription_model,
+            },
+            speech_config: LlmFeatureConfig {
+                service: speech_service,
+                model: config.speech_model,
+            },
+            speech_voice: config.speech_voice,
+            provider_limits: ProviderLimits {
+                max_concurrency: config.llm_max_concurrency,
+                requests_per_minute: config.llm_requests_per_minute,
+                tokens_per_minute: config.llm_tokens_per_minute,
+                failure_threshold: config.llm_circuit_breaker_failures,
+                cooldown: Duration::from_secs(config.llm_circuit_breaker_cooldown),
+            },
+            step_defaults: LlmCallDefaults::default(),
+            learner_memory: LearnerMemoryConfig::default(),
+            moderation: ModerationConfig::default(),
         }
     }
 }
@@ -100,9 +147,37 @@ impl LlmConfig {
             journaling_config: journaling,
             quiz_config: quiz,
             planner_config: planner,
+            transcription_config: LlmFeatureConfig {
+                service: None,
+                model: None,
+            },
+            speech_config: LlmFeatureConfig {
+                service: None,
+                model: None,
+            },
+            speech_voice: DEFAULT_SPEECH_VOICE.to_string(),
+            provider_limits: ProviderLimits::default(),
+            step_defaults: LlmCallDefaults::default(),
+            learner_memory: LearnerMemoryConfig::default(),
+            moderation: ModerationConfig::default(),
         }
     }
 
+    #[must_use]
+    pub fn with_step_defaults(self, step_defaults: LlmCallDefaults) -> Self {
+        Self { step_defaults, ..self }
+    }
+
+    #[must_use]
+    pub fn with_learner_memory(self, learner_memory: LearnerMemoryConfig) -> Self {
+        Self { learner_memory, ..self }
+    }
+
+    #[must_use]
+    pub fn with_moderation(self, moderation: ModerationConfig) -> Self {
+        Self { moderation, ..self }
+    }
+
     #[must_use]
     pub fn get_default_model(&self, service: Option<&LlmService>) -> &str {
         let default = LlmService::default();
@@ -196,4 +271,28 @@ impl LlmConfig {
     pub fn get_planner_openai_config(&self) -> OpenAIConfig {
         self.get_openai_config(self.planner_config.service.as_ref())
     }
+
+    /// The chat models of the services can not transcribe, so the default is a whisper model
+    #[must_use]
+    pub fn get_transcription_model(&self) -> &str {
+        self.transcription_config
+            .model
+            .as_deref()
+            .unwrap_or(DEFAULT_TRANSCRIPTION_MODEL)
+    }
+
+    #[must_use]
+    pub fn get_transcription_openai_config(&self) -> OpenAIConfig {
+        self.get_openai_config(self.transcription_config.service.as_ref())
+    }
+
+    #[must_use]
+    pub fn get_speech_model(&self) -> &str {
+        self.speech_config.model.as_deref().unwrap_or(DEFAULT_SPEECH_MODEL)
+    }
+
+    #[must_use]
+    pub fn get_speech_openai_config(&self) -> OpenAIConfig {
+        self.get_openai_config(self.speech_config.service.as_ref())
+    }
 }
@@ -8,9 +8,9 @@ use async_openai::middleware::retry::OpenAIRetryLayer;
 use async_openai::types::chat::{
     ChatCompletionMessageToolCall, ChatCompletionMessageToolCallChunk, ChatCompletionMessageToolCalls,
     ChatCompletionRequestMessage, ChatCompletionTools, CreateChatCompletionRequestArgs, CreateChatCompletionResponse,
-    FunctionCall, FunctionCallStream,
+    FunctionCall, FunctionCallStream, ResponseFormat,
 };
-use async_stream::try_stream;
+use async_stream::{stream, try_stream};
 use futures::Stream;
 use futures::StreamExt;
 use regex::Regex;
@@ -22,14 +22,18 @@ use std::error::Error;
 use std::fmt::Display;
 use std::pin::Pin;
 use std::str::FromStr;
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
 use std::time::Duration;
 use tokio::time::Instant;
 use tracing::instrument;
 use typed_builder::TypedBuilder;
 
+pub mod audio;
 pub mod error;
+pub mod moderation;
+pub mod pool;
 pub mod streaming;
+pub mod tokens;
 pub mod tools;
 
 fn reject_empty(data: String) -> Option<String> {
@@ -229,6 +233,9 @@ pub struct CallConfig {
     total_timeout: Duration,
     #[builder(default = Duration::from_secs(20))]
     iteration_timeout: Duration,
+    /// Requests of different users are queued fairly, requests without a user share one queue
+    #[builder(default, setter(strip_option, into))]
+    user: Option<String>,
 }
 
 pub enum OpenAiCallResult {
@@ -245,7 +252,8 @@ pub enum OpenAiCallResult {
     model,
     messages,
     tools,
-    tool_choice
+    tool_choice,
+    response_format
 ))]
 pub async fn openai_call_with_timeout(
     config: CallConfig,
@@ -257,6 +265,7 @@ pub async fn openai_call_with_timeout(
     messages: Vec<ChatCompletionRequestMessage>,
     tools: Vec<ToolSchema>,
     tool_choice: Option<ToolChoice>,
+    response_format: Option<ResponseFormat>,
 ) -> Result<OpenAiCallResult, OpenAiError> {
     let start_time = Instant::now();
     let model_label = model.to_string();
@@ -287,34 +296,35 @@ pub async fn openai_call_with_timeout(
         }
     }
 
+    if let Some(response_format) = response_format {
+        request.response_format(response_format);
+    }
+
     tracing::debug!(?request, "OpenAI request");
 
     let request = request.build()?;
 
-    let mut http_client_builder = reqwest::Client::builder();
-    if streaming {
-        // For streaming, only set a connect timeout — a full response timeout would kill
-        // long-running streams before they complete. Also disable auto-decompression since
-        // SSE streams cannot be gzip-decoded incrementally.
-        http_client_builder = http_client_builder
-            .connect_timeout(config.iteration_timeout)
-            .no_gzip()
-            .no_brotli()
-            .no_deflate()
-            .no_zstd();
-    } else {
-        http_client_builder = http_client_builder.timeout(config.iteration_timeout);
+    // Waiting for a slot and the request itself share the total timeout
+    let deadline = start_time + config.total_timeout;
+    let provider = pool::provider(&service);
+    let permit = tokio::time::timeout_at(deadline, provider.acquire(config.user.as_deref().unwrap_or_default()))
+        .await
+        .map_err(|_| {
+            tracing::warn!(%service, "timed out waiting for a free llm request slot");
+            OpenAiError::Timeout
+        })??;
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    if remaining.is_zero() {
+        tracing::warn!(%service, "no time left for the llm request after waiting for a slot");
+        return Err(OpenAiError::Timeout);
     }
-    let http_client = http_client_builder.build().map_err(|error| {
-        tracing::error!(error = &error as &dyn Error, "failed to build http client for openai");
-        OpenAiError::HttpClientBuild(error)
-    })?;
 
     let http_service = tower::ServiceBuilder::new()
-        .concurrency_limit(8)
-        .timeout(config.total_timeout)
+        .timeout(remaining)
         .layer(OpenAIRetryLayer::default())
-        .service(ReqwestService::new(http_client));
+        .service(ReqwestService::new(
+            provider.client(streaming, config.iteration_timeout)?,
+        ));
 
     let client = Client::with_config(openai_config).with_http_service(http_service);
 
@@ -324,13 +334,27 @@ pub async fn openai_call_with_timeout(
         match res {
             Ok(stream) => {
                 let stream = process_stream(stream, start_time, service, model_label);
+                let stream = track_stream(stream, provider, permit);
                 Ok(OpenAiCallResult::Stream(MessageStream::new(stream)))
             }
-            Err(error) => Err(OpenAiError::Api(error)),
+            Err(error) => {
+                record_error(&provider, &error);
+                Err(OpenAiError::Api(error))
+            }
         }
     } else {
         let res: Result<CreateChatCompletionResponse, async_openai::error::OpenAIError> =
             client.chat().create(request).await;
+        drop(permit);
+        match &res {
+            Ok(response) => {
+                provider.record_success();
+                if let Some(usage) = &response.usage {
+                    provider.record_tokens(usage.total_tokens);
+                }
+            }
+            Err(error) => record_error(&provider, error),
+        }
 
         // The precision loss is fine here, as we are only using it for metrics.
         // TODO use as_millis_f64() once it is stable
@@ -354,6 +378,79 @@ pub async fn openai_call_with_timeout(
     }
 }
 
+/// Sends a request other than a chat completion, e.g. for audio, within the limits of the provider and records its
+/// outcome. Waiting for a slot and the request share the timeout.
+pub(crate) async fn provider_call<T, F, Fut>(
+    openai_config: OpenAIConfig,
+    user: &str,
+    timeout: Duration,
+    call: F,
+) -> Result<T, OpenAiError>
+where
+    F: FnOnce(Client<OpenAIConfig>) -> Fut,
+    Fut: Future<Output = Result<T, async_openai::error::OpenAIError>>,
+{
+    let deadline = Instant::now() + timeout;
+    let service = openai_config.api_base().to_string();
+    let provider = pool::provider(&service);
+    let _permit = tokio::time::timeout_at(deadline, provider.acquire(user))
+        .await
+        .map_err(|_| {
+            tracing::warn!(%service, "timed out waiting for a free llm request slot");
+            OpenAiError::Timeout
+        })??;
+
+    let client =
+        Client::with_config(openai_config).with_http_service(ReqwestService::new(provider.client(false, timeout)?));
+    let result = tokio::time::timeout_at(deadline, call(client)).await.map_err(|_| {
+        provider.record_failure();
+        OpenAiError::Timeout
+    })?;
+    match &result {
+        Ok(_) => provider.record_success(),
+        Err(error) => record_error(&provider, error),
+    }
+    Ok(result?)
+}
+
+/// Invalid requests are the caller's fault and don't say anything about the health of the provider
+fn record_error(provider: &pool::Provider, error: &async_openai::error::OpenAIError) {
+    if !matches!(error, async_openai::error::OpenAIError::InvalidArgument(_)) {
+        provider.record_failure();
+    }
+}
+
+/// Holds the slot until the stream is dropped and reports the outcome to the provider once the stream ends.
+/// A stream which is dropped early reports nothing, an open trial request is retried after the cooldown.
+fn track_stream(
+    mut stream: Pin<Box<dyn Stream<Item = Result<Message, error::StreamingError>> + Send>>,
+    provider: Arc<pool::Provider>,
+    permit: pool::Permit,
+) -> Pin<Box<dyn Stream<Item = Result<Message, error::StreamingError>> + Send>> {
+    stream! {
+        let _permit = permit;
+        let mut failed = false;
+        while let Some(message) = stream.next().await {
+            match &message {
+                Ok(Message { tokens: Some(tokens), .. }) => provider.record_tokens(*tokens),
+                Ok(_) => {}
+                Err(error) => {
+                    failed = true;
+                    // Only errors of the provider count, invalid chunks are reported by process_stream
+                    if let Some(error) = error.downcast_ref::<async_openai::error::OpenAIError>() {
+                        record_error(&provider, error);
+                    }
+                }
+            }
+            yield message;
+        }
+        if !failed {
+            provider.record_success();
+        }
+    }
+    .boxed()
+}
+
 pub(crate) fn process_stream(
     mut stream: impl Stream<
         Item = Result<async_openai::types::chat::CreateChatCompletionStreamResponse, async_openai::error::OpenAIError>,
@@ -522,6 +619,7 @@ pub async fn openai_single_tool_call<T: DeserializeOwned + JsonSchema>(
         messages,
         vec![tool_schema],
         Some(ToolChoice::Named(tool_name)),
+        None,
     )
     .await?;
 
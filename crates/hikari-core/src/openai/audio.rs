@@ -0,0 +1,113 @@
+use crate::llm_config::LlmConfig;
+use crate::openai::error::OpenAiError;
+use crate::openai::provider_call;
+use async_openai::types::audio::{
+    AudioInput, CreateSpeechRequestArgs, CreateTranscriptionRequestArgs, SpeechModel, SpeechResponseFormat, Voice,
+};
+use std::time::Duration;
+use tracing::instrument;
+
+const TRANSCRIPTION_TIMEOUT: Duration = Duration::from_secs(60);
+const SPEECH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Mime type of the synthesized speech
+pub const SPEECH_MIME_TYPE: &str = "audio/mpeg";
+
+/// Longest text the speech endpoint accepts
+pub const MAX_SPEECH_INPUT: usize = 4096;
+
+/// File name for the audio, the transcription endpoint detects the format by its extension.
+/// Returns `None` for formats it does not accept.
+#[must_use]
+pub fn audio_file_name(mime_type: &str) -> Option<&'static str> {
+    let mime_type = mime_type.split(';').next().unwrap_or_default().trim();
+    match mime_type {
+        "audio/webm" => Some("audio.webm"),
+        "audio/ogg" => Some("audio.ogg"),
+        "audio/mpeg" | "audio/mp3" => Some("audio.mp3"),
+        "audio/mp4" | "audio/m4a" | "audio/x-m4a" => Some("audio.m4a"),
+        "audio/wav" | "audio/x-wav" | "audio/wave" => Some("audio.wav"),
+        "audio/flac" => Some("audio.flac"),
+        _ => None,
+    }
+}
+
+/// Transcribes the audio with the configured transcription service, within the limits of its provider
+#[instrument(skip(llm_config, data), fields(size = data.len()), err)]
+pub async fn transcribe(
+    llm_config: &LlmConfig,
+    user_id: &str,
+    file_name: &'static str,
+    data: Vec<u8>,
+    language: Option<&str>,
+) -> Result<String, OpenAiError> {
+    let mut request = CreateTranscriptionRequestArgs::default();
+    request
+        .file(AudioInput::from_vec_u8(file_name.to_string(), data))
+        .model(llm_config.get_transcription_model());
+    if let Some(language) = language {
+        request.language(language);
+    }
+    let request = request.build()?;
+
+    let response = provider_call(
+        llm_config.get_transcription_openai_config(),
+        user_id,
+        TRANSCRIPTION_TIMEOUT,
+        |client| async move { client.audio().transcription().create(request).await },
+    )
+    .await?;
+    Ok(response.text.trim().to_string())
+}
+
+/// Synthesizes the text with the configured speech service, within the limits of its provider. Returns mp3 data.
+#[instrument(skip(llm_config, text), fields(len = text.len()), err)]
+pub async fn synthesize(llm_config: &LlmConfig, user_id: &str, text: &str) -> Result<Vec<u8>, OpenAiError> {
+    let voice: Voice = serde_json::from_value(serde_json::Value::String(llm_config.speech_voice.clone()))?;
+    let input = truncate(text, MAX_SPEECH_INPUT);
+    let request = CreateSpeechRequestArgs::default()
+        .input(input)
+        .model(SpeechModel::Other(llm_config.get_speech_model().to_string()))
+        .voice(voice)
+        .response_format(SpeechResponseFormat::Mp3)
+        .build()?;
+
+    let response = provider_call(
+        llm_config.get_speech_openai_config(),
+        user_id,
+        SPEECH_TIMEOUT,
+        |client| async move { client.audio().speech().create(request).await },
+    )
+    .await?;
+    Ok(response.bytes.to_vec())
+}
+
+/// Cuts the text at a char boundary so that it has at most `max` bytes
+fn truncate(text: &str, max: usize) -> &str {
+    if text.len() <= max {
+        return text;
+    }
+    let end = (0..=max).rev().find(|&index| text.is_char_boundary(index)).unwrap_or(0);
+    text.get(..end).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audio_file_name() {
+        assert_eq!(audio_file_name("audio/webm;codecs=opus"), Some("audio.webm"));
+        assert_eq!(audio_file_name("audio/mpeg"), Some("audio.mp3"));
+        assert_eq!(audio_file_name("audio/x-m4a"), Some("audio.m4a"));
+        assert_eq!(audio_file_name("video/mp4"), None);
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("hallo", 10), "hallo");
+        assert_eq!(truncate("hallo", 3), "hal");
+        // "ä" takes two bytes and is not split
+        assert_eq!(truncate("aä", 2), "a");
+    }
+}
@@ -1,4 +1,5 @@
 use futures_retry_policies::ShouldRetry;
+use reqwest::StatusCode;
 use std::error::Error;
 use thiserror::Error;
 
@@ -27,6 +28,9 @@ pub enum OpenAiError {
 
     #[error(transparent)]
     HttpClientBuild(#[from] reqwest::Error),
+
+    #[error("LLM provider {0} is unavailable")]
+    CircuitOpen(String),
 }
 
 #[derive(Error, Debug)]
@@ -41,9 +45,53 @@ pub enum FunctionCallError {
     Missing,
 }
 
+impl OpenAiError {
+    /// Timeouts, rate limits and server errors, which may succeed if the request is sent again
+    #[must_use]
+    pub fn is_transient(&self) -> bool {
+        match self {
+            OpenAiError::Timeout => true,
+            OpenAiError::Api(error) => is_transient_api_error(error),
+            _ => false,
+        }
+    }
+
+    /// The provider rejected the request with a 400 because it doesn't support the requested response format
+    #[must_use]
+    pub fn is_response_format_unsupported(&self) -> bool {
+        match self {
+            OpenAiError::Api(async_openai::error::OpenAIError::ApiError(error)) => {
+                error.param.as_deref() == Some("response_format")
+                    || (error.r#type.as_deref() == Some("invalid_request_error")
+                        && ["response_format", "json_schema"]
+                            .iter()
+                            .any(|term| error.message.contains(term)))
+            }
+            _ => false,
+        }
+    }
+}
+
+fn is_transient_api_error(error: &async_openai::error::OpenAIError) -> bool {
+    match error {
+        async_openai::error::OpenAIError::Reqwest(error) => {
+            error.is_timeout()
+                || error
+                    .status()
+                    .is_some_and(|status| status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error())
+        }
+        // Api errors don't carry the status code, rate limits and server errors are recognized by their type
+        async_openai::error::OpenAIError::ApiError(error) => {
+            matches!(error.r#type.as_deref(), Some("rate_limit_exceeded" | "server_error"))
+                || matches!(error.code.as_deref(), Some("rate_limit_exceeded"))
+        }
+        _ => false,
+    }
+}
+
 impl ShouldRetry for OpenAiError {
     fn should_retry(&self, _: u32) -> bool {
-        true
+        self.is_transient()
     }
 }
 
@@ -0,0 +1,155 @@
+use crate::llm_config::LlmConfig;
+use crate::openai::error::OpenAiError;
+use crate::openai::{CallConfig, openai_single_tool_call, provider_call};
+use async_openai::types::chat::{ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs};
+use async_openai::types::moderations::CreateModerationRequestArgs;
+use hikari_config::global::llm::ModerationProvider;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::time::Duration;
+use tracing::instrument;
+
+const MODERATION_TIMEOUT: Duration = Duration::from_secs(20);
+const DEFAULT_MODERATION_MODEL: &str = "omni-moderation-latest";
+
+const DEFAULT_CLASSIFIER_PROMPT: &str = "Du prüfst Nachrichten aus einer Lernplattform für Studierende auf problematische Inhalte.\n\
+    Markiere die Nachricht nur, wenn sie eindeutig in eine der folgenden Kategorien fällt:\n\
+    - self-harm: Suizidgedanken, Selbstverletzung oder akute psychische Krisen\n\
+    - violence: Gewaltandrohungen oder Verherrlichung von Gewalt\n\
+    - sexual: sexuelle Inhalte\n\
+    - hate: Hass gegen Personen oder Gruppen\n\
+    - harassment: Beleidigungen oder Belästigung\n\
+    Alltägliche Sorgen wie Prüfungsstress, schlechter Schlaf oder Traurigkeit sind nicht problematisch.\n\
+    Rufe die Funktion `Moderation` mit deiner Einschätzung auf.";
+
+/// Whether a message of the user or of the agent is checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuardrailDirection {
+    Input,
+    Output,
+}
+
+impl GuardrailDirection {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GuardrailDirection::Input => "input",
+            GuardrailDirection::Output => "output",
+        }
+    }
+}
+
+/// Result of the moderation of a message
+#[derive(Debug, Clone, Default)]
+pub struct Verdict {
+    pub flagged: bool,
+    /// Flagged categories, e.g. `self-harm/intent`
+    pub categories: Vec<String>,
+    /// Tokens used by a classifier
+    pub tokens: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[schemars(
+    title = "Moderation",
+    description = "Einschätzung, ob die Nachricht problematische Inhalte enthält."
+)]
+struct ClassifierVerdict {
+    /// Ob die Nachricht problematische Inhalte enthält
+    flagged: bool,
+    /// Die zutreffenden Kategorien, leer wenn die Nachricht unproblematisch ist
+    categories: Vec<String>,
+}
+
+/// Checks the text with the configured moderation provider
+#[instrument(skip(llm_config, text), fields(len = text.len()), err)]
+pub async fn moderate(llm_config: &LlmConfig, user_id: &str, text: &str) -> Result<Verdict, OpenAiError> {
+    match &llm_config.moderation.provider {
+        ModerationProvider::Endpoint { service, model } => {
+            let request = CreateModerationRequestArgs::default()
+                .input(text)
+                .model(model.as_deref().unwrap_or(DEFAULT_MODERATION_MODEL))
+                .build()?;
+            let response = provider_call(
+                llm_config.get_openai_config(service.as_ref()),
+                user_id,
+                MODERATION_TIMEOUT,
+                |client| async move { client.moderations().create(request).await },
+            )
+            .await?;
+
+            let mut verdict = Verdict::default();
+            for result in response.results {
+                verdict.flagged |= result.flagged;
+                verdict
+                    .categories
+                    .extend(flagged_categories(&serde_json::to_value(result.categories)?));
+            }
+            verdict.categories.sort();
+            verdict.categories.dedup();
+            Ok(verdict)
+        }
+        ModerationProvider::Classifier { service, model, prompt } => {
+            let messages = vec![
+                ChatCompletionRequestSystemMessageArgs::default()
+                    .content(prompt.as_deref().unwrap_or(DEFAULT_CLASSIFIER_PROMPT))
+                    .build()?
+                    .into(),
+                ChatCompletionRequestUserMessageArgs::default()
+                    .content(text)
+                    .build()?
+                    .into(),
+            ];
+            let model = model
+                .as_deref()
+                .unwrap_or_else(|| llm_config.get_default_model(service.as_ref()));
+            let (verdict, tokens) = openai_single_tool_call::<ClassifierVerdict>(
+                CallConfig::builder()
+                    .total_timeout(MODERATION_TIMEOUT)
+                    .iteration_timeout(MODERATION_TIMEOUT)
+                    .user(user_id)
+                    .build(),
+                llm_config.get_openai_config(service.as_ref()),
+                Some(0.0),
+                None,
+                model,
+                messages,
+            )
+            .await?;
+            Ok(Verdict {
+                flagged: verdict.flagged || !verdict.categories.is_empty(),
+                categories: verdict.categories,
+                tokens,
+            })
+        }
+    }
+}
+
+/// Names of the categories which are set to `true`
+fn flagged_categories(categories: &serde_json::Value) -> Vec<String> {
+    categories
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(_, flagged)| flagged.as_bool().unwrap_or_default())
+        .map(|(category, _)| category.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_flagged_categories() {
+        let categories = json!({
+            "hate": false,
+            "self-harm": true,
+            "self-harm/intent": true,
+            "violence": false,
+        });
+        assert_eq!(flagged_categories(&categories), vec!["self-harm", "self-harm/intent"]);
+        assert!(flagged_categories(&json!(null)).is_empty());
+    }
+}
@@ -0,0 +1,461 @@
+use crate::openai::error::OpenAiError;
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard, OnceLock, PoisonError};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+
+/// Limits applied to every LLM provider (identified by its api base) across the whole process
+#[derive(Debug, Clone)]
+pub struct ProviderLimits {
+    /// Maximum number of requests in flight, streams count until they are dropped
+    pub max_concurrency: usize,
+    pub requests_per_minute: Option<u32>,
+    /// Tokens are charged after a response is received, so a burst may exceed the limit once
+    pub tokens_per_minute: Option<u32>,
+    /// Consecutive failures after which the circuit opens and requests fail fast
+    pub failure_threshold: u32,
+    /// Time the circuit stays open before a single trial request is let through
+    pub cooldown: Duration,
+}
+
+impl Default for ProviderLimits {
+    fn default() -> Self {
+        Self {
+            max_concurrency: 8,
+            requests_per_minute: None,
+            tokens_per_minute: None,
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+static LIMITS: OnceLock<ProviderLimits> = OnceLock::new();
+static PROVIDERS: LazyLock<Mutex<HashMap<String, Arc<Provider>>>> = LazyLock::new(Mutex::default);
+
+/// Sets the limits of all providers. Has to be called before the first LLM request, later calls are ignored.
+pub fn configure(limits: ProviderLimits) {
+    if LIMITS.set(limits).is_err() {
+        tracing::warn!("llm provider limits are already configured");
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // All critical sections only update plain counters, so the state is consistent even after a panic
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns the long-lived provider for the api base, creating it on first use
+pub(crate) fn provider(service: &str) -> Arc<Provider> {
+    let limits = LIMITS.get_or_init(ProviderLimits::default);
+    Arc::clone(
+        lock(&PROVIDERS)
+            .entry(service.to_owned())
+            .or_insert_with(|| Arc::new(Provider::new(service, limits))),
+    )
+}
+
+pub(crate) struct Provider {
+    service: String,
+    clients: Mutex<HashMap<(bool, Duration), reqwest::Client>>,
+    queue: Arc<FairQueue>,
+    requests: Option<Mutex<TokenBucket>>,
+    tokens: Option<Mutex<TokenBucket>>,
+    breaker: Mutex<CircuitBreaker>,
+}
+
+impl Provider {
+    fn new(service: &str, limits: &ProviderLimits) -> Self {
+        Self {
+            service: service.to_owned(),
+            clients: Mutex::default(),
+            queue: Arc::new(FairQueue::new(limits.max_concurrency)),
+            requests: limits
+                .requests_per_minute
+                .map(|limit| Mutex::new(TokenBucket::new(limit))),
+            tokens: limits
+                .tokens_per_minute
+                .map(|limit| Mutex::new(TokenBucket::new(limit))),
+            breaker: Mutex::new(CircuitBreaker::new(limits.failure_threshold, limits.cooldown)),
+        }
+    }
+
+    /// Cheap handle to a shared connection pool. The requests are built by async-openai, so the timeout is set on
+    /// the client and there is one client for every distinct timeout, callers only use a handful of them.
+    pub(crate) fn client(&self, streaming: bool, timeout: Duration) -> Result<reqwest::Client, OpenAiError> {
+        let mut clients = lock(&self.clients);
+        if let Some(client) = clients.get(&(streaming, timeout)) {
+            return Ok(client.clone());
+        }
+        let mut builder = reqwest::Client::builder();
+        if streaming {
+            // For streaming, only set a connect timeout — a full response timeout would kill
+            // long-running streams before they complete. Also disable auto-decompression since
+            // SSE streams cannot be gzip-decoded incrementally.
+            builder = builder
+                .connect_timeout(timeout)
+                .no_gzip()
+                .no_brotli()
+                .no_deflate()
+                .no_zstd();
+        } else {
+            builder = builder.timeout(timeout);
+        }
+        let client = builder.build().map_err(|error| {
+            tracing::error!(error = &error as &dyn Error, "failed to build http client for openai");
+            OpenAiError::HttpClientBuild(error)
+        })?;
+        clients.insert((streaming, timeout), client.clone());
+        Ok(client)
+    }
+
+    /// Waits for a free slot of the user and the rate limits. Fails fast while the circuit is open.
+    pub(crate) async fn acquire(&self, user: &str) -> Result<Permit, OpenAiError> {
+        if lock(&self.breaker).is_open(Instant::now()) {
+            return Err(self.circuit_open());
+        }
+        let permit = self.queue.acquire(user).await;
+        self.wait_for_rate_limits().await;
+        if !lock(&self.breaker).try_pass(Instant::now()) {
+            return Err(self.circuit_open());
+        }
+        Ok(permit)
+    }
+
+    fn circuit_open(&self) -> OpenAiError {
+        tracing::warn!(service = %self.service, "llm provider circuit is open, failing fast");
+        OpenAiError::CircuitOpen(self.service.clone())
+    }
+
+    async fn wait_for_rate_limits(&self) {
+        loop {
+            let now = Instant::now();
+            let wait = self
+                .tokens
+                .as_ref()
+                .and_then(|tokens| lock(tokens).wait_time(0.0, now))
+                .or_else(|| {
+                    self.requests
+                        .as_ref()
+                        .and_then(|requests| lock(requests).take(1.0, now))
+                });
+            match wait {
+                Some(wait) => tokio::time::sleep(wait).await,
+                None => return,
+            }
+        }
+    }
+
+    pub(crate) fn record_tokens(&self, tokens: u32) {
+        if let Some(bucket) = &self.tokens {
+            lock(bucket).charge(f64::from(tokens), Instant::now());
+        }
+    }
+
+    pub(crate) fn record_success(&self) {
+        lock(&self.breaker).record_success();
+    }
+
+    pub(crate) fn record_failure(&self) {
+        if lock(&self.breaker).record_failure(Instant::now()) {
+            tracing::error!(service = %self.service, "llm provider circuit opened");
+            metrics::counter!("llm_circuit_opened", "service" => self.service.clone()).increment(1);
+        }
+    }
+}
+
+/// Refills continuously at the configured rate per minute, holding at most one minute worth of capacity
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    available: f64,
+    per_second: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(per_minute: u32) -> Self {
+        let capacity = f64::from(per_minute.max(1));
+        Self {
+            capacity,
+            available: capacity,
+            per_second: capacity / 60.0,
+            updated: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.available = (self.available + elapsed * self.per_second).min(self.capacity);
+        self.updated = now;
+    }
+
+    /// Time until `amount` is available, or `None` if it is available now
+    fn wait_time(&mut self, amount: f64, now: Instant) -> Option<Duration> {
+        self.refill(now);
+        let missing = amount - self.available;
+        (missing > 0.0).then(|| Duration::from_secs_f64(missing / self.per_second))
+    }
+
+    /// Takes `amount` if it is available, otherwise returns the time to wait
+    fn take(&mut self, amount: f64, now: Instant) -> Option<Duration> {
+        let wait = self.wait_time(amount, now);
+        if wait.is_none() {
+            self.available -= amount;
+        }
+        wait
+    }
+
+    /// Charges usage which is only known afterwards, the bucket may become negative
+    fn charge(&mut self, amount: f64, now: Instant) {
+        self.refill(now);
+        self.available -= amount;
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CircuitState {
+    Closed {
+        failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    /// A single trial request is in flight, if it never reports back another one is allowed after the cooldown
+    HalfOpen {
+        since: Instant,
+    },
+}
+
+#[derive(Debug)]
+struct CircuitBreaker {
+    state: CircuitState,
+    failure_threshold: u32,
+    cooldown: Duration,
+}
+
+impl CircuitBreaker {
+    fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            state: CircuitState::Closed { failures: 0 },
+            failure_threshold,
+            cooldown,
+        }
+    }
+
+    fn is_open(&self, now: Instant) -> bool {
+        match self.state {
+            CircuitState::Closed { .. } => false,
+            CircuitState::Open { until } => now < until,
+            CircuitState::HalfOpen { since } => now < since + self.cooldown,
+        }
+    }
+
+    /// Checks if a request may be sent, the first one after the cooldown becomes the trial request
+    fn try_pass(&mut self, now: Instant) -> bool {
+        if self.is_open(now) {
+            return false;
+        }
+        if !matches!(self.state, CircuitState::Closed { .. }) {
+            self.state = CircuitState::HalfOpen { since: now };
+        }
+        true
+    }
+
+    fn record_success(&mut self) {
+        self.state = CircuitState::Closed { failures: 0 };
+    }
+
+    /// Returns true if the circuit was opened by this failure
+    fn record_failure(&mut self, now: Instant) -> bool {
+        let open = match self.state {
+            CircuitState::Closed { failures } => {
+                let failures = failures + 1;
+                self.state = CircuitState::Closed { failures };
+                failures >= self.failure_threshold
+            }
+            CircuitState::HalfOpen { .. } => true,
+            CircuitState::Open { .. } => false,
+        };
+        if open {
+            self.state = CircuitState::Open {
+                until: now + self.cooldown,
+            };
+        }
+        open
+    }
+}
+
+/// Concurrency limit which hands out free slots round-robin between users instead of first come, first served
+struct FairQueue {
+    max_concurrency: usize,
+    state: Mutex<QueueState>,
+}
+
+#[derive(Default)]
+struct QueueState {
+    running: usize,
+    /// Waiting requests grouped by user, the user at the front is served next
+    waiting: VecDeque<(String, VecDeque<oneshot::Sender<Permit>>)>,
+}
+
+/// Slot of the concurrency limit, released on drop
+pub(crate) struct Permit {
+    queue: Arc<FairQueue>,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.queue.release();
+    }
+}
+
+impl FairQueue {
+    fn new(max_concurrency: usize) -> Self {
+        Self {
+            max_concurrency: max_concurrency.max(1),
+            state: Mutex::default(),
+        }
+    }
+
+    async fn acquire(self: &Arc<Self>, user: &str) -> Permit {
+        let receiver = {
+            let mut state = lock(&self.state);
+            if state.running < self.max_concurrency && state.waiting.is_empty() {
+                state.running += 1;
+                return Permit {
+                    queue: Arc::clone(self),
+                };
+            }
+            let (sender, receiver) = oneshot::channel();
+            match state.waiting.iter_mut().find(|(waiting_user, _)| waiting_user == user) {
+                Some((_, senders)) => senders.push_back(sender),
+                None => state.waiting.push_back((user.to_owned(), VecDeque::from([sender]))),
+            }
+            receiver
+        };
+        // Senders only leave the queue by being sent to
+        receiver.await.expect("fair queue dropped a waiting request")
+    }
+
+    /// Hands the slot to the next waiting user or frees it
+    fn release(self: &Arc<Self>) {
+        let next = {
+            let mut state = lock(&self.state);
+            let mut next = None;
+            while next.is_none()
+                && let Some((user, mut senders)) = state.waiting.pop_front()
+            {
+                next = senders.pop_front().filter(|sender| !sender.is_closed());
+                if !senders.is_empty() {
+                    state.waiting.push_back((user, senders));
+                }
+            }
+            if next.is_none() {
+                state.running = state.running.saturating_sub(1);
+            }
+            next
+        };
+        if let Some(sender) = next {
+            // The lock is released already, if the request was cancelled meanwhile dropping the permit releases again
+            let _ = sender.send(Permit {
+                queue: Arc::clone(self),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::pin::pin;
+
+    #[test]
+    fn test_token_bucket() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(60);
+        bucket.updated = now;
+        assert!(bucket.take(60.0, now).is_none());
+        assert_eq!(bucket.take(1.0, now), Some(Duration::from_secs(1)));
+
+        bucket.charge(30.0, now + Duration::from_secs(10));
+        // 10 refilled, 30 charged
+        assert_eq!(
+            bucket.wait_time(0.0, now + Duration::from_secs(10)),
+            Some(Duration::from_secs(20))
+        );
+        assert!(bucket.wait_time(0.0, now + Duration::from_secs(30)).is_none());
+        // Never refills above the capacity
+        assert!(bucket.take(61.0, now + Duration::from_secs(600)).is_some());
+    }
+
+    #[test]
+    fn test_circuit_breaker() {
+        let now = Instant::now();
+        let cooldown = Duration::from_secs(30);
+        let mut breaker = CircuitBreaker::new(2, cooldown);
+        assert!(!breaker.record_failure(now));
+        breaker.record_success();
+        assert!(!breaker.record_failure(now));
+        assert!(breaker.record_failure(now));
+        assert!(breaker.is_open(now));
+        assert!(!breaker.try_pass(now + Duration::from_secs(10)));
+
+        // Only a single trial request after the cooldown
+        let later = now + cooldown;
+        assert!(breaker.try_pass(later));
+        assert!(!breaker.try_pass(later));
+        assert!(breaker.record_failure(later));
+        assert!(breaker.is_open(later + Duration::from_secs(1)));
+
+        let even_later = later + cooldown;
+        assert!(breaker.try_pass(even_later));
+        breaker.record_success();
+        assert!(breaker.try_pass(even_later));
+        assert!(breaker.try_pass(even_later));
+    }
+
+    #[tokio::test]
+    async fn test_fair_queue_alternates_between_users() {
+        let queue = Arc::new(FairQueue::new(1));
+        let running = queue.acquire("a").await;
+
+        let mut a1 = pin!(queue.acquire("a"));
+        let mut a2 = pin!(queue.acquire("a"));
+        let mut b1 = pin!(queue.acquire("b"));
+        assert!(futures::poll!(a1.as_mut()).is_pending());
+        assert!(futures::poll!(a2.as_mut()).is_pending());
+        assert!(futures::poll!(b1.as_mut()).is_pending());
+
+        drop(running);
+        let std::task::Poll::Ready(running) = futures::poll!(a1.as_mut()) else {
+            panic!("first request of a should run");
+        };
+        assert!(futures::poll!(a2.as_mut()).is_pending());
+
+        drop(running);
+        assert!(futures::poll!(a2.as_mut()).is_pending());
+        let std::task::Poll::Ready(running) = futures::poll!(b1.as_mut()) else {
+            panic!("b should run before the second request of a");
+        };
+
+        drop(running);
+        assert!(futures::poll!(a2.as_mut()).is_ready());
+    }
+
+    #[tokio::test]
+    async fn test_fair_queue_skips_cancelled_requests() {
+        let queue = Arc::new(FairQueue::new(1));
+        let running = queue.acquire("a").await;
+        {
+            let mut cancelled = pin!(queue.acquire("b"));
+            assert!(futures::poll!(cancelled.as_mut()).is_pending());
+        }
+        drop(running);
+        assert_eq!(lock(&queue.state).running, 0);
+        let _running = queue.acquire("c").await;
+        assert_eq!(lock(&queue.state).running, 1);
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::LazyLock;
+use tiktoken_rs::CoreBPE;
+
+/// Tokens added by the chat format for every message
+pub const MESSAGE_OVERHEAD: usize = 3;
+/// Tokens added by the chat format to prime the reply
+pub const REPLY_OVERHEAD: usize = 3;
+/// Upper bound of the tokens of an attached image. OpenAI scales images to 768 pixels on the shorter side and charges
+/// 170 tokens for each of up to 8 tiles of 512 pixels plus 85 base tokens.
+pub const IMAGE_TOKENS: usize = 85 + 8 * 170;
+/// Safety margin added to the approximate counts of models whose tokenizer is unknown
+pub const UNKNOWN_MARGIN_PERCENT: usize = 25;
+
+static O200K: LazyLock<CoreBPE> = LazyLock::new(|| tiktoken_rs::o200k_base().expect("o200k_base encoding is bundled"));
+static CL100K: LazyLock<CoreBPE> =
+    LazyLock::new(|| tiktoken_rs::cl100k_base().expect("cl100k_base encoding is bundled"));
+
+/// Tokenizer used by a family of models
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenizerFamily {
+    /// `gpt-4o`, `gpt-4.1`, `gpt-5`, the `o` reasoning models and `gpt-oss`
+    O200k,
+    /// `gpt-4`, `gpt-3.5` and the embedding models
+    Cl100k,
+    /// Models of other vendors, their tokenizers are not bundled
+    Unknown,
+}
+
+impl TokenizerFamily {
+    /// Detects the family from the model name, a provider prefix like `openai/` is ignored
+    #[must_use]
+    pub fn for_model(model: &str) -> Self {
+        let model = model.rsplit('/').next().unwrap_or(model).to_lowercase();
+        let cl100k = ["gpt-4", "gpt-3.5", "gpt-35", "text-embedding"];
+        let o200k = [
+            "gpt-4o",
+            "gpt-4.1",
+            "gpt-4.5",
+            "chatgpt-4o",
+            "gpt-5",
+            "gpt-oss",
+            "o1",
+            "o3",
+            "o4",
+        ];
+        if o200k.iter().any(|prefix| model.starts_with(prefix)) {
+            TokenizerFamily::O200k
+        } else if cl100k.iter().any(|prefix| model.starts_with(prefix)) {
+            TokenizerFamily::Cl100k
+        } else {
+            TokenizerFamily::Unknown
+        }
+    }
+
+    fn encoding(self) -> &'static CoreBPE {
+        match self {
+            TokenizerFamily::O200k | TokenizerFamily::Unknown => &O200K,
+            TokenizerFamily::Cl100k => &CL100K,
+        }
+    }
+}
+
+/// Number of tokens of the text for the given model.
+///
+/// The count is exact for OpenAI models. Other models are counted with o200k, which is only an approximation, so
+/// [`UNKNOWN_MARGIN_PERCENT`] is added to stay within budgets of models with less efficient tokenizers.
+#[must_use]
+pub fn count_tokens(model: &str, text: &str) -> usize {
+    let family = TokenizerFamily::for_model(model);
+    let tokens = family.encoding().encode_ordinary(text).len();
+    if family == TokenizerFamily::Unknown {
+        (tokens * (100 + UNKNOWN_MARGIN_PERCENT)).div_ceil(100)
+    } else {
+        tokens
+    }
+}
+
+/// Number of tokens of a chat message including the overhead of the chat format
+#[must_use]
+pub fn count_message_tokens(model: &str, text: &str) -> usize {
+    count_tokens(model, text) + MESSAGE_OVERHEAD
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_family_for_model() {
+        assert_eq!(TokenizerFamily::for_model("gpt-4o-mini"), TokenizerFamily::O200k);
+        assert_eq!(TokenizerFamily::for_model("openai/gpt-4.1"), TokenizerFamily::O200k);
+        assert_eq!(TokenizerFamily::for_model("gpt-4-turbo"), TokenizerFamily::Cl100k);
+        assert_eq!(TokenizerFamily::for_model("gpt-3.5-turbo"), TokenizerFamily::Cl100k);
+        assert_eq!(TokenizerFamily::for_model("o3-mini"), TokenizerFamily::O200k);
+        assert_eq!(TokenizerFamily::for_model("gpt-5-mini"), TokenizerFamily::O200k);
+        assert_eq!(TokenizerFamily::for_model("llama-3.1-70b"), TokenizerFamily::Unknown);
+    }
+
+    #[test]
+    fn test_count_tokens() {
+        assert_eq!(count_tokens("gpt-4o", ""), 0);
+        assert_eq!(count_tokens("gpt-4o", "hello world"), 2);
+        assert_eq!(count_tokens("gpt-4", "hello world"), 2);
+        assert_eq!(count_message_tokens("gpt-4o", "hello world"), 2 + MESSAGE_OVERHEAD);
+        // 2 tokens plus the margin, rounded up
+        assert_eq!(count_tokens("mistral-large", "hello world"), 3);
+    }
+}
@@ -17,6 +17,7 @@ use xxhash_rust::xxh3::xxh3_64;
 pub mod documents;
 pub mod embedder;
 pub mod error;
+pub mod learner_memory;
 use tracing::Level;
 
 pub(crate) const EMBEDDING_TABLE: &str = "llm_embeddings";
@@ -0,0 +1,205 @@
+use crate::llm_config::LlmConfig;
+use crate::pgvector::PgVector;
+use crate::pgvector::error::PgVectorError;
+use chrono::{NaiveDateTime, Utc};
+use hikari_db::llm::learner_memory;
+use hikari_model::llm::learner_memory::LearnerMemoryMatch;
+use sea_orm::prelude::Uuid;
+use sea_orm::query::Value;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement, TransactionTrait};
+use tracing::{Level, instrument};
+
+pub(crate) const LEARNER_MEMORY_TABLE: &str = "llm_learner_memory";
+
+/// Facts closer than this cosine distance to a stored memory of the user are not stored again
+const DUPLICATE_DISTANCE: f64 = 0.1;
+
+fn vector_literal(vector: &[f64]) -> String {
+    let values = vector.iter().map(ToString::to_string).collect::<Vec<_>>().join(",");
+    format!("[{values}]")
+}
+
+impl PgVector<'_> {
+    /// Embeds and stores the facts, facts which are already known are skipped. Returns the number of stored facts.
+    #[instrument(skip(self, facts), ret, err(level = Level::ERROR))]
+    pub async fn insert_learner_memories(
+        &self,
+        user_id: Uuid,
+        module_id: &str,
+        session_id: &str,
+        conversation_id: Option<Uuid>,
+        facts: &[String],
+    ) -> Result<usize, PgVectorError> {
+        let embeddings = self.embedder.embed(facts).await?;
+        if embeddings.len() != facts.len() {
+            return Err(PgVectorError::VectorMissMatch);
+        }
+
+        let txn = self.conn.begin().await?;
+        let mut stored = 0;
+        for (fact, embedding) in facts.iter().zip(embeddings) {
+            let embedding = vector_literal(&embedding);
+            let duplicate = Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                format! {r"
+                SELECT id FROM {LEARNER_MEMORY_TABLE}
+                WHERE user_id = $1 AND embedding <=> CAST($2 AS vector) < $3
+                LIMIT 1",
+                },
+                vec![
+                    Value::from(user_id),
+                    Value::from(embedding.clone()),
+                    Value::from(DUPLICATE_DISTANCE),
+                ],
+            );
+            if txn.query_one(duplicate).await?.is_some() {
+                tracing::debug!(%user_id, "learner memory is already known");
+                continue;
+            }
+
+            let statement = Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                format! {r"
+                INSERT INTO {LEARNER_MEMORY_TABLE} (id, user_id, module_id, session_id, conversation_id, content, embedding, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6, CAST($7 AS vector), $8)",
+                },
+                vec![
+                    Value::from(Uuid::new_v4()),
+                    Value::from(user_id),
+                    Value::from(module_id),
+                    Value::from(session_id),
+                    Value::from(conversation_id),
+                    Value::from(fact.as_str()),
+                    Value::from(embedding),
+                    Value::from(Utc::now().naive_utc()),
+                ],
+            );
+            txn.execute(statement).await?;
+            stored += 1;
+        }
+        txn.commit().await?;
+        Ok(stored)
+    }
+
+    /// The memories of the user which are most similar to the query
+    pub async fn search_learner_memories(
+        &self,
+        user_id: Uuid,
+        query: &str,
+        limit: u32,
+        max_distance: Option<f64>,
+    ) -> Result<Vec<LearnerMemoryMatch>, PgVectorError> {
+        let query_vector = self.embedder.embed(&[query.to_owned()]).await?.swap_remove(0);
+        let statement = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            format! {r"
+            SELECT content, created_at, distance
+            FROM (
+                SELECT content, created_at, embedding <=> CAST($2 AS vector) AS distance
+                FROM {LEARNER_MEMORY_TABLE}
+                WHERE user_id = $1
+            ) AS memories
+            WHERE $3 IS NULL OR distance <= $3
+            ORDER BY distance ASC
+            LIMIT $4",
+            },
+            vec![
+                Value::from(user_id),
+                Value::from(vector_literal(&query_vector)),
+                Value::from(max_distance),
+                Value::from(i64::from(limit)),
+            ],
+        );
+
+        let rows = self.conn.query_all(statement).await?;
+        rows.iter()
+            .map(|row| {
+                let content: String = row.try_get_by_index(0)?;
+                let created_at: NaiveDateTime = row.try_get_by_index(1)?;
+                let distance: f64 = row.try_get_by_index(2)?;
+                Ok(LearnerMemoryMatch {
+                    content,
+                    created_at,
+                    distance,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Whether facts about the user may be stored and recalled. The learner memory has to be enabled and the user has to
+/// opt in.
+pub async fn is_enabled_for(
+    llm_config: &LlmConfig,
+    conn: &DatabaseConnection,
+    user_id: Uuid,
+) -> Result<bool, PgVectorError> {
+    if !llm_config.learner_memory.enabled {
+        return Ok(false);
+    }
+    Ok(learner_memory::Query::has_consent(conn, user_id).await?)
+}
+
+/// Stores facts about the user if the learner memory is enabled for them and removes the oldest memories above the
+/// limit
+#[allow(clippy::too_many_arguments)]
+pub async fn memorize(
+    llm_config: &LlmConfig,
+    conn: &DatabaseConnection,
+    user_id: Uuid,
+    module_id: &str,
+    session_id: &str,
+    conversation_id: Option<Uuid>,
+    facts: &[String],
+) -> Result<usize, PgVectorError> {
+    if facts.is_empty() {
+        return Ok(0);
+    }
+    if !is_enabled_for(llm_config, conn, user_id).await? {
+        tracing::debug!(%user_id, "learner memory is disabled for the user, no facts are stored");
+        return Ok(0);
+    }
+
+    let stored = PgVector::new(llm_config, conn)
+        .insert_learner_memories(user_id, module_id, session_id, conversation_id, facts)
+        .await?;
+    if let Some(max_per_user) = llm_config.learner_memory.max_per_user {
+        let pruned = learner_memory::Mutation::prune_user_memories(conn, user_id, max_per_user).await?;
+        tracing::debug!(%user_id, pruned, "pruned learner memories");
+    }
+    Ok(stored)
+}
+
+/// The memories of the user which are relevant for the query, empty if the learner memory is disabled for them
+pub async fn recall(
+    llm_config: &LlmConfig,
+    conn: &DatabaseConnection,
+    user_id: Uuid,
+    query: &str,
+    limit: u32,
+    max_distance: Option<f64>,
+) -> Result<Vec<LearnerMemoryMatch>, PgVectorError> {
+    if !is_enabled_for(llm_config, conn, user_id).await? {
+        tracing::debug!(%user_id, "learner memory is disabled for the user, nothing is recalled");
+        return Ok(vec![]);
+    }
+
+    let retriever = PgVector::new(llm_config, conn);
+    tokio::time::timeout(
+        std::time::Duration::from_secs(10),
+        retriever.search_learner_memories(user_id, query, limit, max_distance),
+    )
+    .await
+    .map_err(|_| PgVectorError::Timeout)?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector_literal() {
+        assert_eq!(vector_literal(&[0.5, -1.0, 2.25]), "[0.5,-1,2.25]");
+        assert_eq!(vector_literal(&[]), "[]");
+    }
+}
@@ -1,8 +1,10 @@
 use hikari_config::module::content::{ContentExam, QuestionBloomLevel};
 use rand::{rng, seq::SliceRandom};
 
+pub mod bank;
 pub mod error;
 pub mod evaluation;
+mod grading;
 pub mod question;
 
 type ExamQuestion = (String, ContentExam);
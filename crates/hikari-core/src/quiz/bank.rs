@@ -0,0 +1,429 @@
+use crate::quiz::error::QuizError;
+use crate::quiz::grading::MAX_GRADE;
+use crate::quiz::question::{shuffled_matching, shuffled_ordering};
+use hikari_config::module::content::{
+    ContentExam, ContentExamNumeric, ContentExamOption, ContentExamPair, QuestionBloomLevel,
+};
+use hikari_db::quiz::question_bank::QuestionBankEntryInput;
+use hikari_entity::quiz::question::{QuestionType as QuestionTypeModel, Rubric as QuestionRubricModel};
+use hikari_model::quiz::question::{CLOZE_GAP_MARKER, Question, QuestionDetails, QuestionOption};
+use hikari_model::quiz::question_bank::QuestionBankEntry;
+use hikari_model_tools::convert::{IntoDbModel, IntoModel};
+use rand::rng;
+use rand::seq::IndexedRandom;
+use sea_orm::DatabaseConnection;
+use sea_orm::prelude::Uuid;
+use std::collections::HashSet;
+use tracing::instrument;
+
+/// Generated questions are only suggested for the question bank if the user answered them with at least this grade, on
+/// the scale from 0 to `MAX_GRADE`. A question which could only be answered poorly may be unclear or wrong.
+pub const MIN_PROMOTION_GRADE: i32 = MAX_GRADE - 1;
+
+/// Serves an approved question of the question bank which the user has not seen yet.
+///
+/// Returns `Ok(None)` if there is no such question, in which case a new question has to be generated.
+#[instrument(skip(conn), err)]
+pub(crate) async fn serve_bank_question(
+    conn: &DatabaseConnection,
+    user_id: &Uuid,
+    quiz_id: &Uuid,
+    module_id: &str,
+    session_id: &str,
+    topic: &str,
+    level: QuestionBloomLevel,
+) -> Result<Option<Question>, QuizError> {
+    let entries = hikari_db::quiz::question_bank::Query::get_approved_entries(
+        conn,
+        module_id,
+        session_id,
+        topic,
+        &level.into_db_model(),
+    )
+    .await?;
+    if entries.is_empty() {
+        return Ok(None);
+    }
+
+    let served: HashSet<Uuid> = hikari_db::quiz::question::Query::get_served_bank_question_ids(conn, user_id)
+        .await?
+        .into_iter()
+        .collect();
+    let unserved: Vec<_> = entries
+        .into_iter()
+        .filter(|entry| !served.contains(&entry.id))
+        .collect();
+
+    let mut rng = rng();
+    let entry = unserved.choose(&mut rng).cloned();
+    drop(rng);
+    let Some(entry) = entry else {
+        return Ok(None);
+    };
+
+    let details = entry
+        .details
+        .as_deref()
+        .map(serde_json::from_str::<QuestionDetails>)
+        .transpose()?
+        .map(reshuffle)
+        .map(|details| serde_json::to_string(&details))
+        .transpose()?;
+
+    let question = hikari_db::quiz::question::Mutation::create_bank_question(conn, quiz_id, &entry, details.as_deref())
+        .await?
+        .into_model();
+    Ok(Some(question))
+}
+
+/// Shuffles the items of ordering and matching questions again, keeping the solution intact.
+fn reshuffle(details: QuestionDetails) -> QuestionDetails {
+    match details {
+        QuestionDetails::Ordering {
+            items,
+            solution: Some(solution),
+        } => shuffled_ordering(solution.iter().filter_map(|index| items.get(*index).cloned()).collect()),
+        QuestionDetails::Matching {
+            left,
+            right,
+            solution: Some(solution),
+        } => shuffled_matching(
+            left.into_iter()
+                .zip(solution.iter().filter_map(|index| right.get(*index).cloned()))
+                .collect(),
+        ),
+        details => details,
+    }
+}
+
+/// Converts a question in the format of the module content exams into a question bank entry.
+///
+/// Text questions are always graded by the LLM, all other formats use the given rubric.
+pub fn entry_input_from_exam(
+    session_id: String,
+    topic: String,
+    content: Option<String>,
+    exam: ContentExam,
+    rubric: QuestionRubricModel,
+) -> Result<QuestionBankEntryInput, QuizError> {
+    let (question_type, options, details) = exam_to_parts(&exam)?;
+    let rubric = if question_type == QuestionTypeModel::Text {
+        QuestionRubricModel::Llm
+    } else {
+        rubric
+    };
+    let options = options.map(|options| serde_json::to_string(&options)).transpose()?;
+    let details = details.map(|details| serde_json::to_string(&details)).transpose()?;
+
+    Ok(QuestionBankEntryInput {
+        session_id,
+        topic,
+        content: content.unwrap_or_else(|| exam.question.clone()),
+        question: exam.question,
+        question_type,
+        options,
+        details,
+        solution: exam.solution,
+        level: exam.level.into_db_model(),
+        rubric,
+    })
+}
+
+/// Converts a generated question into a question bank entry.
+pub fn entry_input_from_question(question: Question) -> Result<QuestionBankEntryInput, QuizError> {
+    let exam = exam_from_parts(
+        question.question,
+        question.level,
+        question.ai_solution,
+        &question.options,
+        question.details.as_ref(),
+    );
+    entry_input_from_exam(
+        question.session_id,
+        question.topic,
+        Some(question.content),
+        exam,
+        question.rubric.into_db_model(),
+    )
+}
+
+/// Converts a question bank entry into the format of the module content exams.
+#[must_use]
+pub fn exam_from_entry(entry: QuestionBankEntry) -> ContentExam {
+    exam_from_parts(
+        entry.question,
+        entry.level,
+        entry.solution,
+        &entry.options,
+        entry.details.as_ref(),
+    )
+}
+
+fn invalid(message: &str) -> QuizError {
+    QuizError::InvalidBankEntry(message.to_string())
+}
+
+/// Validates an exam and returns the type, the options and the details with items in the correct order.
+fn exam_to_parts(
+    exam: &ContentExam,
+) -> Result<(QuestionTypeModel, Option<Vec<QuestionOption>>, Option<QuestionDetails>), QuizError> {
+    if exam.question.trim().is_empty() {
+        return Err(invalid("the question text is empty"));
+    }
+
+    let formats = [
+        !exam.options.is_empty(),
+        !exam.gaps.is_empty(),
+        !exam.sequence.is_empty(),
+        !exam.pairs.is_empty(),
+        exam.numeric.is_some(),
+    ];
+    if formats.into_iter().filter(|is_set| *is_set).count() > 1 {
+        return Err(invalid("the question mixes several question formats"));
+    }
+
+    if !exam.options.is_empty() {
+        if exam.options.len() < 2 || !exam.options.iter().any(|option| option.is_correct) {
+            return Err(invalid(
+                "multiple choice questions need at least two options and one correct option",
+            ));
+        }
+        let options = exam
+            .options
+            .iter()
+            .map(|option| QuestionOption {
+                option: option.option.clone(),
+                correct: Some(option.is_correct),
+            })
+            .collect();
+        return Ok((QuestionTypeModel::MultipleChoice, Some(options), None));
+    }
+
+    if !exam.gaps.is_empty() {
+        if exam.question.matches(CLOZE_GAP_MARKER).count() != exam.gaps.len() {
+            return Err(invalid("the number of gaps does not match the number of solutions"));
+        }
+        let details = QuestionDetails::Cloze {
+            gap_count: exam.gaps.len(),
+            solutions: Some(exam.gaps.clone()),
+        };
+        return Ok((QuestionTypeModel::Cloze, None, Some(details)));
+    }
+
+    if !exam.sequence.is_empty() {
+        if exam.sequence.len() < 2 {
+            return Err(invalid("ordering questions need at least two items"));
+        }
+        let details = QuestionDetails::Ordering {
+            items: exam.sequence.clone(),
+            solution: Some((0..exam.sequence.len()).collect()),
+        };
+        return Ok((QuestionTypeModel::Ordering, None, Some(details)));
+    }
+
+    if !exam.pairs.is_empty() {
+        if exam.pairs.len() < 2 {
+            return Err(invalid("matching questions need at least two pairs"));
+        }
+        let details = QuestionDetails::Matching {
+            left: exam.pairs.iter().map(|pair| pair.left.clone()).collect(),
+            right: exam.pairs.iter().map(|pair| pair.right.clone()).collect(),
+            solution: Some((0..exam.pairs.len()).collect()),
+        };
+        return Ok((QuestionTypeModel::Matching, None, Some(details)));
+    }
+
+    if let Some(numeric) = &exam.numeric {
+        if !numeric.value.is_finite() || !numeric.tolerance.is_finite() || numeric.tolerance < 0.0 {
+            return Err(invalid(
+                "numeric questions need a finite value and a non-negative tolerance",
+            ));
+        }
+        let details = QuestionDetails::Numeric {
+            unit: numeric.unit.clone(),
+            value: Some(numeric.value),
+            tolerance: Some(numeric.tolerance),
+        };
+        return Ok((QuestionTypeModel::Numeric, None, Some(details)));
+    }
+
+    if exam
+        .solution
+        .as_deref()
+        .is_none_or(|solution| solution.trim().is_empty())
+    {
+        return Err(invalid("text questions need a solution"));
+    }
+    Ok((QuestionTypeModel::Text, None, None))
+}
+
+fn exam_from_parts(
+    question: String,
+    level: QuestionBloomLevel,
+    solution: Option<String>,
+    options: &[QuestionOption],
+    details: Option<&QuestionDetails>,
+) -> ContentExam {
+    let mut exam = ContentExam {
+        level,
+        question,
+        solution,
+        options: options
+            .iter()
+            .map(|option| ContentExamOption {
+                option: option.option.clone(),
+                is_correct: option.correct.unwrap_or(false),
+            })
+            .collect(),
+        gaps: Vec::new(),
+        sequence: Vec::new(),
+        pairs: Vec::new(),
+        numeric: None,
+    };
+
+    match details {
+        Some(QuestionDetails::Cloze { solutions, .. }) => {
+            exam.gaps = solutions.clone().unwrap_or_default();
+        }
+        Some(QuestionDetails::Ordering { items, solution }) => {
+            exam.sequence = match solution {
+                Some(solution) => solution.iter().filter_map(|index| items.get(*index).cloned()).collect(),
+                None => items.clone(),
+            };
+        }
+        Some(QuestionDetails::Matching { left, right, solution }) => {
+            exam.pairs = left
+                .iter()
+                .enumerate()
+                .filter_map(|(position, left)| {
+                    let index = solution
+                        .as_ref()
+                        .map_or(Some(position), |solution| solution.get(position).copied())?;
+                    Some(ContentExamPair {
+                        left: left.clone(),
+                        right: right.get(index)?.clone(),
+                    })
+                })
+                .collect();
+        }
+        Some(QuestionDetails::Numeric { unit, value, tolerance }) => {
+            exam.numeric = value.map(|value| ContentExamNumeric {
+                value,
+                tolerance: tolerance.unwrap_or_default(),
+                unit: unit.clone(),
+            });
+        }
+        None => {}
+    }
+
+    exam
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exam(question: &str) -> ContentExam {
+        ContentExam {
+            level: QuestionBloomLevel::Remember,
+            question: question.to_string(),
+            solution: None,
+            options: Vec::new(),
+            gaps: Vec::new(),
+            sequence: Vec::new(),
+            pairs: Vec::new(),
+            numeric: None,
+        }
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_ordering_roundtrip() {
+        let mut ordering = exam("Ordnen Sie die Phasen.");
+        ordering.sequence = strings(&["Planung", "Durchführung", "Auswertung"]);
+
+        let (question_type, options, details) = exam_to_parts(&ordering).unwrap();
+        assert_eq!(question_type, QuestionTypeModel::Ordering);
+        assert!(options.is_none());
+
+        let served = reshuffle(details.unwrap());
+        let exported = exam_from_parts(ordering.question.clone(), ordering.level, None, &[], Some(&served));
+        assert_eq!(exported.sequence, ordering.sequence);
+    }
+
+    #[test]
+    fn test_matching_roundtrip() {
+        let mut matching = exam("Ordnen Sie die Begriffe zu.");
+        matching.pairs = vec![
+            ContentExamPair {
+                left: "Hund".to_string(),
+                right: "bellt".to_string(),
+            },
+            ContentExamPair {
+                left: "Katze".to_string(),
+                right: "miaut".to_string(),
+            },
+            ContentExamPair {
+                left: "Kuh".to_string(),
+                right: "muht".to_string(),
+            },
+        ];
+
+        let (question_type, _, details) = exam_to_parts(&matching).unwrap();
+        assert_eq!(question_type, QuestionTypeModel::Matching);
+
+        let served = reshuffle(details.unwrap());
+        let exported = exam_from_parts(matching.question.clone(), matching.level, None, &[], Some(&served));
+        let pairs: Vec<(String, String)> = exported.pairs.into_iter().map(|pair| (pair.left, pair.right)).collect();
+        let expected: Vec<(String, String)> = matching.pairs.into_iter().map(|pair| (pair.left, pair.right)).collect();
+        assert_eq!(pairs, expected);
+    }
+
+    #[test]
+    fn test_multiple_choice_roundtrip() {
+        let mut multiple_choice = exam("Welche Farben sind Primärfarben?");
+        multiple_choice.options = vec![
+            ContentExamOption {
+                option: "Rot".to_string(),
+                is_correct: true,
+            },
+            ContentExamOption {
+                option: "Grün".to_string(),
+                is_correct: false,
+            },
+        ];
+
+        let (question_type, options, details) = exam_to_parts(&multiple_choice).unwrap();
+        assert_eq!(question_type, QuestionTypeModel::MultipleChoice);
+        assert!(details.is_none());
+
+        let exported = exam_from_parts(
+            multiple_choice.question.clone(),
+            multiple_choice.level,
+            None,
+            &options.unwrap(),
+            None,
+        );
+        assert_eq!(exported.options.len(), 2);
+        assert!(exported.options.first().unwrap().is_correct);
+        assert!(!exported.options.get(1).unwrap().is_correct);
+    }
+
+    #[test]
+    fn test_invalid_exams() {
+        let mut cloze = exam("Die Hauptstadt von Frankreich ist {{gap}}.");
+        cloze.gaps = strings(&["Paris", "Lyon"]);
+        assert!(matches!(exam_to_parts(&cloze), Err(QuizError::InvalidBankEntry(_))));
+
+        let text = exam("Erklären Sie den Begriff Lernziel.");
+        assert!(matches!(exam_to_parts(&text), Err(QuizError::InvalidBankEntry(_))));
+
+        let mut mixed = exam("Ordnen Sie die Zahlen.");
+        mixed.sequence = strings(&["1", "2"]);
+        mixed.gaps = strings(&["3"]);
+        assert!(matches!(exam_to_parts(&mixed), Err(QuizError::InvalidBankEntry(_))));
+    }
+}
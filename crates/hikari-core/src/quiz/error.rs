@@ -22,4 +22,16 @@ pub enum QuizError {
 
     #[error("Unexpected response format from LLM")]
     UnexpectedResponseFormat,
+
+    #[error("Invalid answer: {0}")]
+    InvalidAnswer(String),
+
+    #[error("The question has no stored solution")]
+    MissingSolution,
+
+    #[error("The question has not been answered yet")]
+    NotAnswered,
+
+    #[error("Invalid question bank entry: {0}")]
+    InvalidBankEntry(String),
 }
@@ -2,6 +2,7 @@ use crate::llm_config::LlmConfig;
 use crate::openai::{CallConfig, openai_single_tool_call};
 use crate::pgvector::search;
 use crate::quiz::error::QuizError;
+use crate::quiz::grading::grade_deterministic;
 use crate::quiz::max_five_random_exam_questions;
 use crate::usage::add_usage;
 use async_openai::types::chat::{
@@ -11,8 +12,8 @@ use async_openai::types::chat::{
 };
 use hikari_config::module::content::ContentExam;
 use hikari_model::llm::vector::embedding_chunk::LlmEmbeddingQueryResult;
-use hikari_model::quiz::question::Question;
 use hikari_model::quiz::question::QuestionType;
+use hikari_model::quiz::question::{Question, QuestionAnswer, QuestionDetails};
 use hikari_model_tools::convert::IntoModel;
 use rand::rng;
 use rand::seq::{IndexedRandom, SliceRandom};
@@ -44,39 +45,33 @@ pub async fn evaluate_answer(
     module_id: &str,
     question: &Question,
     exams: &[(String, ContentExam)],
-    answer: &str,
+    answer: &QuestionAnswer,
     llm_config: &LlmConfig,
     conn: &DatabaseConnection,
     session_sources: Vec<String>,
 ) -> Result<Question, QuizError> {
-    let question_type = &question.r#type;
-    let question_topic = question.topic.clone();
+    if let Some(grading) = grade_deterministic(question, answer)? {
+        tracing::debug!(grade = grading.grade, "graded question deterministically");
+        return store_evaluation(
+            conn,
+            user_id,
+            module_id,
+            question,
+            &answer.to_answer_string(),
+            &grading.evaluation,
+            grading.grade,
+        )
+        .await;
+    }
+
+    // Closed formats are shown to the LLM with the texts of the selected items instead of their indices
+    let prompt_answer = answer_for_prompt(question, answer);
+    let answer = &answer.to_answer_string();
     let question_level = question.level;
     let question_content = question.content.clone();
-    let question_session_id = question.session_id.clone();
     let question_question = question.question.clone();
-    let question_solution = question.ai_solution.clone();
-    let question_options = question.options.clone();
 
-    let question_solution_for_prompt = match question_type {
-        QuestionType::Text => question_solution
-            .clone()
-            .unwrap_or("Keine AI Lösung vorhanden.".to_string()),
-
-        QuestionType::MultipleChoice => {
-            let correct_options: Vec<String> = question_options
-                .iter()
-                .filter(|opt| opt.correct.unwrap_or_default())
-                .map(|opt| opt.option.clone())
-                .collect();
-
-            if correct_options.is_empty() {
-                "Keine korrekten Antwortmöglichkeiten vorhanden.".to_string()
-            } else {
-                correct_options.join(", ")
-            }
-        }
-    };
+    let question_solution_for_prompt = solution_for_prompt(question);
 
     // Shuffle and limit to 5 questions
     let limited_exam_questions: Vec<(String, ContentExam)> =
@@ -148,17 +143,7 @@ pub async fn evaluate_answer(
             question.question
         );
 
-        let solution = if let Some(sol) = &question.solution {
-            sol.clone()
-        } else {
-            question
-                .options
-                .iter()
-                .filter(|opt| opt.is_correct)
-                .map(|opt| opt.option.clone())
-                .collect::<Vec<_>>()
-                .join(", ")
-        };
+        let solution = question.solution_text();
 
         let good_block = vec![
             ChatCompletionRequestMessage::User(ChatCompletionRequestUserMessage {
@@ -268,7 +253,7 @@ pub async fn evaluate_answer(
     ));
 
     prompt_messages.push(ChatCompletionRequestMessage::User(ChatCompletionRequestUserMessage {
-        content: ChatCompletionRequestUserMessageContent::Text(format!("Meine Antwort: {answer}")),
+        content: ChatCompletionRequestUserMessageContent::Text(format!("Meine Antwort: {prompt_answer}")),
         name: None,
     }));
 
@@ -292,11 +277,35 @@ pub async fn evaluate_answer(
         add_usage(conn, user_id, usage, "quiz_generation").await?;
     }
 
-    let score_adjustment = f64::from(evaluation.grade) - 2.5;
-    tracing::debug!(grade = evaluation.grade, %score_adjustment, "evaluation result received");
+    tracing::debug!(grade = evaluation.grade, "evaluation result received");
+
+    store_evaluation(
+        conn,
+        user_id,
+        module_id,
+        question,
+        answer,
+        &evaluation.evaluation,
+        evaluation.grade,
+    )
+    .await
+}
+
+/// Updates the topic score according to the grade and stores the evaluation on the question.
+async fn store_evaluation(
+    conn: &DatabaseConnection,
+    user_id: &Uuid,
+    module_id: &str,
+    question: &Question,
+    answer: &str,
+    evaluation: &str,
+    grade: i32,
+) -> Result<Question, QuizError> {
+    let score_adjustment = f64::from(grade) - 2.5;
+    tracing::debug!(grade, %score_adjustment, "updating score");
 
     let current_score: f64 =
-        hikari_db::quiz::score::Query::get_score_by_topic(conn, user_id, &question_session_id, &question_topic)
+        hikari_db::quiz::score::Query::get_score_by_topic(conn, user_id, &question.session_id, &question.topic)
             .await?
             .unwrap_or(0.0);
 
@@ -309,26 +318,203 @@ pub async fn evaluate_answer(
         conn,
         user_id,
         module_id,
-        &question_session_id,
-        &question_topic,
+        &question.session_id,
+        &question.topic,
         &new_score,
     )
     .await?;
 
-    let updated_question = hikari_db::quiz::question::Mutation::add_evaluation(
-        conn,
-        &question.id,
-        answer,
-        &evaluation.evaluation,
-        &evaluation.grade,
-    )
-    .await?;
+    let updated_question =
+        hikari_db::quiz::question::Mutation::add_evaluation(conn, &question.id, answer, evaluation, &grade).await?;
 
     let question_model: Question = updated_question.into_model();
 
     Ok(question_model)
 }
 
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(
+    description = "Dieses Tool speichert die Erklärung zu einer bereits bewerteten Antwort auf eine Prüfungsfrage. \
+        Immer verwenden, wenn eine Erklärung erzeugt werden soll."
+)]
+struct Explanation {
+    /// Die Erklärung in ganzen Sätzen.
+    explanation: String,
+}
+
+/// Generates an explanation for an already graded answer.
+///
+/// Closed question formats are graded without the LLM, so the explanation is only generated on demand.
+#[instrument(skip(question, llm_config, conn), err)]
+pub async fn explain_answer(
+    user_id: &Uuid,
+    question: &Question,
+    llm_config: &LlmConfig,
+    conn: &DatabaseConnection,
+    session_sources: Vec<String>,
+) -> Result<Question, QuizError> {
+    let (Some(answer), Some(evaluation)) = (&question.answer, &question.evaluation) else {
+        return Err(QuizError::NotAnswered);
+    };
+
+    let solution = solution_for_prompt(question);
+
+    let sources: Vec<LlmEmbeddingQueryResult> =
+        search(llm_config, conn, &question.content, 5, &session_sources).await?;
+
+    let sources_string: String = sources
+        .iter()
+        .enumerate()
+        .map(|(i, e)| format!("# Source {}\n{}", i + 1, e.content))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let prompt_messages: Vec<ChatCompletionRequestMessage> = vec![
+        ChatCompletionRequestMessage::System(ChatCompletionRequestSystemMessage {
+            content: ChatCompletionRequestSystemMessageContent::Text(format!("
+                        # SYSTEM ROLLE
+                        Du bist ein erfahrener, wohlwollender Universitätsprofessor. Die Antwort eines Studierenden auf eine Übungsfrage wurde bereits bewertet. Deine Aufgabe ist es, zu erklären, warum die richtige Lösung richtig ist und was an der Antwort des Studierenden gegebenenfalls falsch war.
+
+                        # TONALITÄT
+                        * Freundlich, professionell, unterstützend und motivierend.
+                        * Sprich den Studierenden direkt mit 'Du' an.
+
+                        # KONTEXT & QUELLEN
+                        Nutze ausschließlich die folgenden Informationen als 'Ground Truth' für deine Erklärung:
+                        <context_sources>
+                        {sources_string}
+                        </context_sources>
+
+                        # LÖSUNG
+                        <solution>
+                        {solution}
+                        </solution>
+
+                        # BEWERTUNG
+                        <evaluation>
+                        {evaluation}
+                        </evaluation>
+
+                        # AUSGABEFORMAT
+                        Gib eine kurze Erklärung in ganzen Sätzen zurück (keine Einleitungstexte)."
+            )),
+            name: None,
+        }),
+        ChatCompletionRequestMessage::User(ChatCompletionRequestUserMessage {
+            content: ChatCompletionRequestUserMessageContent::Text(format!(
+                "Frage: {}\n\nMeine Antwort: {answer}",
+                question.question
+            )),
+            name: None,
+        }),
+    ];
+
+    let openai_config = llm_config.get_quiz_openai_config();
+    let model = llm_config.get_quiz_model();
+
+    let (explanation, tokens) = openai_single_tool_call::<Explanation>(
+        CallConfig::builder()
+            .total_timeout(Duration::from_mins(2))
+            .iteration_timeout(Duration::from_secs(30))
+            .build(),
+        openai_config,
+        None,
+        None,
+        model,
+        prompt_messages,
+    )
+    .await?;
+
+    if let Some(usage) = tokens {
+        add_usage(conn, user_id, usage, "quiz_explanation").await?;
+    }
+
+    let updated_question =
+        hikari_db::quiz::question::Mutation::add_explanation(conn, &question.id, &explanation.explanation).await?;
+
+    Ok(updated_question.into_model())
+}
+
+/// Textual representation of the solution of a question for prompts.
+fn solution_for_prompt(question: &Question) -> String {
+    match (&question.r#type, &question.details) {
+        (_, Some(QuestionDetails::Cloze { solutions, .. })) => solutions.as_ref().map_or_else(
+            || "Keine Lösung vorhanden.".to_string(),
+            |solutions| solutions.join(", "),
+        ),
+        (_, Some(QuestionDetails::Ordering { items, solution })) => solution.as_ref().map_or_else(
+            || "Keine Lösung vorhanden.".to_string(),
+            |solution| {
+                solution
+                    .iter()
+                    .filter_map(|index| items.get(*index).cloned())
+                    .collect::<Vec<_>>()
+                    .join(" → ")
+            },
+        ),
+        (_, Some(QuestionDetails::Matching { left, right, solution })) => solution.as_ref().map_or_else(
+            || "Keine Lösung vorhanden.".to_string(),
+            |solution| {
+                left.iter()
+                    .zip(solution.iter())
+                    .filter_map(|(left, index)| right.get(*index).map(|right| format!("{left} = {right}")))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            },
+        ),
+        (_, Some(QuestionDetails::Numeric { unit, value, .. })) => match (value, unit) {
+            (Some(value), Some(unit)) => format!("{value} {unit}"),
+            (Some(value), None) => value.to_string(),
+            (None, _) => "Keine Lösung vorhanden.".to_string(),
+        },
+        (QuestionType::MultipleChoice, None) => {
+            let correct_options: Vec<String> = question
+                .options
+                .iter()
+                .filter(|opt| opt.correct.unwrap_or_default())
+                .map(|opt| opt.option.clone())
+                .collect();
+
+            if correct_options.is_empty() {
+                "Keine korrekten Antwortmöglichkeiten vorhanden.".to_string()
+            } else {
+                correct_options.join(", ")
+            }
+        }
+        (_, None) => question
+            .ai_solution
+            .clone()
+            .unwrap_or("Keine AI Lösung vorhanden.".to_string()),
+    }
+}
+
+/// The answer as it is shown to the LLM, indices are replaced by the texts they refer to
+fn answer_for_prompt(question: &Question, answer: &QuestionAnswer) -> String {
+    let resolve = |indices: &[usize], texts: &[String]| {
+        indices
+            .iter()
+            .filter_map(|index| texts.get(*index).cloned())
+            .collect::<Vec<_>>()
+    };
+    match (&question.details, answer) {
+        (_, QuestionAnswer::Text(text)) => text.clone(),
+        (_, QuestionAnswer::Texts(texts)) => texts.join(", "),
+        (Some(QuestionDetails::Ordering { items, .. }), QuestionAnswer::Indices(order)) => {
+            resolve(order, items).join(" → ")
+        }
+        (Some(QuestionDetails::Matching { left, right, .. }), QuestionAnswer::Indices(matches)) => left
+            .iter()
+            .zip(matches.iter())
+            .filter_map(|(left, index)| right.get(*index).map(|right| format!("{left} = {right}")))
+            .collect::<Vec<_>>()
+            .join(", "),
+        (_, QuestionAnswer::Indices(indices)) => {
+            let options: Vec<String> = question.options.iter().map(|option| option.option.clone()).collect();
+            resolve(indices, &options).join(", ")
+        }
+    }
+}
+
 fn random_perfect_evaluation_response() -> &'static str {
     let mut rng = rng();
     let response = PERFECT_EVALUATION_RESPONSES
@@ -0,0 +1,391 @@
+use crate::quiz::error::QuizError;
+use hikari_model::quiz::question::{
+    Question, QuestionAnswer, QuestionDetails, QuestionOption, QuestionRubric, QuestionType,
+};
+use std::collections::BTreeSet;
+
+pub(crate) const MAX_GRADE: i32 = 5;
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Grading {
+    pub(crate) grade: i32,
+    pub(crate) evaluation: String,
+}
+
+/// Grades closed question formats without involving the LLM according to the rubric of the question.
+///
+/// Returns `Ok(None)` if the question has to be graded by the LLM.
+pub(crate) fn grade_deterministic(question: &Question, answer: &QuestionAnswer) -> Result<Option<Grading>, QuizError> {
+    let rubric = question.rubric;
+    if rubric == QuestionRubric::Llm {
+        return Ok(None);
+    }
+
+    if matches!(question.r#type, QuestionType::MultipleChoice) {
+        let selected = selected_options(&question.options, answer)?;
+        return Ok(Some(apply_rubric(
+            rubric,
+            grade_multiple_choice(&question.options, &selected)?,
+        )));
+    }
+
+    let Some(details) = &question.details else {
+        return Ok(None);
+    };
+
+    let grading = match details {
+        QuestionDetails::Cloze { solutions, .. } => {
+            let solutions = solutions.as_ref().ok_or(QuizError::MissingSolution)?;
+            let QuestionAnswer::Texts(gaps) = answer else {
+                return Err(QuizError::InvalidAnswer("expected one answer per gap".to_string()));
+            };
+            apply_rubric(rubric, grade_cloze(solutions, gaps))
+        }
+        QuestionDetails::Ordering { items, solution } => {
+            let solution = solution.as_ref().ok_or(QuizError::MissingSolution)?;
+            let QuestionAnswer::Indices(order) = answer else {
+                return Err(QuizError::InvalidAnswer(
+                    "expected the item indices in order".to_string(),
+                ));
+            };
+            apply_rubric(rubric, grade_ordering(items, solution, order))
+        }
+        QuestionDetails::Matching { left, right, solution } => {
+            let solution = solution.as_ref().ok_or(QuizError::MissingSolution)?;
+            let QuestionAnswer::Indices(matches) = answer else {
+                return Err(QuizError::InvalidAnswer(
+                    "expected one index of the right side per left side".to_string(),
+                ));
+            };
+            apply_rubric(rubric, grade_matching(left, right, solution, matches))
+        }
+        QuestionDetails::Numeric { unit, value, tolerance } => {
+            let value = value.ok_or(QuizError::MissingSolution)?;
+            let QuestionAnswer::Text(text) = answer else {
+                return Err(QuizError::InvalidAnswer("expected a number".to_string()));
+            };
+            grade_numeric(value, tolerance.unwrap_or_default(), unit.as_deref(), text)
+        }
+    };
+
+    Ok(Some(grading))
+}
+
+/// Partial credits are discarded for the all-or-nothing rubric.
+fn apply_rubric(rubric: QuestionRubric, grading: Grading) -> Grading {
+    match rubric {
+        QuestionRubric::AllOrNothing if grading.grade < MAX_GRADE => Grading { grade: 0, ..grading },
+        _ => grading,
+    }
+}
+
+/// Resolves the selected options of a multiple choice answer to their indices.
+///
+/// Options can either be selected by index or by their text. A single text may also contain several options
+/// separated by commas or semicolons, as sent by older clients.
+fn selected_options(options: &[QuestionOption], answer: &QuestionAnswer) -> Result<BTreeSet<usize>, QuizError> {
+    let find_option = |text: &str| {
+        options
+            .iter()
+            .position(|option| normalize(&option.option) == normalize(text))
+            .ok_or_else(|| QuizError::InvalidAnswer(format!("unknown option: {text}")))
+    };
+
+    match answer {
+        QuestionAnswer::Indices(indices) => {
+            if let Some(index) = indices.iter().find(|index| **index >= options.len()) {
+                return Err(QuizError::InvalidAnswer(format!("unknown option index: {index}")));
+            }
+            Ok(indices.iter().copied().collect())
+        }
+        QuestionAnswer::Texts(texts) => texts.iter().map(|text| find_option(text)).collect(),
+        QuestionAnswer::Text(text) => find_option(text).map(|index| BTreeSet::from([index])).or_else(|error| {
+            let parts: Vec<&str> = text
+                .split([',', ';'])
+                .map(str::trim)
+                .filter(|part| !part.is_empty())
+                .collect();
+            if parts.len() < 2 {
+                return Err(error);
+            }
+            parts.into_iter().map(find_option).collect()
+        }),
+    }
+}
+
+fn grade_multiple_choice(options: &[QuestionOption], selected: &BTreeSet<usize>) -> Result<Grading, QuizError> {
+    let correct_options: BTreeSet<usize> = options
+        .iter()
+        .enumerate()
+        .filter(|(_, option)| option.correct == Some(true))
+        .map(|(index, _)| index)
+        .collect();
+    if correct_options.is_empty() || options.iter().any(|option| option.correct.is_none()) {
+        return Err(QuizError::MissingSolution);
+    }
+
+    let hits = selected.intersection(&correct_options).count();
+    let misses = selected.difference(&correct_options).count();
+    // Every wrong selection cancels one correct selection, so selecting everything gives no credit
+    let credited = hits.saturating_sub(misses);
+
+    let solution = correct_options
+        .iter()
+        .filter_map(|index| options.get(*index).map(|option| option.option.as_str()))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let evaluation = if hits == correct_options.len() && misses == 0 {
+        "Richtig! Du hast alle richtigen Antworten ausgewählt.".to_string()
+    } else {
+        format!(
+            "Du hast {hits} von {} richtigen Antworten und {misses} falsche Antworten ausgewählt. \
+            Richtig wäre: {solution}.",
+            correct_options.len()
+        )
+    };
+
+    Ok(Grading {
+        grade: partial_grade(credited, correct_options.len()),
+        evaluation,
+    })
+}
+
+fn partial_grade(correct: usize, total: usize) -> i32 {
+    if total == 0 {
+        return 0;
+    }
+    let correct = i32::try_from(correct).unwrap_or(i32::MAX);
+    let total = i32::try_from(total).unwrap_or(i32::MAX);
+    // Rounded integer division to keep the grade on the 0-5 scale
+    (correct.saturating_mul(MAX_GRADE).saturating_add(total / 2) / total).clamp(0, MAX_GRADE)
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+fn grade_cloze(solutions: &[String], gaps: &[String]) -> Grading {
+    let mut wrong = Vec::new();
+    let mut correct = 0;
+    for (index, solution) in solutions.iter().enumerate() {
+        match gaps.get(index) {
+            Some(gap) if normalize(gap) == normalize(solution) => correct += 1,
+            _ => wrong.push(format!("Lücke {}: {solution}", index + 1)),
+        }
+    }
+
+    let mut evaluation = format!("Du hast {correct} von {} Lücken richtig ausgefüllt.", solutions.len());
+    if !wrong.is_empty() {
+        evaluation.push_str(&format!(" Richtig wäre: {}.", wrong.join(", ")));
+    }
+
+    Grading {
+        grade: partial_grade(correct, solutions.len()),
+        evaluation,
+    }
+}
+
+fn grade_ordering(items: &[String], solution: &[usize], order: &[usize]) -> Grading {
+    let correct = solution
+        .iter()
+        .zip(order.iter())
+        .filter(|(expected, given)| expected == given)
+        .count();
+
+    let mut evaluation = format!(
+        "Du hast {correct} von {} Elementen an die richtige Position gesetzt.",
+        solution.len()
+    );
+    if correct != solution.len() {
+        let expected = solution
+            .iter()
+            .filter_map(|index| items.get(*index).map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" → ");
+        evaluation.push_str(&format!(" Die richtige Reihenfolge ist: {expected}."));
+    }
+
+    Grading {
+        grade: partial_grade(correct, solution.len()),
+        evaluation,
+    }
+}
+
+fn grade_matching(left: &[String], right: &[String], solution: &[usize], matches: &[usize]) -> Grading {
+    let mut wrong = Vec::new();
+    let mut correct = 0;
+    for (index, expected) in solution.iter().enumerate() {
+        if matches.get(index) == Some(expected) {
+            correct += 1;
+        } else if let (Some(left), Some(right)) = (left.get(index), right.get(*expected)) {
+            wrong.push(format!("{left} = {right}"));
+        }
+    }
+
+    let mut evaluation = format!("Du hast {correct} von {} Paaren richtig zugeordnet.", solution.len());
+    if !wrong.is_empty() {
+        evaluation.push_str(&format!(" Richtig wäre: {}.", wrong.join(", ")));
+    }
+
+    Grading {
+        grade: partial_grade(correct, solution.len()),
+        evaluation,
+    }
+}
+
+fn grade_numeric(value: f64, tolerance: f64, unit: Option<&str>, answer: &str) -> Grading {
+    let solution = match unit {
+        Some(unit) => format!("{value} {unit}"),
+        None => value.to_string(),
+    };
+
+    let answer = answer.trim();
+    let answer = unit.and_then(|unit| answer.strip_suffix(unit)).unwrap_or(answer).trim();
+    // Accept the German decimal comma as well
+    let Ok(given) = answer.replace(',', ".").parse::<f64>() else {
+        return Grading {
+            grade: 0,
+            evaluation: format!("Deine Antwort ist keine gültige Zahl. Die richtige Lösung ist {solution}."),
+        };
+    };
+
+    if (given - value).abs() <= tolerance.abs() {
+        Grading {
+            grade: MAX_GRADE,
+            evaluation: format!("Richtig! Die Lösung ist {solution}."),
+        }
+    } else {
+        Grading {
+            grade: 0,
+            evaluation: format!("Leider falsch. Die richtige Lösung ist {solution}."),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_grade() {
+        assert_eq!(partial_grade(0, 4), 0);
+        assert_eq!(partial_grade(2, 4), 3);
+        assert_eq!(partial_grade(1, 3), 2);
+        assert_eq!(partial_grade(4, 4), 5);
+        assert_eq!(partial_grade(0, 0), 0);
+    }
+
+    fn option(text: &str, correct: bool) -> QuestionOption {
+        QuestionOption {
+            option: text.to_string(),
+            correct: Some(correct),
+        }
+    }
+
+    #[test]
+    fn test_grade_multiple_choice() {
+        let options = vec![
+            option("Rot", true),
+            option("Grün", false),
+            option("Blau", true),
+            option("Gelb", false),
+        ];
+
+        let grading = grade_multiple_choice(&options, &BTreeSet::from([0, 2])).unwrap();
+        assert_eq!(grading.grade, 5);
+
+        let grading = grade_multiple_choice(&options, &BTreeSet::from([0])).unwrap();
+        assert_eq!(grading.grade, 3);
+        assert!(grading.evaluation.contains("Rot, Blau"));
+
+        let grading = grade_multiple_choice(&options, &BTreeSet::from([0, 1])).unwrap();
+        assert_eq!(grading.grade, 0);
+
+        let grading = grade_multiple_choice(&options, &BTreeSet::from([0, 1, 2, 3])).unwrap();
+        assert_eq!(grading.grade, 0);
+
+        assert_eq!(apply_rubric(QuestionRubric::AllOrNothing, grading).grade, 0);
+    }
+
+    #[test]
+    fn test_selected_options() {
+        let options = vec![option("Rot", true), option("Grün", false)];
+
+        let selected = selected_options(&options, &QuestionAnswer::Texts(vec!["grün".to_string()])).unwrap();
+        assert_eq!(selected, BTreeSet::from([1]));
+
+        let selected = selected_options(&options, &QuestionAnswer::Text("Rot".to_string())).unwrap();
+        assert_eq!(selected, BTreeSet::from([0]));
+
+        assert!(selected_options(&options, &QuestionAnswer::Indices(vec![2])).is_err());
+        assert!(selected_options(&options, &QuestionAnswer::Text("Blau".to_string())).is_err());
+
+        // Joined answers of older clients
+        let selected = selected_options(&options, &QuestionAnswer::Text("Rot, Grün".to_string())).unwrap();
+        assert_eq!(selected, BTreeSet::from([0, 1]));
+        assert!(selected_options(&options, &QuestionAnswer::Text("Rot; Blau".to_string())).is_err());
+
+        // Options containing the separator still match as a whole
+        let options = vec![option("Rot, Grün", true), option("Blau", false)];
+        let selected = selected_options(&options, &QuestionAnswer::Text("Rot, Grün".to_string())).unwrap();
+        assert_eq!(selected, BTreeSet::from([0]));
+    }
+
+    #[test]
+    fn test_apply_rubric() {
+        let grading = Grading {
+            grade: 4,
+            evaluation: String::new(),
+        };
+        assert_eq!(apply_rubric(QuestionRubric::PartialCredit, grading).grade, 4);
+
+        let grading = Grading {
+            grade: 4,
+            evaluation: String::new(),
+        };
+        assert_eq!(apply_rubric(QuestionRubric::AllOrNothing, grading).grade, 0);
+    }
+
+    #[test]
+    fn test_grade_cloze() {
+        let solutions = vec!["Mitochondrium".to_string(), "ATP".to_string()];
+        let grading = grade_cloze(&solutions, &[" mitochondrium ".to_string(), "ATP".to_string()]);
+        assert_eq!(grading.grade, 5);
+
+        let grading = grade_cloze(&solutions, &["Zellkern".to_string()]);
+        assert_eq!(grading.grade, 0);
+        assert!(grading.evaluation.contains("Lücke 2: ATP"));
+    }
+
+    #[test]
+    fn test_grade_ordering() {
+        let items = vec!["b".to_string(), "c".to_string(), "a".to_string()];
+        let solution = vec![2, 0, 1];
+        assert_eq!(grade_ordering(&items, &solution, &[2, 0, 1]).grade, 5);
+
+        let grading = grade_ordering(&items, &solution, &[0, 2, 1]);
+        assert_eq!(grading.grade, 2);
+        assert!(grading.evaluation.contains("a → b → c"));
+    }
+
+    #[test]
+    fn test_grade_matching() {
+        let left = vec!["H2O".to_string(), "NaCl".to_string()];
+        let right = vec!["Salz".to_string(), "Wasser".to_string()];
+        let solution = vec![1, 0];
+        assert_eq!(grade_matching(&left, &right, &solution, &[1, 0]).grade, 5);
+
+        let grading = grade_matching(&left, &right, &solution, &[1, 1]);
+        assert_eq!(grading.grade, 3);
+        assert!(grading.evaluation.contains("NaCl = Salz"));
+    }
+
+    #[test]
+    fn test_grade_numeric() {
+        assert_eq!(grade_numeric(9.81, 0.01, Some("m/s²"), "9,7").grade, 0);
+        assert_eq!(grade_numeric(9.81, 0.01, Some("m/s²"), "9,81 m/s²").grade, 5);
+        assert_eq!(grade_numeric(42.0, 0.0, None, "42").grade, 5);
+        assert_eq!(grade_numeric(42.0, 0.0, None, "zweiundvierzig").grade, 0);
+    }
+}
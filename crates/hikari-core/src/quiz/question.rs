@@ -1,6 +1,7 @@
 use crate::llm_config::LlmConfig;
 use crate::openai::{CallConfig, openai_single_tool_call};
 use crate::pgvector::search;
+use crate::quiz::bank::serve_bank_question;
 use crate::quiz::error::QuizError;
 use crate::quiz::max_five_random_exam_questions;
 use crate::usage::add_usage;
@@ -9,12 +10,13 @@ use async_openai::types::chat::{
     ChatCompletionRequestSystemMessage, ChatCompletionRequestSystemMessageContent, ChatCompletionRequestUserMessage,
     ChatCompletionRequestUserMessageContent,
 };
-use hikari_config::module::content::{ContentExam, QuestionBloomLevel};
+use hikari_config::module::content::{ContentExam, GradingRubric, QuestionBloomLevel};
+use hikari_entity::quiz::question::QuestionType as QuestionTypeModel;
 use hikari_model::llm::vector::embedding_chunk::LlmEmbeddingQueryResult;
-use hikari_model::quiz::question::{Question, QuestionFeedback};
+use hikari_model::quiz::question::{CLOZE_GAP_MARKER, Question, QuestionDetails, QuestionFeedback};
 use hikari_model_tools::convert::{IntoDbModel, IntoModel};
 use rand::rng;
-use rand::seq::IndexedRandom;
+use rand::seq::{IndexedRandom, SliceRandom};
 use schemars::JsonSchema;
 use sea_orm::DatabaseConnection;
 use sea_orm::prelude::Uuid;
@@ -30,20 +32,28 @@ struct Operators {
 }
 
 #[derive(Serialize, JsonSchema, Deserialize)]
-#[schemars(description = "Die generierte Frage. Es gibt entweder eine Textfrage oder eine Multiple Choice Frage.")]
+#[schemars(
+    description = "Die generierte Frage. Es gibt Textfragen, Multiple Choice Fragen, Lückentexte, \
+    Reihenfolgefragen, Zuordnungsfragen und numerische Fragen."
+)]
 struct QuizQuestion {
     question: QuestionType,
 }
 
 #[derive(Serialize, JsonSchema, Deserialize)]
 #[schemars(
-    description = "Die Art der Frage. Es gibt entweder eine Textfrage oder eine Multiple Choice Frage.",
+    description = "Die Art der Frage. Es gibt Textfragen, Multiple Choice Fragen, Lückentexte, \
+    Reihenfolgefragen, Zuordnungsfragen und numerische Fragen.",
     inline
 )]
 #[serde(untagged)]
 enum QuestionType {
     Text(TextQuestion),
     MultipleChoice(MultipleChoiceQuestion),
+    Cloze(ClozeQuestion),
+    Ordering(OrderingQuestion),
+    Matching(MatchingQuestion),
+    Numeric(NumericQuestion),
 }
 
 #[derive(Serialize, Deserialize, JsonSchema)]
@@ -77,6 +87,148 @@ struct MultipleChoiceOption {
     correct: bool,
 }
 
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(
+    description = "Dieses Tool sendet einen Lückentext an den Nutzer. \
+    Jede Lücke wird im Text mit {{gap}} markiert.",
+    inline
+)]
+struct ClozeQuestion {
+    /// Der Lückentext. Jede Lücke wird mit {{gap}} markiert.
+    question: String,
+    /// Die richtigen Lösungen der Lücken in der Reihenfolge, in der sie im Text vorkommen. Jede Lösung besteht aus einem einzelnen Wort oder Begriff.
+    gaps: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(
+    description = "Dieses Tool sendet eine Reihenfolgefrage an den Nutzer. \
+    Der Nutzer muss die Elemente in die richtige Reihenfolge bringen.",
+    inline
+)]
+struct OrderingQuestion {
+    /// Die Frage, die an den Nutzer gestellt werden soll.
+    question: String,
+    /// Die Elemente in der richtigen Reihenfolge. Ca. 3-6 Elemente.
+    sequence: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(
+    description = "Dieses Tool sendet eine Zuordnungsfrage an den Nutzer. \
+    Der Nutzer muss jedem linken Element das passende rechte Element zuordnen.",
+    inline
+)]
+struct MatchingQuestion {
+    /// Die Frage, die an den Nutzer gestellt werden soll.
+    question: String,
+    /// Die richtigen Paare. Ca. 3-6 Paare.
+    pairs: Vec<MatchingPair>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(description = "Ein Paar einer Zuordnungsfrage.", inline)]
+struct MatchingPair {
+    /// Das linke Element
+    left: String,
+    /// Das rechte Element, das zum linken Element gehört
+    right: String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(
+    description = "Dieses Tool sendet eine Rechenaufgabe an den Nutzer, deren Lösung eine einzelne Zahl ist.",
+    inline
+)]
+struct NumericQuestion {
+    /// Die Frage, die an den Nutzer gestellt werden soll.
+    question: String,
+    /// Die richtige Lösung.
+    numeric: NumericSolution,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(description = "Die Lösung einer Rechenaufgabe.", inline)]
+struct NumericSolution {
+    /// Der richtige Wert
+    value: f64,
+    /// Die maximale Abweichung vom richtigen Wert, die noch als richtig gewertet wird (z.B. wegen Rundung)
+    tolerance: f64,
+    /// Die Einheit des Wertes, falls vorhanden
+    unit: Option<String>,
+}
+
+impl QuestionType {
+    /// Converts closed question formats into their stored representation.
+    fn into_details(self) -> Result<(String, QuestionTypeModel, QuestionDetails), QuizError> {
+        let result = match self {
+            QuestionType::Text(_) | QuestionType::MultipleChoice(_) => return Err(QuizError::UnexpectedResponseFormat),
+            QuestionType::Cloze(cloze) => {
+                if cloze.gaps.is_empty() || cloze.question.matches(CLOZE_GAP_MARKER).count() != cloze.gaps.len() {
+                    return Err(QuizError::UnexpectedResponseFormat);
+                }
+                let details = QuestionDetails::Cloze {
+                    gap_count: cloze.gaps.len(),
+                    solutions: Some(cloze.gaps),
+                };
+                (cloze.question, QuestionTypeModel::Cloze, details)
+            }
+            QuestionType::Ordering(ordering) => (
+                ordering.question,
+                QuestionTypeModel::Ordering,
+                shuffled_ordering(ordering.sequence),
+            ),
+            QuestionType::Matching(matching) => {
+                let pairs = matching.pairs.into_iter().map(|pair| (pair.left, pair.right)).collect();
+                (matching.question, QuestionTypeModel::Matching, shuffled_matching(pairs))
+            }
+            QuestionType::Numeric(numeric) => {
+                let details = QuestionDetails::Numeric {
+                    unit: numeric.numeric.unit,
+                    value: Some(numeric.numeric.value),
+                    tolerance: Some(numeric.numeric.tolerance),
+                };
+                (numeric.question, QuestionTypeModel::Numeric, details)
+            }
+        };
+        Ok(result)
+    }
+}
+
+/// Creates the details of an ordering question from the items in the correct order.
+///
+/// The items are shuffled, so the order shown to the user does not give away the solution.
+pub(crate) fn shuffled_ordering(sequence: Vec<String>) -> QuestionDetails {
+    let mut order: Vec<usize> = (0..sequence.len()).collect();
+    order.shuffle(&mut rng());
+    let items = order.iter().filter_map(|index| sequence.get(*index).cloned()).collect();
+    let mut solution: Vec<usize> = (0..order.len()).collect();
+    solution.sort_by_key(|position| order.get(*position).copied());
+    QuestionDetails::Ordering {
+        items,
+        solution: Some(solution),
+    }
+}
+
+/// Creates the details of a matching question from the correct pairs.
+///
+/// The right sides are shuffled, so the order shown to the user does not give away the solution.
+pub(crate) fn shuffled_matching(pairs: Vec<(String, String)>) -> QuestionDetails {
+    let mut order: Vec<usize> = (0..pairs.len()).collect();
+    order.shuffle(&mut rng());
+    let right = order
+        .iter()
+        .filter_map(|index| pairs.get(*index).map(|(_, right)| right.clone()))
+        .collect();
+    let mut solution: Vec<usize> = (0..order.len()).collect();
+    solution.sort_by_key(|position| order.get(*position).copied());
+    QuestionDetails::Matching {
+        left: pairs.into_iter().map(|(left, _)| left).collect(),
+        right,
+        solution: Some(solution),
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 #[instrument(skip(exams, llm_config, conn), err)]
 pub async fn create_question(
@@ -88,7 +240,9 @@ pub async fn create_question(
     llm_config: &LlmConfig,
     conn: &DatabaseConnection,
     quiz_id: &Uuid,
+    module_id: &str,
     rag_documents: &[String],
+    rubric: GradingRubric,
 ) -> Result<Question, QuizError> {
     let score: f64 = hikari_db::quiz::score::Query::get_score_by_topic(conn, user_id, session_id, topic)
         .await?
@@ -96,6 +250,12 @@ pub async fn create_question(
     let level = random_bloom_level(score);
     tracing::debug!(%score, ?level, "determined bloom level for question generation");
 
+    // Curated questions are preferred over generating a new one
+    if let Some(question) = serve_bank_question(conn, user_id, quiz_id, module_id, session_id, topic, level).await? {
+        tracing::debug!(question_id = %question.id, "serving question from question bank");
+        return Ok(question);
+    }
+
     let old_questions = hikari_db::quiz::question::Query::get_question_by_user_topic_level(
         conn,
         user_id,
@@ -146,7 +306,7 @@ pub async fn create_question(
 
                 # ANWEISUNGEN Schritt für Schritt:
                 1. **Analyse:** Lies das Lernmaterial gründlich. Identifiziere Kernkonzepte, die zum geforderten Blooms Level passen.
-                2. **Konstruktion:** Erstelle genau EINE Prüfungsfrage. Entscheide dabei, ob es eine Textfrage oder eine Multiple-Choice-Frage wird, basierend auf dem Blooms Level und der Thematik.
+                2. **Konstruktion:** Erstelle genau EINE Prüfungsfrage. Entscheide dabei, ob es eine Textfrage, eine Multiple-Choice-Frage, ein Lückentext, eine Reihenfolgefrage, eine Zuordnungsfrage oder eine numerische Frage wird, basierend auf dem Blooms Level und der Thematik.
                 3. **Operatoren-Einsatz:** - Wähle 1-2 Operatoren aus der Liste der erlaubten Operatoren (oder passend zum Level).
                 - Integriere diese grammatikalisch korrekt in den Satz. Beachte, dass du die Operatoren teilweise anpassen musst, damit sie in den Satz passen.
                 - **Formatierung:** Markiere die Operatoren im Satz fett mit Markdown (z. B. **Analysieren** Sie...). Markiere NICHTS anderes fett.
@@ -161,6 +321,10 @@ pub async fn create_question(
                 Erstelle nun die finale Prüfungsfrage basierend auf den obigen Anweisungen.
                 Bei Textfragen, gib außerdem noch die richte Antwort auf die Frage an.
                 Bei Multiple-Choice-Fragen, gib außerdem die Antwortmöglichkeiten an (2-5) wobei mindestens eine richtig sein muss.
+                Bei Lückentexten, markiere jede Lücke im Text mit {{{{gap}}}} und gib die Lösungen der Lücken in der richtigen Reihenfolge an.
+                Bei Reihenfolgefragen, gib die Elemente in der richtigen Reihenfolge an.
+                Bei Zuordnungsfragen, gib die richtigen Paare an.
+                Bei numerischen Fragen, gib den richtigen Wert, die erlaubte Abweichung und gegebenenfalls die Einheit an.
                 ",
                 level,
                 operators.dos.join(", "),
@@ -296,6 +460,27 @@ pub async fn create_question(
                 quiz_id,
                 &multiple_choice_question.question,
                 &options,
+                &rubric.into_db_model(),
+                &level.into_db_model(),
+                session_id,
+                topic,
+                content,
+            )
+            .await?
+            .into_model();
+
+            Ok(question)
+        }
+        question_type => {
+            let (question_text, question_type, details) = question_type.into_details()?;
+            let details = serde_json::to_string(&details)?;
+            let question = hikari_db::quiz::question::Mutation::create_detailed_question(
+                conn,
+                quiz_id,
+                &question_text,
+                &question_type,
+                &details,
+                &rubric.into_db_model(),
                 &level.into_db_model(),
                 session_id,
                 topic,
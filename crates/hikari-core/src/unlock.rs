@@ -0,0 +1,69 @@
+use crate::assessment::scale::build_scale_values;
+use futures::future::try_join4;
+use hikari_config::assessment::AssessmentConfig;
+use hikari_entity::assessment::session::AssessmentStatus;
+use hikari_model::module::unlock::UnlockContext;
+use hikari_model::user::User;
+use hikari_model_tools::convert::IntoModel;
+use sea_orm::{DatabaseConnection, DbErr};
+use std::collections::HashMap;
+
+/// Loads everything the unlock triggers of the user's modules are evaluated against.
+pub async fn load_unlock_context(
+    conn: &DatabaseConnection,
+    user: &User,
+    assessments: &AssessmentConfig,
+) -> Result<UnlockContext, DbErr> {
+    let (sessions, module_assessments, quiz_scores, assessment_sessions) = try_join4(
+        hikari_db::module::session::status::Query::all(conn, user.id),
+        hikari_db::module::assessment::Query::all(conn, user.id),
+        hikari_db::quiz::score::Query::get_scores(conn, &user.id),
+        hikari_db::assessment::session::Query::load_sessions(conn, user.id),
+    )
+    .await?;
+
+    let mut scores: HashMap<String, HashMap<String, f64>> = HashMap::new();
+    for score in quiz_scores {
+        let best = scores
+            .entry(score.module_id)
+            .or_default()
+            .entry(score.topic)
+            .or_insert(score.score);
+        *best = best.max(score.score);
+    }
+
+    let assessment_sessions: HashMap<_, _> = assessment_sessions
+        .into_iter()
+        .filter(|session| session.status == AssessmentStatus::Finished)
+        .map(|session| (session.id, session))
+        .collect();
+    let mut pre_assessment_scales = HashMap::new();
+    for module_assessment in module_assessments {
+        let Some(session) = module_assessment.last_pre.and_then(|id| assessment_sessions.get(&id)) else {
+            continue;
+        };
+        let Some(assessment) = assessments.get(&session.assessment) else {
+            tracing::warn!(
+                assessment_id = session.assessment,
+                "assessment of pre-assessment is not configured"
+            );
+            continue;
+        };
+        let answers = hikari_db::assessment::answer::Query::load_answers(conn, session.id).await?;
+        // Scales which can't be evaluated keep their triggers locked, the error is logged by the evaluation
+        let Ok(values) = build_scale_values(assessment, &answers) else {
+            continue;
+        };
+        pre_assessment_scales.insert(
+            module_assessment.module,
+            values.into_iter().map(|value| (value.id, value.value)).collect(),
+        );
+    }
+
+    Ok(UnlockContext {
+        sessions: sessions.into_iter().map(IntoModel::into_model).collect(),
+        groups: user.groups.iter().cloned().collect(),
+        pre_assessment_scales,
+        quiz_scores: scores,
+    })
+}
@@ -1,4 +1,8 @@
+use chrono::NaiveDateTime;
+use hikari_entity::llm::usage::Model as UsageModel;
+use hikari_model::admin::{LlmStepUsage, LlmUsage};
 use sea_orm::{ConnectionTrait, TransactionTrait, prelude::Uuid};
+use std::collections::HashMap;
 
 pub async fn add_usage<C: ConnectionTrait + TransactionTrait>(
     conn: &C,
@@ -19,3 +23,79 @@ pub async fn add_usage<C: ConnectionTrait + TransactionTrait>(
 
     Ok(())
 }
+
+/// Loads the llm usage of the user grouped by step
+pub async fn get_usage<C: ConnectionTrait>(
+    conn: &C,
+    user_id: Uuid,
+    from: Option<NaiveDateTime>,
+    to: Option<NaiveDateTime>,
+) -> Result<LlmUsage, sea_orm::DbErr> {
+    let usages = hikari_db::llm::usage::Query::get_user_usage(conn, user_id, from, to).await?;
+    Ok(aggregate_usage(user_id, usages))
+}
+
+fn aggregate_usage(user_id: Uuid, usages: Vec<UsageModel>) -> LlmUsage {
+    let mut steps: HashMap<String, LlmStepUsage> = HashMap::new();
+    for usage in usages {
+        let tokens = u64::from(usage.tokens);
+        steps
+            .entry(usage.step.clone())
+            .and_modify(|step| {
+                step.tokens += tokens;
+                step.requests += 1;
+                step.last_used_at = step.last_used_at.max(usage.time);
+            })
+            .or_insert(LlmStepUsage {
+                step: usage.step,
+                tokens,
+                requests: 1,
+                last_used_at: usage.time,
+            });
+    }
+    let mut steps: Vec<_> = steps.into_values().collect();
+    steps.sort_by(|a, b| b.tokens.cmp(&a.tokens).then_with(|| a.step.cmp(&b.step)));
+
+    LlmUsage {
+        user_id,
+        total_tokens: steps.iter().map(|step| step.tokens).sum(),
+        steps,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn usage(user_id: Uuid, step: &str, tokens: u32, hour: u32) -> UsageModel {
+        UsageModel {
+            user_id,
+            tokens,
+            step: step.to_string(),
+            time: NaiveDate::from_ymd_opt(2025, 1, 1)
+                .unwrap()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_aggregate_usage() {
+        let user_id = Uuid::new_v4();
+        let usage = aggregate_usage(
+            user_id,
+            vec![
+                usage(user_id, "summary", 100, 1),
+                usage(user_id, "chat", 300, 2),
+                usage(user_id, "summary", 50, 3),
+            ],
+        );
+        assert_eq!(usage.total_tokens, 450);
+        assert_eq!(usage.steps.len(), 2);
+        assert_eq!(usage.steps[0].step, "chat");
+        assert_eq!(usage.steps[1].tokens, 150);
+        assert_eq!(usage.steps[1].requests, 2);
+        assert_eq!(usage.steps[1].last_used_at.time().to_string(), "03:00:00");
+    }
+}
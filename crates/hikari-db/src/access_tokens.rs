@@ -3,3 +3,11 @@ mod query;
 
 pub use mutation::*;
 pub use query::*;
+
+use ring::digest;
+
+/// Tokens are random, so a plain SHA-256 hash is enough to keep leaked database rows from being usable.
+#[must_use]
+pub fn hash_token(token: &str) -> Vec<u8> {
+    digest::digest(&digest::SHA256, token.as_bytes()).as_ref().to_vec()
+}
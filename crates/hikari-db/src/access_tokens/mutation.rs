@@ -1,50 +1,92 @@
-use crate::util::{FlattenTransactionResultExt, generate_token};
+use crate::access_tokens::hash_token;
+use crate::util::generate_token;
+use chrono::NaiveDateTime;
 use hikari_entity::{
     access_tokens,
     access_tokens::{ActiveModel, Entity, Model},
 };
 use sea_orm::ActiveValue::Set;
 use sea_orm::prelude::*;
-use sea_orm::{TransactionTrait, sea_query};
+use sea_orm::sea_query::Expr;
+use std::error::Error;
 
 pub struct Mutation;
 
+/// A newly created access token. The plain token is only available right after its creation.
+pub struct NewAccessToken {
+    pub token: String,
+    pub model: Model,
+}
+
 impl Mutation {
-    pub async fn create_access_token<C: TransactionTrait>(conn: &C, user_id: Uuid) -> Result<Model, DbErr> {
-        let token = ActiveModel {
+    pub async fn create_access_token<C: ConnectionTrait>(
+        conn: &C,
+        user_id: Uuid,
+        device_name: Option<String>,
+        now: NaiveDateTime,
+        expires_at: NaiveDateTime,
+    ) -> Result<NewAccessToken, DbErr> {
+        let token = generate_token();
+        let model = ActiveModel {
             user_id: Set(user_id),
-            access_token: Set(generate_token()),
+            token_hash: Set(hash_token(&token)),
+            device_name: Set(device_name),
+            created_at: Set(now),
+            last_used_at: Set(now),
+            expires_at: Set(expires_at),
             ..Default::default()
-        };
-
-        conn.transaction(|txn| {
-            Box::pin(async move {
-                // Can't use returning because of how sea_orm works
-                Entity::insert(token)
-                    .on_conflict(
-                        sea_query::OnConflict::column(access_tokens::Column::UserId)
-                            .do_nothing()
-                            .clone(),
-                    )
-                    .do_nothing()
-                    .exec(txn)
-                    .await?;
-                let token = Entity::find()
-                    .filter(access_tokens::Column::UserId.eq(user_id))
-                    .one(txn)
-                    .await?;
-                token.ok_or(DbErr::RecordNotFound("Token not found after insertion".to_owned()))
-            })
-        })
+        }
+        .insert(conn)
         .await
-        .flatten_res()
+        .inspect_err(|error| tracing::error!(error = error as &dyn Error, "failed to create access token"))?;
+        Ok(NewAccessToken { token, model })
+    }
+
+    /// Marks the token as used and extends its lifetime.
+    pub async fn refresh_access_token<C: ConnectionTrait>(
+        conn: &C,
+        id: i32,
+        now: NaiveDateTime,
+        expires_at: NaiveDateTime,
+    ) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(access_tokens::Column::LastUsedAt, Expr::value(now))
+            .col_expr(access_tokens::Column::ExpiresAt, Expr::value(expires_at))
+            .filter(access_tokens::Column::Id.eq(id))
+            .exec(conn)
+            .await
+            .inspect_err(|error| tracing::error!(error = error as &dyn Error, "failed to refresh access token"))?;
+        Ok(())
     }
 
-    pub async fn delete_access_token<C: ConnectionTrait>(conn: &C, user_id: Uuid) -> Result<(), DbErr> {
+    /// Revokes a single token of the user. Returns `false` if the user has no such token.
+    pub async fn delete_access_token<C: ConnectionTrait>(conn: &C, user_id: Uuid, id: i32) -> Result<bool, DbErr> {
+        let res = Entity::delete_many()
+            .filter(access_tokens::Column::UserId.eq(user_id))
+            .filter(access_tokens::Column::Id.eq(id))
+            .exec(conn)
+            .await
+            .inspect_err(|error| tracing::error!(error = error as &dyn Error, "failed to delete access token"))?;
+        Ok(res.rows_affected > 0)
+    }
+
+    pub async fn delete_user_access_tokens<C: ConnectionTrait>(conn: &C, user_id: Uuid) -> Result<(), DbErr> {
         Entity::delete_many()
             .filter(access_tokens::Column::UserId.eq(user_id))
             .exec(conn)
-            .await?;
+            .await
+            .inspect_err(|error| tracing::error!(error = error as &dyn Error, "failed to delete access tokens"))?;
         Ok(())
     }
+
+    pub async fn delete_expired_access_tokens<C: ConnectionTrait>(conn: &C, now: NaiveDateTime) -> Result<u64, DbErr> {
+        let res = Entity::delete_many()
+            .filter(access_tokens::Column::ExpiresAt.lte(now))
+            .exec(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to delete expired access tokens");
+            })?;
+        Ok(res.rows_affected)
+    }
 }
@@ -1,5 +1,9 @@
-use hikari_entity::access_tokens::{Entity, Model};
-use sea_orm::{DbConn, DbErr, EntityTrait};
+use crate::access_tokens::hash_token;
+use chrono::NaiveDateTime;
+use hikari_entity::access_tokens::{Column, Entity, Model};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbConn, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use std::error::Error;
+use uuid::Uuid;
 
 pub struct Query;
 
@@ -7,4 +11,33 @@ impl Query {
     pub async fn find_by_id(db: &DbConn, id: i32) -> Result<Option<Model>, DbErr> {
         Entity::find_by_id(id).one(db).await
     }
+
+    /// Finds the token if it exists and is not expired
+    pub async fn find_valid_by_token<C: ConnectionTrait>(
+        conn: &C,
+        token: &str,
+        now: NaiveDateTime,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::TokenHash.eq(hash_token(token)))
+            .filter(Column::ExpiresAt.gt(now))
+            .one(conn)
+            .await
+            .inspect_err(|error| tracing::error!(error = error as &dyn Error, "failed to find access token"))
+    }
+
+    /// Lists the valid tokens of the user, most recently used first
+    pub async fn get_user_access_tokens<C: ConnectionTrait>(
+        conn: &C,
+        user_id: Uuid,
+        now: NaiveDateTime,
+    ) -> Result<Vec<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::ExpiresAt.gt(now))
+            .order_by_desc(Column::LastUsedAt)
+            .all(conn)
+            .await
+            .inspect_err(|error| tracing::error!(error = error as &dyn Error, "failed to load access tokens"))
+    }
 }
@@ -0,0 +1,109 @@
+use chrono::{NaiveDateTime, Utc};
+use hikari_entity::allocation::{self, ActiveModel as AllocationActiveModel};
+use hikari_entity::allocation_sequence::{self, ActiveModel as SequenceActiveModel};
+use sea_orm::ActiveValue::Set;
+use sea_orm::sea_query::{Expr, OnConflict};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter};
+use std::error::Error;
+use uuid::Uuid;
+
+pub struct Mutation;
+
+impl Mutation {
+    /// Creates the allocation sequence or returns the sequence which already exists for the token, slot and
+    /// stratum. `seed` and `block_size` are only used for a new sequence.
+    pub async fn get_or_create_sequence<C: ConnectionTrait>(
+        conn: &C,
+        token: &str,
+        slot: i32,
+        stratum: &str,
+        seed: i64,
+        block_size: i32,
+    ) -> Result<allocation_sequence::Model, DbErr> {
+        let sequence = SequenceActiveModel {
+            id: Set(Uuid::new_v4()),
+            token: Set(token.to_owned()),
+            slot: Set(slot),
+            stratum: Set(stratum.to_owned()),
+            seed: Set(seed),
+            block_size: Set(block_size),
+            created_at: Set(Utc::now().naive_utc()),
+        };
+
+        allocation_sequence::Entity::insert(sequence)
+            .on_conflict(
+                OnConflict::columns([
+                    allocation_sequence::Column::Token,
+                    allocation_sequence::Column::Slot,
+                    allocation_sequence::Column::Stratum,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .exec_without_returning(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to create allocation sequence");
+            })?;
+
+        super::Query::find_sequence(conn, token, slot, stratum)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound("allocation sequence not found after get_or_create".to_owned()))
+    }
+
+    /// Appends a block of unallocated positions starting at `start`.
+    ///
+    /// Blocks are generated deterministically, so a block inserted concurrently by another request is identical and
+    /// the conflict is ignored.
+    pub async fn insert_block<C: ConnectionTrait>(
+        conn: &C,
+        sequence_id: Uuid,
+        start: i32,
+        groups: Vec<String>,
+    ) -> Result<(), DbErr> {
+        let rows = (start..)
+            .zip(groups)
+            .map(|(position, group_name)| AllocationActiveModel {
+                sequence_id: Set(sequence_id),
+                position: Set(position),
+                group_name: Set(group_name),
+                user_id: Set(None),
+                allocated_at: Set(None),
+            });
+
+        allocation::Entity::insert_many(rows)
+            .on_conflict(
+                OnConflict::columns([allocation::Column::SequenceId, allocation::Column::Position])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, %sequence_id, "failed to insert allocation block");
+            })?;
+        Ok(())
+    }
+
+    /// Allocates the position to the user. Returns `false` if it was allocated by someone else in the meantime.
+    pub async fn claim<C: ConnectionTrait>(
+        conn: &C,
+        sequence_id: Uuid,
+        position: i32,
+        user_id: Uuid,
+        now: NaiveDateTime,
+    ) -> Result<bool, DbErr> {
+        let res = allocation::Entity::update_many()
+            .col_expr(allocation::Column::UserId, Expr::value(user_id))
+            .col_expr(allocation::Column::AllocatedAt, Expr::value(now))
+            .filter(allocation::Column::SequenceId.eq(sequence_id))
+            .filter(allocation::Column::Position.eq(position))
+            .filter(allocation::Column::AllocatedAt.is_null())
+            .exec(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, %sequence_id, position, "failed to claim allocation");
+            })?;
+        Ok(res.rows_affected > 0)
+    }
+}
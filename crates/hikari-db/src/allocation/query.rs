@@ -0,0 +1,93 @@
+use hikari_entity::allocation;
+use hikari_entity::allocation_sequence;
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use std::error::Error;
+use uuid::Uuid;
+
+pub struct Query;
+
+impl Query {
+    pub async fn find_sequence<C: ConnectionTrait>(
+        conn: &C,
+        token: &str,
+        slot: i32,
+        stratum: &str,
+    ) -> Result<Option<allocation_sequence::Model>, DbErr> {
+        allocation_sequence::Entity::find()
+            .filter(allocation_sequence::Column::Token.eq(token))
+            .filter(allocation_sequence::Column::Slot.eq(slot))
+            .filter(allocation_sequence::Column::Stratum.eq(stratum))
+            .one(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load allocation sequence");
+            })
+    }
+
+    /// All sequences, optionally only the ones of a single access token
+    pub async fn sequences<C: ConnectionTrait>(
+        conn: &C,
+        token: Option<&str>,
+    ) -> Result<Vec<allocation_sequence::Model>, DbErr> {
+        let mut query = allocation_sequence::Entity::find();
+        if let Some(token) = token {
+            query = query.filter(allocation_sequence::Column::Token.eq(token));
+        }
+        query
+            .order_by_asc(allocation_sequence::Column::Token)
+            .order_by_asc(allocation_sequence::Column::Slot)
+            .order_by_asc(allocation_sequence::Column::Stratum)
+            .all(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load allocation sequences");
+            })
+    }
+
+    /// The positions of the sequences in order
+    pub async fn allocations<C: ConnectionTrait>(
+        conn: &C,
+        sequence_ids: Vec<Uuid>,
+    ) -> Result<Vec<allocation::Model>, DbErr> {
+        allocation::Entity::find()
+            .filter(allocation::Column::SequenceId.is_in(sequence_ids))
+            .order_by_asc(allocation::Column::SequenceId)
+            .order_by_asc(allocation::Column::Position)
+            .all(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load allocations");
+            })
+    }
+
+    /// The first position of the sequence which is not allocated yet
+    pub async fn next_free<C: ConnectionTrait>(
+        conn: &C,
+        sequence_id: Uuid,
+    ) -> Result<Option<allocation::Model>, DbErr> {
+        allocation::Entity::find()
+            .filter(allocation::Column::SequenceId.eq(sequence_id))
+            .filter(allocation::Column::AllocatedAt.is_null())
+            .order_by_asc(allocation::Column::Position)
+            .one(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, %sequence_id, "failed to load next free allocation");
+            })
+    }
+
+    /// Number of positions generated for the sequence so far
+    pub async fn length<C: ConnectionTrait>(conn: &C, sequence_id: Uuid) -> Result<i32, DbErr> {
+        let last = allocation::Entity::find()
+            .select_only()
+            .column_as(allocation::Column::Position.max(), "position")
+            .filter(allocation::Column::SequenceId.eq(sequence_id))
+            .into_tuple::<Option<i32>>()
+            .one(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, %sequence_id, "failed to load allocation sequence length");
+            })?;
+        Ok(last.flatten().map_or(0, |last| last + 1))
+    }
+}
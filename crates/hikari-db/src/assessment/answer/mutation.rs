@@ -81,4 +81,21 @@ impl Mutation {
             .await?;
         Ok(())
     }
+
+    /// Deletes all answers of the session except the ones to the given questions
+    pub async fn delete_except<C: ConnectionTrait>(
+        conn: &C,
+        assessment_session_id: Uuid,
+        questions: Vec<String>,
+    ) -> Result<u64, DbErr> {
+        let res = AnswerEntity::delete_many()
+            .filter(answer::Column::AssessmentSessionId.eq(assessment_session_id))
+            .filter(answer::Column::Question.is_not_in(questions))
+            .exec(conn)
+            .await
+            .inspect_err(
+                |error| tracing::error!(error = error as &dyn Error, %assessment_session_id, "failed to delete answers"),
+            )?;
+        Ok(res.rows_affected)
+    }
 }
@@ -15,4 +15,21 @@ impl Query {
                 |error| tracing::error!(error = error as &dyn std::error::Error, %session_id, "failed to load answers"),
             )
     }
+
+    /// Loads the answers of multiple sessions at once.
+    pub async fn load_answers_of_sessions<C: ConnectionTrait>(
+        conn: &C,
+        session_ids: Vec<Uuid>,
+    ) -> Result<Vec<Answer>, DbErr> {
+        AnswerEntity::find()
+            .filter(answer::Column::AssessmentSessionId.is_in(session_ids))
+            .all(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(
+                    error = error as &dyn std::error::Error,
+                    "failed to load answers of sessions"
+                );
+            })
+    }
 }
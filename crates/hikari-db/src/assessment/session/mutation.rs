@@ -56,6 +56,9 @@ impl Mutation {
         conn.transaction(|conn| {
             Box::pin(async move {
                 Self::set_assessment_status(conn, session_id, AssessmentStatus::Finished).await?;
+                // Answers to questions which were skipped in the end are removed
+                let questions = answers.iter().map(|answer| answer.question.clone()).collect();
+                answer::Mutation::delete_except(conn, session_id, questions).await?;
                 answer::Mutation::insert_or_update_many(conn, session_id, answers).await?;
                 Ok(())
             })
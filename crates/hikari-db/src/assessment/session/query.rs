@@ -26,4 +26,16 @@ impl Query {
             .await
             .inspect_err(|error| tracing::error!(error = error as &dyn Error, %user_id, "failed to load sessions"))
     }
+
+    /// Loads the sessions with the given ids, unknown ids are left out.
+    pub async fn load_sessions_by_ids<C: ConnectionTrait>(
+        conn: &C,
+        session_ids: Vec<Uuid>,
+    ) -> Result<Vec<Session>, DbErr> {
+        SessionEntity::find()
+            .filter(session::Column::Id.is_in(session_ids))
+            .all(conn)
+            .await
+            .inspect_err(|error| tracing::error!(error = error as &dyn Error, "failed to load sessions by id"))
+    }
 }
@@ -0,0 +1,35 @@
+use chrono::Utc;
+use hikari_entity::audit_log::{ActiveModel, Model};
+use sea_orm::ActiveValue::Set;
+use sea_orm::prelude::*;
+use std::error::Error;
+
+pub struct Mutation;
+
+/// An entry which is about to be written to the audit log
+#[derive(Debug, Clone)]
+pub struct NewAuditEntry {
+    pub actor_id: Option<Uuid>,
+    pub action: String,
+    pub subject_id: Option<Uuid>,
+    pub request_id: Option<String>,
+    pub details: Json,
+}
+
+impl Mutation {
+    /// Appends an entry to the audit log. Entries can never be changed or removed.
+    pub async fn append<C: ConnectionTrait>(conn: &C, entry: NewAuditEntry) -> Result<Model, DbErr> {
+        ActiveModel {
+            id: Set(Uuid::new_v4()),
+            created_at: Set(Utc::now().naive_utc()),
+            actor_id: Set(entry.actor_id),
+            action: Set(entry.action),
+            subject_id: Set(entry.subject_id),
+            request_id: Set(entry.request_id),
+            details: Set(entry.details),
+        }
+        .insert(conn)
+        .await
+        .inspect_err(|error| tracing::error!(error = error as &dyn Error, "failed to write audit log entry"))
+    }
+}
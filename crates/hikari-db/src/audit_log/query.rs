@@ -0,0 +1,52 @@
+use chrono::NaiveDateTime;
+use hikari_entity::audit_log::{Column, Entity, Model};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use std::error::Error;
+use uuid::Uuid;
+
+pub struct Query;
+
+/// Restricts which audit log entries are returned. Unset fields match all entries.
+#[derive(Debug, Clone, Default)]
+pub struct AuditLogFilter {
+    pub actor_id: Option<Uuid>,
+    pub subject_id: Option<Uuid>,
+    pub action: Option<String>,
+    pub from: Option<NaiveDateTime>,
+    pub to: Option<NaiveDateTime>,
+}
+
+impl Query {
+    /// Lists matching entries, newest first
+    pub async fn find<C: ConnectionTrait>(
+        conn: &C,
+        filter: AuditLogFilter,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<Model>, DbErr> {
+        let mut query = Entity::find();
+        if let Some(actor_id) = filter.actor_id {
+            query = query.filter(Column::ActorId.eq(actor_id));
+        }
+        if let Some(subject_id) = filter.subject_id {
+            query = query.filter(Column::SubjectId.eq(subject_id));
+        }
+        if let Some(action) = filter.action {
+            query = query.filter(Column::Action.eq(action));
+        }
+        if let Some(from) = filter.from {
+            query = query.filter(Column::CreatedAt.gte(from));
+        }
+        if let Some(to) = filter.to {
+            query = query.filter(Column::CreatedAt.lt(to));
+        }
+        query
+            .order_by_desc(Column::CreatedAt)
+            .order_by_desc(Column::Id)
+            .limit(limit)
+            .offset(offset)
+            .all(conn)
+            .await
+            .inspect_err(|error| tracing::error!(error = error as &dyn Error, "failed to load audit log"))
+    }
+}
@@ -0,0 +1,5 @@
+mod mutation;
+mod query;
+
+pub use mutation::*;
+pub use query::*;
@@ -0,0 +1,54 @@
+use chrono::{NaiveDateTime, Utc};
+use hikari_entity::crisis_event::{ActiveModel, Column, Entity, Model};
+use sea_orm::ActiveValue::Set;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter};
+use std::error::Error;
+use uuid::Uuid;
+
+pub struct Mutation;
+
+impl Mutation {
+    /// Logs a detection, the text which triggered it is not part of the event
+    pub async fn log<C: ConnectionTrait>(
+        conn: &C,
+        user_id: Uuid,
+        source: &str,
+        reference_id: Option<Uuid>,
+        detector: &str,
+    ) -> Result<Model, DbErr> {
+        ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            source: Set(source.to_owned()),
+            reference_id: Set(reference_id),
+            detector: Set(detector.to_owned()),
+            created_at: Set(Utc::now().naive_utc()),
+            notified_at: Set(None),
+        }
+        .insert(conn)
+        .await
+        .inspect_err(|error| tracing::error!(error = error as &dyn Error, "failed to log crisis event"))
+    }
+
+    /// Marks the event as delivered to the counselor webhook
+    pub async fn mark_notified<C: ConnectionTrait>(conn: &C, id: Uuid) -> Result<bool, DbErr> {
+        let result = Entity::update_many()
+            .col_expr(Column::NotifiedAt, Expr::value(Utc::now().naive_utc()))
+            .filter(Column::Id.eq(id))
+            .exec(conn)
+            .await
+            .inspect_err(|error| tracing::error!(error = error as &dyn Error, "failed to mark crisis event"))?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Removes events created before the given time, returns the number of removed events
+    pub async fn delete_before<C: ConnectionTrait>(conn: &C, before: NaiveDateTime) -> Result<u64, DbErr> {
+        let result = Entity::delete_many()
+            .filter(Column::CreatedAt.lt(before))
+            .exec(conn)
+            .await
+            .inspect_err(|error| tracing::error!(error = error as &dyn Error, "failed to remove crisis events"))?;
+        Ok(result.rows_affected)
+    }
+}
@@ -0,0 +1,34 @@
+use chrono::NaiveDateTime;
+use hikari_entity::crisis_event::{Column, Entity, Model};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use std::error::Error;
+use uuid::Uuid;
+
+pub struct Query;
+
+impl Query {
+    pub async fn find_by_id<C: ConnectionTrait>(conn: &C, id: Uuid) -> Result<Option<Model>, DbErr> {
+        Entity::find_by_id(id)
+            .one(conn)
+            .await
+            .inspect_err(|error| tracing::error!(error = error as &dyn Error, "failed to load crisis event"))
+    }
+
+    /// Events created before the given time which were not delivered to the counselor webhook yet, oldest first
+    pub async fn find_unnotified<C: ConnectionTrait>(
+        conn: &C,
+        before: NaiveDateTime,
+        limit: u64,
+    ) -> Result<Vec<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::NotifiedAt.is_null())
+            .filter(Column::CreatedAt.lt(before))
+            .order_by_asc(Column::CreatedAt)
+            .limit(limit)
+            .all(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load undelivered crisis events");
+            })
+    }
+}
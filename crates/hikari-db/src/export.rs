@@ -0,0 +1,4 @@
+pub mod job;
+mod query;
+
+pub use query::*;
@@ -0,0 +1,90 @@
+use crate::util::lease;
+use chrono::{NaiveDateTime, Utc};
+use hikari_entity::export_job::{ActiveModel, Column, Entity, Model, Status};
+use sea_orm::ActiveValue::Set;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter};
+use std::error::Error;
+use uuid::Uuid;
+
+pub struct Mutation;
+
+impl Mutation {
+    pub async fn create<C: ConnectionTrait>(
+        conn: &C,
+        user_id: Uuid,
+        expires_at: NaiveDateTime,
+    ) -> Result<Model, DbErr> {
+        let now = Utc::now().naive_utc();
+        ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            status: Set(Status::Pending),
+            attempts: Set(0),
+            lease_owner: Set(None),
+            lease_expires_at: Set(None),
+            archive_key: Set(None),
+            error: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+            expires_at: Set(expires_at),
+        }
+        .insert(conn)
+        .await
+        .inspect_err(|error| {
+            tracing::error!(error = error as &dyn Error, "failed to create export job");
+        })
+    }
+
+    /// Tries to claim a job for `owner` until `lease_expires_at`, see [`lease::claim`].
+    pub async fn claim<C: ConnectionTrait>(
+        conn: &C,
+        job_id: Uuid,
+        owner: &str,
+        lease_expires_at: NaiveDateTime,
+    ) -> Result<Option<Model>, DbErr> {
+        lease::claim::<Entity, _>(conn, job_id, owner, lease_expires_at).await
+    }
+
+    /// Marks a job as completed. Does nothing if the lease was taken over by another instance.
+    pub async fn complete<C: ConnectionTrait>(
+        conn: &C,
+        job_id: Uuid,
+        owner: &str,
+        archive_key: &str,
+    ) -> Result<bool, DbErr> {
+        let res = lease::release::<Entity>(job_id, owner, Status::Completed, None)
+            .col_expr(Column::ArchiveKey, Expr::value(archive_key))
+            .exec(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to complete export job");
+            })?;
+        Ok(res.rows_affected > 0)
+    }
+
+    /// Releases a job after an error. It is picked up again if `retry` is set, otherwise it is marked as failed.
+    pub async fn fail<C: ConnectionTrait>(
+        conn: &C,
+        job_id: Uuid,
+        owner: &str,
+        error: &str,
+        retry: bool,
+    ) -> Result<bool, DbErr> {
+        lease::fail::<Entity, _>(conn, job_id, owner, error, retry).await
+    }
+
+    /// Deletes the given jobs and returns how many were removed.
+    ///
+    /// Their archives have to be removed from the export storage beforehand.
+    pub async fn delete<C: ConnectionTrait>(conn: &C, job_ids: Vec<Uuid>) -> Result<u64, DbErr> {
+        let res = Entity::delete_many()
+            .filter(Column::Id.is_in(job_ids))
+            .exec(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to delete export jobs");
+            })?;
+        Ok(res.rows_affected)
+    }
+}
@@ -0,0 +1,67 @@
+use crate::util::lease;
+use chrono::NaiveDateTime;
+use hikari_entity::export_job::{Column, Entity, Model, Status};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, PaginatorTrait, QueryFilter};
+use std::error::Error;
+use uuid::Uuid;
+
+pub struct Query;
+
+impl Query {
+    pub async fn get_user_job<C: ConnectionTrait>(
+        conn: &C,
+        user_id: Uuid,
+        job_id: Uuid,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::Id.eq(job_id))
+            .filter(Column::UserId.eq(user_id))
+            .one(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load export job");
+            })
+    }
+
+    /// Number of jobs of the user which are queued or running.
+    pub async fn count_active<C: ConnectionTrait>(conn: &C, user_id: Uuid) -> Result<u64, DbErr> {
+        Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::Status.is_in([Status::Pending, Status::Running]))
+            .count(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to count active export jobs");
+            })
+    }
+
+    /// Jobs which are waiting to be processed or whose lease expired, oldest first.
+    pub async fn find_claimable<C: ConnectionTrait>(
+        conn: &C,
+        now: NaiveDateTime,
+        limit: u64,
+    ) -> Result<Vec<Model>, DbErr> {
+        lease::find_claimable::<Entity, _>(conn, now, limit).await
+    }
+
+    /// Jobs which expired before `now`.
+    pub async fn find_expired<C: ConnectionTrait>(conn: &C, now: NaiveDateTime) -> Result<Vec<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::ExpiresAt.lt(now))
+            .all(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load expired export jobs");
+            })
+    }
+
+    pub async fn find_by_user<C: ConnectionTrait>(conn: &C, user_id: Uuid) -> Result<Vec<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .all(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load export jobs of user");
+            })
+    }
+}
@@ -0,0 +1,491 @@
+use hikari_entity::assessment::{answer, session as assessment_session};
+use hikari_entity::history::{history_assessment, history_module, history_session};
+use hikari_entity::journal::{
+    journal_content, journal_entry, journal_entry_journal_prompt, journal_entry_tag, journal_prompt, journal_summary,
+    journal_topic,
+};
+use hikari_entity::llm::slot::{conversation_slot, global_slot, module_slot, session_slot};
+use hikari_entity::llm::{
+    audio, conversation, conversation_state, guardrail_event, image, learner_memory, memory_summary, message, usage,
+};
+use hikari_entity::module::{assessment as module_assessment, session::status as session_status, status};
+use hikari_entity::quiz::{question, quiz, quiz_sessions, score};
+use hikari_entity::{
+    access_tokens, allocation, config, crisis_event, custom_groups, groups_token, history, oidc_groups, oidc_mapping,
+    planner_entry, tag, user, user_context_logs, user_handle,
+};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, JsonValue, QueryFilter, QuerySelect, Select};
+use std::error::Error;
+use uuid::Uuid;
+
+/// Part of the personal data of a user, exported as one document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExportDomain {
+    Profile,
+    Configs,
+    ContextLogs,
+    Journal,
+    Conversations,
+    Slots,
+    Quizzes,
+    Assessments,
+    Planner,
+    History,
+}
+
+impl ExportDomain {
+    pub const ALL: [Self; 10] = [
+        Self::Profile,
+        Self::Configs,
+        Self::ContextLogs,
+        Self::Journal,
+        Self::Conversations,
+        Self::Slots,
+        Self::Quizzes,
+        Self::Assessments,
+        Self::Planner,
+        Self::History,
+    ];
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Profile => "profile",
+            Self::Configs => "configs",
+            Self::ContextLogs => "context_logs",
+            Self::Journal => "journal",
+            Self::Conversations => "conversations",
+            Self::Slots => "slots",
+            Self::Quizzes => "quizzes",
+            Self::Assessments => "assessments",
+            Self::Planner => "planner",
+            Self::History => "history",
+        }
+    }
+}
+
+pub struct Query;
+
+async fn rows<C: ConnectionTrait, E: EntityTrait>(conn: &C, select: Select<E>) -> Result<JsonValue, DbErr> {
+    Ok(JsonValue::Array(select.into_json().all(conn).await?))
+}
+
+async fn ids<C: ConnectionTrait, E: EntityTrait>(
+    conn: &C,
+    select: Select<E>,
+    column: E::Column,
+) -> Result<Vec<Uuid>, DbErr> {
+    select.select_only().column(column).into_tuple::<Uuid>().all(conn).await
+}
+
+impl Query {
+    /// Loads all records of the user belonging to the domain as an object which maps table names to rows.
+    ///
+    /// Secrets like token hashes are left out.
+    pub async fn load<C: ConnectionTrait>(conn: &C, user_id: Uuid, domain: ExportDomain) -> Result<JsonValue, DbErr> {
+        let tables = match domain {
+            ExportDomain::Profile => Self::profile(conn, user_id).await,
+            ExportDomain::Configs => Self::configs(conn, user_id).await,
+            ExportDomain::ContextLogs => Self::context_logs(conn, user_id).await,
+            ExportDomain::Journal => Self::journal(conn, user_id).await,
+            ExportDomain::Conversations => Self::conversations(conn, user_id).await,
+            ExportDomain::Slots => Self::slots(conn, user_id).await,
+            ExportDomain::Quizzes => Self::quizzes(conn, user_id).await,
+            ExportDomain::Assessments => Self::assessments(conn, user_id).await,
+            ExportDomain::Planner => Self::planner(conn, user_id).await,
+            ExportDomain::History => Self::history(conn, user_id).await,
+        }
+        .inspect_err(|error| {
+            tracing::error!(error = error as &dyn Error, %user_id, domain = domain.name(), "failed to export user data");
+        })?;
+        Ok(tables.into_iter().collect())
+    }
+
+    async fn profile<C: ConnectionTrait>(conn: &C, user_id: Uuid) -> Result<Vec<(&'static str, JsonValue)>, DbErr> {
+        Ok(vec![
+            ("users", rows(conn, user::Entity::find_by_id(user_id)).await?),
+            (
+                "oidc_groups",
+                rows(
+                    conn,
+                    oidc_groups::Entity::find().filter(oidc_groups::Column::UserId.eq(user_id)),
+                )
+                .await?,
+            ),
+            (
+                "custom_groups",
+                rows(
+                    conn,
+                    custom_groups::Entity::find().filter(custom_groups::Column::UserId.eq(user_id)),
+                )
+                .await?,
+            ),
+            (
+                "allocation",
+                rows(
+                    conn,
+                    allocation::Entity::find().filter(allocation::Column::UserId.eq(user_id)),
+                )
+                .await?,
+            ),
+            (
+                "groups_token",
+                rows(
+                    conn,
+                    groups_token::Entity::find().filter(groups_token::Column::UserId.eq(user_id)),
+                )
+                .await?,
+            ),
+            (
+                "oidc_mapping",
+                rows(
+                    conn,
+                    oidc_mapping::Entity::find().filter(oidc_mapping::Column::UserId.eq(user_id)),
+                )
+                .await?,
+            ),
+            (
+                "user_handle",
+                rows(
+                    conn,
+                    user_handle::Entity::find().filter(user_handle::Column::UserId.eq(user_id)),
+                )
+                .await?,
+            ),
+            (
+                "access_tokens",
+                rows(
+                    conn,
+                    access_tokens::Entity::find()
+                        .select_only()
+                        .columns([
+                            access_tokens::Column::Id,
+                            access_tokens::Column::DeviceName,
+                            access_tokens::Column::CreatedAt,
+                            access_tokens::Column::LastUsedAt,
+                            access_tokens::Column::ExpiresAt,
+                        ])
+                        .filter(access_tokens::Column::UserId.eq(user_id)),
+                )
+                .await?,
+            ),
+            (
+                "crisis_event",
+                rows(
+                    conn,
+                    crisis_event::Entity::find().filter(crisis_event::Column::UserId.eq(user_id)),
+                )
+                .await?,
+            ),
+        ])
+    }
+
+    async fn configs<C: ConnectionTrait>(conn: &C, user_id: Uuid) -> Result<Vec<(&'static str, JsonValue)>, DbErr> {
+        Ok(vec![(
+            "user_configs",
+            rows(conn, config::Entity::find().filter(config::Column::UserId.eq(user_id))).await?,
+        )])
+    }
+
+    async fn context_logs<C: ConnectionTrait>(
+        conn: &C,
+        user_id: Uuid,
+    ) -> Result<Vec<(&'static str, JsonValue)>, DbErr> {
+        Ok(vec![(
+            "user_context_logs",
+            rows(
+                conn,
+                user_context_logs::Entity::find().filter(user_context_logs::Column::UserId.eq(user_id)),
+            )
+            .await?,
+        )])
+    }
+
+    async fn journal<C: ConnectionTrait>(conn: &C, user_id: Uuid) -> Result<Vec<(&'static str, JsonValue)>, DbErr> {
+        let entries = journal_entry::Entity::find().filter(journal_entry::Column::UserId.eq(user_id));
+        let entry_ids = ids(conn, entries.clone(), journal_entry::Column::Id).await?;
+        let summaries = journal_summary::Entity::find().filter(journal_summary::Column::UserId.eq(user_id));
+        let summary_ids = ids(conn, summaries.clone(), journal_summary::Column::Id).await?;
+        let prompt_ids = journal_entry_journal_prompt::Entity::find()
+            .select_only()
+            .column(journal_entry_journal_prompt::Column::JournalPromptId)
+            .filter(journal_entry_journal_prompt::Column::JournalEntryId.is_in(entry_ids.clone()))
+            .into_tuple::<Uuid>()
+            .all(conn)
+            .await?;
+
+        Ok(vec![
+            ("journal_entry", rows(conn, entries).await?),
+            (
+                "journal_content",
+                rows(
+                    conn,
+                    journal_content::Entity::find()
+                        .filter(journal_content::Column::JournalEntryId.is_in(entry_ids.clone())),
+                )
+                .await?,
+            ),
+            (
+                "journal_entry_tag",
+                rows(
+                    conn,
+                    journal_entry_tag::Entity::find()
+                        .filter(journal_entry_tag::Column::JournalEntryId.is_in(entry_ids.clone())),
+                )
+                .await?,
+            ),
+            (
+                "journal_entry_journal_prompt",
+                rows(
+                    conn,
+                    journal_entry_journal_prompt::Entity::find()
+                        .filter(journal_entry_journal_prompt::Column::JournalEntryId.is_in(entry_ids)),
+                )
+                .await?,
+            ),
+            (
+                "journal_prompt",
+                rows(
+                    conn,
+                    journal_prompt::Entity::find().filter(journal_prompt::Column::Id.is_in(prompt_ids)),
+                )
+                .await?,
+            ),
+            (
+                "tag",
+                rows(conn, tag::Entity::find().filter(tag::Column::UserId.eq(user_id))).await?,
+            ),
+            ("journal_summary", rows(conn, summaries).await?),
+            (
+                "journal_topic",
+                rows(
+                    conn,
+                    journal_topic::Entity::find().filter(journal_topic::Column::JournalSummaryId.is_in(summary_ids)),
+                )
+                .await?,
+            ),
+        ])
+    }
+
+    async fn conversations<C: ConnectionTrait>(
+        conn: &C,
+        user_id: Uuid,
+    ) -> Result<Vec<(&'static str, JsonValue)>, DbErr> {
+        let conversations = conversation::Entity::find().filter(conversation::Column::UserId.eq(user_id));
+        let conversation_ids = ids(conn, conversations.clone(), conversation::Column::ConversationId).await?;
+
+        Ok(vec![
+            ("llm_conversation", rows(conn, conversations).await?),
+            (
+                "llm_message",
+                rows(
+                    conn,
+                    message::Entity::find().filter(message::Column::ConversationId.is_in(conversation_ids.clone())),
+                )
+                .await?,
+            ),
+            (
+                "llm_conversation_state",
+                rows(
+                    conn,
+                    conversation_state::Entity::find()
+                        .filter(conversation_state::Column::ConversationId.is_in(conversation_ids.clone())),
+                )
+                .await?,
+            ),
+            (
+                "llm_memory_summary",
+                rows(
+                    conn,
+                    memory_summary::Entity::find()
+                        .filter(memory_summary::Column::ConversationId.is_in(conversation_ids)),
+                )
+                .await?,
+            ),
+            (
+                "llm_usage",
+                rows(conn, usage::Entity::find().filter(usage::Column::UserId.eq(user_id))).await?,
+            ),
+            (
+                "llm_learner_memory",
+                rows(
+                    conn,
+                    learner_memory::Entity::find().filter(learner_memory::Column::UserId.eq(user_id)),
+                )
+                .await?,
+            ),
+            (
+                "llm_image",
+                rows(conn, image::Entity::find().filter(image::Column::UserId.eq(user_id))).await?,
+            ),
+            (
+                "llm_audio",
+                rows(conn, audio::Entity::find().filter(audio::Column::UserId.eq(user_id))).await?,
+            ),
+            (
+                "llm_guardrail_event",
+                rows(
+                    conn,
+                    guardrail_event::Entity::find().filter(guardrail_event::Column::UserId.eq(user_id)),
+                )
+                .await?,
+            ),
+        ])
+    }
+
+    async fn slots<C: ConnectionTrait>(conn: &C, user_id: Uuid) -> Result<Vec<(&'static str, JsonValue)>, DbErr> {
+        let conversation_ids = ids(
+            conn,
+            conversation::Entity::find().filter(conversation::Column::UserId.eq(user_id)),
+            conversation::Column::ConversationId,
+        )
+        .await?;
+
+        Ok(vec![
+            (
+                "llm_global_slot",
+                rows(
+                    conn,
+                    global_slot::Entity::find().filter(global_slot::Column::UserId.eq(user_id)),
+                )
+                .await?,
+            ),
+            (
+                "llm_module_slot",
+                rows(
+                    conn,
+                    module_slot::Entity::find().filter(module_slot::Column::UserId.eq(user_id)),
+                )
+                .await?,
+            ),
+            (
+                "llm_session_slot",
+                rows(
+                    conn,
+                    session_slot::Entity::find().filter(session_slot::Column::UserId.eq(user_id)),
+                )
+                .await?,
+            ),
+            (
+                "llm_slot",
+                rows(
+                    conn,
+                    conversation_slot::Entity::find()
+                        .filter(conversation_slot::Column::ConversationId.is_in(conversation_ids)),
+                )
+                .await?,
+            ),
+        ])
+    }
+
+    async fn quizzes<C: ConnectionTrait>(conn: &C, user_id: Uuid) -> Result<Vec<(&'static str, JsonValue)>, DbErr> {
+        let quizzes = quiz::Entity::find().filter(quiz::Column::UserId.eq(user_id));
+        let quiz_ids = ids(conn, quizzes.clone(), quiz::Column::Id).await?;
+
+        Ok(vec![
+            ("quiz", rows(conn, quizzes).await?),
+            (
+                "quiz_sessions",
+                rows(
+                    conn,
+                    quiz_sessions::Entity::find().filter(quiz_sessions::Column::QuizId.is_in(quiz_ids.clone())),
+                )
+                .await?,
+            ),
+            (
+                "question",
+                rows(
+                    conn,
+                    question::Entity::find().filter(question::Column::QuizId.is_in(quiz_ids)),
+                )
+                .await?,
+            ),
+            (
+                "quiz_score",
+                rows(conn, score::Entity::find().filter(score::Column::UserId.eq(user_id))).await?,
+            ),
+        ])
+    }
+
+    async fn assessments<C: ConnectionTrait>(conn: &C, user_id: Uuid) -> Result<Vec<(&'static str, JsonValue)>, DbErr> {
+        let sessions = assessment_session::Entity::find().filter(assessment_session::Column::UserId.eq(user_id));
+        let session_ids = ids(conn, sessions.clone(), assessment_session::Column::Id).await?;
+
+        Ok(vec![
+            ("assessment_session", rows(conn, sessions).await?),
+            (
+                "answer",
+                rows(
+                    conn,
+                    answer::Entity::find().filter(answer::Column::AssessmentSessionId.is_in(session_ids)),
+                )
+                .await?,
+            ),
+            (
+                "module_assessment",
+                rows(
+                    conn,
+                    module_assessment::Entity::find().filter(module_assessment::Column::UserId.eq(user_id)),
+                )
+                .await?,
+            ),
+        ])
+    }
+
+    async fn planner<C: ConnectionTrait>(conn: &C, user_id: Uuid) -> Result<Vec<(&'static str, JsonValue)>, DbErr> {
+        Ok(vec![(
+            "planner_entry",
+            rows(
+                conn,
+                planner_entry::Entity::find().filter(planner_entry::Column::UserId.eq(user_id)),
+            )
+            .await?,
+        )])
+    }
+
+    async fn history<C: ConnectionTrait>(conn: &C, user_id: Uuid) -> Result<Vec<(&'static str, JsonValue)>, DbErr> {
+        let histories = history::Entity::find().filter(history::Column::UserId.eq(user_id));
+        let history_ids = ids(conn, histories.clone(), history::Column::Id).await?;
+
+        Ok(vec![
+            ("history", rows(conn, histories).await?),
+            (
+                "history_modules",
+                rows(
+                    conn,
+                    history_module::Entity::find().filter(history_module::Column::HistoryId.is_in(history_ids.clone())),
+                )
+                .await?,
+            ),
+            (
+                "history_session",
+                rows(
+                    conn,
+                    history_session::Entity::find()
+                        .filter(history_session::Column::HistoryId.is_in(history_ids.clone())),
+                )
+                .await?,
+            ),
+            (
+                "history_assessment",
+                rows(
+                    conn,
+                    history_assessment::Entity::find().filter(history_assessment::Column::HistoryId.is_in(history_ids)),
+                )
+                .await?,
+            ),
+            (
+                "module_status",
+                rows(conn, status::Entity::find().filter(status::Column::UserId.eq(user_id))).await?,
+            ),
+            (
+                "session_status",
+                rows(
+                    conn,
+                    session_status::Entity::find().filter(session_status::Column::UserId.eq(user_id)),
+                )
+                .await?,
+            ),
+        ])
+    }
+}
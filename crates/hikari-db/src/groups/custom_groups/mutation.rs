@@ -29,4 +29,14 @@ impl Mutation {
         .flatten_res()?;
         Ok(())
     }
+
+    /// Removes the group from the user. Returns `false` if the user was not in the group.
+    pub async fn remove<C: ConnectionTrait>(db: &C, user_id: Uuid, group: &str) -> Result<bool, DbErr> {
+        let res = Entity::delete_many()
+            .filter(custom_groups::Column::UserId.eq(user_id))
+            .filter(custom_groups::Column::Value.eq(group))
+            .exec(db)
+            .await?;
+        Ok(res.rows_affected > 0)
+    }
 }
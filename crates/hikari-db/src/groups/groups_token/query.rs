@@ -1 +1,19 @@
+use hikari_entity::groups_token::{Column, Entity, Model};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use std::error::Error;
+
 pub struct Query;
+
+impl Query {
+    /// Loads the redemptions of the given access tokens, oldest first.
+    pub async fn get_by_tokens<C: ConnectionTrait>(conn: &C, tokens: &[String]) -> Result<Vec<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::Token.is_in(tokens))
+            .order_by_asc(Column::AddedAt)
+            .all(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load group tokens");
+            })
+    }
+}
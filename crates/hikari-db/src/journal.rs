@@ -2,3 +2,4 @@ pub mod journal_content;
 pub mod journal_entry;
 pub mod journal_entry_journal_focus;
 pub mod journal_summary;
+pub mod journal_summary_job;
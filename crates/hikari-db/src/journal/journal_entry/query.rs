@@ -13,6 +13,7 @@ use hikari_entity::journal::{journal_entry_tag, journal_entry_tag::Entity as Jou
 
 use hikari_entity::journal::{journal_prompt::Entity as JournalPrompt, journal_prompt::Model as JournalPromptModel};
 
+use chrono::{DateTime, FixedOffset};
 use hikari_entity::journal::{
     journal_entry_journal_prompt, journal_entry_journal_prompt::Entity as JournalEntryPrompt,
 };
@@ -139,4 +140,25 @@ impl Query {
         journal_entry
             .inspect_err(|error| tracing::error!(error = error as &dyn Error, "failed to load user journal entry"))
     }
+
+    /// Load the journal entries of the user created in the given range, oldest first
+    pub async fn get_user_journal_entries_between<C: ConnectionTrait>(
+        conn: &C,
+        user_id: Uuid,
+        from: Option<DateTime<FixedOffset>>,
+        to: Option<DateTime<FixedOffset>>,
+    ) -> Result<Vec<JournalEntryModel>, DbErr> {
+        let mut query = JournalEntry::find().filter(journal_entry::Column::UserId.eq(user_id));
+        if let Some(from) = from {
+            query = query.filter(journal_entry::Column::CreatedAt.gte(from));
+        }
+        if let Some(to) = to {
+            query = query.filter(journal_entry::Column::CreatedAt.lt(to));
+        }
+        query
+            .order_by_asc(journal_entry::Column::CreatedAt)
+            .all(conn)
+            .await
+            .inspect_err(|error| tracing::error!(error = error as &dyn Error, "failed to load user journal entries"))
+    }
 }
@@ -1 +1,32 @@
+use hikari_entity::journal::journal_entry_tag;
+use hikari_entity::tag;
+use hikari_entity::tag::{Entity as Tag, Model as TagModel};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter};
+use std::error::Error;
+use uuid::Uuid;
+
 pub struct Query;
+
+impl Query {
+    /// Load the focus tags of the given journal entries as `(journal_entry_id, focus)` pairs
+    pub async fn get_journal_entries_focus<C: ConnectionTrait>(
+        conn: &C,
+        journal_entry_ids: Vec<Uuid>,
+    ) -> Result<Vec<(Uuid, TagModel)>, DbErr> {
+        if journal_entry_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let focus = journal_entry_tag::Entity::find()
+            .find_also_related(Tag)
+            .filter(journal_entry_tag::Column::JournalEntryId.is_in(journal_entry_ids))
+            .filter(tag::Column::Kind.eq(tag::Kind::Focus))
+            .all(conn)
+            .await
+            .inspect_err(|error| tracing::error!(error = error as &dyn Error, "failed to load journal entry focus"))?;
+
+        Ok(focus
+            .into_iter()
+            .filter_map(|(entry_tag, focus)| focus.map(|focus| (entry_tag.journal_entry_id, focus)))
+            .collect())
+    }
+}
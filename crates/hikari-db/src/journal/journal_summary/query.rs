@@ -52,4 +52,53 @@ impl Query {
 
         Ok(Some((summary, topics)))
     }
+
+    pub async fn find_by_id<C: ConnectionTrait>(
+        conn: &C,
+        user_id: Uuid,
+        summary_id: Uuid,
+    ) -> Result<Option<(Model, Vec<JournalTopicModel>)>, DbErr> {
+        let summary = Entity::find_by_id(summary_id)
+            .filter(journal_summary::Column::UserId.eq(user_id))
+            .one(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, %summary_id, "error loading journal summary");
+            })?;
+
+        let Some(summary) = summary else {
+            return Ok(None);
+        };
+
+        let topics = JournalTopicEntity::find()
+            .filter(journal_topic::Column::JournalSummaryId.eq(summary.id))
+            .all(conn)
+            .await?;
+
+        Ok(Some((summary, topics)))
+    }
+
+    /// Load all summaries of the user created in the given range together with their topics, oldest first
+    pub async fn get_user_summaries_between<C: ConnectionTrait>(
+        conn: &C,
+        user_id: Uuid,
+        from: Option<NaiveDateTime>,
+        to: Option<NaiveDateTime>,
+    ) -> Result<Vec<(Model, Vec<JournalTopicModel>)>, DbErr> {
+        let mut query = Entity::find().filter(journal_summary::Column::UserId.eq(user_id));
+        if let Some(from) = from {
+            query = query.filter(journal_summary::Column::CreatedAt.gte(from));
+        }
+        if let Some(to) = to {
+            query = query.filter(journal_summary::Column::CreatedAt.lt(to));
+        }
+        query
+            .order_by_asc(journal_summary::Column::CreatedAt)
+            .find_with_related(JournalTopicEntity)
+            .all(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "error loading journal summaries");
+            })
+    }
 }
@@ -0,0 +1,27 @@
+use crate::util::lease::LeasedJob;
+use hikari_entity::journal::journal_summary_job::{Column, Entity, Status};
+
+pub mod mutation;
+pub mod query;
+
+pub use mutation::*;
+pub use query::*;
+
+impl LeasedJob for Entity {
+    type Status = Status;
+
+    const NAME: &'static str = "journal summary job";
+
+    const PENDING: Status = Status::Pending;
+    const RUNNING: Status = Status::Running;
+    const FAILED: Status = Status::Failed;
+
+    const ID: Column = Column::Id;
+    const STATUS: Column = Column::Status;
+    const ATTEMPTS: Column = Column::Attempts;
+    const LEASE_OWNER: Column = Column::LeaseOwner;
+    const LEASE_EXPIRES_AT: Column = Column::LeaseExpiresAt;
+    const ERROR: Column = Column::Error;
+    const CREATED_AT: Column = Column::CreatedAt;
+    const UPDATED_AT: Column = Column::UpdatedAt;
+}
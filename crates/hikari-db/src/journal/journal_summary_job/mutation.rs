@@ -0,0 +1,112 @@
+use crate::util::lease;
+use chrono::{NaiveDateTime, Utc};
+use hikari_entity::journal::journal_summary_job::{ActiveModel, Column, Entity, Model, Status};
+use sea_orm::ActiveValue::Set;
+use sea_orm::sea_query::{Expr, OnConflict};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter};
+use std::error::Error;
+use uuid::Uuid;
+
+pub struct Mutation;
+
+impl Mutation {
+    /// Creates a pending job for the summary or returns the job which already exists for it.
+    pub async fn get_or_create<C: ConnectionTrait>(
+        conn: &C,
+        user_id: Uuid,
+        key: &[u8; 32],
+        date: NaiveDateTime,
+        timestamp: NaiveDateTime,
+        utc_offset: i32,
+    ) -> Result<Model, DbErr> {
+        let now = Utc::now().naive_utc();
+        let job = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            key: Set(key.to_vec()),
+            date: Set(date),
+            timestamp: Set(timestamp),
+            utc_offset: Set(utc_offset),
+            status: Set(Status::Pending),
+            attempts: Set(0),
+            lease_owner: Set(None),
+            lease_expires_at: Set(None),
+            summary_id: Set(None),
+            error: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        Entity::insert(job)
+            .on_conflict(
+                OnConflict::columns([Column::UserId, Column::Key, Column::Date])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to create journal summary job");
+            })?;
+
+        super::Query::find(conn, user_id, key, date)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound("journal summary job not found after get_or_create".to_owned()))
+    }
+
+    /// Tries to claim a job for `owner` until `lease_expires_at`, see [`lease::claim`].
+    pub async fn claim<C: ConnectionTrait>(
+        conn: &C,
+        job_id: Uuid,
+        owner: &str,
+        lease_expires_at: NaiveDateTime,
+    ) -> Result<Option<Model>, DbErr> {
+        lease::claim::<Entity, _>(conn, job_id, owner, lease_expires_at).await
+    }
+
+    /// Marks a job as completed. Does nothing if the lease was taken over by another instance.
+    pub async fn complete<C: ConnectionTrait>(
+        conn: &C,
+        job_id: Uuid,
+        owner: &str,
+        summary_id: Uuid,
+    ) -> Result<bool, DbErr> {
+        let res = lease::release::<Entity>(job_id, owner, Status::Completed, None)
+            .col_expr(Column::SummaryId, Expr::value(summary_id))
+            .exec(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to complete journal summary job");
+            })?;
+        Ok(res.rows_affected > 0)
+    }
+
+    /// Releases a job after an error. It is picked up again if `retry` is set, otherwise it is marked as failed.
+    pub async fn fail<C: ConnectionTrait>(
+        conn: &C,
+        job_id: Uuid,
+        owner: &str,
+        error: &str,
+        retry: bool,
+    ) -> Result<bool, DbErr> {
+        lease::fail::<Entity, _>(conn, job_id, owner, error, retry).await
+    }
+
+    /// Queues a failed or completed job again, e.g. when its summary was deleted in the meantime.
+    pub async fn requeue<C: ConnectionTrait>(conn: &C, job_id: Uuid) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::Status, Expr::value(Status::Pending))
+            .col_expr(Column::Attempts, Expr::value(0))
+            .col_expr(Column::SummaryId, Expr::value(Option::<Uuid>::None))
+            .col_expr(Column::Error, Expr::value(Option::<String>::None))
+            .col_expr(Column::UpdatedAt, Expr::value(Utc::now().naive_utc()))
+            .filter(Column::Id.eq(job_id))
+            .filter(Column::Status.is_in([Status::Failed, Status::Completed]))
+            .exec(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to requeue journal summary job");
+            })?;
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use crate::util::lease;
+use chrono::NaiveDateTime;
+use hikari_entity::journal::journal_summary_job::{Column, Entity, Model};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter};
+use std::error::Error;
+use uuid::Uuid;
+
+pub struct Query;
+
+impl Query {
+    pub async fn get_user_job<C: ConnectionTrait>(
+        conn: &C,
+        user_id: Uuid,
+        job_id: Uuid,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::Id.eq(job_id))
+            .filter(Column::UserId.eq(user_id))
+            .one(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load journal summary job");
+            })
+    }
+
+    pub async fn find<C: ConnectionTrait>(
+        conn: &C,
+        user_id: Uuid,
+        key: &[u8; 32],
+        date: NaiveDateTime,
+    ) -> Result<Option<Model>, DbErr> {
+        Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::Key.eq(key.as_slice()))
+            .filter(Column::Date.eq(date))
+            .one(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to find journal summary job");
+            })
+    }
+
+    /// Jobs which are waiting to be processed or whose lease expired, oldest first.
+    pub async fn find_claimable<C: ConnectionTrait>(
+        conn: &C,
+        now: NaiveDateTime,
+        limit: u64,
+    ) -> Result<Vec<Model>, DbErr> {
+        lease::find_claimable::<Entity, _>(conn, now, limit).await
+    }
+}
@@ -1,5 +1,7 @@
 pub mod access_tokens;
+pub mod allocation;
 pub mod config;
+pub mod export;
 pub mod groups;
 pub mod history;
 pub mod journal;
@@ -11,6 +13,8 @@ pub mod user;
 pub mod util;
 
 pub mod assessment;
+pub mod audit_log;
+pub mod crisis_event;
 pub mod planner;
 pub mod tag;
 pub mod user_context_logs;
@@ -0,0 +1,5 @@
+pub mod mutation;
+pub mod query;
+
+pub use mutation::*;
+pub use query::*;
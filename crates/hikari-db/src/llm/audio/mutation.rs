@@ -0,0 +1,71 @@
+use chrono::Utc;
+use hikari_entity::llm::audio::{self, Entity as Audio, Model as AudioModel};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter};
+use std::error::Error;
+use uuid::Uuid;
+
+pub struct Mutation;
+
+impl Mutation {
+    /// Stores a voice message of the user together with its transcript
+    pub async fn insert_voice_message<C: ConnectionTrait>(
+        db: &C,
+        user_id: Uuid,
+        conversation_id: Uuid,
+        mime_type: String,
+        data: Vec<u8>,
+        transcript: String,
+    ) -> Result<AudioModel, DbErr> {
+        insert(db, user_id, conversation_id, None, mime_type, data, Some(transcript)).await
+    }
+
+    /// Stores the spoken output of a message
+    pub async fn insert_message_speech<C: ConnectionTrait>(
+        db: &C,
+        user_id: Uuid,
+        conversation_id: Uuid,
+        message_order: i32,
+        mime_type: String,
+        data: Vec<u8>,
+    ) -> Result<AudioModel, DbErr> {
+        insert(db, user_id, conversation_id, Some(message_order), mime_type, data, None).await
+    }
+
+    /// Deletes an audio of the user, returns false if the user has no audio with this id
+    pub async fn delete_audio<C: ConnectionTrait>(db: &C, user_id: Uuid, id: Uuid) -> Result<bool, DbErr> {
+        let result = Audio::delete_many()
+            .filter(audio::Column::Id.eq(id))
+            .filter(audio::Column::UserId.eq(user_id))
+            .exec(db)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to delete audio");
+            })?;
+        Ok(result.rows_affected > 0)
+    }
+}
+
+async fn insert<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    conversation_id: Uuid,
+    message_order: Option<i32>,
+    mime_type: String,
+    data: Vec<u8>,
+    transcript: Option<String>,
+) -> Result<AudioModel, DbErr> {
+    let model = audio::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        conversation_id: Set(Some(conversation_id)),
+        message_order: Set(message_order),
+        mime_type: Set(mime_type),
+        data: Set(data),
+        transcript: Set(transcript),
+        created_at: Set(Utc::now().naive_utc()),
+    };
+    model.insert(db).await.inspect_err(|error| {
+        tracing::error!(error = error as &dyn Error, "failed to store audio");
+    })
+}
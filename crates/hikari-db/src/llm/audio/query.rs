@@ -0,0 +1,37 @@
+use hikari_entity::llm::audio::{self, Entity as Audio, Model as AudioModel};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter};
+use std::error::Error;
+use uuid::Uuid;
+
+pub struct Query;
+
+impl Query {
+    /// The audio if it belongs to the user
+    pub async fn get_audio<C: ConnectionTrait>(db: &C, user_id: Uuid, id: Uuid) -> Result<Option<AudioModel>, DbErr> {
+        Audio::find_by_id(id)
+            .filter(audio::Column::UserId.eq(user_id))
+            .one(db)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load audio");
+            })
+    }
+
+    /// The spoken output of a message
+    pub async fn get_message_audio<C: ConnectionTrait>(
+        db: &C,
+        user_id: Uuid,
+        conversation_id: Uuid,
+        message_order: i32,
+    ) -> Result<Option<AudioModel>, DbErr> {
+        Audio::find()
+            .filter(audio::Column::UserId.eq(user_id))
+            .filter(audio::Column::ConversationId.eq(conversation_id))
+            .filter(audio::Column::MessageOrder.eq(message_order))
+            .one(db)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load message audio");
+            })
+    }
+}
@@ -0,0 +1,59 @@
+use chrono::Utc;
+use hikari_entity::llm::guardrail_event::{self, Entity as GuardrailEvent, Model as GuardrailEventModel};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ActiveModelTrait, ConnectionTrait, DbErr, EntityTrait, IntoActiveModel};
+use std::error::Error;
+use uuid::Uuid;
+
+pub struct Mutation;
+
+impl Mutation {
+    /// Records a triggered guardrail
+    #[allow(clippy::too_many_arguments)]
+    pub async fn insert_event<C: ConnectionTrait>(
+        db: &C,
+        conversation_id: Uuid,
+        user_id: Uuid,
+        step: String,
+        direction: String,
+        categories: &[String],
+        action: String,
+        content: String,
+    ) -> Result<GuardrailEventModel, DbErr> {
+        let model = guardrail_event::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            conversation_id: Set(conversation_id),
+            user_id: Set(user_id),
+            step: Set(step),
+            direction: Set(direction),
+            categories: Set(categories.join(",")),
+            action: Set(action),
+            content: Set(content),
+            created_at: Set(Utc::now().naive_utc()),
+            reviewed_at: Set(None),
+            reviewed_by: Set(None),
+        };
+        model.insert(db).await.inspect_err(|error| {
+            tracing::error!(error = error as &dyn Error, "failed to record guardrail event");
+        })
+    }
+
+    /// Marks the event as reviewed, returns `None` if it does not exist
+    pub async fn mark_reviewed<C: ConnectionTrait>(
+        db: &C,
+        id: Uuid,
+        reviewer: Uuid,
+    ) -> Result<Option<GuardrailEventModel>, DbErr> {
+        let Some(event) = GuardrailEvent::find_by_id(id).one(db).await? else {
+            return Ok(None);
+        };
+        let mut event = event.into_active_model();
+        event.reviewed_at = Set(Some(Utc::now().naive_utc()));
+        event.reviewed_by = Set(Some(reviewer));
+        event
+            .update(db)
+            .await
+            .map(Some)
+            .inspect_err(|error| tracing::error!(error = error as &dyn Error, "failed to review guardrail event"))
+    }
+}
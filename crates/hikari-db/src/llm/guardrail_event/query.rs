@@ -0,0 +1,53 @@
+use chrono::NaiveDateTime;
+use hikari_entity::llm::guardrail_event::{Column, Entity, Model};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use std::error::Error;
+use uuid::Uuid;
+
+pub struct Query;
+
+/// Restricts which guardrail events are returned. Unset fields match all events.
+#[derive(Debug, Clone, Default)]
+pub struct GuardrailEventFilter {
+    pub conversation_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    /// Only events which were not reviewed yet
+    pub unreviewed: bool,
+    pub from: Option<NaiveDateTime>,
+    pub to: Option<NaiveDateTime>,
+}
+
+impl Query {
+    /// Lists matching events, newest first
+    pub async fn find<C: ConnectionTrait>(
+        conn: &C,
+        filter: GuardrailEventFilter,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<Model>, DbErr> {
+        let mut query = Entity::find();
+        if let Some(conversation_id) = filter.conversation_id {
+            query = query.filter(Column::ConversationId.eq(conversation_id));
+        }
+        if let Some(user_id) = filter.user_id {
+            query = query.filter(Column::UserId.eq(user_id));
+        }
+        if filter.unreviewed {
+            query = query.filter(Column::ReviewedAt.is_null());
+        }
+        if let Some(from) = filter.from {
+            query = query.filter(Column::CreatedAt.gte(from));
+        }
+        if let Some(to) = filter.to {
+            query = query.filter(Column::CreatedAt.lt(to));
+        }
+        query
+            .order_by_desc(Column::CreatedAt)
+            .order_by_desc(Column::Id)
+            .limit(limit)
+            .offset(offset)
+            .all(conn)
+            .await
+            .inspect_err(|error| tracing::error!(error = error as &dyn Error, "failed to load guardrail events"))
+    }
+}
@@ -0,0 +1,41 @@
+use chrono::Utc;
+use hikari_entity::llm::image::{self, Entity as Image, Model as ImageModel};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter};
+use std::error::Error;
+use uuid::Uuid;
+
+pub struct Mutation;
+
+impl Mutation {
+    pub async fn insert_image<C: ConnectionTrait>(
+        db: &C,
+        user_id: Uuid,
+        mime_type: String,
+        data: Vec<u8>,
+    ) -> Result<ImageModel, DbErr> {
+        let model = image::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            mime_type: Set(mime_type),
+            data: Set(data),
+            created_at: Set(Utc::now().naive_utc()),
+        };
+        model.insert(db).await.inspect_err(|error| {
+            tracing::error!(error = error as &dyn Error, "failed to store image");
+        })
+    }
+
+    /// Deletes an image of the user, returns false if the user has no image with this id
+    pub async fn delete_image<C: ConnectionTrait>(db: &C, user_id: Uuid, id: Uuid) -> Result<bool, DbErr> {
+        let result = Image::delete_many()
+            .filter(image::Column::Id.eq(id))
+            .filter(image::Column::UserId.eq(user_id))
+            .exec(db)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to delete image");
+            })?;
+        Ok(result.rows_affected > 0)
+    }
+}
@@ -0,0 +1,54 @@
+use hikari_entity::llm::image::{self, Entity as Image, Model as ImageModel};
+use sea_orm::sea_query::{Alias, Expr, Func};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QuerySelect};
+use std::error::Error;
+use uuid::Uuid;
+
+pub struct Query;
+
+impl Query {
+    /// The image if it belongs to the user
+    pub async fn get_image<C: ConnectionTrait>(db: &C, user_id: Uuid, id: Uuid) -> Result<Option<ImageModel>, DbErr> {
+        Image::find_by_id(id)
+            .filter(image::Column::UserId.eq(user_id))
+            .one(db)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load image");
+            })
+    }
+
+    /// The images of the user with the given ids, ids of other users' images are ignored
+    pub async fn get_images<C: ConnectionTrait>(
+        db: &C,
+        user_id: Uuid,
+        ids: Vec<Uuid>,
+    ) -> Result<Vec<ImageModel>, DbErr> {
+        Image::find()
+            .filter(image::Column::Id.is_in(ids))
+            .filter(image::Column::UserId.eq(user_id))
+            .all(db)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load images");
+            })
+    }
+
+    /// Total size in bytes of all images of the user
+    pub async fn get_user_storage<C: ConnectionTrait>(db: &C, user_id: Uuid) -> Result<i64, DbErr> {
+        let size = Image::find()
+            .select_only()
+            .column_as(
+                Expr::expr(Func::cust(Alias::new("LENGTH")).arg(Expr::col(image::Column::Data))).sum(),
+                "size",
+            )
+            .filter(image::Column::UserId.eq(user_id))
+            .into_tuple::<Option<i64>>()
+            .one(db)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load image storage of user");
+            })?;
+        Ok(size.flatten().unwrap_or_default())
+    }
+}
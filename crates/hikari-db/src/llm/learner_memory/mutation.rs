@@ -0,0 +1,86 @@
+use chrono::Utc;
+use hikari_entity::llm::learner_memory::{self, Entity as LearnerMemory};
+use hikari_entity::llm::learner_memory_consent::{self, Entity as LearnerMemoryConsent};
+use sea_orm::ActiveValue::Set;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use std::error::Error;
+use uuid::Uuid;
+
+pub struct Mutation;
+
+impl Mutation {
+    /// Deletes a memory of the user, returns false if the user has no memory with this id
+    pub async fn delete_memory<C: ConnectionTrait>(db: &C, user_id: Uuid, id: Uuid) -> Result<bool, DbErr> {
+        let result = LearnerMemory::delete_many()
+            .filter(learner_memory::Column::Id.eq(id))
+            .filter(learner_memory::Column::UserId.eq(user_id))
+            .exec(db)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to delete learner memory");
+            })?;
+        Ok(result.rows_affected > 0)
+    }
+
+    pub async fn delete_user_memories<C: ConnectionTrait>(db: &C, user_id: Uuid) -> Result<u64, DbErr> {
+        let result = LearnerMemory::delete_many()
+            .filter(learner_memory::Column::UserId.eq(user_id))
+            .exec(db)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to delete learner memories");
+            })?;
+        Ok(result.rows_affected)
+    }
+
+    /// Opts the user in to or out of the learner memory
+    pub async fn set_consent<C: ConnectionTrait>(db: &C, user_id: Uuid, enabled: bool) -> Result<(), DbErr> {
+        if enabled {
+            LearnerMemoryConsent::insert(learner_memory_consent::ActiveModel {
+                user_id: Set(user_id),
+                created_at: Set(Utc::now().naive_utc()),
+            })
+            .on_conflict(
+                OnConflict::column(learner_memory_consent::Column::UserId)
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(db)
+            .await
+        } else {
+            LearnerMemoryConsent::delete_by_id(user_id)
+                .exec(db)
+                .await
+                .map(|result| result.rows_affected)
+        }
+        .inspect_err(|error| {
+            tracing::error!(error = error as &dyn Error, "failed to update learner memory consent");
+        })?;
+        Ok(())
+    }
+
+    /// Deletes the oldest memories of the user which exceed the limit
+    pub async fn prune_user_memories<C: ConnectionTrait>(db: &C, user_id: Uuid, keep: u64) -> Result<u64, DbErr> {
+        let outdated: Vec<Uuid> = LearnerMemory::find()
+            .select_only()
+            .column(learner_memory::Column::Id)
+            .filter(learner_memory::Column::UserId.eq(user_id))
+            .order_by_desc(learner_memory::Column::CreatedAt)
+            .offset(keep)
+            .into_tuple()
+            .all(db)
+            .await?;
+        if outdated.is_empty() {
+            return Ok(0);
+        }
+        let result = LearnerMemory::delete_many()
+            .filter(learner_memory::Column::Id.is_in(outdated))
+            .exec(db)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to prune learner memories");
+            })?;
+        Ok(result.rows_affected)
+    }
+}
@@ -0,0 +1,35 @@
+use hikari_entity::llm::learner_memory::{self, Entity as LearnerMemory, Model as LearnerMemoryModel};
+use hikari_entity::llm::learner_memory_consent::Entity as LearnerMemoryConsent;
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use std::error::Error;
+use uuid::Uuid;
+
+pub struct Query;
+
+impl Query {
+    /// All memories of the user, newest first
+    pub async fn get_user_memories<C: ConnectionTrait>(
+        db: &C,
+        user_id: Uuid,
+    ) -> Result<Vec<LearnerMemoryModel>, DbErr> {
+        LearnerMemory::find()
+            .filter(learner_memory::Column::UserId.eq(user_id))
+            .order_by_desc(learner_memory::Column::CreatedAt)
+            .all(db)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load learner memories");
+            })
+    }
+
+    /// Whether the user opted in to the learner memory
+    pub async fn has_consent<C: ConnectionTrait>(db: &C, user_id: Uuid) -> Result<bool, DbErr> {
+        let consent = LearnerMemoryConsent::find_by_id(user_id)
+            .one(db)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load learner memory consent");
+            })?;
+        Ok(consent.is_some())
+    }
+}
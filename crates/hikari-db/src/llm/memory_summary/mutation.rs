@@ -0,0 +1,49 @@
+use chrono::Utc;
+use hikari_entity::llm::memory_summary;
+use hikari_entity::llm::memory_summary::Entity as MemorySummary;
+use sea_orm::ActiveValue::Set;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ConnectionTrait, DbErr, EntityTrait};
+use std::error::Error;
+use uuid::Uuid;
+
+pub struct Mutation;
+
+impl Mutation {
+    /// Replaces the summary of the scope, the new summary has to include the previous one
+    pub async fn upsert_memory_summary<C: ConnectionTrait>(
+        db: &C,
+        conversation_id: Uuid,
+        scope: String,
+        summary: String,
+        covered_until: i32,
+        tokens: i32,
+    ) -> Result<(), DbErr> {
+        let model = memory_summary::ActiveModel {
+            conversation_id: Set(conversation_id),
+            scope: Set(scope),
+            summary: Set(summary),
+            covered_until: Set(covered_until),
+            tokens: Set(tokens),
+            updated_at: Set(Utc::now().naive_utc()),
+        };
+
+        MemorySummary::insert(model)
+            .on_conflict(
+                OnConflict::columns([memory_summary::Column::ConversationId, memory_summary::Column::Scope])
+                    .update_columns([
+                        memory_summary::Column::Summary,
+                        memory_summary::Column::CoveredUntil,
+                        memory_summary::Column::Tokens,
+                        memory_summary::Column::UpdatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(db)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to store memory summary");
+            })?;
+        Ok(())
+    }
+}
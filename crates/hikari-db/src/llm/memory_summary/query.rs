@@ -0,0 +1,21 @@
+use hikari_entity::llm::memory_summary::{Entity as MemorySummary, Model as MemorySummaryModel};
+use sea_orm::{ConnectionTrait, DbErr, EntityTrait};
+use std::error::Error;
+use uuid::Uuid;
+
+pub struct Query;
+
+impl Query {
+    pub async fn get_memory_summary<C: ConnectionTrait>(
+        db: &C,
+        conversation_id: Uuid,
+        scope: &str,
+    ) -> Result<Option<MemorySummaryModel>, DbErr> {
+        MemorySummary::find_by_id((conversation_id, scope.to_string()))
+            .one(db)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load memory summary");
+            })
+    }
+}
@@ -1,6 +1,8 @@
 use chrono::Utc;
+use sea_orm::sea_query::Expr;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, NotSet, PaginatorTrait, QueryFilter, Set,
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, NotSet, PaginatorTrait,
+    QueryFilter, Set,
 };
 use uuid::Uuid;
 
@@ -9,6 +11,15 @@ use hikari_entity::llm::{message, message::Model as MessageModel};
 
 pub struct Mutation;
 
+/// Number of messages of the conversation including superseded ones, which is the order of the next message
+async fn message_count<C: ConnectionTrait>(db: &C, conversation_id: Uuid) -> Result<i32, DbErr> {
+    let message_count = message::Entity::find()
+        .filter(message::Column::ConversationId.eq(conversation_id))
+        .count(db)
+        .await?;
+    i32::try_from(message_count).map_err(|_| DbErr::Custom("message count is too large to fit in a u16".to_string()))
+}
+
 impl Mutation {
     pub async fn insert_new_message(
         db: &DatabaseConnection,
@@ -19,12 +30,7 @@ impl Mutation {
         direction: Direction,
         status: Status,
     ) -> Result<MessageModel, DbErr> {
-        let message_count = message::Entity::find()
-            .filter(message::Column::ConversationId.eq(conversation_id))
-            .count(db)
-            .await?;
-        let message_count = i32::try_from(message_count)
-            .map_err(|_| DbErr::Custom("message count is too large to fit in a u16".to_string()))?;
+        let message_count = message_count(db, conversation_id).await?;
 
         let new_message = message::ActiveModel {
             conversation_id: Set(conversation_id),
@@ -35,10 +41,31 @@ impl Mutation {
             payload: Set(payload),
             direction: Set(direction),
             status: Set(status),
+            superseded_by: Set(None),
         };
         new_message.insert(db).await
     }
 
+    /// Moves the current messages from `from_order` on into a superseded branch, the next inserted message starts the
+    /// new branch. Returns the number of superseded messages.
+    ///
+    /// Counting and updating are two statements, so this should run in a transaction.
+    pub async fn supersede_messages<C: ConnectionTrait>(
+        db: &C,
+        conversation_id: Uuid,
+        from_order: i32,
+    ) -> Result<u64, DbErr> {
+        let branch = message_count(db, conversation_id).await?;
+        let res = message::Entity::update_many()
+            .col_expr(message::Column::SupersededBy, Expr::value(branch))
+            .filter(message::Column::ConversationId.eq(conversation_id))
+            .filter(message::Column::MessageOrder.gte(from_order))
+            .filter(message::Column::SupersededBy.is_null())
+            .exec(db)
+            .await?;
+        Ok(res.rows_affected)
+    }
+
     pub async fn update_message(
         db: &DatabaseConnection,
         conversation_id: Uuid,
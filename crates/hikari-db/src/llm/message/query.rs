@@ -1,4 +1,4 @@
-use hikari_entity::llm::message::{self, Entity as Message, Model as MessageModel, Status};
+use hikari_entity::llm::message::{self, Direction, Entity as Message, Model as MessageModel, Status};
 use sea_orm::{
     ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
     TransactionTrait,
@@ -13,14 +13,19 @@ impl Query {
         db: &DatabaseConnection,
         conversation_id: &Uuid,
         steps: Option<&[String]>,
+        after: Option<i32>,
         limit: Option<u64>,
     ) -> Result<Vec<MessageModel>, DbErr> {
         let mut query = Message::find()
             .filter(message::Column::ConversationId.eq(*conversation_id))
-            .filter(message::Column::Status.eq(Status::Completed));
+            .filter(message::Column::Status.eq(Status::Completed))
+            .filter(message::Column::SupersededBy.is_null());
         if let Some(steps) = steps {
             query = query.filter(message::Column::Step.is_in(steps));
         }
+        if let Some(after) = after {
+            query = query.filter(message::Column::MessageOrder.gt(after));
+        }
         // Get newest messages first (if we limit the number of messages)
         query = query.order_by_desc(message::Column::MessageOrder);
         if let Some(limit) = limit {
@@ -34,6 +39,23 @@ impl Query {
         Ok(messages)
     }
 
+    /// The newest message of the user in the current branch
+    pub async fn get_last_user_message(
+        db: &DatabaseConnection,
+        conversation_id: Uuid,
+    ) -> Result<Option<MessageModel>, DbErr> {
+        Message::find()
+            .filter(message::Column::ConversationId.eq(conversation_id))
+            .filter(message::Column::Direction.eq(Direction::Receive))
+            .filter(message::Column::SupersededBy.is_null())
+            .order_by_desc(message::Column::MessageOrder)
+            .one(db)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load last user message");
+            })
+    }
+
     pub async fn get_not_finished_message<C: ConnectionTrait + TransactionTrait>(
         db: &C,
         conversation_id: Uuid,
@@ -1,5 +1,6 @@
+use chrono::NaiveDateTime;
 use hikari_entity::llm::usage;
-use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder};
 use std::error::Error;
 use uuid::Uuid;
 pub struct Query;
@@ -16,4 +17,27 @@ impl Query {
         let usage = usages.iter().map(|usage| u64::from(usage.tokens)).sum();
         Ok(usage)
     }
+
+    /// Loads the single usage records of the user, optionally limited to a time range
+    pub async fn get_user_usage<C: ConnectionTrait>(
+        conn: &C,
+        user_id: Uuid,
+        from: Option<NaiveDateTime>,
+        to: Option<NaiveDateTime>,
+    ) -> Result<Vec<usage::Model>, DbErr> {
+        let mut query = usage::Entity::find().filter(usage::Column::UserId.eq(user_id));
+        if let Some(from) = from {
+            query = query.filter(usage::Column::Time.gte(from));
+        }
+        if let Some(to) = to {
+            query = query.filter(usage::Column::Time.lt(to));
+        }
+        query
+            .order_by_asc(usage::Column::Time)
+            .all(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load llm usage");
+            })
+    }
 }
@@ -21,6 +21,22 @@ impl Query {
         })
     }
 
+    /// Loads the module assessments of the users, users without one are left out.
+    pub async fn get_for_module_users<C: ConnectionTrait>(
+        conn: &C,
+        user_ids: Vec<Uuid>,
+        module_id: &str,
+    ) -> Result<Vec<ModuleAssessment>, DbErr> {
+        ModuleAssessmentEntity::find()
+            .filter(assessment::Column::UserId.is_in(user_ids))
+            .filter(assessment::Column::Module.eq(module_id))
+            .all(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, %module_id, "failed to load module assessments of users");
+            })
+    }
+
     pub async fn get_for_module<C: ConnectionTrait>(
         db: &C,
         user_id: Uuid,
@@ -6,6 +6,7 @@ use hikari_entity::module::session::{
 
 use crate::util::FlattenTransactionResultExt;
 use sea_orm::prelude::*;
+use sea_orm::sea_query::Expr;
 use sea_orm::{ActiveValue, IntoActiveValue, TransactionTrait, TryInsertResult, sea_query};
 use uuid::Uuid;
 
@@ -121,4 +122,28 @@ impl Mutation {
             .await
             .and_then(|model| model.ok_or(DbErr::RecordNotFound("Record not found after insertion".to_owned())))
     }
+
+    /// Resets the session of the user so it can be started from scratch. Returns `false` if the session was never
+    /// started.
+    pub async fn reset<C: ConnectionTrait>(
+        conn: &C,
+        user_id: Uuid,
+        module: &str,
+        session: &str,
+    ) -> Result<bool, DbErr> {
+        let res = Instance::update_many()
+            .col_expr(status::Column::Status, Expr::value(Status::NotStarted))
+            .col_expr(status::Column::BotId, Expr::value(Option::<String>::None))
+            .col_expr(status::Column::LastConvId, Expr::value(Option::<Uuid>::None))
+            .col_expr(status::Column::Completion, Expr::value(Option::<DateTime>::None))
+            .filter(status::Column::UserId.eq(user_id))
+            .filter(status::Column::Module.eq(module))
+            .filter(status::Column::Session.eq(session))
+            .exec(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn std::error::Error, %user_id, %module, %session, "failed to reset session");
+            })?;
+        Ok(res.rows_affected > 0)
+    }
 }
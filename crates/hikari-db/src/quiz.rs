@@ -1,4 +1,5 @@
 pub mod question;
+pub mod question_bank;
 #[allow(clippy::module_inception)]
 pub mod quiz;
 pub mod score;
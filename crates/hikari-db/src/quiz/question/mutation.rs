@@ -1,4 +1,5 @@
 use hikari_entity::quiz::question::{self, BloomLevel};
+use hikari_entity::quiz::question_bank;
 use sea_orm::{ActiveModelTrait, ActiveValue::NotSet, DatabaseConnection, DbErr, Set};
 use uuid::Uuid;
 pub struct Mutation;
@@ -22,6 +23,8 @@ impl Mutation {
             Some(ai_solution),
             &question::QuestionType::Text,
             None,
+            None,
+            &question::Rubric::Llm,
             level,
             session_id,
             topic,
@@ -36,6 +39,7 @@ impl Mutation {
         quiz_id: &Uuid,
         question: &str,
         options: &str,
+        rubric: &question::Rubric,
         level: &BloomLevel,
         session_id: &str,
         topic: &str,
@@ -48,6 +52,38 @@ impl Mutation {
             None,
             &question::QuestionType::MultipleChoice,
             Some(options),
+            None,
+            rubric,
+            level,
+            session_id,
+            topic,
+            content,
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_detailed_question(
+        db: &DatabaseConnection,
+        quiz_id: &Uuid,
+        question: &str,
+        question_type: &question::QuestionType,
+        details: &str,
+        rubric: &question::Rubric,
+        level: &BloomLevel,
+        session_id: &str,
+        topic: &str,
+        content: &str,
+    ) -> Result<question::Model, DbErr> {
+        Self::create_question(
+            db,
+            quiz_id,
+            question,
+            None,
+            question_type,
+            None,
+            Some(details),
+            rubric,
             level,
             session_id,
             topic,
@@ -64,6 +100,8 @@ impl Mutation {
         ai_solution: Option<&str>,
         question_type: &question::QuestionType,
         options: Option<&str>,
+        details: Option<&str>,
+        rubric: &question::Rubric,
         level: &BloomLevel,
         session_id: &str,
         topic: &str,
@@ -79,19 +117,60 @@ impl Mutation {
             content: Set(content.to_string()),
             r#type: Set(*question_type),
             options: Set(options.map(ToString::to_string)),
+            details: Set(details.map(ToString::to_string)),
             created_at: Set(chrono::Utc::now().naive_utc()),
             answered_at: NotSet,
             answer: NotSet,
             evaluation: NotSet,
             grade: NotSet,
             ai_solution: Set(ai_solution.map(ToString::to_string)),
+            rubric: Set(*rubric),
+            explanation: NotSet,
             status: Set(question::Status::Open),
             feedback: NotSet,
             feedback_explanation: NotSet,
+            bank_question_id: NotSet,
         };
         quiz.insert(db).await
     }
 
+    /// Serves a question of the question bank to the user.
+    ///
+    /// `details` replaces the stored details of the entry, as the items of ordering and matching
+    /// questions are shuffled again for every user.
+    pub async fn create_bank_question(
+        db: &DatabaseConnection,
+        quiz_id: &Uuid,
+        entry: &question_bank::Model,
+        details: Option<&str>,
+    ) -> Result<question::Model, DbErr> {
+        let question = question::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            quiz_id: Set(*quiz_id),
+            question: Set(entry.question.clone()),
+            level: Set(entry.level),
+            session_id: Set(entry.session_id.clone()),
+            topic: Set(entry.topic.clone()),
+            content: Set(entry.content.clone()),
+            r#type: Set(entry.r#type),
+            options: Set(entry.options.clone()),
+            details: Set(details.map(ToString::to_string)),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+            answered_at: NotSet,
+            answer: NotSet,
+            evaluation: NotSet,
+            grade: NotSet,
+            ai_solution: Set(entry.solution.clone()),
+            rubric: Set(entry.rubric),
+            explanation: NotSet,
+            status: Set(question::Status::Open),
+            feedback: NotSet,
+            feedback_explanation: NotSet,
+            bank_question_id: Set(Some(entry.id)),
+        };
+        question.insert(db).await
+    }
+
     pub async fn add_evaluation(
         db: &DatabaseConnection,
         question_id: &Uuid,
@@ -112,6 +191,20 @@ impl Mutation {
         question.update(db).await
     }
 
+    pub async fn add_explanation(
+        db: &DatabaseConnection,
+        question_id: &Uuid,
+        explanation: &str,
+    ) -> Result<question::Model, DbErr> {
+        let question = super::Query::get_question_by_id(db, question_id)
+            .await?
+            .ok_or_else(|| DbErr::Custom("question not found".to_string()))?;
+
+        let mut question: question::ActiveModel = question.into();
+        question.explanation = Set(Some(explanation.to_string()));
+        question.update(db).await
+    }
+
     pub async fn skip_question(db: &DatabaseConnection, question_id: &Uuid) -> Result<question::Model, DbErr> {
         let question = super::Query::get_question_by_id(db, question_id)
             .await?
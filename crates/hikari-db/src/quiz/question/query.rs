@@ -1,7 +1,7 @@
 use hikari_entity::quiz::question::{self, BloomLevel, Entity as Question, Model as QuestionModel};
 use hikari_entity::quiz::quiz::{self};
 use sea_orm::RelationTrait;
-use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QuerySelect};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
 use std::error::Error;
 use uuid::Uuid;
 pub struct Query;
@@ -60,4 +60,40 @@ impl Query {
 
         Ok(result)
     }
+
+    /// Ids of the bank questions which were already served to the user.
+    pub async fn get_served_bank_question_ids(db: &DatabaseConnection, user_id: &Uuid) -> Result<Vec<Uuid>, DbErr> {
+        let ids: Vec<Option<Uuid>> = Question::find()
+            .select_only()
+            .column(question::Column::BankQuestionId)
+            .join(sea_orm::JoinType::InnerJoin, question::Relation::Quiz.def())
+            .filter(quiz::Column::UserId.eq(*user_id))
+            .filter(question::Column::BankQuestionId.is_not_null())
+            .into_tuple()
+            .all(db)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load served bank question ids");
+            })?;
+        Ok(ids.into_iter().flatten().collect())
+    }
+
+    /// Generated questions of a module which were rated as good by the user and answered with at least `min_grade`.
+    pub async fn get_promotion_candidates(
+        db: &DatabaseConnection,
+        module_id: &str,
+        min_grade: i32,
+    ) -> Result<Vec<QuestionModel>, DbErr> {
+        let query = Question::find()
+            .join(sea_orm::JoinType::InnerJoin, question::Relation::Quiz.def())
+            .filter(quiz::Column::ModuleId.eq(module_id))
+            .filter(question::Column::BankQuestionId.is_null())
+            .filter(question::Column::Feedback.eq(question::Feedback::Good))
+            .filter(question::Column::Grade.gte(min_grade))
+            .order_by_desc(question::Column::CreatedAt);
+
+        query.all(db).await.inspect_err(|error| {
+            tracing::error!(error = error as &dyn Error, "failed to load question bank candidates");
+        })
+    }
 }
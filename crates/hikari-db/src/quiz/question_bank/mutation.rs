@@ -0,0 +1,97 @@
+use chrono::Utc;
+use hikari_entity::quiz::question::{BloomLevel, QuestionType, Rubric};
+use hikari_entity::quiz::question_bank::{self, ActiveModel, Entity as QuestionBank, Model as QuestionBankModel};
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, Set};
+use std::error::Error;
+use uuid::Uuid;
+
+pub struct QuestionBankEntryInput {
+    pub session_id: String,
+    pub topic: String,
+    pub content: String,
+    pub question: String,
+    pub question_type: QuestionType,
+    pub options: Option<String>,
+    pub details: Option<String>,
+    pub solution: Option<String>,
+    pub level: BloomLevel,
+    pub rubric: Rubric,
+}
+
+pub struct Mutation;
+
+impl Mutation {
+    pub async fn create_entry<C: ConnectionTrait>(
+        db: &C,
+        module_id: &str,
+        input: QuestionBankEntryInput,
+        status: question_bank::Status,
+        source_question_id: Option<Uuid>,
+        created_by: Option<Uuid>,
+    ) -> Result<QuestionBankModel, DbErr> {
+        let now = Utc::now().naive_utc();
+        let entry = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            module_id: Set(module_id.to_string()),
+            session_id: Set(input.session_id),
+            topic: Set(input.topic),
+            content: Set(input.content),
+            question: Set(input.question),
+            r#type: Set(input.question_type),
+            options: Set(input.options),
+            details: Set(input.details),
+            solution: Set(input.solution),
+            level: Set(input.level),
+            rubric: Set(input.rubric),
+            status: Set(status),
+            source_question_id: Set(source_question_id),
+            created_by: Set(created_by),
+            reviewed_by: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+            reviewed_at: Set(None),
+        };
+        entry.insert(db).await.inspect_err(|error| {
+            tracing::error!(error = error as &dyn Error, "failed to create question bank entry");
+        })
+    }
+
+    pub async fn update_entry<C: ConnectionTrait>(
+        db: &C,
+        mut active_model: ActiveModel,
+    ) -> Result<QuestionBankModel, DbErr> {
+        active_model.updated_at = Set(Utc::now().naive_utc());
+        active_model.update(db).await.inspect_err(|error| {
+            tracing::error!(error = error as &dyn Error, "failed to update question bank entry");
+        })
+    }
+
+    pub async fn review_entry<C: ConnectionTrait>(
+        db: &C,
+        entry: QuestionBankModel,
+        status: question_bank::Status,
+        reviewed_by: &Uuid,
+    ) -> Result<QuestionBankModel, DbErr> {
+        let now = Utc::now().naive_utc();
+        let mut entry: ActiveModel = entry.into();
+        entry.status = Set(status);
+        entry.reviewed_by = Set(Some(*reviewed_by));
+        entry.reviewed_at = Set(Some(now));
+        entry.updated_at = Set(now);
+        entry.update(db).await.inspect_err(|error| {
+            tracing::error!(error = error as &dyn Error, "failed to review question bank entry");
+        })
+    }
+
+    pub async fn delete_entry<C: ConnectionTrait>(db: &C, module_id: &str, entry_id: &Uuid) -> Result<u64, DbErr> {
+        let res = QuestionBank::delete_many()
+            .filter(question_bank::Column::Id.eq(*entry_id))
+            .filter(question_bank::Column::ModuleId.eq(module_id))
+            .exec(db)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to delete question bank entry");
+            })?;
+        Ok(res.rows_affected)
+    }
+}
@@ -0,0 +1,79 @@
+use hikari_entity::quiz::question::BloomLevel;
+use hikari_entity::quiz::question_bank::{self, Entity as QuestionBank, Model as QuestionBankModel};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use std::error::Error;
+use uuid::Uuid;
+pub struct Query;
+
+impl Query {
+    pub async fn get_entry_by_id<C: ConnectionTrait>(
+        db: &C,
+        module_id: &str,
+        entry_id: &Uuid,
+    ) -> Result<Option<QuestionBankModel>, DbErr> {
+        let query = QuestionBank::find()
+            .filter(question_bank::Column::Id.eq(*entry_id))
+            .filter(question_bank::Column::ModuleId.eq(module_id));
+        query.one(db).await.inspect_err(|error| {
+            tracing::error!(error = error as &dyn Error, "failed to load question bank entry by id");
+        })
+    }
+
+    pub async fn get_entries_by_module<C: ConnectionTrait>(
+        db: &C,
+        module_id: &str,
+        status: Option<question_bank::Status>,
+    ) -> Result<Vec<QuestionBankModel>, DbErr> {
+        let mut query = QuestionBank::find().filter(question_bank::Column::ModuleId.eq(module_id));
+        if let Some(status) = status {
+            query = query.filter(question_bank::Column::Status.eq(status));
+        }
+        query
+            .order_by_asc(question_bank::Column::SessionId)
+            .order_by_asc(question_bank::Column::Topic)
+            .order_by_asc(question_bank::Column::CreatedAt)
+            .all(db)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load question bank entries");
+            })
+    }
+
+    /// Approved entries which can be served for the given content and level.
+    pub async fn get_approved_entries<C: ConnectionTrait>(
+        db: &C,
+        module_id: &str,
+        session_id: &str,
+        topic: &str,
+        level: &BloomLevel,
+    ) -> Result<Vec<QuestionBankModel>, DbErr> {
+        let query = QuestionBank::find()
+            .filter(question_bank::Column::ModuleId.eq(module_id))
+            .filter(question_bank::Column::SessionId.eq(session_id))
+            .filter(question_bank::Column::Topic.eq(topic))
+            .filter(question_bank::Column::Level.eq(*level))
+            .filter(question_bank::Column::Status.eq(question_bank::Status::Approved));
+        query.all(db).await.inspect_err(|error| {
+            tracing::error!(
+                error = error as &dyn Error,
+                "failed to load approved question bank entries"
+            );
+        })
+    }
+
+    /// Ids of generated questions which were already promoted into the bank of a module.
+    pub async fn get_promoted_question_ids<C: ConnectionTrait>(db: &C, module_id: &str) -> Result<Vec<Uuid>, DbErr> {
+        let ids: Vec<Option<Uuid>> = QuestionBank::find()
+            .select_only()
+            .column(question_bank::Column::SourceQuestionId)
+            .filter(question_bank::Column::ModuleId.eq(module_id))
+            .filter(question_bank::Column::SourceQuestionId.is_not_null())
+            .into_tuple()
+            .all(db)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn Error, "failed to load promoted question ids");
+            })?;
+        Ok(ids.into_iter().flatten().collect())
+    }
+}
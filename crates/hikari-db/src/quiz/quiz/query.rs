@@ -40,6 +40,14 @@ impl Query {
         })
     }
 
+    /// Loads a quiz regardless of the user it belongs to.
+    pub async fn get_quiz(db: &DatabaseConnection, quiz_id: &Uuid) -> Result<Option<QuizModel>, DbErr> {
+        let query = Quiz::find().filter(quiz::Column::Id.eq(*quiz_id));
+        query.one(db).await.inspect_err(|error| {
+            tracing::error!(error = error as &dyn Error, "failed to load quiz");
+        })
+    }
+
     pub async fn get_quiz_sessions(db: &DatabaseConnection, quiz_id: &Uuid) -> Result<Vec<String>, DbErr> {
         let sessions = QuizSessions::find()
             .filter(<QuizSessions as EntityTrait>::Column::QuizId.eq(*quiz_id))
@@ -1,12 +1,13 @@
+use chrono::NaiveDateTime;
 use futures_util::try_join;
-use hikari_entity::access_tokens::{Column as AccessTokenColumn, Entity as AccessToken};
-use hikari_entity::custom_groups::{
-    Entity as CustomGroupEntity, Model as CustomGroup, Relation as CustomGroupRelations,
-};
-use hikari_entity::oidc_groups::{Entity as OidcGroupEntity, Model as OidcGroup, Relation as OidcGroupRelations};
+use hikari_entity::access_tokens::Model as AccessToken;
+use hikari_entity::custom_groups::{Column as CustomGroupColumn, Entity as CustomGroupEntity, Model as CustomGroup};
+use hikari_entity::oidc_groups::{Column as OidcGroupColumn, Entity as OidcGroupEntity, Model as OidcGroup};
 
-use hikari_entity::user::{Entity as UserEntity, Model as User, Relation as UserRelations};
-use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, JoinType, QueryFilter, QuerySelect, RelationTrait};
+use hikari_entity::user::{Column as UserColumn, Entity as UserEntity, Model as User};
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect, QueryTrait,
+};
 use uuid::Uuid;
 
 pub struct Query;
@@ -18,29 +19,84 @@ impl Query {
         })
     }
 
+    /// Finds the user of a valid access token together with the token and the groups of the user
     pub async fn find_by_token<C: ConnectionTrait>(
         conn: &C,
         token: &str,
-    ) -> Result<Option<(User, Vec<OidcGroup>, Vec<CustomGroup>)>, DbErr> {
+        now: NaiveDateTime,
+    ) -> Result<Option<(AccessToken, (User, Vec<OidcGroup>, Vec<CustomGroup>))>, DbErr> {
+        let Some(access_token) = crate::access_tokens::Query::find_valid_by_token(conn, token, now).await? else {
+            return Ok(None);
+        };
+        let user_id = access_token.user_id;
         let (user, oidc_groups, custom_groups) = try_join!(
-            UserEntity::find()
-                .inner_join(AccessToken)
-                .filter(AccessTokenColumn::AccessToken.eq(token))
-                .one(conn),
+            UserEntity::find_by_id(user_id).one(conn),
             OidcGroupEntity::find()
-                .join(JoinType::LeftJoin, OidcGroupRelations::User.def())
-                .join(JoinType::LeftJoin, UserRelations::AccessToken.def())
-                .filter(AccessTokenColumn::AccessToken.eq(token))
+                .filter(OidcGroupColumn::UserId.eq(user_id))
                 .all(conn),
             CustomGroupEntity::find()
-                .join(JoinType::LeftJoin, CustomGroupRelations::User.def())
-                .join(JoinType::LeftJoin, UserRelations::AccessToken.def())
-                .filter(AccessTokenColumn::AccessToken.eq(token))
+                .filter(CustomGroupColumn::UserId.eq(user_id))
                 .all(conn)
         )
         .inspect_err(|error| {
             tracing::error!(error = error as &dyn std::error::Error, "error finding user by token");
         })?;
-        Ok(user.map(|user| (user, oidc_groups, custom_groups)))
+        Ok(user.map(|user| (access_token, (user, oidc_groups, custom_groups))))
+    }
+
+    /// Lists the users which are in the group, either through the identity provider or as custom group, together with
+    /// all their groups
+    pub async fn get_users_by_group<C: ConnectionTrait>(
+        conn: &C,
+        group: &str,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<(User, Vec<OidcGroup>, Vec<CustomGroup>)>, DbErr> {
+        let oidc_members = OidcGroupEntity::find()
+            .select_only()
+            .column(OidcGroupColumn::UserId)
+            .filter(OidcGroupColumn::Value.eq(group))
+            .into_query();
+        let custom_members = CustomGroupEntity::find()
+            .select_only()
+            .column(CustomGroupColumn::UserId)
+            .filter(CustomGroupColumn::Value.eq(group))
+            .into_query();
+        let users = UserEntity::find()
+            .filter(
+                Condition::any()
+                    .add(UserColumn::Id.in_subquery(oidc_members))
+                    .add(UserColumn::Id.in_subquery(custom_members)),
+            )
+            .order_by_asc(UserColumn::Id)
+            .limit(limit)
+            .offset(offset)
+            .all(conn)
+            .await
+            .inspect_err(|error| {
+                tracing::error!(error = error as &dyn std::error::Error, %group, "error loading users by group");
+            })?;
+
+        let user_ids: Vec<Uuid> = users.iter().map(|user| user.id).collect();
+        let (oidc_groups, custom_groups) = try_join!(
+            OidcGroupEntity::find()
+                .filter(OidcGroupColumn::UserId.is_in(user_ids.clone()))
+                .all(conn),
+            CustomGroupEntity::find()
+                .filter(CustomGroupColumn::UserId.is_in(user_ids))
+                .all(conn)
+        )
+        .inspect_err(|error| {
+            tracing::error!(error = error as &dyn std::error::Error, %group, "error loading groups of users");
+        })?;
+
+        Ok(users
+            .into_iter()
+            .map(|user| {
+                let user_oidc_groups = oidc_groups.iter().filter(|g| g.user_id == user.id).cloned().collect();
+                let user_custom_groups = custom_groups.iter().filter(|g| g.user_id == user.id).cloned().collect();
+                (user, user_oidc_groups, user_custom_groups)
+            })
+            .collect())
     }
 }
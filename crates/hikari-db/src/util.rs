@@ -3,6 +3,8 @@ use ring::rand::{self, SecureRandom};
 use sea_orm::{DbErr, TransactionError};
 use std::error::Error;
 
+pub mod lease;
+
 pub trait FlattenTransactionResultExt<T> {
     fn flatten_res(self) -> T;
 }
@@ -0,0 +1,123 @@
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect, UpdateMany,
+    Value,
+};
+use std::error::Error;
+use uuid::Uuid;
+
+/// A queued job which is processed by one instance at a time.
+///
+/// The instance which claims a job holds a lease on it. Other instances can take the job over once the lease expired,
+/// e.g. because the instance crashed.
+pub trait LeasedJob: EntityTrait {
+    type Status: Into<Value> + Copy;
+
+    /// Name of the job in log messages
+    const NAME: &'static str;
+
+    const PENDING: Self::Status;
+    const RUNNING: Self::Status;
+    const FAILED: Self::Status;
+
+    const ID: Self::Column;
+    const STATUS: Self::Column;
+    const ATTEMPTS: Self::Column;
+    const LEASE_OWNER: Self::Column;
+    const LEASE_EXPIRES_AT: Self::Column;
+    const ERROR: Self::Column;
+    const CREATED_AT: Self::Column;
+    const UPDATED_AT: Self::Column;
+}
+
+/// Jobs which are waiting to be processed or whose lease expired
+pub fn claimable_condition<J: LeasedJob>(now: NaiveDateTime) -> Condition {
+    Condition::any().add(J::STATUS.eq(J::PENDING)).add(
+        Condition::all()
+            .add(J::STATUS.eq(J::RUNNING))
+            .add(J::LEASE_EXPIRES_AT.lt(now)),
+    )
+}
+
+/// Jobs which are waiting to be processed or whose lease expired, oldest first.
+pub async fn find_claimable<J: LeasedJob, C: ConnectionTrait>(
+    conn: &C,
+    now: NaiveDateTime,
+    limit: u64,
+) -> Result<Vec<J::Model>, DbErr> {
+    J::find()
+        .filter(claimable_condition::<J>(now))
+        .order_by_asc(J::CREATED_AT)
+        .limit(limit)
+        .all(conn)
+        .await
+        .inspect_err(|error| {
+            tracing::error!(
+                error = error as &dyn Error,
+                job = J::NAME,
+                "failed to load claimable jobs"
+            );
+        })
+}
+
+/// Tries to claim a job for `owner` until `lease_expires_at`.
+///
+/// The claim only succeeds if the job is still pending or its lease expired, so a job is never processed by two
+/// instances at the same time. Returns the claimed job on success.
+pub async fn claim<J: LeasedJob, C: ConnectionTrait>(
+    conn: &C,
+    job_id: Uuid,
+    owner: &str,
+    lease_expires_at: NaiveDateTime,
+) -> Result<Option<J::Model>, DbErr> {
+    let now = Utc::now().naive_utc();
+    let res = J::update_many()
+        .col_expr(J::STATUS, Expr::value(J::RUNNING))
+        .col_expr(J::LEASE_OWNER, Expr::value(owner))
+        .col_expr(J::LEASE_EXPIRES_AT, Expr::value(lease_expires_at))
+        .col_expr(J::ATTEMPTS, Expr::col(J::ATTEMPTS).add(1))
+        .col_expr(J::UPDATED_AT, Expr::value(now))
+        .filter(J::ID.eq(job_id))
+        .filter(claimable_condition::<J>(now))
+        .exec(conn)
+        .await
+        .inspect_err(|error| {
+            tracing::error!(error = error as &dyn Error, job = J::NAME, "failed to claim job");
+        })?;
+    if res.rows_affected == 0 {
+        return Ok(None);
+    }
+    J::find().filter(J::ID.eq(job_id)).one(conn).await
+}
+
+/// Ends the lease of `owner` and sets the status of the job. The update does nothing if the lease was taken over by
+/// another instance. Job types add the columns of their result before executing it.
+pub fn release<J: LeasedJob>(job_id: Uuid, owner: &str, status: J::Status, error: Option<&str>) -> UpdateMany<J> {
+    J::update_many()
+        .col_expr(J::STATUS, Expr::value(status))
+        .col_expr(J::LEASE_OWNER, Expr::value(Option::<String>::None))
+        .col_expr(J::LEASE_EXPIRES_AT, Expr::value(Option::<NaiveDateTime>::None))
+        .col_expr(J::ERROR, Expr::value(error.map(ToOwned::to_owned)))
+        .col_expr(J::UPDATED_AT, Expr::value(Utc::now().naive_utc()))
+        .filter(J::ID.eq(job_id))
+        .filter(J::LEASE_OWNER.eq(owner))
+}
+
+/// Releases a job after an error. It is picked up again if `retry` is set, otherwise it is marked as failed.
+pub async fn fail<J: LeasedJob, C: ConnectionTrait>(
+    conn: &C,
+    job_id: Uuid,
+    owner: &str,
+    error: &str,
+    retry: bool,
+) -> Result<bool, DbErr> {
+    let status = if retry { J::PENDING } else { J::FAILED };
+    let res = release::<J>(job_id, owner, status, Some(error))
+        .exec(conn)
+        .await
+        .inspect_err(|error| {
+            tracing::error!(error = error as &dyn Error, job = J::NAME, "failed to release job");
+        })?;
+    Ok(res.rows_affected > 0)
+}
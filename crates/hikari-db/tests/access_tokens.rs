@@ -0,0 +1,107 @@
+mod common;
+
+use crate::common::setup_schema;
+use crate::common::user::create_test_user;
+use chrono::{Duration, Utc};
+use hikari_db::access_tokens::{Mutation, Query, hash_token};
+use hikari_db::user;
+use sea_orm::Database;
+
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_access_tokens_per_device() {
+    let db = &Database::connect("sqlite::memory:").await.unwrap();
+    setup_schema(db).await.unwrap();
+    let user = create_test_user(db).await;
+
+    let now = Utc::now().naive_utc();
+    let expires_at = now + Duration::days(30);
+    let phone = Mutation::create_access_token(db, user.id, Some("phone".to_owned()), now, expires_at)
+        .await
+        .unwrap();
+    let laptop = Mutation::create_access_token(db, user.id, Some("laptop".to_owned()), now, expires_at)
+        .await
+        .unwrap();
+
+    // Only the hash of the token is stored
+    assert_ne!(phone.model.token_hash, phone.token.as_bytes());
+    assert_eq!(phone.model.token_hash, hash_token(&phone.token));
+
+    let (token, (found_user, ..)) = user::Query::find_by_token(db, &phone.token, now)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(token.id, phone.model.id);
+    assert_eq!(found_user.id, user.id);
+
+    assert_eq!(Query::get_user_access_tokens(db, user.id, now).await.unwrap().len(), 2);
+
+    // Revoking one device keeps the other logged in
+    assert!(
+        Mutation::delete_access_token(db, user.id, phone.model.id)
+            .await
+            .unwrap()
+    );
+    assert!(
+        !Mutation::delete_access_token(db, user.id, phone.model.id)
+            .await
+            .unwrap()
+    );
+    assert!(
+        user::Query::find_by_token(db, &phone.token, now)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        user::Query::find_by_token(db, &laptop.token, now)
+            .await
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[test(tokio::test)]
+async fn test_access_token_expiry() {
+    let db = &Database::connect("sqlite::memory:").await.unwrap();
+    setup_schema(db).await.unwrap();
+    let user = create_test_user(db).await;
+
+    let now = Utc::now().naive_utc();
+    let token = Mutation::create_access_token(db, user.id, None, now, now + Duration::days(1))
+        .await
+        .unwrap();
+
+    let later = now + Duration::days(2);
+    assert!(
+        user::Query::find_by_token(db, &token.token, later)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    // Refreshing slides the expiry forward
+    Mutation::refresh_access_token(db, token.model.id, now, later + Duration::days(1))
+        .await
+        .unwrap();
+    assert!(
+        user::Query::find_by_token(db, &token.token, later)
+            .await
+            .unwrap()
+            .is_some()
+    );
+
+    assert_eq!(
+        Mutation::delete_expired_access_tokens(db, later + Duration::days(2))
+            .await
+            .unwrap(),
+        1
+    );
+    assert!(
+        Query::get_user_access_tokens(db, user.id, now)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
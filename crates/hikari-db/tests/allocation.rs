@@ -0,0 +1,51 @@
+mod common;
+
+use crate::common::setup_schema;
+use crate::common::user::create_test_user;
+use chrono::Utc;
+use hikari_db::allocation::{Mutation, Query};
+use sea_orm::Database;
+
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_allocation_sequence() {
+    let db = &Database::connect("sqlite::memory:").await.unwrap();
+    setup_schema(db).await.unwrap();
+    let user = create_test_user(db).await;
+    let now = Utc::now().naive_utc();
+
+    let sequence = Mutation::get_or_create_sequence(db, "study", 0, "semester=3", 42, 2)
+        .await
+        .unwrap();
+    // An existing sequence keeps its seed
+    let existing = Mutation::get_or_create_sequence(db, "study", 0, "semester=3", 7, 2)
+        .await
+        .unwrap();
+    assert_eq!(existing.id, sequence.id);
+    assert_eq!(existing.seed, 42);
+
+    assert_eq!(Query::length(db, sequence.id).await.unwrap(), 0);
+    assert!(Query::next_free(db, sequence.id).await.unwrap().is_none());
+
+    let block = vec!["treatment".to_owned(), "control".to_owned()];
+    Mutation::insert_block(db, sequence.id, 0, block.clone()).await.unwrap();
+    // Inserting the same block again is ignored
+    Mutation::insert_block(db, sequence.id, 0, block).await.unwrap();
+    assert_eq!(Query::length(db, sequence.id).await.unwrap(), 2);
+
+    let free = Query::next_free(db, sequence.id).await.unwrap().unwrap();
+    assert_eq!(free.position, 0);
+    assert_eq!(free.group_name, "treatment");
+    assert!(Mutation::claim(db, sequence.id, 0, user.id, now).await.unwrap());
+    assert!(!Mutation::claim(db, sequence.id, 0, user.id, now).await.unwrap());
+    assert_eq!(Query::next_free(db, sequence.id).await.unwrap().unwrap().position, 1);
+
+    let allocations = Query::allocations(db, vec![sequence.id]).await.unwrap();
+    assert_eq!(allocations.len(), 2);
+    assert_eq!(allocations[0].user_id, Some(user.id));
+    assert!(allocations[1].allocated_at.is_none());
+
+    assert_eq!(Query::sequences(db, Some("study")).await.unwrap().len(), 1);
+    assert!(Query::sequences(db, Some("other")).await.unwrap().is_empty());
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use crate::common::setup_schema;
+use crate::common::user::create_test_user;
+use hikari_db::llm::audio::{Mutation, Query};
+use sea_orm::Database;
+use uuid::Uuid;
+
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_audio() {
+    let db = &Database::connect("sqlite::memory:").await.unwrap();
+    setup_schema(db).await.unwrap();
+    let user = create_test_user(db).await;
+    let other = create_test_user(db).await;
+    let conversation_id = Uuid::new_v4();
+
+    let voice = Mutation::insert_voice_message(
+        db,
+        user.id,
+        conversation_id,
+        "audio/webm".to_owned(),
+        vec![1, 2],
+        "Hallo".to_owned(),
+    )
+    .await
+    .unwrap();
+    Mutation::insert_message_speech(db, user.id, conversation_id, 3, "audio/mpeg".to_owned(), vec![3])
+        .await
+        .unwrap();
+
+    let loaded = Query::get_audio(db, user.id, voice.id).await.unwrap().unwrap();
+    assert_eq!(loaded.transcript.as_deref(), Some("Hallo"));
+    assert_eq!(loaded.message_order, None);
+    assert!(Query::get_audio(db, other.id, voice.id).await.unwrap().is_none());
+
+    let speech = Query::get_message_audio(db, user.id, conversation_id, 3)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(speech.data, vec![3]);
+    assert!(
+        Query::get_message_audio(db, user.id, conversation_id, 4)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    // Audio of other users can not be deleted
+    assert!(!Mutation::delete_audio(db, other.id, voice.id).await.unwrap());
+    assert!(Mutation::delete_audio(db, user.id, voice.id).await.unwrap());
+    assert!(Query::get_audio(db, user.id, voice.id).await.unwrap().is_none());
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use crate::common::setup_schema;
+use hikari_db::audit_log::{AuditLogFilter, Mutation, NewAuditEntry, Query};
+use hikari_entity::audit_log::Entity;
+use sea_orm::prelude::*;
+use sea_orm::{Database, EntityTrait};
+
+use test_log::test;
+
+fn entry(actor_id: Uuid, action: &str, subject_id: Option<Uuid>) -> NewAuditEntry {
+    NewAuditEntry {
+        actor_id: Some(actor_id),
+        action: action.to_owned(),
+        subject_id,
+        request_id: Some("request".to_owned()),
+        details: Json::String("details".to_owned()),
+    }
+}
+
+#[test(tokio::test)]
+async fn test_audit_log_filter() {
+    let db = &Database::connect("sqlite::memory:").await.unwrap();
+    setup_schema(db).await.unwrap();
+
+    let admin = Uuid::new_v4();
+    let user = Uuid::new_v4();
+    Mutation::append(db, entry(admin, "grant_groups", Some(user)))
+        .await
+        .unwrap();
+    Mutation::append(db, entry(user, "delete_user", Some(user)))
+        .await
+        .unwrap();
+    Mutation::append(db, entry(admin, "list_users", None)).await.unwrap();
+
+    let all = Query::find(db, AuditLogFilter::default(), 10, 0).await.unwrap();
+    assert_eq!(all.len(), 3);
+
+    let by_admin = Query::find(
+        db,
+        AuditLogFilter {
+            actor_id: Some(admin),
+            ..Default::default()
+        },
+        10,
+        0,
+    )
+    .await
+    .unwrap();
+    assert_eq!(by_admin.len(), 2);
+
+    let on_user = Query::find(
+        db,
+        AuditLogFilter {
+            subject_id: Some(user),
+            action: Some("delete_user".to_owned()),
+            ..Default::default()
+        },
+        10,
+        0,
+    )
+    .await
+    .unwrap();
+    assert_eq!(on_user.len(), 1);
+    assert_eq!(on_user[0].actor_id, Some(user));
+    assert_eq!(on_user[0].details, Json::String("details".to_owned()));
+
+    assert_eq!(Query::find(db, AuditLogFilter::default(), 2, 2).await.unwrap().len(), 1);
+}
+
+#[test(tokio::test)]
+async fn test_audit_log_is_append_only() {
+    let db = &Database::connect("sqlite::memory:").await.unwrap();
+    setup_schema(db).await.unwrap();
+
+    let model = Mutation::append(db, entry(Uuid::new_v4(), "delete_user", None))
+        .await
+        .unwrap();
+
+    assert!(Entity::delete_by_id(model.id).exec(db).await.is_err());
+    assert!(
+        Entity::update_many()
+            .col_expr(hikari_entity::audit_log::Column::Action, Expr::value("something_else"))
+            .exec(db)
+            .await
+            .is_err()
+    );
+    assert_eq!(
+        Query::find(db, AuditLogFilter::default(), 10, 0).await.unwrap().len(),
+        1
+    );
+}
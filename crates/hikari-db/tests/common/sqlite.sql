@@ -20,13 +20,16 @@ CREATE TABLE users
 CREATE TABLE access_tokens
 (
     id           INTEGER PRIMARY KEY NOT NULL,
-    user_id      BLOB                NOT NULL UNIQUE,
-    --  TODO (LOW) shorten the varchar
-    access_token varchar(255)        NOT NULL,
+    user_id      BLOB                NOT NULL,
+    token_hash   BLOB                NOT NULL UNIQUE,
+    device_name  TEXT,
+    created_at   TEXT                NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_at TEXT                NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at   TEXT                NOT NULL,
     FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
 );
 
-CREATE INDEX index_access_tokens ON access_tokens (access_token);
+CREATE INDEX access_tokens_user_id ON access_tokens (user_id);
 
 CREATE TABLE user_handle
 (
@@ -243,3 +246,171 @@ CREATE TABLE "journal_topic"
     summary            TEXT             NOT NULL,
     FOREIGN KEY (journal_summary_id) REFERENCES "journal_summary" (id) ON DELETE CASCADE
 );
+
+CREATE TABLE "journal_summary_job"
+(
+    id               BLOB PRIMARY KEY NOT NULL,
+    user_id          BLOB             NOT NULL,
+    key              BLOB             NOT NULL,
+    date             TEXT             NOT NULL,
+    timestamp        TEXT             NOT NULL,
+    utc_offset       INTEGER          NOT NULL DEFAULT 0,
+    status           TEXT             NOT NULL DEFAULT 'pending',
+    attempts         INTEGER          NOT NULL DEFAULT 0,
+    lease_owner      TEXT,
+    lease_expires_at TEXT,
+    summary_id       BLOB,
+    error            TEXT,
+    created_at       TEXT             NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at       TEXT             NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (user_id, key, date),
+    FOREIGN KEY (user_id) REFERENCES "users" (id) ON DELETE CASCADE,
+    FOREIGN KEY (summary_id) REFERENCES "journal_summary" (id) ON DELETE SET NULL
+);
+
+CREATE TABLE "audit_log"
+(
+    id         BLOB PRIMARY KEY NOT NULL,
+    created_at TEXT             NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    actor_id   BLOB,
+    action     TEXT             NOT NULL,
+    subject_id BLOB,
+    request_id TEXT,
+    details    TEXT             NOT NULL DEFAULT '{}'
+);
+
+CREATE TRIGGER audit_log_no_update BEFORE UPDATE ON "audit_log"
+BEGIN
+    SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
+
+CREATE TRIGGER audit_log_no_delete BEFORE DELETE ON "audit_log"
+BEGIN
+    SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
+
+CREATE TABLE "export_job"
+(
+    id               BLOB PRIMARY KEY NOT NULL,
+    user_id          BLOB             NOT NULL,
+    status           TEXT             NOT NULL DEFAULT 'pending',
+    attempts         INTEGER          NOT NULL DEFAULT 0,
+    lease_owner      TEXT,
+    lease_expires_at TEXT,
+    archive_key      TEXT,
+    error            TEXT,
+    created_at       TEXT             NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at       TEXT             NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at       TEXT             NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES "users" (id) ON DELETE CASCADE
+);
+
+CREATE TABLE "allocation_sequence"
+(
+    id         BLOB PRIMARY KEY NOT NULL,
+    token      TEXT             NOT NULL,
+    slot       INTEGER          NOT NULL,
+    stratum    TEXT             NOT NULL,
+    seed       BIGINT           NOT NULL,
+    block_size INTEGER          NOT NULL,
+    created_at TEXT             NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (token, slot, stratum)
+);
+
+CREATE TABLE "allocation"
+(
+    sequence_id  BLOB    NOT NULL,
+    position     INTEGER NOT NULL,
+    group_name   TEXT    NOT NULL,
+    user_id      BLOB,
+    allocated_at TEXT,
+    PRIMARY KEY (sequence_id, position),
+    FOREIGN KEY (sequence_id) REFERENCES "allocation_sequence" (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES "users" (id) ON DELETE SET NULL
+);
+
+-- No conversation foreign key, the tests do not create conversations
+CREATE TABLE "llm_learner_memory"
+(
+    id              BLOB PRIMARY KEY NOT NULL,
+    user_id         BLOB             NOT NULL,
+    module_id       TEXT             NOT NULL,
+    session_id      TEXT             NOT NULL,
+    conversation_id BLOB,
+    content         TEXT             NOT NULL,
+    created_at      TEXT             NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES "users" (id) ON DELETE CASCADE
+);
+
+CREATE TABLE "llm_learner_memory_consent"
+(
+    user_id    BLOB PRIMARY KEY NOT NULL,
+    created_at TEXT             NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES "users" (id) ON DELETE CASCADE
+);
+
+CREATE TABLE "llm_image"
+(
+    id         BLOB PRIMARY KEY NOT NULL,
+    user_id    BLOB             NOT NULL,
+    mime_type  TEXT             NOT NULL,
+    data       BLOB             NOT NULL,
+    created_at TEXT             NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES "users" (id) ON DELETE CASCADE
+);
+
+-- No conversation foreign key, the tests do not create conversations
+CREATE TABLE "llm_audio"
+(
+    id              BLOB PRIMARY KEY NOT NULL,
+    user_id         BLOB             NOT NULL,
+    conversation_id BLOB,
+    message_order   INTEGER,
+    mime_type       TEXT             NOT NULL,
+    data            BLOB             NOT NULL,
+    transcript      TEXT,
+    created_at      TEXT             NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES "users" (id) ON DELETE CASCADE
+);
+
+CREATE TABLE "llm_guardrail_event"
+(
+    id              BLOB PRIMARY KEY NOT NULL,
+    conversation_id BLOB             NOT NULL,
+    user_id         BLOB             NOT NULL,
+    step            TEXT             NOT NULL,
+    direction       TEXT             NOT NULL,
+    categories      TEXT             NOT NULL,
+    action          TEXT             NOT NULL,
+    content         TEXT             NOT NULL,
+    created_at      TEXT             NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    reviewed_at     TEXT,
+    reviewed_by     BLOB,
+    FOREIGN KEY (user_id) REFERENCES "users" (id) ON DELETE CASCADE
+);
+
+CREATE TABLE "crisis_event"
+(
+    id           BLOB PRIMARY KEY NOT NULL,
+    user_id      BLOB             NOT NULL,
+    source       TEXT             NOT NULL,
+    reference_id BLOB,
+    detector     TEXT             NOT NULL,
+    created_at   TEXT             NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    notified_at  TEXT,
+    FOREIGN KEY (user_id) REFERENCES "users" (id) ON DELETE CASCADE
+);
+
+CREATE TABLE "llm_message"
+(
+    conversation_id BLOB    NOT NULL,
+    message_order   INTEGER NOT NULL,
+    step            TEXT    NOT NULL,
+    created_at      TEXT    NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    content_type    TEXT    NOT NULL,
+    payload         TEXT    NOT NULL,
+    direction       TEXT    NOT NULL,
+    status          TEXT    NOT NULL,
+    superseded_by   INTEGER,
+    PRIMARY KEY (conversation_id, message_order)
+);
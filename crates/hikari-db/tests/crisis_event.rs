@@ -0,0 +1,55 @@
+mod common;
+
+use crate::common::setup_schema;
+use crate::common::user::create_test_user;
+use chrono::{Duration, Utc};
+use hikari_db::crisis_event::{Mutation, Query};
+use hikari_entity::crisis_event::Entity;
+use sea_orm::{Database, EntityTrait};
+use uuid::Uuid;
+
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_crisis_events() {
+    let db = &Database::connect("sqlite::memory:").await.unwrap();
+    setup_schema(db).await.unwrap();
+    let user = create_test_user(db).await;
+
+    let event = Mutation::log(db, user.id, "journal", Some(Uuid::new_v4()), "keyword")
+        .await
+        .unwrap();
+    assert!(event.notified_at.is_none());
+    assert_eq!(Query::find_by_id(db, event.id).await.unwrap().unwrap().user_id, user.id);
+    assert!(Query::find_by_id(db, Uuid::new_v4()).await.unwrap().is_none());
+
+    // Only events older than the given time are retried
+    let later = Utc::now().naive_utc() + Duration::minutes(1);
+    assert!(
+        Query::find_unnotified(db, event.created_at, 10)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(Query::find_unnotified(db, later, 10).await.unwrap().len(), 1);
+
+    assert!(Mutation::mark_notified(db, event.id).await.unwrap());
+    assert!(Query::find_unnotified(db, later, 10).await.unwrap().is_empty());
+    assert!(!Mutation::mark_notified(db, Uuid::new_v4()).await.unwrap());
+    let stored = Entity::find_by_id(event.id).one(db).await.unwrap().unwrap();
+    assert!(stored.notified_at.is_some());
+
+    assert_eq!(
+        Mutation::delete_before(db, Utc::now().naive_utc() - Duration::days(1))
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        Mutation::delete_before(db, Utc::now().naive_utc() + Duration::days(1))
+            .await
+            .unwrap(),
+        1
+    );
+    assert!(Entity::find().all(db).await.unwrap().is_empty());
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use crate::common::setup_schema;
+use crate::common::user::create_test_user;
+use chrono::{Duration, Utc};
+use hikari_db::export::job::{Mutation, Query};
+use hikari_entity::export_job::Status;
+use sea_orm::Database;
+
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_export_job_lifecycle() {
+    let db = &Database::connect("sqlite::memory:").await.unwrap();
+    setup_schema(db).await.unwrap();
+    let user = create_test_user(db).await;
+
+    let now = Utc::now().naive_utc();
+    let job = Mutation::create(db, user.id, now + Duration::days(7)).await.unwrap();
+    assert_eq!(job.status, Status::Pending);
+    assert_eq!(Query::count_active(db, user.id).await.unwrap(), 1);
+
+    let lease = now + Duration::minutes(5);
+    let claimed = Mutation::claim(db, job.id, "a", lease).await.unwrap().unwrap();
+    assert_eq!(claimed.status, Status::Running);
+    assert_eq!(claimed.attempts, 1);
+    // A running job with a valid lease can not be claimed by another instance
+    assert!(Mutation::claim(db, job.id, "b", lease).await.unwrap().is_none());
+    assert!(Query::find_claimable(db, now, 10).await.unwrap().is_empty());
+
+    // Only the lease owner can finish the job
+    assert!(!Mutation::complete(db, job.id, "b", "exports/a.zip").await.unwrap());
+    assert!(Mutation::complete(db, job.id, "a", "exports/a.zip").await.unwrap());
+    let loaded = Query::get_user_job(db, user.id, job.id).await.unwrap().unwrap();
+    assert_eq!(loaded.status, Status::Completed);
+    assert_eq!(loaded.archive_key.as_deref(), Some("exports/a.zip"));
+    assert_eq!(Query::count_active(db, user.id).await.unwrap(), 0);
+
+    // Jobs of other users are not visible
+    assert!(
+        Query::get_user_job(db, uuid::Uuid::new_v4(), job.id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    assert!(Query::find_expired(db, now).await.unwrap().is_empty());
+    let expired = Query::find_expired(db, now + Duration::days(8)).await.unwrap();
+    assert_eq!(expired.len(), 1);
+    assert_eq!(Mutation::delete(db, vec![job.id]).await.unwrap(), 1);
+    assert!(Query::get_user_job(db, user.id, job.id).await.unwrap().is_none());
+}
+
+#[test(tokio::test)]
+async fn test_export_job_expired_lease_and_retry() {
+    let db = &Database::connect("sqlite::memory:").await.unwrap();
+    setup_schema(db).await.unwrap();
+    let user = create_test_user(db).await;
+
+    let now = Utc::now().naive_utc();
+    let job = Mutation::create(db, user.id, now + Duration::days(7)).await.unwrap();
+
+    // The lease of a crashed instance expires and the job can be taken over
+    Mutation::claim(db, job.id, "a", now - Duration::minutes(1))
+        .await
+        .unwrap()
+        .unwrap();
+    let claimable = Query::find_claimable(db, now, 10).await.unwrap();
+    assert_eq!(claimable.len(), 1);
+    let claimed = Mutation::claim(db, job.id, "b", now + Duration::minutes(5))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(claimed.attempts, 2);
+
+    // The old owner can no longer release the job
+    assert!(!Mutation::fail(db, job.id, "a", "error", false).await.unwrap());
+    assert!(Mutation::fail(db, job.id, "b", "error", true).await.unwrap());
+    let loaded = Query::get_user_job(db, user.id, job.id).await.unwrap().unwrap();
+    assert_eq!(loaded.status, Status::Pending);
+    assert_eq!(loaded.error.as_deref(), Some("error"));
+}
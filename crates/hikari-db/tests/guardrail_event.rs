@@ -0,0 +1,76 @@
+mod common;
+
+use crate::common::setup_schema;
+use crate::common::user::create_test_user;
+use hikari_db::llm::guardrail_event::{GuardrailEventFilter, Mutation, Query};
+use sea_orm::Database;
+use uuid::Uuid;
+
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_guardrail_events() {
+    let db = &Database::connect("sqlite::memory:").await.unwrap();
+    setup_schema(db).await.unwrap();
+    let user = create_test_user(db).await;
+    let reviewer = create_test_user(db).await;
+    let conversation_id = Uuid::new_v4();
+
+    let input = Mutation::insert_event(
+        db,
+        conversation_id,
+        user.id,
+        "chat".to_owned(),
+        "input".to_owned(),
+        &["self-harm".to_owned(), "self-harm/intent".to_owned()],
+        "escalate".to_owned(),
+        "Ich kann nicht mehr".to_owned(),
+    )
+    .await
+    .unwrap();
+    Mutation::insert_event(
+        db,
+        Uuid::new_v4(),
+        user.id,
+        "chat".to_owned(),
+        "output".to_owned(),
+        &["violence".to_owned()],
+        "block".to_owned(),
+        "...".to_owned(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(input.categories, "self-harm,self-harm/intent");
+
+    let all = Query::find(db, GuardrailEventFilter::default(), 10, 0).await.unwrap();
+    assert_eq!(all.len(), 2);
+
+    let filter = GuardrailEventFilter {
+        conversation_id: Some(conversation_id),
+        ..Default::default()
+    };
+    let events = Query::find(db, filter, 10, 0).await.unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].action, "escalate");
+
+    let reviewed = Mutation::mark_reviewed(db, input.id, reviewer.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(reviewed.reviewed_by, Some(reviewer.id));
+    assert!(reviewed.reviewed_at.is_some());
+    assert!(
+        Mutation::mark_reviewed(db, Uuid::new_v4(), reviewer.id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    let filter = GuardrailEventFilter {
+        unreviewed: true,
+        ..Default::default()
+    };
+    let unreviewed = Query::find(db, filter, 10, 0).await.unwrap();
+    assert_eq!(unreviewed.len(), 1);
+    assert_eq!(unreviewed[0].direction, "output");
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use crate::common::setup_schema;
+use crate::common::user::create_test_user;
+use hikari_db::llm::image::{Mutation, Query};
+use sea_orm::Database;
+
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_image() {
+    let db = &Database::connect("sqlite::memory:").await.unwrap();
+    setup_schema(db).await.unwrap();
+    let user = create_test_user(db).await;
+    let other = create_test_user(db).await;
+
+    let image = Mutation::insert_image(db, user.id, "image/png".to_owned(), vec![1, 2, 3])
+        .await
+        .unwrap();
+    let foreign = Mutation::insert_image(db, other.id, "image/jpeg".to_owned(), vec![4])
+        .await
+        .unwrap();
+
+    assert_eq!(Query::get_user_storage(db, user.id).await.unwrap(), 3);
+    assert_eq!(Query::get_user_storage(db, other.id).await.unwrap(), 1);
+
+    let loaded = Query::get_image(db, user.id, image.id).await.unwrap().unwrap();
+    assert_eq!(loaded.mime_type, "image/png");
+    assert_eq!(loaded.data, vec![1, 2, 3]);
+
+    // Images of other users are not accessible
+    assert!(Query::get_image(db, user.id, foreign.id).await.unwrap().is_none());
+    let images = Query::get_images(db, user.id, vec![image.id, foreign.id])
+        .await
+        .unwrap();
+    assert_eq!(images.len(), 1);
+
+    assert!(!Mutation::delete_image(db, user.id, foreign.id).await.unwrap());
+    assert!(Mutation::delete_image(db, user.id, image.id).await.unwrap());
+    assert!(Query::get_image(db, user.id, image.id).await.unwrap().is_none());
+    assert_eq!(Query::get_user_storage(db, user.id).await.unwrap(), 0);
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use crate::common::setup_schema;
+use crate::common::user::create_test_user;
+use chrono::{Duration, Utc};
+use hikari_db::journal::journal_summary_job::{Mutation, Query};
+use hikari_entity::journal::journal_summary_job::Status;
+use sea_orm::Database;
+
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_summary_job_lease() {
+    let db = &Database::connect("sqlite::memory:").await.unwrap();
+    setup_schema(db).await.unwrap();
+    let user = create_test_user(db).await;
+
+    let key = [1u8; 32];
+    let date = Utc::now().naive_utc();
+    let job = Mutation::get_or_create(db, user.id, &key, date, date, 3600)
+        .await
+        .unwrap();
+    assert_eq!(job.status, Status::Pending);
+
+    // Requesting the same summary again reuses the job
+    let same_job = Mutation::get_or_create(db, user.id, &key, date, date, 3600)
+        .await
+        .unwrap();
+    assert_eq!(job.id, same_job.id);
+
+    let lease_expires_at = (Utc::now() + Duration::minutes(5)).naive_utc();
+    let claimed = Mutation::claim(db, job.id, "instance-a", lease_expires_at)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(claimed.status, Status::Running);
+    assert_eq!(claimed.attempts, 1);
+
+    // A running job with a valid lease can not be claimed by another instance
+    assert!(
+        Mutation::claim(db, job.id, "instance-b", lease_expires_at)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        Query::find_claimable(db, Utc::now().naive_utc(), 10)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    // Only the lease owner may release the job
+    assert!(!Mutation::fail(db, job.id, "instance-b", "error", true).await.unwrap());
+    assert!(Mutation::fail(db, job.id, "instance-a", "error", true).await.unwrap());
+
+    let claimable = Query::find_claimable(db, Utc::now().naive_utc(), 10).await.unwrap();
+    assert_eq!(claimable.len(), 1);
+
+    let claimed = Mutation::claim(db, job.id, "instance-b", lease_expires_at)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(claimed.attempts, 2);
+    assert_eq!(claimed.lease_owner.as_deref(), Some("instance-b"));
+}
+
+#[test(tokio::test)]
+async fn test_summary_job_expired_lease() {
+    let db = &Database::connect("sqlite::memory:").await.unwrap();
+    setup_schema(db).await.unwrap();
+    let user = create_test_user(db).await;
+
+    let date = Utc::now().naive_utc();
+    let job = Mutation::get_or_create(db, user.id, &[2u8; 32], date, date, 0)
+        .await
+        .unwrap();
+
+    let expired = (Utc::now() - Duration::minutes(1)).naive_utc();
+    Mutation::claim(db, job.id, "instance-a", expired)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // The lease of the crashed instance expired, so the job can be taken over
+    let claimed = Mutation::claim(
+        db,
+        job.id,
+        "instance-b",
+        (Utc::now() + Duration::minutes(5)).naive_utc(),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(claimed.lease_owner.as_deref(), Some("instance-b"));
+}
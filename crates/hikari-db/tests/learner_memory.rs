@@ -0,0 +1,75 @@
+mod common;
+
+use crate::common::setup_schema;
+use crate::common::user::create_test_user;
+use chrono::{TimeDelta, Utc};
+use hikari_db::llm::learner_memory::{Mutation, Query};
+use hikari_entity::llm::learner_memory;
+use sea_orm::ActiveValue::Set;
+use sea_orm::prelude::*;
+use sea_orm::{Database, EntityTrait};
+
+use test_log::test;
+
+async fn insert_memory(db: &DatabaseConnection, user_id: Uuid, content: &str, age: i64) -> Uuid {
+    let id = Uuid::new_v4();
+    learner_memory::Entity::insert(learner_memory::ActiveModel {
+        id: Set(id),
+        user_id: Set(user_id),
+        module_id: Set("module".to_owned()),
+        session_id: Set("session".to_owned()),
+        conversation_id: Set(None),
+        content: Set(content.to_owned()),
+        created_at: Set(Utc::now().naive_utc() - TimeDelta::days(age)),
+    })
+    .exec(db)
+    .await
+    .unwrap();
+    id
+}
+
+#[test(tokio::test)]
+async fn test_learner_memory() {
+    let db = &Database::connect("sqlite::memory:").await.unwrap();
+    setup_schema(db).await.unwrap();
+    let user = create_test_user(db).await;
+    let other = create_test_user(db).await;
+
+    let oldest = insert_memory(db, user.id, "Studiert Informatik", 3).await;
+    insert_memory(db, user.id, "Mag Beispiele", 2).await;
+    let newest = insert_memory(db, user.id, "Lernt abends", 1).await;
+    let foreign = insert_memory(db, other.id, "Studiert Medizin", 1).await;
+
+    let memories = Query::get_user_memories(db, user.id).await.unwrap();
+    assert_eq!(memories.len(), 3);
+    assert_eq!(memories.first().unwrap().id, newest);
+
+    // Memories of other users can not be deleted
+    assert!(!Mutation::delete_memory(db, user.id, foreign).await.unwrap());
+    assert!(Mutation::delete_memory(db, user.id, newest).await.unwrap());
+
+    assert_eq!(Mutation::prune_user_memories(db, user.id, 1).await.unwrap(), 1);
+    let memories = Query::get_user_memories(db, user.id).await.unwrap();
+    assert_eq!(memories.len(), 1);
+    assert_ne!(memories.first().unwrap().id, oldest);
+
+    assert_eq!(Mutation::delete_user_memories(db, user.id).await.unwrap(), 1);
+    assert!(Query::get_user_memories(db, user.id).await.unwrap().is_empty());
+    assert_eq!(Query::get_user_memories(db, other.id).await.unwrap().len(), 1);
+}
+
+#[test(tokio::test)]
+async fn test_learner_memory_consent() {
+    let db = &Database::connect("sqlite::memory:").await.unwrap();
+    setup_schema(db).await.unwrap();
+    let user = create_test_user(db).await;
+
+    assert!(!Query::has_consent(db, user.id).await.unwrap());
+    Mutation::set_consent(db, user.id, true).await.unwrap();
+    // Opting in twice is fine
+    Mutation::set_consent(db, user.id, true).await.unwrap();
+    assert!(Query::has_consent(db, user.id).await.unwrap());
+
+    Mutation::set_consent(db, user.id, false).await.unwrap();
+    assert!(!Query::has_consent(db, user.id).await.unwrap());
+}
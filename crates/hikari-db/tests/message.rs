@@ -0,0 +1,78 @@
+mod common;
+
+use crate::common::setup_schema;
+use hikari_db::llm::message::{Mutation, Query};
+use hikari_entity::llm::message::{ContentType, Direction, Status};
+use sea_orm::{Database, DatabaseConnection};
+use uuid::Uuid;
+
+use test_log::test;
+
+async fn insert(db: &DatabaseConnection, conversation_id: Uuid, direction: Direction, text: &str) -> i32 {
+    Mutation::insert_new_message(
+        db,
+        conversation_id,
+        "chat".to_owned(),
+        ContentType::Text,
+        format!(r#"{{"text":"{text}"}}"#),
+        direction,
+        Status::Completed,
+    )
+    .await
+    .unwrap()
+    .message_order
+}
+
+#[test(tokio::test)]
+async fn test_supersede_messages() {
+    let db = &Database::connect("sqlite::memory:").await.unwrap();
+    setup_schema(db).await.unwrap();
+    let conversation_id = Uuid::new_v4();
+
+    insert(db, conversation_id, Direction::Send, "Hallo").await;
+    let question = insert(db, conversation_id, Direction::Receive, "Wie geht es?").await;
+    insert(db, conversation_id, Direction::Send, "Gut").await;
+
+    let last = Query::get_last_user_message(db, conversation_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(last.message_order, question);
+
+    // The answer is moved into a branch which is replaced by the messages from order 3 on
+    assert_eq!(
+        Mutation::supersede_messages(db, conversation_id, question + 1)
+            .await
+            .unwrap(),
+        1
+    );
+    let answer = insert(db, conversation_id, Direction::Send, "Sehr gut").await;
+    assert_eq!(answer, 3);
+
+    let memory = Query::get_memory_from_conversation(db, &conversation_id, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        memory.iter().map(|message| message.message_order).collect::<Vec<_>>(),
+        vec![0, 1, 3]
+    );
+
+    // Superseding the question also supersedes the new answer, but not the old branch again
+    assert_eq!(
+        Mutation::supersede_messages(db, conversation_id, question)
+            .await
+            .unwrap(),
+        2
+    );
+    assert!(
+        Query::get_last_user_message(db, conversation_id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let memory = Query::get_memory_from_conversation(db, &conversation_id, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(memory.len(), 1);
+    assert_eq!(memory[0].superseded_by, None);
+}
@@ -3,6 +3,9 @@ mod common;
 use crate::common::setup_schema;
 use crate::common::user::create_test_user;
 
+use hikari_db::assessment::answer::QuestionAnswer;
+use hikari_entity::assessment::answer::AnswerType;
+use hikari_entity::assessment::session::AssessmentStatus;
 use sea_orm::Database;
 use test_log::test;
 use uuid::Uuid;
@@ -44,3 +47,53 @@ async fn test_assessment_module_creation() {
     assert_eq!(module_assessment.last_pre.unwrap(), assessment.id);
     assert!(module_assessment.last_post.is_none());
 }
+
+#[test(tokio::test)]
+async fn test_load_module_assessments_of_users() {
+    let conn = &Database::connect("sqlite::memory:").await.unwrap();
+    setup_schema(conn).await.unwrap();
+    let user = create_test_user(conn).await;
+    let other = create_test_user(conn).await;
+
+    let pre = hikari_db::assessment::session::Mutation::new_assessment(conn, user.id, "test".to_string())
+        .await
+        .unwrap();
+    hikari_db::assessment::session::Mutation::finish_assessment(
+        conn,
+        pre.id,
+        vec![QuestionAnswer {
+            question: "q1".to_owned(),
+            answer_type: AnswerType::Int,
+            data: "3".to_owned(),
+        }],
+    )
+    .await
+    .unwrap();
+    hikari_db::module::assessment::Mutation::insert_or_update_module_assessment(
+        conn,
+        user.id,
+        "test".to_owned(),
+        Some(pre.id),
+        None,
+    )
+    .await
+    .unwrap();
+
+    let assessments = hikari_db::module::assessment::Query::get_for_module_users(conn, vec![user.id, other.id], "test")
+        .await
+        .unwrap();
+    assert_eq!(assessments.len(), 1);
+    assert_eq!(assessments[0].user_id, user.id);
+
+    let sessions = hikari_db::assessment::session::Query::load_sessions_by_ids(conn, vec![pre.id, Uuid::new_v4()])
+        .await
+        .unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].status, AssessmentStatus::Finished);
+
+    let answers = hikari_db::assessment::answer::Query::load_answers_of_sessions(conn, vec![pre.id])
+        .await
+        .unwrap();
+    assert_eq!(answers.len(), 1);
+    assert_eq!(answers[0].data, "3");
+}
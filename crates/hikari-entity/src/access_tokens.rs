@@ -6,7 +6,12 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
     pub user_id: Uuid,
-    pub access_token: String,
+    /// SHA-256 hash of the token, the token itself is only known to the client
+    pub token_hash: Vec<u8>,
+    pub device_name: Option<String>,
+    pub created_at: DateTime,
+    pub last_used_at: DateTime,
+    pub expires_at: DateTime,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
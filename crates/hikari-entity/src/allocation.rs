@@ -0,0 +1,45 @@
+use sea_orm::entity::prelude::*;
+
+/// One position of an allocation sequence, assigned to a user once `allocated_at` is set
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "allocation")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub sequence_id: Uuid,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub position: i32,
+    pub group_name: String,
+    /// Cleared when the user is deleted, the position stays allocated
+    pub user_id: Option<Uuid>,
+    pub allocated_at: Option<DateTime>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::allocation_sequence::Entity",
+        from = "Column::SequenceId",
+        to = "super::allocation_sequence::Column::Id"
+    )]
+    AllocationSequence,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id"
+    )]
+    User,
+}
+
+impl Related<super::allocation_sequence::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::AllocationSequence.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
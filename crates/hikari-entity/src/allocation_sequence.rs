@@ -0,0 +1,31 @@
+use sea_orm::entity::prelude::*;
+
+/// A persisted randomization sequence of one random group assignment of an access token and stratum
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "allocation_sequence")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub token: String,
+    /// Index of the random group assignment within the groups of the token
+    pub slot: i32,
+    pub stratum: String,
+    /// Seed the blocks of the sequence are generated from
+    pub seed: i64,
+    pub block_size: i32,
+    pub created_at: DateTime,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::allocation::Entity")]
+    Allocation,
+}
+
+impl Related<super::allocation::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Allocation.def()
+    }
+}
@@ -0,0 +1,37 @@
+use sea_orm::entity::prelude::*;
+
+/// A detected signal of acute distress. The text which triggered the detection is never stored.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "crisis_event")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// `journal` or `chat`
+    pub source: String,
+    /// The journal entry, the journal content or the conversation
+    pub reference_id: Option<Uuid>,
+    /// `keyword` or `classifier`
+    pub detector: String,
+    pub created_at: DateTime,
+    /// Set once the counselor webhook accepted the notification
+    pub notified_at: Option<DateTime>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::user::Entity",
+        from = "Column::UserId",
+        to = "crate::user::Column::Id"
+    )]
+    User,
+}
+
+impl Related<crate::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
@@ -5,4 +5,5 @@ pub mod journal_entry_journal_prompt;
 pub mod journal_entry_tag;
 pub mod journal_prompt;
 pub mod journal_summary;
+pub mod journal_summary_job;
 pub mod journal_topic;
@@ -0,0 +1,66 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "journal_summary_job_status_enum")]
+pub enum Status {
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "running")]
+    Running,
+    #[sea_orm(string_value = "completed")]
+    Completed,
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "journal_summary_job")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub key: Vec<u8>,
+    /// Start of the day the summary is created for
+    pub date: DateTime,
+    /// Time of the request in UTC, together with `utc_offset` it restores the local time of the user
+    pub timestamp: DateTime,
+    pub utc_offset: i32,
+    pub status: Status,
+    pub attempts: i32,
+    pub lease_owner: Option<String>,
+    pub lease_expires_at: Option<DateTime>,
+    pub summary_id: Option<Uuid>,
+    pub error: Option<String>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::journal_summary::Entity",
+        from = "Column::SummaryId",
+        to = "super::journal_summary::Column::Id"
+    )]
+    JournalSummary,
+    #[sea_orm(
+        belongs_to = "crate::user::Entity",
+        from = "Column::UserId",
+        to = "crate::user::Column::Id"
+    )]
+    User,
+}
+
+impl Related<super::journal_summary::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::JournalSummary.def()
+    }
+}
+
+impl Related<crate::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
@@ -1,7 +1,12 @@
 pub mod access_tokens;
+pub mod allocation;
+pub mod allocation_sequence;
 pub mod assessment;
+pub mod audit_log;
 pub mod config;
+pub mod crisis_event;
 pub mod custom_groups;
+pub mod export_job;
 pub mod groups_token;
 pub mod history;
 pub mod journal;
@@ -1,5 +1,11 @@
+pub mod audio;
 pub mod conversation;
 pub mod conversation_state;
+pub mod guardrail_event;
+pub mod image;
+pub mod learner_memory;
+pub mod learner_memory_consent;
+pub mod memory_summary;
 pub mod message;
 pub mod slot;
 pub mod usage;
@@ -0,0 +1,40 @@
+use sea_orm::entity::prelude::*;
+
+/// A triggered guardrail of the moderation, kept for review
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "llm_guardrail_event")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub conversation_id: Uuid,
+    pub user_id: Uuid,
+    pub step: String,
+    /// `input` for messages of the user, `output` for messages of the agent
+    pub direction: String,
+    /// Comma separated flagged categories
+    pub categories: String,
+    pub action: String,
+    /// The checked message
+    pub content: String,
+    pub created_at: DateTime,
+    pub reviewed_at: Option<DateTime>,
+    pub reviewed_by: Option<Uuid>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::user::Entity",
+        from = "Column::UserId",
+        to = "crate::user::Column::Id"
+    )]
+    User,
+}
+
+impl Related<crate::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
@@ -0,0 +1,35 @@
+use sea_orm::entity::prelude::*;
+
+/// Rolling summary of the messages of a conversation which no longer fit into the memory of a step
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "llm_memory_summary")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub conversation_id: Uuid,
+    /// Steps the summarized messages were selected from, steps with the same selection share the summary
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub scope: String,
+    pub summary: String,
+    /// Order of the newest message included in the summary
+    pub covered_until: i32,
+    pub tokens: i32,
+    pub updated_at: DateTime,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::conversation::Entity",
+        from = "Column::ConversationId",
+        to = "super::conversation::Column::ConversationId"
+    )]
+    Conversation,
+}
+
+impl Related<super::conversation::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Conversation.def()
+    }
+}
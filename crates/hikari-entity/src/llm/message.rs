@@ -27,6 +27,10 @@ pub enum ContentType {
     Payload,
     #[sea_orm(string_value = "buttons")]
     Buttons,
+    #[sea_orm(string_value = "image")]
+    Image,
+    #[sea_orm(string_value = "audio")]
+    Audio,
 }
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
@@ -49,6 +53,9 @@ pub struct Model {
     pub direction: Direction,
 
     pub status: Status,
+
+    /// Order of the first message of the branch which replaced this message, `None` for the current branch
+    pub superseded_by: Option<i32>,
 }
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -44,6 +44,25 @@ pub enum QuestionType {
     Text,
     #[sea_orm(string_value = "multiplechoice")]
     MultipleChoice,
+    #[sea_orm(string_value = "cloze")]
+    Cloze,
+    #[sea_orm(string_value = "ordering")]
+    Ordering,
+    #[sea_orm(string_value = "matching")]
+    Matching,
+    #[sea_orm(string_value = "numeric")]
+    Numeric,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "question_rubric_enum")]
+pub enum Rubric {
+    #[sea_orm(string_value = "llm")]
+    Llm,
+    #[sea_orm(string_value = "all_or_nothing")]
+    AllOrNothing,
+    #[sea_orm(string_value = "partial_credit")]
+    PartialCredit,
 }
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
@@ -58,6 +77,7 @@ pub struct Model {
     pub question: String,
     pub r#type: QuestionType,
     pub options: Option<String>,
+    pub details: Option<String>,
     pub level: BloomLevel,
     pub created_at: DateTime,
     pub answered_at: Option<DateTime>,
@@ -66,9 +86,12 @@ pub struct Model {
     #[sea_orm(column_type = "Integer")]
     pub grade: Option<i32>,
     pub ai_solution: Option<String>,
+    pub rubric: Rubric,
+    pub explanation: Option<String>,
     pub status: Status,
     pub feedback: Option<Feedback>,
     pub feedback_explanation: Option<String>,
+    pub bank_question_id: Option<Uuid>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
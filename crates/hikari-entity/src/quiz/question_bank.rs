@@ -0,0 +1,56 @@
+use super::question::{BloomLevel, QuestionType, Rubric};
+use sea_orm::entity::prelude::*;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "question_bank_status_enum")]
+pub enum Status {
+    #[sea_orm(string_value = "draft")]
+    Draft,
+    #[sea_orm(string_value = "approved")]
+    Approved,
+    #[sea_orm(string_value = "rejected")]
+    Rejected,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "question_bank")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub module_id: String,
+    pub session_id: String,
+    pub topic: String,
+    pub content: String,
+    pub question: String,
+    pub r#type: QuestionType,
+    pub options: Option<String>,
+    pub details: Option<String>,
+    pub solution: Option<String>,
+    pub level: BloomLevel,
+    pub rubric: Rubric,
+    pub status: Status,
+    pub source_question_id: Option<Uuid>,
+    pub created_by: Option<Uuid>,
+    pub reviewed_by: Option<Uuid>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+    pub reviewed_at: Option<DateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::question::Entity",
+        from = "Column::SourceQuestionId",
+        to = "super::question::Column::Id"
+    )]
+    SourceQuestion,
+}
+
+impl Related<super::question::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SourceQuestion.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -31,7 +31,7 @@ pub struct Model {
 pub enum Relation {
     #[sea_orm(has_many = "super::config::Entity")]
     Config,
-    #[sea_orm(has_one = "super::access_tokens::Entity")]
+    #[sea_orm(has_many = "super::access_tokens::Entity")]
     AccessToken,
     #[sea_orm(has_many = "super::oidc_mapping::Entity")]
     OidcMapping,
@@ -16,4 +16,10 @@ pub enum LlmBuildingError {
     MissedFormatation(String),
     #[error(transparent)]
     DatabaseError(#[from] sea_orm::DbErr),
+    #[error("Missing constant: {0}")]
+    MissingConstant(String),
+    #[error("Invalid schema: {0}")]
+    InvalidSchema(String),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
 }
@@ -6,8 +6,11 @@ use crate::builder::steps::api::ApiBuilder;
 use crate::builder::steps::counter::CounterBuilder;
 use crate::builder::steps::extractor::ExtractorBuilder;
 use crate::builder::steps::llm::LlmBuilder;
+use crate::builder::steps::memorize::MemorizeBuilder;
+use crate::builder::steps::recall::RecallBuilder;
 use crate::builder::steps::retriever::RetrieverBuilder;
 use crate::builder::steps::sse::SseBuilder;
+use crate::builder::steps::structured::StructuredBuilder;
 use crate::builder::steps::summarizer::SummarizerBuilder;
 use crate::builder::steps::validator::ValidatorBuilder;
 use crate::execution::steps::LlmStep;
@@ -38,10 +41,13 @@ pub mod counter;
 pub mod extractor;
 pub mod flow;
 pub mod llm;
+pub mod memorize;
 pub mod message;
+pub mod recall;
 pub mod retriever;
 pub mod set_slot;
 pub mod sse;
+pub mod structured;
 pub mod summarizer;
 pub mod validator;
 
@@ -86,14 +92,30 @@ pub struct LlmModel {
     pub model: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reasining_effort: Option<ReasoningEffort>,
+    /// # Total timeout of a single attempt in seconds
+    /// Defaults to the global config or 30 seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// # Timeout until the first token is received in seconds
+    /// For calls which are not streamed this limits every http request of an attempt.
+    /// Defaults to the global config or 5 seconds for streamed and 15 seconds for other calls
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_token_timeout: Option<u64>,
+    /// # Number of retries after a failed attempt
+    /// Defaults to the global config or no retries
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+    /// # Delay before the first retry in seconds, doubled for every further retry
+    /// Defaults to the global config or 1 second
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backoff: Option<f64>,
 }
 
 impl LlmModel {
     fn with_default_temperature(self, temp: f32) -> Self {
         Self {
             temperature: self.temperature.or(Some(temp)),
-            model: self.model,
-            reasining_effort: self.reasining_effort,
+            ..self
         }
     }
 }
@@ -193,9 +215,18 @@ impl StepBuilder {
             StepType::Extractor(extractor) => {
                 create_step(extractor, parent_steps, self.conditions, self.id, constants, documents)
             }
+            StepType::Structured(structured) => {
+                create_step(structured, parent_steps, self.conditions, self.id, constants, documents)
+            }
             StepType::Retriever(retriever) => {
                 create_step(retriever, parent_steps, self.conditions, self.id, constants, documents)
             }
+            StepType::Memorize(memorize) => {
+                create_step(memorize, parent_steps, self.conditions, self.id, constants, documents)
+            }
+            StepType::Recall(recall) => {
+                create_step(recall, parent_steps, self.conditions, self.id, constants, documents)
+            }
             StepType::Message(message) => {
                 create_step(message, parent_steps, self.conditions, self.id, constants, documents)
             }
@@ -254,8 +285,15 @@ pub enum StepType {
     Validator(ValidatorBuilder),
     /// # Step that extracts information from the conversation and store it into slots
     Extractor(ExtractorBuilder),
+    /// # Step that asks for output matching a JSON schema and stores its fields into slots
+    Structured(StructuredBuilder),
     /// # Step that retrieves documents from a vector store and store them into slots
     Retriever(RetrieverBuilder),
+    /// # Step that extracts facts about the user from the conversation and stores them for later sessions
+    /// Only stores facts if the learner memory is enabled in the global config
+    Memorize(MemorizeBuilder),
+    /// # Step that recalls facts about the user from earlier sessions and stores them into slots
+    Recall(RecallBuilder),
     /// # Step that makes an API call
     ApiCall(ApiBuilder),
     /// # Step that makes a Server-Sent Events (SSE) call
@@ -286,6 +324,51 @@ pub struct Memory {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     /// # Limit of memory entries to consider
     pub memory_limit: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// # Token budget of the memory
+    /// The newest messages which fit into the budget are sent to the model, older messages are summarized
+    pub memory_tokens: Option<usize>,
+    #[serde(default)]
+    /// # Summary of the messages which exceed the token budget
+    pub memory_summary: MemorySummary,
+    #[serde(default)]
+    /// # Number of the newest images in the memory which are sent to the model
+    /// Requires a model which supports image inputs. Other images are replaced by a placeholder and their text
+    pub memory_images: usize,
+}
+
+const fn default_summary_enabled() -> bool {
+    true
+}
+
+const fn default_summary_tokens() -> usize {
+    400
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct MemorySummary {
+    /// # Summarize messages which exceed the token budget
+    /// If disabled, these messages are dropped
+    #[serde(default = "default_summary_enabled")]
+    pub enabled: bool,
+    /// # Model used for the summary
+    /// Defaults to the model of the step
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// # Tokens reserved for the summary within the token budget
+    #[serde(default = "default_summary_tokens")]
+    pub max_tokens: usize,
+}
+
+impl Default for MemorySummary {
+    fn default() -> Self {
+        Self {
+            enabled: default_summary_enabled(),
+            model: None,
+            max_tokens: default_summary_tokens(),
+        }
+    }
 }
 
 #[derive(Deserialize, Debug, Clone, JsonSchema)]
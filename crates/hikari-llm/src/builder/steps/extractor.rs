@@ -53,10 +53,14 @@ impl IntoLlmStep for ExtractorBuilder {
         let ExtractorBuilder {
             values,
             mut prompts,
-            memory: Memory {
-                memory_limit,
-                memory: memory_selection,
-            },
+            memory:
+                Memory {
+                    memory_limit,
+                    memory_tokens,
+                    memory_summary,
+                    memory_images,
+                    memory: memory_selection,
+                },
             success,
             fail,
             model,
@@ -84,7 +88,9 @@ impl IntoLlmStep for ExtractorBuilder {
             memory_filter,
             memory_limit,
             Some(Tool::ExtractionTool(values)),
-        );
+        )
+        .with_memory_budget(memory_tokens, memory_summary)
+        .with_memory_images(memory_images);
 
         let value_extractor = LlmStep::ValueExtractor(ValueExtractor::new(
             id,
@@ -8,9 +8,12 @@ use crate::execution::steps::LlmStep;
 use crate::execution::steps::message_generator::MessageGenerator;
 use async_openai::types::chat::{
     ChatCompletionRequestAssistantMessageArgs, ChatCompletionRequestAssistantMessageContent,
-    ChatCompletionRequestMessage, ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestSystemMessageContent,
-    ChatCompletionRequestUserMessageArgs, ChatCompletionRequestUserMessageContent,
+    ChatCompletionRequestMessage, ChatCompletionRequestMessageContentPartImage,
+    ChatCompletionRequestMessageContentPartText, ChatCompletionRequestSystemMessageArgs,
+    ChatCompletionRequestSystemMessageContent, ChatCompletionRequestUserMessageArgs,
+    ChatCompletionRequestUserMessageContent, ChatCompletionRequestUserMessageContentPart, ImageUrl,
 };
+use hikari_model::chat::TypeSafePayload;
 use hikari_model::llm::message::ConversationMessage;
 use schemars::JsonSchema;
 use serde::Deserialize;
@@ -57,6 +60,9 @@ impl IntoLlmStep for LlmBuilder {
             hold,
             memory: Memory {
                 memory_limit,
+                memory_tokens,
+                memory_summary,
+                memory_images,
                 memory: memory_selector,
             },
             model,
@@ -70,7 +76,9 @@ impl IntoLlmStep for LlmBuilder {
 
         let memory_filter = build_memory_filter(&memory_selector, &id);
 
-        let core = LlmCore::new(prompts, model, memory_filter, memory_limit, None);
+        let core = LlmCore::new(prompts, model, memory_filter, memory_limit, None)
+            .with_memory_budget(memory_tokens, memory_summary)
+            .with_memory_images(memory_images);
         let message_generator = LlmStep::MessageGenerator(MessageGenerator::new(id, core, hold, conditions, store));
         Ok(message_generator)
     }
@@ -91,6 +99,28 @@ pub enum PromptType {
     User(Template),
     AI(Template),
     Constant(String),
+    /// A user message with images, only created from the memory
+    #[serde(skip)]
+    #[schemars(skip)]
+    UserImage {
+        text: Template,
+        /// Data URLs of the images
+        images: Vec<String>,
+    },
+}
+
+/// Stands in for images which are not sent to the model
+pub const IMAGE_PLACEHOLDER: &str = "[Bild]";
+
+/// Text of a message as the model sees it, images are replaced by the placeholder
+pub(crate) fn message_content(message: TypeSafePayload) -> String {
+    match message {
+        TypeSafePayload::Image(image) => image.text.map_or_else(
+            || IMAGE_PLACEHOLDER.to_string(),
+            |text| format!("{IMAGE_PLACEHOLDER} {text}"),
+        ),
+        message => message.message_string().unwrap_or_default(),
+    }
 }
 
 impl PromptType {
@@ -108,7 +138,7 @@ impl PromptType {
 impl From<ConversationMessage> for PromptType {
     fn from(message: ConversationMessage) -> Self {
         let ConversationMessage { message, direction, .. } = message;
-        let content = message.message_string().unwrap_or_default();
+        let content = message_content(message);
         match direction {
             hikari_model::chat::Direction::Receive => PromptType::User(Template::from(content)),
             hikari_model::chat::Direction::Send => PromptType::AI(Template::from(content)),
@@ -133,6 +163,27 @@ impl TryFrom<PromptType> for ChatCompletionRequestMessage {
                     .build()?;
                 Ok(ChatCompletionRequestMessage::User(user))
             }
+            PromptType::UserImage { text, images } => {
+                let text = text.to_string();
+                let text_part = (!text.is_empty()).then(|| {
+                    ChatCompletionRequestUserMessageContentPart::Text(ChatCompletionRequestMessageContentPartText {
+                        text,
+                    })
+                });
+                let image_parts = images.into_iter().map(|url| {
+                    ChatCompletionRequestUserMessageContentPart::ImageUrl(
+                        ChatCompletionRequestMessageContentPartImage {
+                            image_url: ImageUrl { url, detail: None },
+                        },
+                    )
+                });
+                let user = ChatCompletionRequestUserMessageArgs::default()
+                    .content(ChatCompletionRequestUserMessageContent::Array(
+                        text_part.into_iter().chain(image_parts).collect(),
+                    ))
+                    .build()?;
+                Ok(ChatCompletionRequestMessage::User(user))
+            }
             PromptType::AI(template) => {
                 let ai = ChatCompletionRequestAssistantMessageArgs::default()
                     .content(ChatCompletionRequestAssistantMessageContent::Text(template.to_string()))
@@ -149,9 +200,10 @@ impl TryFrom<PromptType> for ChatCompletionRequestMessage {
 impl InjectionTrait for PromptType {
     fn injection_slots(&self) -> Vec<SlotPath> {
         match self {
-            PromptType::System(template) | PromptType::User(template) | PromptType::AI(template) => {
-                template.injection_slots()
-            }
+            PromptType::System(template)
+            | PromptType::User(template)
+            | PromptType::AI(template)
+            | PromptType::UserImage { text: template, .. } => template.injection_slots(),
             PromptType::Constant(_) => {
                 // Constants do not have slots, but we return an empty vector to satisfy the trait
                 vec![]
@@ -163,6 +215,10 @@ impl InjectionTrait for PromptType {
             PromptType::System(template) => PromptType::System(template.inject(values)),
             PromptType::User(template) => PromptType::User(template.inject(values)),
             PromptType::AI(template) => PromptType::AI(template.inject(values)),
+            PromptType::UserImage { text, images } => PromptType::UserImage {
+                text: text.inject(values),
+                images: images.clone(),
+            },
             PromptType::Constant(path) => PromptType::Constant(path.clone()),
         }
     }
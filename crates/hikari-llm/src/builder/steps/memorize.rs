@@ -0,0 +1,134 @@
+use super::llm::PromptType;
+use super::{LlmModel, Memory};
+use crate::builder::build_memory_filter;
+use crate::builder::error::LlmBuildingError;
+use crate::builder::steps::{Condition, Documents, IntoLlmStep, ParentStep};
+use crate::builder::tools::Tool;
+use crate::execution::core::LlmCore;
+use crate::execution::steps::LlmStep;
+use crate::execution::steps::learner_memory_writer::LearnerMemoryWriter;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use yaml_serde::Value;
+
+const PREFIX: &str = "Du extrahierst aus dem Gesprächsverlauf dauerhafte Fakten über den Nutzer, die in späteren Sitzungen \
+     hilfreich sind, z.B. Ziele, Vorwissen, Interessen, Schwierigkeiten und Vorlieben. Jeder Fakt ist ein kurzer, für sich \
+     verständlicher Satz in der dritten Person. Ignoriere Smalltalk und Inhalte, die nur für diese Sitzung gelten. \
+     Wenn es nichts zu merken gibt, gib eine leere Liste zurück.";
+
+const fn default_max_facts() -> u32 {
+    5
+}
+
+#[derive(Deserialize, Debug, Clone, JsonSchema)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct MemorizeBuilder {
+    /// # Maximum number of facts stored per call
+    #[serde(default = "default_max_facts")]
+    pub max_facts: u32,
+    #[serde(default)]
+    pub prompts: Vec<PromptType>,
+    #[serde(default, flatten)]
+    pub memory: Memory,
+    #[serde(flatten)]
+    pub model: LlmModel,
+    #[serde(default)]
+    pub skip_prefix: bool,
+}
+
+impl IntoLlmStep for MemorizeBuilder {
+    fn into_llm_step(
+        mut self,
+        parent_steps: Vec<ParentStep>,
+        mut conditions: Vec<Condition>,
+        id: String,
+        constants: HashMap<String, Value>,
+        _documents: Documents,
+    ) -> Result<LlmStep, LlmBuildingError> {
+        self.prompts.iter_mut().for_each(|p| {
+            p.insert_constant(&constants);
+        });
+
+        let MemorizeBuilder {
+            max_facts,
+            mut prompts,
+            memory:
+                Memory {
+                    memory_limit,
+                    memory_tokens,
+                    memory_summary,
+                    memory_images,
+                    memory: memory_selection,
+                },
+            model,
+            skip_prefix,
+        } = self;
+
+        if !skip_prefix {
+            prompts.insert(0, PromptType::System(PREFIX.into()));
+        }
+
+        for step in parent_steps {
+            conditions.extend(step.conditions);
+        }
+
+        let memory_filter = build_memory_filter(&memory_selection, &id);
+
+        let core = LlmCore::new(
+            prompts,
+            model,
+            memory_filter,
+            memory_limit,
+            Some(Tool::Structured {
+                name: "RememberFacts".to_string(),
+                description: "Stores facts about the user for later sessions.".to_string(),
+                schema: facts_schema(max_facts),
+            }),
+        )
+        .with_memory_budget(memory_tokens, memory_summary)
+        .with_memory_images(memory_images);
+
+        Ok(LlmStep::LearnerMemoryWriter(LearnerMemoryWriter::new(
+            id, core, max_facts, conditions,
+        )))
+    }
+}
+
+fn facts_schema(max_facts: u32) -> serde_json::Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "facts": {
+                "type": "array",
+                "description": "Facts about the user, each a short self-contained sentence",
+                "items": {"type": "string"},
+                "maxItems": max_facts,
+            }
+        },
+        "required": ["facts"],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_memorize_step() {
+        let builder: MemorizeBuilder = yaml_serde::from_str(
+            r"
+max-facts: 3
+memory:
+  - selector: all
+memory-tokens: 2000
+",
+        )
+        .unwrap();
+        assert_eq!(builder.max_facts, 3);
+        assert_eq!(builder.memory.memory_tokens, Some(2000));
+        assert!(!builder.skip_prefix);
+        assert_eq!(facts_schema(3)["properties"]["facts"]["maxItems"], 3);
+    }
+}
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use crate::{
+    builder::{
+        error::LlmBuildingError,
+        slot::{SaveTarget, paths::SlotPath},
+        steps::Documents,
+    },
+    execution::steps::{LlmStep, learner_memory_recall::LearnerMemoryRecall},
+};
+
+use super::{Condition, IntoLlmStep, ParentStep};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use yaml_serde::Value;
+
+#[derive(Deserialize, Debug, Clone, JsonSchema)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct RecallBuilder {
+    /// # A Slot path containing the query to recall memories for
+    /// Can be a list of strings for multiple queries
+    pub query: SlotPath,
+    pub target: SaveTarget,
+    /// # Limit of memories to recall per query
+    /// Default: 5
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+    /// # Maximum cosine distance of a recalled memory to the query
+    /// Less similar memories are ignored, by default all memories are considered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_distance: Option<f64>,
+}
+
+fn default_limit() -> u32 {
+    5
+}
+
+impl IntoLlmStep for RecallBuilder {
+    fn into_llm_step(
+        self,
+        parent_steps: Vec<ParentStep>,
+        mut conditions: Vec<Condition>,
+        id: String,
+        _constants: HashMap<String, Value>,
+        _documents: Documents,
+    ) -> Result<LlmStep, LlmBuildingError> {
+        for step in parent_steps {
+            conditions.extend(step.conditions);
+        }
+
+        Ok(LlmStep::LearnerMemoryRecall(LearnerMemoryRecall::new(
+            id,
+            self.target,
+            self.limit,
+            self.max_distance,
+            self.query,
+            conditions,
+        )))
+    }
+}
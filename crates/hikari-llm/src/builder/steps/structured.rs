@@ -0,0 +1,255 @@
+use super::llm::PromptType;
+use super::{LlmModel, Memory};
+use crate::builder::error::LlmBuildingError;
+use crate::builder::slot::SaveTarget;
+use crate::builder::steps::{Condition, Documents, Flow, IntoLlmStep, ParentStep};
+use crate::builder::tools::Tool;
+use crate::builder::{build_memory_filter, step_id_from_flow};
+use crate::execution::core::LlmCore;
+use crate::execution::steps::LlmStep;
+use crate::execution::steps::structured_output::StructuredOutput;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use yaml_serde::Value;
+
+fn default_name() -> String {
+    "StructuredOutput".to_string()
+}
+
+fn default_description() -> String {
+    "Returns the requested information as structured output. Always use this tool to answer.".to_string()
+}
+
+const fn default_validation_retries() -> u32 {
+    2
+}
+
+#[derive(Deserialize, Debug, Clone, JsonSchema)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct StructuredBuilder {
+    /// # JSON schema of the output
+    /// The root has to be an object
+    pub schema: SchemaSource,
+    /// # Name of the output shown to the model
+    /// Has to match `^[a-zA-Z0-9_-]+$`
+    #[serde(default = "default_name")]
+    pub name: String,
+    /// # Description of the output shown to the model
+    #[serde(default = "default_description")]
+    pub description: String,
+    /// # Fields of the output which are stored into slots
+    #[serde(default)]
+    pub fields: Vec<StructuredField>,
+    /// # How the output is requested from the provider
+    #[serde(default)]
+    pub mode: StructuredMode,
+    /// # Number of retries if the output does not match the schema
+    /// The validation error is sent to the model, so it can correct the output
+    #[serde(default = "default_validation_retries")]
+    pub validation_retries: u32,
+    #[serde(default)]
+    pub prompts: Vec<PromptType>,
+    #[serde(flatten)]
+    pub memory: Memory,
+    pub success: Flow,
+    pub fail: Flow,
+    #[serde(flatten)]
+    pub model: LlmModel,
+}
+
+#[derive(Deserialize, Debug, Clone, JsonSchema)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub enum SchemaSource {
+    /// # The schema itself
+    Inline(#[schemars(with = "serde_json::Value")] serde_json::Value),
+    /// # Key of the constant containing the schema
+    Constant(String),
+}
+
+#[derive(Deserialize, Debug, Clone, JsonSchema)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct StructuredField {
+    /// # Path of the field in the output
+    /// Nested fields are separated by dots, e.g. `mood.score`
+    pub field: String,
+    /// # Slot the value of the field is stored into
+    pub target: SaveTarget,
+}
+
+impl StructuredField {
+    /// The field path as JSON pointer
+    #[must_use]
+    pub fn pointer(&self) -> String {
+        self.field.split('.').fold(String::new(), |pointer, segment| {
+            let segment = segment.replace('~', "~0").replace('/', "~1");
+            format!("{pointer}/{segment}")
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub enum StructuredMode {
+    /// # Uses the JSON schema response format and falls back to tool calling if the provider rejects it
+    #[default]
+    Auto,
+    /// # Always uses the JSON schema response format
+    JsonSchema,
+    /// # Always uses a forced tool call
+    ToolCall,
+}
+
+impl SchemaSource {
+    fn resolve(self, constants: &HashMap<String, Value>) -> Result<serde_json::Value, LlmBuildingError> {
+        match self {
+            SchemaSource::Inline(schema) => Ok(schema),
+            SchemaSource::Constant(key) => {
+                let constant = constants.get(&key).ok_or(LlmBuildingError::MissingConstant(key))?;
+                Ok(serde_json::to_value(constant)?)
+            }
+        }
+    }
+}
+
+impl IntoLlmStep for StructuredBuilder {
+    fn into_llm_step(
+        mut self,
+        parent_steps: Vec<ParentStep>,
+        mut conditions: Vec<Condition>,
+        id: String,
+        constants: HashMap<String, Value>,
+        _documents: Documents,
+    ) -> Result<LlmStep, LlmBuildingError> {
+        self.prompts.iter_mut().for_each(|p| {
+            p.insert_constant(&constants);
+        });
+
+        let StructuredBuilder {
+            schema,
+            name,
+            description,
+            fields,
+            mode,
+            validation_retries,
+            prompts,
+            memory:
+                Memory {
+                    memory_limit,
+                    memory_tokens,
+                    memory_summary,
+                    memory_images,
+                    memory: memory_selection,
+                },
+            success,
+            fail,
+            model,
+        } = self;
+
+        let schema = schema.resolve(&constants)?;
+        if !schema.get("type").is_some_and(|r#type| r#type == "object") {
+            return Err(LlmBuildingError::InvalidSchema(format!(
+                "the root of the schema of step {id} has to be an object"
+            )));
+        }
+        let validator = jsonschema::validator_for(&schema)
+            .map_err(|error| LlmBuildingError::InvalidSchema(format!("schema of step {id} is invalid: {error}")))?;
+
+        let goto_on_success = step_id_from_flow(success, &parent_steps);
+        let goto_on_fail = step_id_from_flow(fail, &parent_steps);
+
+        for step in parent_steps {
+            conditions.extend(step.conditions);
+        }
+
+        let memory_filter = build_memory_filter(&memory_selection, &id);
+
+        let core = LlmCore::new(
+            prompts,
+            model,
+            memory_filter,
+            memory_limit,
+            Some(Tool::Structured {
+                name: name.clone(),
+                description: description.clone(),
+                schema: schema.clone(),
+            }),
+        )
+        .with_memory_budget(memory_tokens, memory_summary)
+        .with_memory_images(memory_images);
+
+        Ok(LlmStep::StructuredOutput(StructuredOutput::new(
+            id,
+            core,
+            name,
+            description,
+            schema,
+            Arc::new(validator),
+            fields,
+            mode,
+            validation_retries,
+            goto_on_success,
+            goto_on_fail,
+            conditions,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_structured_step() {
+        let builder: StructuredBuilder = yaml_serde::from_str(
+            r#"
+schema:
+  inline:
+    type: object
+    properties:
+      mood:
+        type: object
+        properties:
+          score:
+            type: integer
+    required: [mood]
+fields:
+  - field: mood.score
+    target:
+      slot:
+        name: mood_score
+mode: tool-call
+success:
+  action: continue
+fail:
+  action: repeat
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(builder.mode, StructuredMode::ToolCall);
+        assert_eq!(builder.validation_retries, 2);
+        assert_eq!(builder.name, "StructuredOutput");
+        assert_eq!(builder.fields[0].pointer(), "/mood/score");
+        assert!(matches!(builder.schema, SchemaSource::Inline(_)));
+    }
+
+    #[test]
+    fn test_schema_from_constant() {
+        let constants: HashMap<String, Value> = HashMap::from([(
+            "MOOD_SCHEMA".to_string(),
+            yaml_serde::from_str("{type: object, properties: {mood: {type: string}}}").unwrap(),
+        )]);
+        let schema = SchemaSource::Constant("MOOD_SCHEMA".to_string())
+            .resolve(&constants)
+            .unwrap();
+        assert_eq!(schema["properties"]["mood"]["type"], "string");
+
+        assert!(
+            SchemaSource::Constant("MISSING".to_string())
+                .resolve(&constants)
+                .is_err()
+        );
+    }
+}
@@ -47,10 +47,14 @@ impl IntoLlmStep for SummarizerBuilder {
 
         let SummarizerBuilder {
             mut prompts,
-            memory: Memory {
-                memory_limit,
-                memory: memory_selection,
-            },
+            memory:
+                Memory {
+                    memory_limit,
+                    memory_tokens,
+                    memory_summary,
+                    memory_images,
+                    memory: memory_selection,
+                },
             update_type,
             model,
             skip_prefix,
@@ -75,7 +79,9 @@ impl IntoLlmStep for SummarizerBuilder {
             memory_filter,
             memory_limit,
             Some(Tool::Summarizer),
-        );
+        )
+        .with_memory_budget(memory_tokens, memory_summary)
+        .with_memory_images(memory_images);
         let conversation_summarizer =
             LlmStep::ConversationSummarizer(ConversationSummarizer::new(id, core, update_type, conditions));
         Ok(conversation_summarizer)
@@ -86,10 +86,14 @@ impl IntoLlmStep for ValidatorBuilder {
         let ValidatorBuilder {
             goals,
             mut prompts,
-            memory: Memory {
-                memory_limit,
-                memory: memory_selection,
-            },
+            memory:
+                Memory {
+                    memory_limit,
+                    memory_tokens,
+                    memory_summary,
+                    memory_images,
+                    memory: memory_selection,
+                },
             success,
             fail,
             model,
@@ -117,7 +121,9 @@ impl IntoLlmStep for ValidatorBuilder {
             memory_filter,
             memory_limit,
             Some(Tool::ValidationTool(goals)),
-        );
+        )
+        .with_memory_budget(memory_tokens, memory_summary)
+        .with_memory_images(memory_images);
         tracing::trace!(?goto_on_success, ?goto_on_fail, "Goto ");
         let conversation_validator = LlmStep::ConversationValidator(ConversationValidator::new(
             id,
@@ -4,6 +4,7 @@ use crate::builder::steps::InjectionTrait;
 use crate::builder::steps::extractor::ExtractionValues;
 use crate::builder::steps::validator::ConversationGoal;
 use hikari_core::openai::tools::{AsOpenApiField, OpenApiField, ToolSchema};
+use serde_json::Value;
 use std::collections::HashMap;
 
 #[derive(Clone)]
@@ -11,6 +12,11 @@ pub enum Tool {
     ValidationTool(Vec<ConversationGoal>),
     ExtractionTool(Vec<ExtractionValues>),
     Summarizer,
+    Structured {
+        name: String,
+        description: String,
+        schema: Value,
+    },
 }
 
 impl InjectionTrait for Tool {
@@ -18,7 +24,7 @@ impl InjectionTrait for Tool {
         match self {
             Tool::ValidationTool(goals) => goals.iter().flat_map(InjectionTrait::injection_slots).collect(),
             Tool::ExtractionTool(values) => values.iter().flat_map(InjectionTrait::injection_slots).collect(),
-            Tool::Summarizer => vec![],
+            Tool::Summarizer | Tool::Structured { .. } => vec![],
         }
     }
     fn inject(&self, values: &[SlotValuePair]) -> Self {
@@ -28,6 +34,7 @@ impl InjectionTrait for Tool {
                 Tool::ExtractionTool(extractions.iter().map(|e| e.inject(values)).collect())
             }
             Tool::Summarizer => Tool::Summarizer,
+            Tool::Structured { .. } => self.clone(),
         }
     }
 }
@@ -90,7 +97,21 @@ impl Tool {
                         .description("This tool processes and stores the conversation summary. Always use this tool when you need to create a summary for the conversation. The function receives the summary as input")
                         .properties(
                             HashMap::from([("summary", OpenApiField::new("string"))]))
-                        .required(vec!["summary"]).into()
+                        .required(vec!["summary"]).into(),
+            Tool::Structured { name, description, schema } => {
+                let mut schema = schema.clone();
+                if let Value::Object(schema) = &mut schema {
+                    // The title and description of the root describe the function, so the description of the
+                    // schema itself is appended instead of being replaced
+                    let description = match schema.get("description").and_then(Value::as_str) {
+                        Some(root) if root != description => format!("{description}\n\n{root}"),
+                        _ => description.clone(),
+                    };
+                    schema.insert("title".to_string(), Value::String(name.clone()));
+                    schema.insert("description".to_string(), Value::String(description));
+                }
+                ToolSchema(serde_json::from_value(schema).expect("schema was validated while building the step"))
+            }
         }
     }
 }
@@ -212,4 +233,26 @@ mod tests {
         assert!(properties.get("summary").is_some());
         assert_eq!(properties["summary"]["type"], "string");
     }
+
+    #[test]
+    fn test_structured_keeps_root_description() {
+        let tool = Tool::Structured {
+            name: "Mood".to_string(),
+            description: "Returns the mood.".to_string(),
+            schema: serde_json::json!({
+                "type": "object",
+                "description": "Mood of the user on a scale from 1 to 5.",
+                "properties": {"score": {"type": "integer"}}
+            }),
+        }
+        .tool_schema();
+        let ChatCompletionTools::Function(openai_tool) = tool.try_into().unwrap() else {
+            panic!("Expected Function tool");
+        };
+        assert_eq!(openai_tool.function.name, "Mood");
+        assert_eq!(
+            openai_tool.function.description.as_deref(),
+            Some("Returns the mood.\n\nMood of the user on a scale from 1 to 5.")
+        );
+    }
 }
@@ -3,4 +3,5 @@ pub(crate) mod bubble;
 pub mod core;
 pub mod error;
 pub mod iterator;
+pub mod memory;
 pub mod steps;
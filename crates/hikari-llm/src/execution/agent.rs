@@ -12,15 +12,16 @@ use futures_util::{FutureExt, StreamExt};
 use hikari_config::module::llm_agent::LlmService;
 use hikari_core::llm_config::LlmConfig;
 use hikari_core::openai::Content;
+use hikari_core::openai::moderation::{self, GuardrailDirection};
 use hikari_core::usage::add_usage;
 use hikari_model::chat::{Direction, TextContent, TypeSafePayload};
-use hikari_model::llm::message::MessageStatus;
+use hikari_model::llm::message::{ConversationMessage, MessageStatus};
 use hikari_model::llm::slot::Slot;
 use hikari_model::llm::state::{LlmConversationState, LlmStepStatus};
-use hikari_model_tools::convert::IntoDbModel;
 use hikari_model_tools::convert::llm::split_payload_for_database;
+use hikari_model_tools::convert::{FromDbModel, IntoDbModel};
 use hikari_utils::values::ValueDecoder;
-use sea_orm::DatabaseConnection;
+use sea_orm::{ConnectionTrait, DatabaseConnection, TransactionTrait};
 use std::error::Error;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -93,7 +94,7 @@ impl LlmAgent {
                 let start_time = tokio::time::Instant::now();
                 self.start_time = Some(start_time);
                 if history_needed {
-                    let messages = get_memory(&self.conn, &self.conversation_id, None, None).await?;
+                    let messages = get_memory(&self.conn, &self.conversation_id, None, None, None).await?;
                     yield Response::History(messages);
                 }
                 loop {
@@ -113,11 +114,24 @@ impl LlmAgent {
                         }
                         LlmStepStatus::WaitingForInput => {
                             if let Some(message) = message.take() {
+                                self.check_attachment(&message).await?;
+                                let text = message.clone().message_string().filter(|text| !text.trim().is_empty());
+                                if let Some(text) = text
+                                    && self.config.moderation.checks_input()
+                                    && let Some(notice) = self.moderate(&step_id, GuardrailDirection::Input, &text).await?
+                                {
+                                    // A blocked message is not stored, so it never becomes part of the memory. The guardrail event keeps
+                                    // the text for the review. The step keeps waiting for input, the notice is shown instead of a response
+                                    let id = self.insert_notice(&step_id, notice.clone()).await?;
+                                    yield Response::Chat(ChatChunk::new(notice, id, step_id));
+                                    yield Response::Hold;
+                                    break;
+                                }
                                 let (content_type, message) = split_payload_for_database(message).map_err(|e| LlmExecutionError::Unexpected(e.to_string()))?;
                                 hikari_db::llm::message::Mutation::insert_new_message(
                                     &self.conn,
                                     self.conversation_id,
-                                    step_id,
+                                    step_id.clone(),
                                     content_type,
                                     message,
                                     Direction::Receive.into_db_model(),
@@ -150,7 +164,10 @@ impl LlmAgent {
                     let response = {
                         let mut current_action = self.current_action.as_ref().ok_or(LlmExecutionError::NoAction)?.lock().await;
                         let resp = current_action.execute(&self.config, &self.conversation_id, &self.user_id, &self.module_id, &self.session_id, self.llm_service.clone(), self.conn.clone()).await;
-                        let state = current_action.state();
+                        let mut state = current_action.state();
+                        if let Err(error) = &resp {
+                            state.value.error = Some(error.to_string());
+                        }
                         self.set_step_state(state).await?;
                         resp?
                     };
@@ -170,6 +187,151 @@ impl LlmAgent {
         })
     }
 
+    /// Generates a new answer to the last message of the user. The previous answer is kept as a superseded branch.
+    pub fn regenerate(&mut self) -> Pin<Box<dyn Stream<Item = Result<Response, LlmExecutionError>> + '_ + Send>> {
+        self.rewind(None)
+    }
+
+    /// Replaces the last message of the user and generates a new answer. The previous message and its answers are
+    /// kept as a superseded branch.
+    pub fn edit_last_message(
+        &mut self,
+        message: TypeSafePayload,
+    ) -> Pin<Box<dyn Stream<Item = Result<Response, LlmExecutionError>> + '_ + Send>> {
+        self.rewind(Some(message))
+    }
+
+    /// Supersedes the last message of the user and everything after it, rewinds to the step which received the
+    /// message and sends the replacement or the original message again. Slots set in between are not restored.
+    fn rewind(
+        &mut self,
+        replacement: Option<TypeSafePayload>,
+    ) -> Pin<Box<dyn Stream<Item = Result<Response, LlmExecutionError>> + '_ + Send>> {
+        Box::pin(try_stream! {
+            self.current_action.as_ref().ok_or(LlmExecutionError::ConversationEnded)?;
+            let last = hikari_db::llm::message::Query::get_last_user_message(&self.conn, self.conversation_id)
+                .await?
+                .ok_or(LlmExecutionError::NoUserMessage)?;
+            let order = last.message_order;
+            let step_id = last.step.clone();
+            let message = match replacement {
+                Some(message) => message,
+                None => ConversationMessage::from_db_model(last).message,
+            };
+
+            // The branch and the rewound state are stored together, so a failed rewind keeps the history intact
+            let state = self.rewind_to(&step_id).await?;
+            let txn = self.conn.begin().await?;
+            let superseded =
+                hikari_db::llm::message::Mutation::supersede_messages(&txn, self.conversation_id, order).await?;
+            self.set_step_state_in(&txn, state).await?;
+            txn.commit().await?;
+            tracing::debug!(%step_id, superseded, "rewound conversation");
+
+            // The history shows the new branch before the answer is streamed
+            let mut chat = self.chat(Some(message), true);
+            while let Some(response) = chat.next().await {
+                yield response?;
+            }
+        })
+    }
+
+    /// Images and voice messages can only be sent by the user who uploaded them
+    async fn check_attachment(&self, message: &TypeSafePayload) -> Result<(), LlmExecutionError> {
+        match message {
+            TypeSafePayload::Image(image) => {
+                hikari_db::llm::image::Query::get_image(&self.conn, self.user_id, image.image_id)
+                    .await?
+                    .ok_or(LlmExecutionError::ImageNotFound(image.image_id))?;
+            }
+            TypeSafePayload::Audio(audio) => {
+                hikari_db::llm::audio::Query::get_audio(&self.conn, self.user_id, audio.audio_id)
+                    .await?
+                    .ok_or(LlmExecutionError::AudioNotFound(audio.audio_id))?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Checks the text with the moderation and records triggered guardrails.
+    /// Returns the text which is shown instead, `None` if the text passes.
+    async fn moderate(
+        &self,
+        step_id: &str,
+        direction: GuardrailDirection,
+        text: &str,
+    ) -> Result<Option<String>, LlmExecutionError> {
+        let config = &self.config.moderation;
+        let categories = match moderation::moderate(&self.config, &self.user_id.to_string(), text).await {
+            Ok(verdict) => {
+                if let Some(tokens) = verdict.tokens {
+                    add_usage(&self.conn, &self.user_id, tokens, "moderation").await?;
+                }
+                if !verdict.flagged {
+                    return Ok(None);
+                }
+                verdict.categories
+            }
+            Err(error) if config.fail_closed => {
+                tracing::error!(
+                    error = &error as &dyn Error,
+                    "moderation failed, applying the default action"
+                );
+                vec![]
+            }
+            Err(error) => {
+                tracing::error!(
+                    error = &error as &dyn Error,
+                    "moderation failed, the message passes unchecked"
+                );
+                return Ok(None);
+            }
+        };
+
+        let action = config.action_for(&categories);
+        tracing::info!(
+            ?categories,
+            action = action.name(),
+            direction = direction.as_str(),
+            "guardrail triggered"
+        );
+        hikari_db::llm::guardrail_event::Mutation::insert_event(
+            &self.conn,
+            self.conversation_id,
+            self.user_id,
+            step_id.to_owned(),
+            direction.as_str().to_owned(),
+            &categories,
+            action.name().to_owned(),
+            text.to_owned(),
+        )
+        .await?;
+        Ok(config.replacement(action).map(ToOwned::to_owned))
+    }
+
+    /// Stores a message of the agent which is not generated, returns its order
+    async fn insert_notice(&self, step_id: &str, text: String) -> Result<i32, LlmExecutionError> {
+        let (content_type, message) = split_payload_for_database(TypeSafePayload::Text(TextContent { text }))
+            .map_err(|e| LlmExecutionError::Unexpected(e.to_string()))?;
+        let message = hikari_db::llm::message::Mutation::insert_new_message(
+            &self.conn,
+            self.conversation_id,
+            step_id.to_owned(),
+            content_type,
+            message,
+            Direction::Send.into_db_model(),
+            MessageStatus::Completed.into_db_model(),
+        )
+        .await?;
+        Ok(message.message_order)
+    }
+
+    #[must_use]
+    pub fn conversation_id(&self) -> Uuid {
+        self.conversation_id
+    }
+
     fn handle_response<'a>(
         &'a mut self,
         response: LlmStepContent,
@@ -232,6 +394,8 @@ impl LlmAgent {
 
 
 
+                    // Checked messages are held back until they are complete
+                    let mut held_back = self.config.moderation.checks_output().then(String::new);
                     while let Some(result) = message.next().await {
                         match result {
                             Ok(value) => {
@@ -254,6 +418,11 @@ impl LlmAgent {
                                 tracing::trace!(?usage, "tokens used");
                                 add_usage(&self.conn, &self.user_id, usage, step_id).await?;
 
+                                if let Some(held_back) = &mut held_back {
+                                    held_back.push_str(content);
+                                    continue;
+                                }
+
                                 // Push the new content
                                 let bubble_chunks = acc.push(content).await?;
 
@@ -273,6 +442,13 @@ impl LlmAgent {
                         }
                     }
 
+                    if let Some(text) = held_back {
+                        let text = self.moderate(step_id, GuardrailDirection::Output, &text).await?.unwrap_or(text);
+                        for (delta, bubble_id) in acc.push(&text).await? {
+                            yield Some(Response::Chat(ChatChunk::new(delta, bubble_id, step_id.to_owned())));
+                        }
+                    }
+
                     // Finalize the last bubbble for the database
                     let complete_message = acc.finalize().await?;
 
@@ -395,13 +571,28 @@ impl LlmAgent {
 
     // Status
     async fn set_step_state(&self, state: LlmConversationState) -> Result<(), LlmExecutionError> {
+        self.set_step_state_in(&self.conn, state).await
+    }
+
+    async fn set_step_state_in<C: ConnectionTrait>(
+        &self,
+        conn: &C,
+        state: LlmConversationState,
+    ) -> Result<(), LlmExecutionError> {
         tracing::trace!(?state, "set step state");
+        // Responses are not stored yet, so only a failed step has a value. It is cleared by the next state change
+        let value = state
+            .value
+            .error
+            .is_some()
+            .then(|| serde_json::to_string(&state.value))
+            .transpose()?;
         hikari_db::llm::conversation_state::Mutation::upsert_conversation_state(
-            &self.conn,
+            conn,
             self.conversation_id,
             Some(state.status.into_db_model()),
             Some(state.current_step),
-            None,
+            value,
         )
         .await?;
         Ok(())
@@ -430,6 +621,22 @@ impl LlmAgent {
         Ok(())
     }
 
+    /// Makes the step wait for input again, as it did before it received the message which is replaced
+    /// Moves to the step and resets it to wait for input. Returns its state, which the caller has to store.
+    async fn rewind_to(&mut self, step_id: &str) -> Result<LlmConversationState, LlmExecutionError> {
+        tracing::trace!(?step_id, "rewind");
+        self.iterator.goto(step_id)?;
+        self.current_action = self.iterator.next();
+        let mut lock_guard = self
+            .current_action
+            .as_ref()
+            .ok_or(LlmExecutionError::NoAction)?
+            .lock()
+            .await;
+        lock_guard.reset();
+        Ok(lock_guard.set_status(LlmStepStatus::WaitingForInput))
+    }
+
     async fn goto(&mut self, step_id: &str) -> Result<(), LlmExecutionError> {
         tracing::trace!(?step_id, "goto");
         self.iterator.goto(step_id)?;
@@ -1,9 +1,11 @@
 use hikari_model::{
     chat::{ErrorResponse, TypeSafePayload},
+    crisis::CrisisResources,
     llm::message::ConversationMessage,
 };
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use uuid::Uuid;
 
 #[derive(ToSchema, Serialize, Deserialize, Debug)]
 #[serde(rename_all = "snake_case", tag = "type", content = "value")]
@@ -15,6 +17,9 @@ pub enum Response {
     Typing,
     Hold,
     Error(ErrorResponse),
+    Transcription(Transcription), // Transcript of a voice message before the agent answers
+    Audio(AudioChunk),            // Spoken output of a completed message
+    CrisisResources(CrisisResources), // Hotlines for a message with signals of acute distress
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -30,3 +35,22 @@ impl ChatChunk {
         Self { content, id, step }
     }
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Transcription {
+    pub audio_id: Uuid,
+    pub text: String,
+}
+
+/// Part of the spoken output of a message, the parts are sent in order
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AudioChunk {
+    /// The id of the message, like the id of [`ChatChunk`]
+    pub id: i32,
+    pub audio_id: Uuid,
+    pub mime_type: String,
+    /// Base64 encoded audio data
+    pub data: String,
+    pub sequence: u32,
+    pub last: bool,
+}
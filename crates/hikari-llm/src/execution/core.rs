@@ -1,31 +1,110 @@
+use std::error::Error;
 use std::time::Duration;
 
 use super::error::LlmExecutionError;
+use super::memory::{MemoryBudget, memory_prompts, summary_message, summary_prompt, summary_tokens};
 use crate::{
     builder::{
-        steps::{InjectionTrait, LlmModel, llm::PromptType, resolve_multiple},
+        steps::{InjectionTrait, LlmModel, MemorySummary, llm::PromptType, resolve_multiple},
         tools::Tool,
     },
     utils::get_memory,
 };
-use async_openai::types::chat::ChatCompletionRequestMessage;
+use async_openai::types::chat::{ChatCompletionRequestMessage, ResponseFormat};
+use futures_util::StreamExt;
+use hikari_config::global::llm::LlmCallDefaults;
 use hikari_config::module::llm_agent::LlmService;
 use hikari_core::openai::{
-    CallConfig, Message, OpenAiCallResult, openai_call_with_timeout,
+    CallConfig, Content, Message, OpenAiCallResult, openai_call_with_timeout,
     streaming::MessageStream,
     tools::{ToolChoice, ToolSchema},
 };
 
 use hikari_core::llm_config::LlmConfig;
+use hikari_db::llm::memory_summary;
+use hikari_model::llm::message::ConversationMessage;
 use sea_orm::DatabaseConnection;
 use uuid::Uuid;
 
+const DEFAULT_TIMEOUT: u64 = 30;
+const DEFAULT_FIRST_TOKEN_TIMEOUT: u64 = 15;
+const DEFAULT_STREAM_FIRST_TOKEN_TIMEOUT: u64 = 5;
+const DEFAULT_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Timeouts and retries of a call, resolved from the step, the global config and the built-in defaults
+#[derive(Debug, Clone)]
+struct CallSettings {
+    timeout: Duration,
+    first_token_timeout: Duration,
+    max_retries: u32,
+    backoff: Duration,
+}
+
+impl CallSettings {
+    fn new(model: &LlmModel, defaults: &LlmCallDefaults, default_first_token_timeout: u64) -> Self {
+        Self {
+            timeout: Duration::from_secs(model.timeout.or(defaults.timeout).unwrap_or(DEFAULT_TIMEOUT)),
+            first_token_timeout: Duration::from_secs(
+                model
+                    .first_token_timeout
+                    .or(defaults.first_token_timeout)
+                    .unwrap_or(default_first_token_timeout),
+            ),
+            max_retries: model.max_retries.or(defaults.max_retries).unwrap_or_default(),
+            backoff: model
+                .backoff
+                .or(defaults.backoff)
+                .and_then(|backoff| Duration::try_from_secs_f64(backoff).ok())
+                .unwrap_or(DEFAULT_BACKOFF),
+        }
+    }
+
+    fn call_config(&self, user_id: &Uuid) -> CallConfig {
+        CallConfig::builder()
+            .total_timeout(self.timeout)
+            .iteration_timeout(self.first_token_timeout)
+            .user(user_id.to_string())
+            .build()
+    }
+
+    /// Retries failed attempts with exponential backoff, a provider which is known to be down is not retried
+    async fn retry<T, F, Fut>(&self, mut attempt: F) -> Result<T, LlmExecutionError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, LlmExecutionError>>,
+    {
+        let mut retry = 0;
+        loop {
+            match attempt().await {
+                Err(error) if retry < self.max_retries && is_retryable(&error) => {
+                    let delay = self.backoff.saturating_mul(2u32.saturating_pow(retry));
+                    tracing::warn!(error = &error as &dyn Error, retry, ?delay, "llm call failed, retrying");
+                    tokio::time::sleep(delay).await;
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Only timeouts, rate limits and server errors are retried, other errors fail again with the same request
+fn is_retryable(error: &LlmExecutionError) -> bool {
+    match error {
+        LlmExecutionError::OpenAiError(error) => error.is_transient(),
+        LlmExecutionError::FirstTokenTimeout(_) => true,
+        _ => false,
+    }
+}
+
 #[derive(Clone)]
 pub struct LlmCore {
     prompt: Vec<PromptType>,
     model: LlmModel,
     memory: Option<Vec<String>>,
     memory_limit: Option<usize>,
+    memory_budget: Option<MemoryBudget>,
+    memory_images: usize,
     tool: Option<Tool>,
 }
 
@@ -44,10 +123,34 @@ impl LlmCore {
             model,
             memory: memory_filter,
             memory_limit,
+            memory_budget: None,
+            memory_images: 0,
             tool,
         }
     }
 
+    /// Limits the memory to a token budget, older messages are summarized if enabled
+    #[must_use]
+    pub fn with_memory_budget(self, tokens: Option<usize>, summary: MemorySummary) -> Self {
+        Self {
+            memory_budget: tokens.map(|tokens| MemoryBudget { tokens, summary }),
+            ..self
+        }
+    }
+
+    /// Attaches the newest images of the memory, the model has to support image inputs
+    #[must_use]
+    pub fn with_memory_images(self, memory_images: usize) -> Self {
+        Self { memory_images, ..self }
+    }
+
+    fn model_name<'a>(&'a self, config: &'a LlmConfig, llm_service: &LlmService) -> &'a str {
+        self.model
+            .model
+            .as_deref()
+            .unwrap_or_else(|| config.get_default_model(Some(llm_service)))
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub async fn invoke(
         &mut self,
@@ -60,10 +163,55 @@ impl LlmCore {
         conn: &DatabaseConnection,
         previous_response: Option<String>,
     ) -> Result<Message, LlmExecutionError> {
-        let (prompt, tool) = self
-            .inner(conversation_id, user_id, module_id, session_id, conn, previous_response)
+        self.invoke_with(
+            config,
+            conversation_id,
+            user_id,
+            module_id,
+            session_id,
+            llm_service,
+            conn,
+            previous_response,
+            None,
+            Vec::new(),
+        )
+        .await
+    }
+
+    /// Like [`LlmCore::invoke`], but asks for the given response format instead of calling the tool and appends
+    /// `feedback` to the prompt, e.g. to let the model correct a previous answer.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn invoke_with(
+        &mut self,
+        config: &LlmConfig,
+        conversation_id: &Uuid,
+        user_id: &Uuid,
+        module_id: &str,
+        session_id: &str,
+        llm_service: LlmService,
+        conn: &DatabaseConnection,
+        previous_response: Option<String>,
+        response_format: Option<ResponseFormat>,
+        feedback: Vec<PromptType>,
+    ) -> Result<Message, LlmExecutionError> {
+        let (mut prompt, tool) = self
+            .inner(
+                config,
+                &llm_service,
+                conversation_id,
+                user_id,
+                module_id,
+                session_id,
+                conn,
+                previous_response,
+            )
             .await?;
+        for message in feedback {
+            prompt.push(message.try_into()?);
+        }
 
+        // The response format replaces the tool, both describe the expected output
+        let tool = tool.filter(|_| response_format.is_none());
         let tool_choice = tool
             .as_ref()
             .and_then(|tool| tool.name().map(ToString::to_string))
@@ -72,27 +220,26 @@ impl LlmCore {
         let tools: Vec<ToolSchema> = tool.into_iter().collect();
 
         let openai_config = config.get_openai_config(Some(&llm_service));
-        let model = self
-            .model
-            .model
-            .as_deref()
-            .unwrap_or_else(|| config.get_default_model(Some(&llm_service)));
-
-        let message = openai_call_with_timeout(
-            CallConfig::builder()
-                .total_timeout(Duration::from_secs(30))
-                .iteration_timeout(Duration::from_secs(15))
-                .build(),
-            openai_config,
-            false,
-            self.model.temperature,
-            self.model.reasining_effort,
-            model,
-            prompt,
-            tools,
-            tool_choice,
-        )
-        .await?;
+        let model = self.model_name(config, &llm_service);
+
+        let settings = CallSettings::new(&self.model, &config.step_defaults, DEFAULT_FIRST_TOKEN_TIMEOUT);
+        let message = settings
+            .retry(|| {
+                let call = openai_call_with_timeout(
+                    settings.call_config(user_id),
+                    openai_config.clone(),
+                    false,
+                    self.model.temperature,
+                    self.model.reasining_effort,
+                    model,
+                    prompt.clone(),
+                    tools.clone(),
+                    tool_choice.clone(),
+                    response_format.clone(),
+                );
+                async move { Ok(call.await?) }
+            })
+            .await?;
 
         match message {
             OpenAiCallResult::Stream(_) => Err(LlmExecutionError::UnexpectedResponseFormat),
@@ -113,41 +260,65 @@ impl LlmCore {
         previous_response: Option<String>,
     ) -> Result<MessageStream, LlmExecutionError> {
         let (prompt, _) = self
-            .inner(conversation_id, user_id, module_id, session_id, conn, previous_response)
+            .inner(
+                config,
+                &llm_service,
+                conversation_id,
+                user_id,
+                module_id,
+                session_id,
+                conn,
+                previous_response,
+            )
             .await?;
 
         let openai_config = config.get_openai_config(Some(&llm_service));
-        let model = self
-            .model
-            .model
-            .as_deref()
-            .unwrap_or_else(|| config.get_default_model(Some(&llm_service)));
-
-        let answer = openai_call_with_timeout(
-            CallConfig::builder()
-                .total_timeout(Duration::from_secs(30))
-                .iteration_timeout(Duration::from_secs(5))
-                .build(),
-            openai_config,
-            true,
-            self.model.temperature,
-            None,
-            model,
-            prompt,
-            vec![],
-            None,
-        )
-        .await?;
+        let model = self.model_name(config, &llm_service);
 
-        match answer {
-            OpenAiCallResult::Stream(stream) => Ok(stream),
-            OpenAiCallResult::Message(_) => Err(LlmExecutionError::UnexpectedResponseFormat),
-        }
+        let settings = CallSettings::new(&self.model, &config.step_defaults, DEFAULT_STREAM_FIRST_TOKEN_TIMEOUT);
+        settings
+            .retry(|| {
+                let call = openai_call_with_timeout(
+                    settings.call_config(user_id),
+                    openai_config.clone(),
+                    true,
+                    self.model.temperature,
+                    None,
+                    model,
+                    prompt.clone(),
+                    vec![],
+                    None,
+                    None,
+                );
+                let first_token_timeout = settings.first_token_timeout;
+                async move {
+                    let first_token = async {
+                        let OpenAiCallResult::Stream(stream) = call.await? else {
+                            return Err(LlmExecutionError::UnexpectedResponseFormat);
+                        };
+                        let first = stream.next().await;
+                        Ok((first, stream))
+                    };
+                    let (first, rest) = tokio::time::timeout(first_token_timeout, first_token)
+                        .await
+                        .map_err(|_| LlmExecutionError::FirstTokenTimeout(first_token_timeout))??;
+                    // The first message was taken to check the timeout, so it is put in front again
+                    let rest = futures_util::stream::unfold(rest, |rest| async move {
+                        rest.next().await.map(|message| (message, rest))
+                    });
+                    Ok(MessageStream::new(
+                        futures_util::stream::iter(first).chain(rest).boxed(),
+                    ))
+                }
+            })
+            .await
     }
 
     #[allow(clippy::too_many_arguments)]
     pub async fn inner(
         &mut self,
+        config: &LlmConfig,
+        llm_service: &LlmService,
         conversation_id: &Uuid,
         user_id: &Uuid,
         module_id: &str,
@@ -155,7 +326,9 @@ impl LlmCore {
         conn: &DatabaseConnection,
         previous_response: Option<String>,
     ) -> Result<(Vec<ChatCompletionRequestMessage>, Option<ToolSchema>), LlmExecutionError> {
-        let memory = self.generate_memory(conn, conversation_id).await?;
+        let memory = self
+            .generate_memory(config, llm_service, conn, conversation_id, user_id)
+            .await?;
 
         let tool_schema: Option<ToolSchema> = if let Some(body) = &self.tool {
             let tool = body
@@ -187,18 +360,208 @@ impl LlmCore {
 
     async fn generate_memory(
         &self,
+        config: &LlmConfig,
+        llm_service: &LlmService,
         conn: &DatabaseConnection,
         conversation_id: &Uuid,
+        user_id: &Uuid,
     ) -> Result<Vec<PromptType>, LlmExecutionError> {
         tracing::trace!(steps = ?self.memory, ?conversation_id, "Memory steps");
+        let limit = self.memory_limit.map(|l| l as u64);
+        let Some(budget) = &self.memory_budget else {
+            let messages = get_memory(conn, conversation_id, self.memory.as_deref(), None, limit).await?;
+            tracing::trace!(?messages, "Generated memory");
+            return memory_prompts(conn, user_id, messages, self.memory_images).await;
+        };
+
+        let scope = budget.scope(self.memory.as_deref());
+        let existing = if budget.summary.enabled {
+            memory_summary::Query::get_memory_summary(conn, *conversation_id, &scope).await?
+        } else {
+            None
+        };
+        let covered_until = existing.as_ref().map(|summary| summary.covered_until);
+        // With a summary, the limit is applied after loading so the messages beyond it are summarized instead of
+        // being skipped by the cursor
+        let (query_limit, limit) = if budget.summary.enabled {
+            (None, self.memory_limit)
+        } else {
+            (limit, None)
+        };
         let messages = get_memory(
             conn,
             conversation_id,
             self.memory.as_deref(),
-            self.memory_limit.map(|l| l as u64),
+            covered_until,
+            query_limit,
         )
         .await?;
-        tracing::trace!(?messages, "Generated memory");
-        Ok(messages.into_iter().map(Into::into).collect::<Vec<_>>())
+
+        let model = self.model_name(config, llm_service);
+        let existing_tokens = existing
+            .as_ref()
+            .map(|summary| usize::try_from(summary.tokens).unwrap_or_default());
+        let (overflow, kept) = budget.split(model, messages, existing_tokens, limit, self.memory_images);
+        let mut summary = existing.map(|summary| summary.summary);
+
+        if budget.summary.enabled
+            && let Some(covered_until) = overflow.last().map(|message| message.message_order)
+        {
+            match self
+                .summarize(config, llm_service, user_id, budget, summary.as_deref(), &overflow)
+                .await
+            {
+                Ok((new_summary, tokens)) => {
+                    memory_summary::Mutation::upsert_memory_summary(
+                        conn,
+                        *conversation_id,
+                        scope,
+                        new_summary.clone(),
+                        covered_until,
+                        i32::try_from(tokens).unwrap_or(i32::MAX),
+                    )
+                    .await?;
+                    summary = Some(new_summary);
+                }
+                Err(error) => {
+                    // The messages are summarized again by the next call
+                    tracing::warn!(
+                        error = &error as &dyn Error,
+                        ?conversation_id,
+                        "failed to summarize memory, dropping older messages"
+                    );
+                }
+            }
+        }
+        tracing::trace!(?summary, ?kept, dropped = overflow.len(), "Generated memory");
+
+        let mut memory = Vec::with_capacity(kept.len() + 1);
+        memory.extend(summary.as_deref().map(summary_message));
+        memory.extend(memory_prompts(conn, user_id, kept, self.memory_images).await?);
+        Ok(memory)
+    }
+
+    /// Merges the previous summary with the messages which exceeded the budget
+    async fn summarize(
+        &self,
+        config: &LlmConfig,
+        llm_service: &LlmService,
+        user_id: &Uuid,
+        budget: &MemoryBudget,
+        previous: Option<&str>,
+        overflow: &[ConversationMessage],
+    ) -> Result<(String, usize), LlmExecutionError> {
+        let model = budget
+            .summary
+            .model
+            .as_deref()
+            .unwrap_or_else(|| self.model_name(config, llm_service));
+        let prompt = summary_prompt(previous, overflow, budget.summary.max_tokens)
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<_>, _>>()?;
+        let openai_config = config.get_openai_config(Some(llm_service));
+
+        let settings = CallSettings::new(&self.model, &config.step_defaults, DEFAULT_FIRST_TOKEN_TIMEOUT);
+        let result = settings
+            .retry(|| {
+                let call = openai_call_with_timeout(
+                    settings.call_config(user_id),
+                    openai_config.clone(),
+                    false,
+                    None,
+                    None,
+                    model,
+                    prompt.clone(),
+                    vec![],
+                    None,
+                    None,
+                );
+                async move { Ok(call.await?) }
+            })
+            .await?;
+
+        let OpenAiCallResult::Message(Message {
+            content: Content::Text { text, .. },
+            ..
+        }) = result
+        else {
+            return Err(LlmExecutionError::UnexpectedResponseFormat);
+        };
+        summary_tokens(self.model_name(config, llm_service), text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hikari_core::openai::error::OpenAiError;
+
+    #[test]
+    fn test_call_settings_precedence() {
+        let model = LlmModel {
+            timeout: Some(120),
+            backoff: Some(0.5),
+            ..Default::default()
+        };
+        let defaults = LlmCallDefaults {
+            timeout: Some(60),
+            first_token_timeout: Some(20),
+            max_retries: None,
+            backoff: Some(2.0),
+        };
+        let settings = CallSettings::new(&model, &defaults, DEFAULT_FIRST_TOKEN_TIMEOUT);
+        assert_eq!(settings.timeout, Duration::from_secs(120));
+        assert_eq!(settings.first_token_timeout, Duration::from_secs(20));
+        assert_eq!(settings.max_retries, 0);
+        assert_eq!(settings.backoff, Duration::from_millis(500));
+
+        let settings = CallSettings::new(&LlmModel::default(), &LlmCallDefaults::default(), 5);
+        assert_eq!(settings.timeout, Duration::from_secs(DEFAULT_TIMEOUT));
+        assert_eq!(settings.first_token_timeout, Duration::from_secs(5));
+        assert_eq!(settings.backoff, DEFAULT_BACKOFF);
+    }
+
+    #[tokio::test]
+    async fn test_retry_stops_at_max_retries() {
+        let settings = CallSettings {
+            timeout: Duration::from_secs(1),
+            first_token_timeout: Duration::from_secs(1),
+            max_retries: 2,
+            backoff: Duration::ZERO,
+        };
+        let mut attempts = 0;
+        let result: Result<(), _> = settings
+            .retry(|| {
+                attempts += 1;
+                async { Err(LlmExecutionError::FirstTokenTimeout(Duration::from_secs(1))) }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 3);
+
+        let mut attempts = 0;
+        let result: Result<(), _> = settings
+            .retry(|| {
+                attempts += 1;
+                async { Err(LlmExecutionError::UnexpectedResponseFormat) }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(is_retryable(&LlmExecutionError::OpenAiError(OpenAiError::Timeout)));
+        assert!(!is_retryable(&LlmExecutionError::OpenAiError(
+            OpenAiError::CircuitOpen("provider".to_owned())
+        )));
+        assert!(!is_retryable(&LlmExecutionError::OpenAiError(
+            OpenAiError::EmptyResponse
+        )));
+        assert!(!is_retryable(&LlmExecutionError::OpenAiError(OpenAiError::Api(
+            async_openai::error::OpenAIError::InvalidArgument("bad request".to_owned())
+        ))));
     }
 }
@@ -4,6 +4,7 @@ use crate::{
 };
 use sea_orm::DbErr;
 use std::str::ParseBoolError;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -46,6 +47,16 @@ pub enum LlmExecutionError {
     YamlError(#[from] yaml_serde::Error),
     #[error("Unexpected response format")]
     UnexpectedResponseFormat,
+    #[error("No token received within {0:?}")]
+    FirstTokenTimeout(Duration),
+    #[error("Image not found: {0}")]
+    ImageNotFound(uuid::Uuid),
+    #[error("Audio not found: {0}")]
+    AudioNotFound(uuid::Uuid),
+    #[error("No message of the user to rewind to")]
+    NoUserMessage,
+    #[error("The conversation has ended and can not be rewound")]
+    ConversationEnded,
     #[error("Goto target resolved to a non-string value: {0}")]
     InvalidGotoTarget(String),
     #[error(transparent)]
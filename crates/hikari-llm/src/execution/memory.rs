@@ -0,0 +1,317 @@
+use crate::builder::steps::MemorySummary;
+use crate::builder::steps::llm::{PromptType, message_content};
+use crate::execution::error::LlmExecutionError;
+use base64::Engine;
+use hikari_core::openai::tokens::{IMAGE_TOKENS, MESSAGE_OVERHEAD, count_message_tokens, count_tokens};
+use hikari_model::chat::{Direction, TypeSafePayload};
+use hikari_model::llm::message::ConversationMessage;
+use sea_orm::DatabaseConnection;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Token budget of the memory of a step
+#[derive(Debug, Clone)]
+pub struct MemoryBudget {
+    pub tokens: usize,
+    pub summary: MemorySummary,
+}
+
+impl MemoryBudget {
+    /// Key of the persisted summary, steps with the same selection and budget share it
+    #[must_use]
+    pub fn scope(&self, steps: Option<&[String]>) -> String {
+        let selection = steps.map_or_else(
+            || "*".to_string(),
+            |steps| {
+                let mut steps = steps.to_vec();
+                steps.sort();
+                steps.dedup();
+                steps.join(",")
+            },
+        );
+        format!("{selection}@{}", self.tokens)
+    }
+
+    /// Tokens left for messages if the given number of tokens is used by the summary
+    fn available(&self, summary_tokens: Option<usize>) -> usize {
+        let reserved = summary_tokens.map_or(0, |tokens| tokens + MESSAGE_OVERHEAD);
+        self.tokens.saturating_sub(reserved)
+    }
+
+    /// Splits the messages, oldest first, into the older ones which exceed the budget or the message `limit` and the
+    /// newest ones which fit. If a summary will be used, the tokens it may take are reserved. The newest `images`
+    /// images of the user are attached, so they are counted with [`IMAGE_TOKENS`].
+    #[must_use]
+    pub fn split(
+        &self,
+        model: &str,
+        messages: Vec<ConversationMessage>,
+        summary_tokens: Option<usize>,
+        limit: Option<usize>,
+        images: usize,
+    ) -> (Vec<ConversationMessage>, Vec<ConversationMessage>) {
+        let beyond_limit = limit.map_or(0, |limit| messages.len().saturating_sub(limit));
+        let split = split_at_budget(model, &messages, self.available(summary_tokens), images).max(beyond_limit);
+        if split > 0 && self.summary.enabled && summary_tokens.is_none_or(|tokens| tokens < self.summary.max_tokens) {
+            // The overflow is summarized, so the summary may grow up to its limit
+            let available = self.available(Some(self.summary.max_tokens));
+            let split = split_at_budget(model, &messages, available, images).max(beyond_limit);
+            return split_off_front(messages, split);
+        }
+        split_off_front(messages, split)
+    }
+}
+
+/// Index of the oldest message which fits into the budget together with all newer ones.
+/// The newest message is always kept, even if it exceeds the budget on its own.
+fn split_at_budget(model: &str, messages: &[ConversationMessage], budget: usize, mut images: usize) -> usize {
+    let mut used = 0;
+    for (index, message) in messages.iter().enumerate().rev() {
+        used += count_message_tokens(model, &message_text(message));
+        if images > 0 && is_user_image(message) {
+            images -= 1;
+            used += IMAGE_TOKENS;
+        }
+        if used > budget {
+            return (index + 1).min(messages.len().saturating_sub(1));
+        }
+    }
+    0
+}
+
+fn split_off_front(
+    mut messages: Vec<ConversationMessage>,
+    at: usize,
+) -> (Vec<ConversationMessage>, Vec<ConversationMessage>) {
+    let kept = messages.split_off(at);
+    (messages, kept)
+}
+
+fn message_text(message: &ConversationMessage) -> String {
+    message_content(message.message.clone())
+}
+
+/// Prompt asking the model to merge the previous summary with the messages which exceeded the budget
+#[must_use]
+pub fn summary_prompt(previous: Option<&str>, overflow: &[ConversationMessage], max_tokens: usize) -> Vec<PromptType> {
+    let transcript = overflow
+        .iter()
+        .map(|message| {
+            let speaker = match message.direction {
+                Direction::Receive => "Nutzer",
+                Direction::Send => "Assistent",
+            };
+            format!("{speaker}: {}", message_text(message))
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let previous = previous.map_or_else(String::new, |previous| {
+        format!("Bisherige Zusammenfassung:\n{previous}\n\n")
+    });
+    vec![
+        PromptType::System(
+            format!(
+                "Fasse den Gesprächsverlauf zwischen Nutzer und Assistent zusammen. Behalte Ziele, Vereinbarungen, \
+                 offene Fragen und wichtige Angaben des Nutzers bei. Die Zusammenfassung darf höchstens {max_tokens} \
+                 Tokens lang sein. Antworte nur mit der Zusammenfassung."
+            )
+            .into(),
+        ),
+        PromptType::User(format!("{previous}Neue Nachrichten:\n{transcript}").into()),
+    ]
+}
+
+/// The summary as it is put in front of the memory
+#[must_use]
+pub fn summary_message(summary: &str) -> PromptType {
+    PromptType::System(format!("Zusammenfassung des bisherigen Gesprächs:\n{summary}").into())
+}
+
+/// Checks the generated summary and counts its tokens with the model of the step, whose budget it is part of
+pub fn summary_tokens(model: &str, summary: Option<String>) -> Result<(String, usize), LlmExecutionError> {
+    let summary = summary
+        .map(|summary| summary.trim().to_string())
+        .filter(|summary| !summary.is_empty())
+        .ok_or(LlmExecutionError::UnexpectedResponseFormat)?;
+    let tokens = count_tokens(model, &summary);
+    Ok((summary, tokens))
+}
+
+fn is_user_image(message: &ConversationMessage) -> bool {
+    message.direction == Direction::Receive && matches!(message.message, TypeSafePayload::Image(_))
+}
+
+/// Ids of the newest images sent by the user, at most `limit`
+fn attached_images(messages: &[ConversationMessage], limit: usize) -> Vec<Uuid> {
+    messages
+        .iter()
+        .rev()
+        .filter(|message| message.direction == Direction::Receive)
+        .filter_map(|message| match &message.message {
+            TypeSafePayload::Image(image) => Some(image.image_id),
+            _ => None,
+        })
+        .take(limit)
+        .collect()
+}
+
+/// Converts the memory into prompts. The newest `images` images of the user are attached, older ones are replaced by
+/// a placeholder.
+pub async fn memory_prompts(
+    conn: &DatabaseConnection,
+    user_id: &Uuid,
+    messages: Vec<ConversationMessage>,
+    images: usize,
+) -> Result<Vec<PromptType>, LlmExecutionError> {
+    let ids = attached_images(&messages, images);
+    if ids.is_empty() {
+        return Ok(messages.into_iter().map(Into::into).collect());
+    }
+
+    let urls: HashMap<Uuid, String> = hikari_db::llm::image::Query::get_images(conn, *user_id, ids)
+        .await?
+        .into_iter()
+        .map(|image| {
+            let data = base64::engine::general_purpose::STANDARD.encode(&image.data);
+            (image.id, format!("data:{};base64,{data}", image.mime_type))
+        })
+        .collect();
+
+    Ok(messages
+        .into_iter()
+        .map(|message| match &message.message {
+            TypeSafePayload::Image(image) if message.direction == Direction::Receive => {
+                match urls.get(&image.image_id) {
+                    Some(url) => PromptType::UserImage {
+                        text: image.text.clone().unwrap_or_default().into(),
+                        images: vec![url.clone()],
+                    },
+                    None => message.into(),
+                }
+            }
+            _ => message.into(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hikari_model::chat::{ImageContent, TextContent};
+    use hikari_model::llm::message::MessageStatus;
+    use uuid::Uuid;
+
+    fn message(order: i32, text: &str) -> ConversationMessage {
+        ConversationMessage::new(
+            Uuid::nil(),
+            order,
+            TypeSafePayload::Text(TextContent { text: text.to_string() }),
+            "step".to_string(),
+            Direction::Receive,
+            MessageStatus::Completed,
+        )
+    }
+
+    fn budget(tokens: usize, enabled: bool) -> MemoryBudget {
+        MemoryBudget {
+            tokens,
+            summary: MemorySummary {
+                enabled,
+                model: None,
+                max_tokens: 10,
+            },
+        }
+    }
+
+    #[test]
+    fn test_split_keeps_newest_messages() {
+        // "hello world" has 2 tokens, 5 with the message overhead
+        let messages = (0..4).map(|order| message(order, "hello world")).collect::<Vec<_>>();
+
+        let (overflow, kept) = budget(100, false).split("gpt-4o", messages.clone(), None, None, 0);
+        assert!(overflow.is_empty());
+        assert_eq!(kept.len(), 4);
+
+        let (overflow, kept) = budget(10, false).split("gpt-4o", messages.clone(), None, None, 0);
+        assert_eq!(overflow.iter().map(|m| m.message_order).collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(kept.iter().map(|m| m.message_order).collect::<Vec<_>>(), vec![2, 3]);
+
+        // The summary reserves its maximum plus the overhead
+        let (overflow, kept) = budget(18, true).split("gpt-4o", messages.clone(), None, None, 0);
+        assert_eq!(overflow.len(), 3);
+        assert_eq!(kept.len(), 1);
+
+        // The newest message is kept even if it exceeds the budget
+        let (overflow, kept) = budget(1, false).split("gpt-4o", messages.clone(), None, None, 0);
+        assert_eq!(overflow.len(), 3);
+        assert_eq!(kept.len(), 1);
+
+        // Messages beyond the limit are moved to the overflow, so they are summarized and not skipped
+        let (overflow, kept) = budget(100, true).split("gpt-4o", messages, None, Some(2), 0);
+        assert_eq!(overflow.iter().map(|m| m.message_order).collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(kept.len(), 2);
+    }
+
+    #[test]
+    fn test_split_counts_attached_images() {
+        let image = ConversationMessage::new(
+            Uuid::nil(),
+            0,
+            TypeSafePayload::Image(ImageContent {
+                image_id: Uuid::nil(),
+                text: None,
+            }),
+            "step".to_string(),
+            Direction::Receive,
+            MessageStatus::Completed,
+        );
+        let messages = vec![image, message(1, "hello world")];
+
+        // The placeholder of an image which is not attached is cheap
+        let (overflow, _) = budget(IMAGE_TOKENS, false).split("gpt-4o", messages.clone(), None, None, 0);
+        assert!(overflow.is_empty());
+        let (overflow, kept) = budget(IMAGE_TOKENS, false).split("gpt-4o", messages, None, None, 1);
+        assert_eq!(overflow.len(), 1);
+        assert_eq!(kept.len(), 1);
+    }
+
+    #[test]
+    fn test_scope() {
+        let budget = budget(500, true);
+        assert_eq!(budget.scope(None), "*@500");
+        assert_eq!(
+            budget.scope(Some(&["b".to_string(), "a".to_string(), "b".to_string()])),
+            "a,b@500"
+        );
+    }
+
+    #[test]
+    fn test_attached_images() {
+        let image = |order: i32, direction: Direction| {
+            ConversationMessage::new(
+                Uuid::nil(),
+                order,
+                TypeSafePayload::Image(ImageContent {
+                    image_id: Uuid::from_u128(u128::from(order.unsigned_abs())),
+                    text: None,
+                }),
+                "step".to_string(),
+                direction,
+                MessageStatus::Completed,
+            )
+        };
+        let messages = vec![
+            image(1, Direction::Receive),
+            message(2, "hello"),
+            image(3, Direction::Receive),
+            image(4, Direction::Send),
+        ];
+
+        assert_eq!(attached_images(&messages, 1), vec![Uuid::from_u128(3)]);
+        assert_eq!(
+            attached_images(&messages, 5),
+            vec![Uuid::from_u128(3), Uuid::from_u128(1)]
+        );
+        assert!(attached_images(&messages, 0).is_empty());
+    }
+}
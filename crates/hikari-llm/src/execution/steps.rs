@@ -20,11 +20,14 @@ use hikari_core::llm_config::LlmConfig;
 use hikari_core::openai::streaming::MessageStream;
 use hikari_core::usage::add_usage;
 use hikari_model::llm::state::{LlmConversationState, LlmStepStatus, StateValue};
+use learner_memory_recall::LearnerMemoryRecall;
+use learner_memory_writer::LearnerMemoryWriter;
 use message_generator::MessageGenerator;
 use sea_orm::DatabaseConnection;
 use set_slot::SetSlot;
 use std::collections::HashMap;
 use std::error::Error;
+use structured_output::StructuredOutput;
 use text_message::TextMessage;
 use thiserror::Error;
 use uuid::Uuid;
@@ -38,9 +41,12 @@ pub mod conversation_summarizer;
 pub mod conversation_validator;
 pub mod counter;
 pub mod go_to;
+pub mod learner_memory_recall;
+pub mod learner_memory_writer;
 pub mod message_generator;
 pub mod set_slot;
 pub mod sse_call;
+pub mod structured_output;
 pub mod text_message;
 pub mod value_extractor;
 pub mod vector_db_extractor;
@@ -238,7 +244,10 @@ pub enum LlmStep {
     ConversationSummarizer(ConversationSummarizer),
     ConversationValidator(ConversationValidator),
     ValueExtractor(ValueExtractor),
+    StructuredOutput(StructuredOutput),
     VectorDBExtractor(VectorDBExtractor),
+    LearnerMemoryWriter(LearnerMemoryWriter),
+    LearnerMemoryRecall(LearnerMemoryRecall),
     ApiCall(ApiCall),
     SseCall(SseCall),
     SetSlot(SetSlot),
@@ -312,6 +321,15 @@ impl LlmStepTrait for LlmStep {
                 llm_service,
                 conn,
             ),
+            LlmStep::StructuredOutput(step) => step.call(
+                config,
+                conversation_id,
+                user_id,
+                module_id,
+                session_id,
+                llm_service,
+                conn,
+            ),
             LlmStep::VectorDBExtractor(step) => step.call(
                 config,
                 conversation_id,
@@ -321,6 +339,24 @@ impl LlmStepTrait for LlmStep {
                 llm_service,
                 conn,
             ),
+            LlmStep::LearnerMemoryWriter(step) => step.call(
+                config,
+                conversation_id,
+                user_id,
+                module_id,
+                session_id,
+                llm_service,
+                conn,
+            ),
+            LlmStep::LearnerMemoryRecall(step) => step.call(
+                config,
+                conversation_id,
+                user_id,
+                module_id,
+                session_id,
+                llm_service,
+                conn,
+            ),
             LlmStep::ApiCall(step) => step.call(
                 config,
                 conversation_id,
@@ -377,7 +413,10 @@ impl LlmStepTrait for LlmStep {
             LlmStep::ConversationSummarizer(step) => step.add_previous_response(response),
             LlmStep::ConversationValidator(step) => step.add_previous_response(response),
             LlmStep::ValueExtractor(step) => step.add_previous_response(response),
+            LlmStep::StructuredOutput(step) => step.add_previous_response(response),
             LlmStep::VectorDBExtractor(step) => step.add_previous_response(response),
+            LlmStep::LearnerMemoryWriter(step) => step.add_previous_response(response),
+            LlmStep::LearnerMemoryRecall(step) => step.add_previous_response(response),
             LlmStep::ApiCall(step) => step.add_previous_response(response),
             LlmStep::SseCall(step) => step.add_previous_response(response),
             LlmStep::SetSlot(step) => step.add_previous_response(response),
@@ -394,7 +433,10 @@ impl LlmStepTrait for LlmStep {
             LlmStep::ConversationSummarizer(step) => step.remove_previous_response(),
             LlmStep::ConversationValidator(step) => step.remove_previous_response(),
             LlmStep::ValueExtractor(step) => step.remove_previous_response(),
+            LlmStep::StructuredOutput(step) => step.remove_previous_response(),
             LlmStep::VectorDBExtractor(step) => step.remove_previous_response(),
+            LlmStep::LearnerMemoryWriter(step) => step.remove_previous_response(),
+            LlmStep::LearnerMemoryRecall(step) => step.remove_previous_response(),
             LlmStep::ApiCall(step) => step.remove_previous_response(),
             LlmStep::SseCall(step) => step.remove_previous_response(),
             LlmStep::SetSlot(step) => step.remove_previous_response(),
@@ -411,7 +453,10 @@ impl LlmStepTrait for LlmStep {
             LlmStep::ConversationSummarizer(step) => step.set_status(status),
             LlmStep::ConversationValidator(step) => step.set_status(status),
             LlmStep::ValueExtractor(step) => step.set_status(status),
+            LlmStep::StructuredOutput(step) => step.set_status(status),
             LlmStep::VectorDBExtractor(step) => step.set_status(status),
+            LlmStep::LearnerMemoryWriter(step) => step.set_status(status),
+            LlmStep::LearnerMemoryRecall(step) => step.set_status(status),
             LlmStep::ApiCall(step) => step.set_status(status),
             LlmStep::SseCall(step) => step.set_status(status),
             LlmStep::SetSlot(step) => step.set_status(status),
@@ -428,7 +473,10 @@ impl LlmStepTrait for LlmStep {
             LlmStep::ConversationSummarizer(step) => step.finish(),
             LlmStep::ConversationValidator(step) => step.finish(),
             LlmStep::ValueExtractor(step) => step.finish(),
+            LlmStep::StructuredOutput(step) => step.finish(),
             LlmStep::VectorDBExtractor(step) => step.finish(),
+            LlmStep::LearnerMemoryWriter(step) => step.finish(),
+            LlmStep::LearnerMemoryRecall(step) => step.finish(),
             LlmStep::ApiCall(step) => step.finish(),
             LlmStep::SseCall(step) => step.finish(),
             LlmStep::SetSlot(step) => step.finish(),
@@ -445,7 +493,10 @@ impl LlmStepTrait for LlmStep {
             LlmStep::ConversationSummarizer(step) => step.status(),
             LlmStep::ConversationValidator(step) => step.status(),
             LlmStep::ValueExtractor(step) => step.status(),
+            LlmStep::StructuredOutput(step) => step.status(),
             LlmStep::VectorDBExtractor(step) => step.status(),
+            LlmStep::LearnerMemoryWriter(step) => step.status(),
+            LlmStep::LearnerMemoryRecall(step) => step.status(),
             LlmStep::ApiCall(step) => step.status(),
             LlmStep::SseCall(step) => step.status(),
             LlmStep::SetSlot(step) => step.status(),
@@ -462,7 +513,10 @@ impl LlmStepTrait for LlmStep {
             LlmStep::ConversationSummarizer(step) => step.conditions(),
             LlmStep::ConversationValidator(step) => step.conditions(),
             LlmStep::ValueExtractor(step) => step.conditions(),
+            LlmStep::StructuredOutput(step) => step.conditions(),
             LlmStep::VectorDBExtractor(step) => step.conditions(),
+            LlmStep::LearnerMemoryWriter(step) => step.conditions(),
+            LlmStep::LearnerMemoryRecall(step) => step.conditions(),
             LlmStep::ApiCall(step) => step.conditions(),
             LlmStep::SseCall(step) => step.conditions(),
             LlmStep::SetSlot(step) => step.conditions(),
@@ -479,7 +533,10 @@ impl LlmStepTrait for LlmStep {
             LlmStep::ConversationSummarizer(step) => step.id(),
             LlmStep::ConversationValidator(step) => step.id(),
             LlmStep::ValueExtractor(step) => step.id(),
+            LlmStep::StructuredOutput(step) => step.id(),
             LlmStep::VectorDBExtractor(step) => step.id(),
+            LlmStep::LearnerMemoryWriter(step) => step.id(),
+            LlmStep::LearnerMemoryRecall(step) => step.id(),
             LlmStep::ApiCall(step) => step.id(),
             LlmStep::SseCall(step) => step.id(),
             LlmStep::SetSlot(step) => step.id(),
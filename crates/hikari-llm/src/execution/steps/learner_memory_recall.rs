@@ -0,0 +1,186 @@
+use crate::{
+    builder::{
+        slot::{SaveTarget, paths::SlotPath},
+        steps::Condition,
+    },
+    execution::{error::LlmExecutionError, steps::LlmStepContent},
+    utils::get_slot,
+};
+use futures_core::future::BoxFuture;
+use futures_util::FutureExt;
+use hikari_config::module::llm_agent::LlmService;
+use hikari_core::llm_config::LlmConfig;
+use hikari_core::pgvector::learner_memory::recall;
+use hikari_model::llm::learner_memory::LearnerMemoryMatch;
+use hikari_model::llm::state::{LlmConversationState, LlmStepStatus};
+use hikari_utils::values::ValueDecoder;
+use sea_orm::DatabaseConnection;
+use std::collections::HashMap;
+use uuid::Uuid;
+use yaml_serde::Value;
+
+use super::{LlmStepResponse, LlmStepTrait};
+
+#[derive(Clone)]
+pub struct LearnerMemoryRecall {
+    id: String,
+    target: SaveTarget,
+    limit: u32,
+    max_distance: Option<f64>,
+    query: SlotPath,
+    conditions: Vec<Condition>,
+    status: LlmStepStatus,
+}
+
+impl LearnerMemoryRecall {
+    #[must_use]
+    pub fn new(
+        id: String,
+        target: SaveTarget,
+        limit: u32,
+        max_distance: Option<f64>,
+        query: SlotPath,
+        conditions: Vec<Condition>,
+    ) -> Self {
+        Self {
+            id,
+            target,
+            limit,
+            max_distance,
+            query,
+            conditions,
+            status: LlmStepStatus::NotStarted,
+        }
+    }
+}
+
+/// Merges the memories of all queries, the most relevant first, and lists them with their date
+pub(crate) fn format_memories(mut memories: Vec<LearnerMemoryMatch>) -> String {
+    memories.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    let mut seen = Vec::new();
+    memories
+        .into_iter()
+        .filter(|memory| {
+            let new = !seen.contains(&memory.content);
+            seen.push(memory.content.clone());
+            new
+        })
+        .map(|memory| format!("- {} ({})", memory.content, memory.created_at.format("%d.%m.%Y")))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl LlmStepTrait for LearnerMemoryRecall {
+    fn call<'a>(
+        &'a mut self,
+        config: &'a LlmConfig,
+        conversation_id: &'a Uuid,
+        user_id: &'a Uuid,
+        module_id: &'a str,
+        session_id: &'a str,
+        _llm_service: LlmService,
+        conn: DatabaseConnection,
+    ) -> BoxFuture<'a, Result<LlmStepResponse, LlmExecutionError>> {
+        async move {
+            let slot = get_slot(
+                &conn,
+                conversation_id,
+                user_id,
+                module_id,
+                session_id,
+                self.query.clone(),
+            )
+            .await?;
+
+            let queries = match slot.value.as_ref() {
+                Value::Sequence(seq) => seq.iter().map(ValueDecoder::encode).collect(),
+                other => vec![other.encode()],
+            };
+
+            tracing::trace!(?queries, "recall queries");
+
+            let mut memories = Vec::new();
+            for query in &queries {
+                memories.extend(recall(config, &conn, *user_id, query, self.limit, self.max_distance).await?);
+            }
+            let content = format_memories(memories);
+
+            tracing::trace!(content, "recalled memories");
+
+            let mut values = HashMap::new();
+            if !content.is_empty() {
+                values.insert(self.target.clone(), Value::String(content));
+            }
+
+            Ok(LlmStepResponse::new(
+                LlmStepContent::StepValue {
+                    values,
+                    next_step: None,
+                },
+                None,
+            ))
+        }
+        .boxed()
+    }
+
+    fn add_previous_response(&mut self, _response: String) {
+        tracing::error!(
+            "Adding previous response to recall should not happen, since this step does not produce a response."
+        );
+    }
+
+    fn remove_previous_response(&mut self) {
+        // Nothing will happen here; Function gets called at the beginning of the step
+    }
+
+    fn set_status(&mut self, status: LlmStepStatus) -> LlmConversationState {
+        self.status = status;
+        self.state()
+    }
+
+    fn finish(&mut self) -> LlmConversationState {
+        self.set_status(LlmStepStatus::Completed);
+        self.state()
+    }
+
+    fn status(&self) -> LlmStepStatus {
+        self.status
+    }
+
+    fn conditions(&self) -> &[Condition] {
+        &self.conditions
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_format_memories() {
+        let created_at = NaiveDate::from_ymd_opt(2026, 9, 1)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap();
+        let memory = |content: &str, distance| LearnerMemoryMatch {
+            content: content.to_string(),
+            created_at,
+            distance,
+        };
+        let formatted = format_memories(vec![
+            memory("Mag Beispiele", 0.4),
+            memory("Studiert Informatik", 0.2),
+            memory("Mag Beispiele", 0.3),
+        ]);
+        assert_eq!(
+            formatted,
+            "- Studiert Informatik (01.09.2026)\n- Mag Beispiele (01.09.2026)"
+        );
+        assert!(format_memories(vec![]).is_empty());
+    }
+}
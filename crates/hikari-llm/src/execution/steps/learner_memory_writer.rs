@@ -0,0 +1,182 @@
+use super::LlmStepContent;
+use crate::builder::steps::Condition;
+use crate::execution::core::LlmCore;
+use crate::execution::error::LlmExecutionError;
+use crate::execution::steps::{LlmStepResponse, LlmStepTrait};
+use futures_core::future::BoxFuture;
+use futures_util::FutureExt;
+use hikari_config::module::llm_agent::LlmService;
+use hikari_core::llm_config::LlmConfig;
+use hikari_core::openai::{Content, Message};
+use hikari_core::pgvector::learner_memory::{is_enabled_for, memorize};
+use hikari_model::llm::state::{LlmConversationState, LlmStepStatus};
+use sea_orm::DatabaseConnection;
+use serde_json::Value;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct LearnerMemoryWriter {
+    id: String,
+    core: LlmCore,
+    max_facts: u32,
+    conditions: Vec<Condition>,
+    status: LlmStepStatus,
+    previous_response: Option<String>,
+}
+
+impl LearnerMemoryWriter {
+    #[must_use]
+    pub fn new(id: String, core: LlmCore, max_facts: u32, conditions: Vec<Condition>) -> Self {
+        Self {
+            id,
+            core,
+            max_facts,
+            conditions,
+            status: LlmStepStatus::NotStarted,
+            previous_response: None,
+        }
+    }
+}
+
+/// The facts of the tool call or of a JSON answer, empty and duplicate facts are removed
+pub(crate) fn parse_facts(content: Content, max_facts: u32) -> Result<Vec<String>, LlmExecutionError> {
+    let output = match content {
+        Content::Tool(tool_calls) => {
+            tool_calls
+                .into_iter()
+                .next()
+                .ok_or(LlmExecutionError::UnexpectedResponseFormat)?
+                .arguments
+        }
+        Content::Text { text: Some(text), .. } => serde_json::from_str(&text)?,
+        Content::Text { text: None, .. } => return Err(LlmExecutionError::UnexpectedResponseFormat),
+    };
+    let Some(Value::Array(facts)) = output.get("facts") else {
+        return Err(LlmExecutionError::UnexpectedResponseFormat);
+    };
+    let mut unique = Vec::new();
+    for fact in facts.iter().filter_map(Value::as_str).map(str::trim) {
+        if !fact.is_empty() && !unique.iter().any(|known: &String| known == fact) {
+            unique.push(fact.to_string());
+        }
+    }
+    unique.truncate(usize::try_from(max_facts).unwrap_or(usize::MAX));
+    Ok(unique)
+}
+
+impl LlmStepTrait for LearnerMemoryWriter {
+    fn call<'a>(
+        &'a mut self,
+        config: &'a LlmConfig,
+        conversation_id: &'a Uuid,
+        user_id: &'a Uuid,
+        module_id: &'a str,
+        session_id: &'a str,
+        llm_service: LlmService,
+        conn: DatabaseConnection,
+    ) -> BoxFuture<'a, Result<LlmStepResponse, LlmExecutionError>> {
+        async move {
+            let empty = LlmStepContent::StepValue {
+                values: HashMap::new(),
+                next_step: None,
+            };
+            if !is_enabled_for(config, &conn, *user_id).await? {
+                tracing::debug!(id = %self.id, "learner memory is disabled for the user, skipping memorize step");
+                return Ok(LlmStepResponse::new(empty, None));
+            }
+
+            let Message { content, tokens } = self
+                .core
+                .invoke(
+                    config,
+                    conversation_id,
+                    user_id,
+                    module_id,
+                    session_id,
+                    llm_service,
+                    &conn,
+                    self.previous_response.take(),
+                )
+                .await?;
+
+            let facts = parse_facts(content, self.max_facts)?;
+            let stored = memorize(
+                config,
+                &conn,
+                *user_id,
+                module_id,
+                session_id,
+                Some(*conversation_id),
+                &facts,
+            )
+            .await?;
+            tracing::debug!(id = %self.id, extracted = facts.len(), stored, "memorized facts about the user");
+
+            Ok(LlmStepResponse::new(empty, tokens))
+        }
+        .boxed()
+    }
+
+    fn add_previous_response(&mut self, response: String) {
+        self.previous_response = Some(response);
+    }
+
+    fn remove_previous_response(&mut self) {
+        self.previous_response = None;
+    }
+
+    fn set_status(&mut self, status: LlmStepStatus) -> LlmConversationState {
+        self.status = status;
+        self.state()
+    }
+
+    fn finish(&mut self) -> LlmConversationState {
+        self.set_status(LlmStepStatus::Completed);
+        self.state()
+    }
+
+    fn status(&self) -> LlmStepStatus {
+        self.status
+    }
+
+    fn conditions(&self) -> &[Condition] {
+        &self.conditions
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hikari_core::openai::ToolCallResponse;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_facts() {
+        let content = Content::Tool(vec![ToolCallResponse {
+            name: "RememberFacts".to_string(),
+            thinking: None,
+            arguments: json!({"facts": ["Studiert Informatik", " ", "Studiert Informatik", "Mag Beispiele", "Lernt abends"]}),
+        }]);
+        assert_eq!(
+            parse_facts(content, 2).unwrap(),
+            vec!["Studiert Informatik".to_string(), "Mag Beispiele".to_string()]
+        );
+
+        let content = Content::Text {
+            text: Some(r#"{"facts": []}"#.to_string()),
+            thinking: None,
+        };
+        assert!(parse_facts(content, 5).unwrap().is_empty());
+
+        let content = Content::Text {
+            text: Some(r#"{"other": 1}"#.to_string()),
+            thinking: None,
+        };
+        assert!(parse_facts(content, 5).is_err());
+    }
+}
@@ -0,0 +1,294 @@
+use crate::builder::NextStep;
+use crate::builder::slot::SaveTarget;
+use crate::builder::steps::llm::PromptType;
+use crate::builder::steps::structured::{StructuredField, StructuredMode};
+use crate::builder::steps::{Condition, resolve_optional};
+use crate::execution::core::LlmCore;
+use crate::execution::error::LlmExecutionError;
+use crate::execution::steps::{LlmStepContent, LlmStepResponse, LlmStepTrait};
+use async_openai::types::chat::{ResponseFormat, ResponseFormatJsonSchema};
+use futures_core::future::BoxFuture;
+use futures_util::FutureExt;
+use hikari_config::module::llm_agent::LlmService;
+use hikari_core::llm_config::LlmConfig;
+use hikari_core::openai::{Content, Message};
+use hikari_model::llm::state::{LlmConversationState, LlmStepStatus};
+use jsonschema::Validator;
+use sea_orm::DatabaseConnection;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct StructuredOutput {
+    id: String,
+    core: LlmCore,
+    name: String,
+    description: String,
+    schema: Value,
+    /// Compiled once while building the step
+    validator: Arc<Validator>,
+    fields: Vec<StructuredField>,
+    mode: StructuredMode,
+    /// Set once the provider rejected the JSON schema response format in [`StructuredMode::Auto`]
+    json_schema_rejected: bool,
+    validation_retries: u32,
+    goto_on_success: NextStep,
+    goto_on_fail: NextStep,
+    conditions: Vec<Condition>,
+    status: LlmStepStatus,
+    previous_response: Option<String>,
+}
+
+impl StructuredOutput {
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: String,
+        core: LlmCore,
+        name: String,
+        description: String,
+        schema: Value,
+        validator: Arc<Validator>,
+        fields: Vec<StructuredField>,
+        mode: StructuredMode,
+        validation_retries: u32,
+        goto_on_success: NextStep,
+        goto_on_fail: NextStep,
+        conditions: Vec<Condition>,
+    ) -> Self {
+        Self {
+            id,
+            core,
+            name,
+            description,
+            schema,
+            validator,
+            fields,
+            mode,
+            json_schema_rejected: false,
+            validation_retries,
+            goto_on_success,
+            goto_on_fail,
+            conditions,
+            status: LlmStepStatus::NotStarted,
+            previous_response: None,
+        }
+    }
+
+    fn response_format(&self) -> Option<ResponseFormat> {
+        let use_json_schema = match self.mode {
+            StructuredMode::Auto => !self.json_schema_rejected,
+            StructuredMode::JsonSchema => true,
+            StructuredMode::ToolCall => false,
+        };
+        use_json_schema.then(|| ResponseFormat::JsonSchema {
+            json_schema: ResponseFormatJsonSchema {
+                description: Some(self.description.clone()),
+                name: self.name.clone(),
+                schema: Some(self.schema.clone()),
+                strict: None,
+            },
+        })
+    }
+
+    /// Stores the fields of a valid output into their slots. Fails if any field is missing or null.
+    fn collect_fields(
+        &self,
+        output: &Value,
+    ) -> Result<(HashMap<SaveTarget, yaml_serde::Value>, bool), LlmExecutionError> {
+        let mut values = HashMap::new();
+        let mut success = true;
+        for field in &self.fields {
+            match output.pointer(&field.pointer()) {
+                Some(value) if !value.is_null() => {
+                    values.insert(field.target.clone(), yaml_serde::to_value(value)?);
+                }
+                _ => {
+                    tracing::debug!(id = %self.id, field = %field.field, "structured output misses field");
+                    success = false;
+                }
+            }
+        }
+        Ok((values, success))
+    }
+}
+
+/// Raw output of the model, taken from the tool call or the message text
+fn raw_output(content: Content) -> Result<String, LlmExecutionError> {
+    match content {
+        Content::Tool(tool_calls) => {
+            let first = tool_calls
+                .into_iter()
+                .next()
+                .ok_or(LlmExecutionError::UnexpectedResponseFormat)?;
+            Ok(first.arguments.to_string())
+        }
+        Content::Text { text: Some(text), .. } => Ok(text),
+        Content::Text { text: None, .. } => Err(LlmExecutionError::UnexpectedResponseFormat),
+    }
+}
+
+/// Parses the output and validates it against the schema, the error is meant to be read by the model
+pub(crate) fn validate_output(validator: &Validator, raw: &str) -> Result<Value, String> {
+    let output: Value = serde_json::from_str(raw).map_err(|error| format!("The output is not valid JSON: {error}"))?;
+    let errors: Vec<String> = validator
+        .iter_errors(&output)
+        .map(|error| format!("- {error}"))
+        .collect();
+    if errors.is_empty() {
+        Ok(output)
+    } else {
+        Err(format!("The output does not match the schema:\n{}", errors.join("\n")))
+    }
+}
+
+impl LlmStepTrait for StructuredOutput {
+    fn call<'a>(
+        &'a mut self,
+        config: &'a LlmConfig,
+        conversation_id: &'a Uuid,
+        user_id: &'a Uuid,
+        module_id: &'a str,
+        session_id: &'a str,
+        llm_service: LlmService,
+        conn: DatabaseConnection,
+    ) -> BoxFuture<'a, Result<LlmStepResponse, LlmExecutionError>> {
+        async move {
+            let mut previous_response = self.previous_response.take();
+            let mut feedback = Vec::new();
+            let mut tokens: Option<u32> = None;
+            let mut retries = 0;
+
+            let output = loop {
+                let response_format = self.response_format();
+                let uses_json_schema = response_format.is_some();
+                let result = self
+                    .core
+                    .invoke_with(
+                        config,
+                        conversation_id,
+                        user_id,
+                        module_id,
+                        session_id,
+                        llm_service.clone(),
+                        &conn,
+                        previous_response.clone(),
+                        response_format,
+                        feedback.clone(),
+                    )
+                    .await;
+                let Message { content, tokens: used } = match result {
+                    // Rate limits and server errors say nothing about the support of the response format
+                    Err(LlmExecutionError::OpenAiError(error))
+                        if uses_json_schema
+                            && self.mode == StructuredMode::Auto
+                            && error.is_response_format_unsupported() =>
+                    {
+                        tracing::warn!(
+                            error = &error as &dyn Error,
+                            id = %self.id,
+                            "json schema response format was rejected, falling back to tool calling"
+                        );
+                        self.json_schema_rejected = true;
+                        continue;
+                    }
+                    result => result?,
+                };
+                if let Some(used) = used {
+                    tokens = Some(tokens.unwrap_or_default().saturating_add(used));
+                }
+
+                let raw = raw_output(content)?;
+                match validate_output(&self.validator, &raw) {
+                    Ok(output) => break Some(output),
+                    Err(error) if retries < self.validation_retries => {
+                        tracing::info!(id = %self.id, retries, %error, "structured output is invalid, retrying");
+                        retries += 1;
+                        previous_response = None;
+                        feedback.push(PromptType::AI(raw.into()));
+                        feedback.push(PromptType::System(
+                            format!("{error}\nRespond again with output that matches the schema.").into(),
+                        ));
+                    }
+                    Err(error) => {
+                        tracing::warn!(id = %self.id, %error, "structured output is still invalid, giving up");
+                        break None;
+                    }
+                }
+            };
+
+            let (values, success) = match &output {
+                Some(output) => self.collect_fields(output)?,
+                None => (HashMap::new(), false),
+            };
+
+            let goto = super::select_goto(success, &self.goto_on_success, &self.goto_on_fail);
+            let goto = resolve_optional(goto, conversation_id, user_id, module_id, session_id, &conn).await?;
+            let next_step = goto.map(super::template_to_step_id).transpose()?;
+
+            Ok(LlmStepResponse::new(
+                LlmStepContent::StepValue { values, next_step },
+                tokens,
+            ))
+        }
+        .boxed()
+    }
+
+    fn add_previous_response(&mut self, response: String) {
+        self.previous_response = Some(response);
+    }
+
+    fn remove_previous_response(&mut self) {
+        self.previous_response = None;
+    }
+
+    fn set_status(&mut self, status: LlmStepStatus) -> LlmConversationState {
+        self.status = status;
+        self.state()
+    }
+
+    fn finish(&mut self) -> LlmConversationState {
+        self.set_status(LlmStepStatus::Completed);
+        self.state()
+    }
+
+    fn status(&self) -> LlmStepStatus {
+        self.status
+    }
+
+    fn conditions(&self) -> &[Condition] {
+        self.conditions.as_slice()
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_output() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"score": {"type": "integer", "minimum": 1}},
+            "required": ["score"]
+        });
+        let validator = jsonschema::validator_for(&schema).unwrap();
+        assert_eq!(validate_output(&validator, r#"{"score": 3}"#).unwrap()["score"], 3);
+        assert!(
+            validate_output(&validator, "no json")
+                .unwrap_err()
+                .contains("not valid JSON")
+        );
+        let error = validate_output(&validator, r#"{"score": 0}"#).unwrap_err();
+        assert!(error.contains("does not match the schema"));
+        assert!(validate_output(&validator, "{}").is_err());
+    }
+}
@@ -20,10 +20,11 @@ pub async fn get_memory(
     conn: &DatabaseConnection,
     conversation_id: &Uuid,
     steps: Option<&[String]>,
+    after: Option<i32>,
     limit: Option<u64>,
 ) -> Result<Vec<ConversationMessage>, sea_orm::DbErr> {
     let res: Vec<ConversationMessage> =
-        hikari_db::llm::message::Query::get_memory_from_conversation(conn, conversation_id, steps, limit)
+        hikari_db::llm::message::Query::get_memory_from_conversation(conn, conversation_id, steps, after, limit)
             .await?
             .into_iter()
             .map(hikari_model_tools::convert::IntoModel::into_model)
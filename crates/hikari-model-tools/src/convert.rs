@@ -1,4 +1,6 @@
 pub mod assessment;
+pub mod audit_log;
+pub mod crisis;
 pub mod history;
 pub mod journal;
 pub mod llm;
@@ -0,0 +1,17 @@
+use crate::convert::FromDbModel;
+use hikari_entity::audit_log::Model;
+use hikari_model::audit_log::AuditLogEntry;
+
+impl FromDbModel<Model> for AuditLogEntry {
+    fn from_db_model(model: Model) -> Self {
+        Self {
+            id: model.id,
+            created_at: model.created_at,
+            actor_id: model.actor_id,
+            action: model.action,
+            subject_id: model.subject_id,
+            request_id: model.request_id,
+            details: model.details,
+        }
+    }
+}
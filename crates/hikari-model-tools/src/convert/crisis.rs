@@ -0,0 +1,17 @@
+use crate::convert::FromDbModel;
+use hikari_entity::crisis_event::Model;
+use hikari_model::crisis::CrisisEvent;
+
+impl FromDbModel<Model> for CrisisEvent {
+    fn from_db_model(model: Model) -> Self {
+        Self {
+            id: model.id,
+            user_id: model.user_id,
+            source: model.source,
+            reference_id: model.reference_id,
+            detector: model.detector,
+            created_at: model.created_at,
+            notified_at: model.notified_at,
+        }
+    }
+}
@@ -1,4 +1,7 @@
 mod conversation;
+mod guardrail;
+mod image;
+mod learner_memory;
 mod message;
 mod slot;
 mod state;
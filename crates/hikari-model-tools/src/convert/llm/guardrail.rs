@@ -0,0 +1,26 @@
+use crate::convert::FromDbModel;
+use hikari_entity::llm::guardrail_event::Model;
+use hikari_model::llm::guardrail::GuardrailEvent;
+
+impl FromDbModel<Model> for GuardrailEvent {
+    fn from_db_model(model: Model) -> Self {
+        Self {
+            id: model.id,
+            conversation_id: model.conversation_id,
+            user_id: model.user_id,
+            step: model.step,
+            direction: model.direction,
+            categories: model
+                .categories
+                .split(',')
+                .filter(|category| !category.is_empty())
+                .map(ToOwned::to_owned)
+                .collect(),
+            action: model.action,
+            content: model.content,
+            created_at: model.created_at,
+            reviewed_at: model.reviewed_at,
+            reviewed_by: model.reviewed_by,
+        }
+    }
+}
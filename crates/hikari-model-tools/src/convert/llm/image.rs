@@ -0,0 +1,13 @@
+use crate::convert::FromDbModel;
+use hikari_entity::llm::image::Model;
+use hikari_model::llm::image::UploadedImage;
+
+impl FromDbModel<Model> for UploadedImage {
+    fn from_db_model(model: Model) -> Self {
+        Self {
+            id: model.id,
+            mime_type: model.mime_type,
+            created_at: model.created_at,
+        }
+    }
+}
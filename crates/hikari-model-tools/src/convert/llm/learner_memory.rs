@@ -0,0 +1,15 @@
+use crate::convert::FromDbModel;
+use hikari_entity::llm::learner_memory::Model;
+use hikari_model::llm::learner_memory::LearnerMemory;
+
+impl FromDbModel<Model> for LearnerMemory {
+    fn from_db_model(model: Model) -> Self {
+        Self {
+            id: model.id,
+            module_id: model.module_id,
+            session_id: model.session_id,
+            content: model.content,
+            created_at: model.created_at,
+        }
+    }
+}
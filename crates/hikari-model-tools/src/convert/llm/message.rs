@@ -61,6 +61,12 @@ impl FromDbModel<Model> for ConversationMessage {
                 ContentTypeModel::Buttons => TypeSafePayload::Button(
                     serde_json::from_str(&model.payload).expect("failed to decode button payload"),
                 ),
+                ContentTypeModel::Image => TypeSafePayload::Image(
+                    serde_json::from_str(&model.payload).expect("failed to decode image payload"),
+                ),
+                ContentTypeModel::Audio => TypeSafePayload::Audio(
+                    serde_json::from_str(&model.payload).expect("failed to decode audio payload"),
+                ),
             },
             step: model.step,
             direction: model.direction.into_model(),
@@ -79,11 +85,14 @@ impl FromModel<ConversationMessage> for Model {
             content_type: match &model.message {
                 TypeSafePayload::Text(_) => ContentTypeModel::Text,
                 TypeSafePayload::Button(_) => ContentTypeModel::Buttons,
+                TypeSafePayload::Image(_) => ContentTypeModel::Image,
+                TypeSafePayload::Audio(_) => ContentTypeModel::Audio,
                 _ => ContentTypeModel::Payload,
             },
             payload: serde_json::to_string(&model.message).expect("failed to decode message payload"),
             direction: model.direction.into_db_model(),
             status: model.status.into_db_model(),
+            superseded_by: None,
         }
     }
 }
@@ -102,6 +111,14 @@ pub fn split_payload_for_database(payload: TypeSafePayload) -> Result<(ContentTy
             let payload_str = serde_json::to_string(&payload)?;
             Ok((ContentType::Payload, payload_str))
         }
+        TypeSafePayload::Image(image) => {
+            let image_str = serde_json::to_string(&image)?;
+            Ok((ContentType::Image, image_str))
+        }
+        TypeSafePayload::Audio(audio) => {
+            let audio_str = serde_json::to_string(&audio)?;
+            Ok((ContentType::Audio, audio_str))
+        }
         TypeSafePayload::FlowTrigger(_flow_trigger) => {
             Err(anyhow::Error::msg("FlowTrigger payload type not supported".to_owned()))
         }
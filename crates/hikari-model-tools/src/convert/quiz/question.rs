@@ -1,12 +1,14 @@
-use hikari_config::module::content::QuestionBloomLevel;
+use hikari_config::module::content::{GradingRubric, QuestionBloomLevel};
 use hikari_entity::quiz::question::BloomLevel as QuestionBloomLevelModel;
 use hikari_entity::quiz::question::Feedback as QuestionFeedbackModel;
 use hikari_entity::quiz::question::Model as QuestionModel;
 use hikari_entity::quiz::question::QuestionType as QuestionTypeModel;
+use hikari_entity::quiz::question::Rubric as QuestionRubricModel;
 use hikari_entity::quiz::question::Status as QuestionStatusModel;
 use hikari_model::quiz::question::Question;
 use hikari_model::quiz::question::QuestionFeedback;
 use hikari_model::quiz::question::QuestionOption;
+use hikari_model::quiz::question::QuestionRubric;
 use hikari_model::quiz::question::QuestionStatus;
 
 use crate::convert::FromDbModel;
@@ -19,6 +21,9 @@ impl FromDbModel<QuestionModel> for Question {
         } else {
             Vec::new()
         };
+        let details = model
+            .details
+            .and_then(|details_json| serde_json::from_str(&details_json).ok());
 
         Self {
             id: model.id,
@@ -29,11 +34,14 @@ impl FromDbModel<QuestionModel> for Question {
             question: model.question,
             r#type: FromDbModel::from_db_model(model.r#type),
             options,
+            details,
             level: FromDbModel::from_db_model(model.level),
             answer: model.answer,
             evaluation: model.evaluation,
             grade: model.grade,
             ai_solution: model.ai_solution,
+            rubric: FromDbModel::from_db_model(model.rubric),
+            explanation: model.explanation,
             status: FromDbModel::from_db_model(model.status),
             feedback: model.feedback.map(FromDbModel::from_db_model),
             feedback_explanation: model.feedback_explanation,
@@ -48,6 +56,52 @@ impl FromDbModel<QuestionTypeModel> for hikari_model::quiz::question::QuestionTy
         match model {
             QuestionTypeModel::MultipleChoice => hikari_model::quiz::question::QuestionType::MultipleChoice,
             QuestionTypeModel::Text => hikari_model::quiz::question::QuestionType::Text,
+            QuestionTypeModel::Cloze => hikari_model::quiz::question::QuestionType::Cloze,
+            QuestionTypeModel::Ordering => hikari_model::quiz::question::QuestionType::Ordering,
+            QuestionTypeModel::Matching => hikari_model::quiz::question::QuestionType::Matching,
+            QuestionTypeModel::Numeric => hikari_model::quiz::question::QuestionType::Numeric,
+        }
+    }
+}
+
+impl IntoDbModel<QuestionTypeModel> for hikari_model::quiz::question::QuestionType {
+    fn into_db_model(self) -> QuestionTypeModel {
+        match self {
+            hikari_model::quiz::question::QuestionType::MultipleChoice => QuestionTypeModel::MultipleChoice,
+            hikari_model::quiz::question::QuestionType::Text => QuestionTypeModel::Text,
+            hikari_model::quiz::question::QuestionType::Cloze => QuestionTypeModel::Cloze,
+            hikari_model::quiz::question::QuestionType::Ordering => QuestionTypeModel::Ordering,
+            hikari_model::quiz::question::QuestionType::Matching => QuestionTypeModel::Matching,
+            hikari_model::quiz::question::QuestionType::Numeric => QuestionTypeModel::Numeric,
+        }
+    }
+}
+
+impl FromDbModel<QuestionRubricModel> for QuestionRubric {
+    fn from_db_model(model: QuestionRubricModel) -> Self {
+        match model {
+            QuestionRubricModel::Llm => QuestionRubric::Llm,
+            QuestionRubricModel::AllOrNothing => QuestionRubric::AllOrNothing,
+            QuestionRubricModel::PartialCredit => QuestionRubric::PartialCredit,
+        }
+    }
+}
+
+impl IntoDbModel<QuestionRubricModel> for QuestionRubric {
+    fn into_db_model(self) -> QuestionRubricModel {
+        match self {
+            QuestionRubric::Llm => QuestionRubricModel::Llm,
+            QuestionRubric::AllOrNothing => QuestionRubricModel::AllOrNothing,
+            QuestionRubric::PartialCredit => QuestionRubricModel::PartialCredit,
+        }
+    }
+}
+
+impl IntoDbModel<QuestionRubricModel> for GradingRubric {
+    fn into_db_model(self) -> QuestionRubricModel {
+        match self {
+            GradingRubric::AllOrNothing => QuestionRubricModel::AllOrNothing,
+            GradingRubric::PartialCredit => QuestionRubricModel::PartialCredit,
         }
     }
 }
@@ -0,0 +1,62 @@
+use hikari_entity::quiz::question_bank::Model as QuestionBankModel;
+use hikari_entity::quiz::question_bank::Status as QuestionBankStatusModel;
+use hikari_model::quiz::question::QuestionOption;
+use hikari_model::quiz::question_bank::{QuestionBankEntry, QuestionBankStatus};
+
+use crate::convert::FromDbModel;
+use crate::convert::IntoDbModel;
+
+impl FromDbModel<QuestionBankModel> for QuestionBankEntry {
+    fn from_db_model(model: QuestionBankModel) -> Self {
+        let options: Vec<QuestionOption> = if let Some(options_json) = model.options {
+            serde_json::from_str(&options_json).unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        let details = model
+            .details
+            .and_then(|details_json| serde_json::from_str(&details_json).ok());
+
+        Self {
+            id: model.id,
+            module_id: model.module_id,
+            session_id: model.session_id,
+            topic: model.topic,
+            content: model.content,
+            question: model.question,
+            r#type: FromDbModel::from_db_model(model.r#type),
+            options,
+            details,
+            solution: model.solution,
+            level: FromDbModel::from_db_model(model.level),
+            rubric: FromDbModel::from_db_model(model.rubric),
+            status: FromDbModel::from_db_model(model.status),
+            source_question_id: model.source_question_id,
+            created_by: model.created_by,
+            reviewed_by: model.reviewed_by,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+            reviewed_at: model.reviewed_at,
+        }
+    }
+}
+
+impl FromDbModel<QuestionBankStatusModel> for QuestionBankStatus {
+    fn from_db_model(model: QuestionBankStatusModel) -> Self {
+        match model {
+            QuestionBankStatusModel::Draft => QuestionBankStatus::Draft,
+            QuestionBankStatusModel::Approved => QuestionBankStatus::Approved,
+            QuestionBankStatusModel::Rejected => QuestionBankStatus::Rejected,
+        }
+    }
+}
+
+impl IntoDbModel<QuestionBankStatusModel> for QuestionBankStatus {
+    fn into_db_model(self) -> QuestionBankStatusModel {
+        match self {
+            QuestionBankStatus::Draft => QuestionBankStatusModel::Draft,
+            QuestionBankStatus::Approved => QuestionBankStatusModel::Approved,
+            QuestionBankStatus::Rejected => QuestionBankStatusModel::Rejected,
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use crate::module::instance::ModuleInstance;
+use crate::module::session::instance::SessionInstance;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Progress of a user in all modules and sessions they started
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UserProgress {
+    pub user_id: Uuid,
+    pub modules: Vec<ModuleInstance>,
+    pub sessions: Vec<SessionInstance>,
+}
+
+/// Tokens used by a single llm step
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct LlmStepUsage {
+    pub step: String,
+    pub tokens: u64,
+    pub requests: u64,
+    pub last_used_at: NaiveDateTime,
+}
+
+/// Llm usage of a user, most expensive steps first
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct LlmUsage {
+    pub user_id: Uuid,
+    pub total_tokens: u64,
+    pub steps: Vec<LlmStepUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GrantGroups {
+    pub groups: Vec<String>,
+}
+
+/// How often a group was assigned within one allocation sequence
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct GroupAllocation {
+    pub group: String,
+    pub allocated: u64,
+}
+
+/// State of the randomization of one random group assignment of an access token within one stratum
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AllocationReport {
+    pub token: String,
+    /// Index of the random group assignment within the groups of the token
+    pub slot: i32,
+    pub stratum: String,
+    pub block_size: i32,
+    /// Number of users allocated so far
+    pub allocated: u64,
+    /// Positions of the current block which are not allocated yet
+    pub remaining_in_block: u64,
+    pub groups: Vec<GroupAllocation>,
+    pub created_at: NaiveDateTime,
+}
@@ -1,2 +1,3 @@
+pub mod change;
 pub mod scales;
 pub mod session;
@@ -0,0 +1,63 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Change of a scale between the pre- and the post-assessment of a module
+#[derive(Serialize, ToSchema, Debug, Clone)]
+pub struct ScaleChange {
+    pub id: String,
+    pub title: String,
+    pub pre: f64,
+    pub post: f64,
+    /// Post value minus pre value
+    pub difference: f64,
+    /// Reliable change index, only available if the scale has a reliability and norms
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rci: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reliable_change: Option<ReliableChange>,
+}
+
+#[derive(Serialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReliableChange {
+    Increase,
+    Decrease,
+    Unchanged,
+}
+
+#[derive(Serialize, ToSchema, Debug, Clone)]
+pub struct ModuleAssessmentChange {
+    pub module: String,
+    pub scales: Vec<ScaleChange>,
+}
+
+/// Changes of a scale summarized over all users of a cohort who finished both assessments
+#[derive(Serialize, ToSchema, Debug, Clone)]
+pub struct CohortScaleChange {
+    pub id: String,
+    pub title: String,
+    pub participants: usize,
+    pub mean_pre: f64,
+    pub mean_post: f64,
+    pub mean_difference: f64,
+    /// Standard deviation of the differences, missing for less than two participants
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sd_difference: Option<f64>,
+    pub reliable_increase: usize,
+    pub reliable_decrease: usize,
+    pub reliably_unchanged: usize,
+}
+
+#[derive(Serialize, ToSchema, Debug, Clone)]
+pub struct CohortAssessmentChange {
+    pub module: String,
+    pub group: String,
+    /// Number of users in the group
+    pub users: usize,
+    /// Number of users who finished the pre- and post-assessment
+    pub participants: usize,
+    /// Number of users who finished both assessments but whose scales could not be calculated, e.g. because of
+    /// missing answers. They are not part of the participants.
+    pub skipped: usize,
+    pub scales: Vec<CohortScaleChange>,
+}
@@ -6,4 +6,31 @@ pub struct ItemValue {
     pub id: String,
     pub title: String,
     pub value: f64,
+    /// Number of unanswered items which were prorated from the answered ones
+    #[serde(skip_serializing_if = "is_zero")]
+    pub prorated: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub norm: Option<NormScore>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub band: Option<ScaleBand>,
+}
+
+/// Scores of a value relative to the norm sample of the scale
+#[derive(Serialize, ToSchema, Debug, Clone, Copy)]
+pub struct NormScore {
+    pub z: f64,
+    pub t: f64,
+    pub percentile: f64,
+}
+
+/// Interpretation band the value lies in
+#[derive(Serialize, ToSchema, Debug, Clone)]
+pub struct ScaleBand {
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feedback: Option<String>,
+}
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
 }
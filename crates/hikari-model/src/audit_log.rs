@@ -0,0 +1,17 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AuditLogEntry {
+    pub id: Uuid,
+    pub created_at: NaiveDateTime,
+    /// The user who performed the action, not set for system actions
+    pub actor_id: Option<Uuid>,
+    pub action: String,
+    /// The user whose data was accessed or changed
+    pub subject_id: Option<Uuid>,
+    pub request_id: Option<String>,
+    pub details: serde_json::Value,
+}
@@ -87,6 +87,24 @@ pub struct ButtonContent {
     pub payload: Option<String>,
 }
 
+/// An uploaded image, see `POST /api/v0/llm/images`
+#[derive(ToSchema, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct ImageContent {
+    pub image_id: Uuid,
+    /// Text sent together with the image
+    pub text: Option<String>,
+}
+
+/// A transcribed voice message, the recording is available at `GET /api/v0/llm/audio/{audio_id}`
+#[derive(ToSchema, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct AudioContent {
+    pub audio_id: Uuid,
+    /// The transcript
+    pub text: String,
+}
+
 #[derive(ToSchema, Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct TypingContent {
@@ -101,6 +119,8 @@ pub enum TypeSafePayload {
     Payload(PayloadContent),
     Button(ButtonContent),
     FlowTrigger(FlowTrigger),
+    Image(ImageContent),
+    Audio(AudioContent),
 }
 
 impl TypeSafePayload {
@@ -109,6 +129,8 @@ impl TypeSafePayload {
         match self {
             TypeSafePayload::Text(text) => Some(text.text),
             TypeSafePayload::Payload(payload) => Some(payload.payload),
+            TypeSafePayload::Image(image) => image.text,
+            TypeSafePayload::Audio(audio) => Some(audio.text),
             TypeSafePayload::Button(_) | TypeSafePayload::FlowTrigger(_) => None,
         }
     }
@@ -124,6 +146,8 @@ impl TryFrom<TypeSafePayload> for Payload {
             TypeSafePayload::Payload(inner) => Some(serde_json::to_value(inner)?),
             TypeSafePayload::Button(inner) => Some(serde_json::to_value(inner)?),
             TypeSafePayload::FlowTrigger(inner) => Some(serde_json::to_value(inner)?),
+            TypeSafePayload::Image(inner) => Some(serde_json::to_value(inner)?),
+            TypeSafePayload::Audio(inner) => Some(serde_json::to_value(inner)?),
         };
         Ok(Payload {
             content_type,
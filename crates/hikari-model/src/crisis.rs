@@ -0,0 +1,37 @@
+use chrono::NaiveDateTime;
+use hikari_config::global::crisis::{CrisisConfig, CrisisHotline};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Shown to the user if signals of acute distress were detected
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CrisisResources {
+    pub message: String,
+    pub hotlines: Vec<CrisisHotline>,
+}
+
+impl From<&CrisisConfig> for CrisisResources {
+    fn from(config: &CrisisConfig) -> Self {
+        Self {
+            message: config.message.clone(),
+            hotlines: config.hotlines.clone(),
+        }
+    }
+}
+
+/// A detected signal of acute distress, it never contains the text which triggered the detection
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CrisisEvent {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// `journal` or `chat`
+    pub source: String,
+    /// The journal entry, the journal content or the conversation
+    pub reference_id: Option<Uuid>,
+    /// `keyword` or `classifier`
+    pub detector: String,
+    pub created_at: NaiveDateTime,
+    /// Set once the counselor webhook accepted the notification
+    pub notified_at: Option<NaiveDateTime>,
+}
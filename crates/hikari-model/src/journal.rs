@@ -1,9 +1,11 @@
 pub mod content;
+pub mod insights;
 pub mod partial;
 
 use chrono::FixedOffset;
 use serde::{Deserialize, Serialize};
 
+use crate::crisis::CrisisResources;
 use crate::tag::Tag;
 use utoipa::ToSchema;
 use uuid::Uuid;
@@ -42,4 +44,7 @@ pub struct MetaJournalEntryWithMetaContent {
     pub mood: Option<f32>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub prompts: Vec<String>,
+    /// Set if the content contains signals of acute distress
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub crisis_resources: Option<CrisisResources>,
 }
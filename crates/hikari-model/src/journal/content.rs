@@ -1,3 +1,4 @@
+use crate::crisis::CrisisResources;
 use chrono::FixedOffset;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
@@ -16,4 +17,7 @@ pub struct JournalContent {
 #[derive(Debug, Serialize, ToSchema)]
 pub struct JournalContentId {
     pub id: Uuid,
+    /// Set if the content contains signals of acute distress
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crisis_resources: Option<CrisisResources>,
 }
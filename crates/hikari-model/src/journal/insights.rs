@@ -0,0 +1,76 @@
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::tag::Tag;
+
+/// Size of the periods the mood trend is grouped into
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum InsightsGranularity {
+    Day,
+    #[default]
+    Week,
+    Month,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct JournalInsights {
+    pub from: DateTime<FixedOffset>,
+    pub to: DateTime<FixedOffset>,
+    pub granularity: InsightsGranularity,
+    pub entry_count: u32,
+    pub mood: MoodInsights,
+    pub focus: Vec<FocusMoodCorrelation>,
+    pub topics: Vec<RecurringTopic>,
+    pub streaks: StreakInsights,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct MoodInsights {
+    /// Average mood of all entries with a mood in the window
+    pub average: Option<f32>,
+    /// Change of the mood per week according to a linear fit over all entries
+    pub slope_per_week: Option<f32>,
+    pub trend: Vec<MoodTrendPoint>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct MoodTrendPoint {
+    /// First day of the period in the requested time zone
+    pub period_start: NaiveDate,
+    /// Number of entries with a mood in this period
+    pub entries: u32,
+    pub average: Option<f32>,
+    /// Average over this and the preceding periods of the moving average window
+    pub moving_average: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FocusMoodCorrelation {
+    pub focus: Tag,
+    /// Number of entries with this focus and a mood
+    pub entries: u32,
+    pub average_mood_with: Option<f32>,
+    pub average_mood_without: Option<f32>,
+    /// Correlation between having this focus and the mood of an entry, from -1 to 1
+    pub correlation: Option<f32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RecurringTopic {
+    /// Name of the topic as it was last used
+    pub topic: String,
+    /// Number of summaries the topic appeared in
+    pub occurrences: u32,
+    pub first_seen: DateTime<FixedOffset>,
+    pub last_seen: DateTime<FixedOffset>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct StreakInsights {
+    /// Consecutive days with an entry up to today (or yesterday if there is no entry today yet)
+    pub current: u32,
+    pub longest: u32,
+    pub active_days: u32,
+}
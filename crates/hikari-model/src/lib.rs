@@ -1,5 +1,8 @@
+pub mod admin;
 pub mod assessment;
+pub mod audit_log;
 pub mod chat;
+pub mod crisis;
 pub mod history;
 pub mod journal;
 pub mod llm;
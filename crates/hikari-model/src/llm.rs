@@ -1,4 +1,8 @@
+pub mod audio;
 pub mod conversation;
+pub mod guardrail;
+pub mod image;
+pub mod learner_memory;
 pub mod message;
 pub mod slot;
 pub mod state;
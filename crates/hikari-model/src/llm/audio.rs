@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A voice message, it is transcribed before the agent answers
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AudioMessage {
+    /// e.g. `audio/webm` or `audio/mpeg`
+    pub mime_type: String,
+    /// The recording, base64 encoded
+    pub data: String,
+    /// ISO-639-1 code of the spoken language, detected if missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
@@ -0,0 +1,22 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GuardrailEvent {
+    pub id: Uuid,
+    pub conversation_id: Uuid,
+    pub user_id: Uuid,
+    pub step: String,
+    /// `input` for messages of the user, `output` for messages of the agent
+    pub direction: String,
+    pub categories: Vec<String>,
+    /// `block`, `replace`, `escalate` or `flag`
+    pub action: String,
+    /// The checked message
+    pub content: String,
+    pub created_at: NaiveDateTime,
+    pub reviewed_at: Option<NaiveDateTime>,
+    pub reviewed_by: Option<Uuid>,
+}
@@ -0,0 +1,21 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// An image to send in an agent conversation
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImageUpload {
+    /// One of `image/png`, `image/jpeg`, `image/webp` and `image/gif`
+    pub mime_type: String,
+    /// The image, base64 encoded
+    pub data: String,
+}
+
+/// A stored image, the id is referenced by image messages
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UploadedImage {
+    pub id: Uuid,
+    pub mime_type: String,
+    pub created_at: NaiveDateTime,
+}
@@ -0,0 +1,30 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A fact about the user which agents recall in later sessions
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LearnerMemory {
+    pub id: Uuid,
+    /// The module of the conversation the fact was learned in
+    pub module_id: String,
+    /// The session of the conversation the fact was learned in
+    pub session_id: String,
+    pub content: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// Whether the user allows agents to remember facts about them across sessions
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+pub struct LearnerMemoryConsent {
+    pub enabled: bool,
+}
+
+/// A memory found for a query, a lower distance means a more relevant memory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LearnerMemoryMatch {
+    pub content: String,
+    pub created_at: NaiveDateTime,
+    pub distance: f64,
+}
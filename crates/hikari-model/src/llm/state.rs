@@ -11,6 +11,9 @@ pub struct LlmConversationState {
 pub struct StateValue {
     #[serde(default)]
     pub response: Option<String>,
+    /// Error of the last failed execution of the step
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
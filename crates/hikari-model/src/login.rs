@@ -1,7 +1,23 @@
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct Token {
     pub access_token: String,
+    /// The token expires at this time (UTC) unless it is used before
+    #[serde(default)]
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+/// A device the user is logged in on
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Device {
+    pub id: i32,
+    pub name: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub last_used_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    /// Whether the request was made with the token of this device
+    pub current: bool,
 }
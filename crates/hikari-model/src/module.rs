@@ -1,25 +1,24 @@
+use crate::module::assessment::ModuleAssessmentFull;
 use crate::module::assessment::instance::ModuleAssessmentInstance;
 use crate::module::session::SessionFull;
-use crate::module::{assessment::ModuleAssessmentFull, session::instance::SessionInstance};
-use chrono::{DateTime, TimeZone, Utc};
+use crate::module::unlock::UnlockContext;
+use chrono::{DateTime, Utc};
 use hikari_config::module::Module;
 use hikari_config::module::assessment::ModuleAssessment;
-use hikari_config::module::unlock::{
-    LockedUntil, UnlockTrigger, UnlockTriggerMode, UnlockTriggerTimeFormat, UnlockTriggerWait,
-};
 use hikari_config::{
     generic::{Metadata, Theme},
-    module::{ModuleCategory, unlock::Unlock},
+    module::ModuleCategory,
 };
 
 use serde::Serialize;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 use utoipa::ToSchema;
 
 pub mod assessment;
 pub mod group;
 pub mod instance;
 pub mod session;
+pub mod unlock;
 
 #[derive(Serialize, ToSchema)]
 pub struct ModuleFull<'a> {
@@ -84,7 +83,7 @@ impl<'a> ModuleFull<'a> {
     pub fn from_config<'mg: 'a>(
         module: &'a Module,
         deep: bool,
-        module_entries: &'a [SessionInstance],
+        unlock_context: &UnlockContext,
         assessment: Option<&'a ModuleAssessmentInstance>,
         completion: Option<DateTime<Utc>>,
     ) -> Self {
@@ -92,7 +91,7 @@ impl<'a> ModuleFull<'a> {
             module
                 .sessions
                 .values()
-                .map(|s| SessionFull::from_config(s, &module.id, module_entries))
+                .map(|s| SessionFull::from_config(s, &module.id, unlock_context))
                 .collect()
         });
 
@@ -137,83 +136,3 @@ pub(crate) fn new_module_assessment<'a>(
         last_post,
     }
 }
-
-#[allow(clippy::unwrap_in_result)]
-pub fn locked_until(unlocked: &Unlock, module_session_entries: &[SessionInstance]) -> Option<LockedUntil> {
-    let entries: HashMap<&str, &SessionInstance> =
-        module_session_entries.iter().map(|e| (e.session.as_str(), e)).collect();
-
-    let locks = unlocked.triggers.iter().map(|trigger| match trigger {
-        UnlockTrigger::Time { after } => match after {
-            UnlockTriggerTimeFormat::Local(date) => LockedUntil::Time(Utc.from_utc_datetime(date)),
-            UnlockTriggerTimeFormat::Utc(date) => LockedUntil::Time(*date),
-        },
-        UnlockTrigger::Completion { after, wait } => {
-            // We only support one session for now and made sure that it has length 1 during validation
-            let after = after.first().cloned().unwrap_or(String::new());
-            if let Some(instance) = entries.get(after.as_str()).copied() {
-                if let Some(completion_time) = instance.completion {
-                    let completion_time = match wait {
-                        Some(UnlockTriggerWait::Days(days)) => {
-                            // We add the days and set hours, minutes, and seconds to zero
-                            let naive = completion_time.date_naive().and_hms_opt(0, 0, 0).expect("Invalid date")
-                                + chrono::Duration::days(i64::from(*days));
-                            Utc.from_utc_datetime(&naive)
-                        }
-                        Some(UnlockTriggerWait::Seconds(seconds)) => {
-                            completion_time + chrono::Duration::seconds(i64::from(*seconds))
-                        }
-                        None => completion_time,
-                    };
-                    LockedUntil::Time(completion_time)
-                } else {
-                    LockedUntil::Undefined
-                }
-            } else {
-                LockedUntil::Undefined // If the session status is not found, the session it not started yet
-            }
-        }
-    });
-
-    let future_locks: Vec<_> = locks
-        .filter(|lock| match lock {
-            LockedUntil::Time(time) => *time > Utc::now(),
-            LockedUntil::Undefined => true,
-        })
-        .collect();
-
-    if future_locks.is_empty() {
-        None
-    } else {
-        match unlocked.trigger_mode {
-            UnlockTriggerMode::Any => {
-                // Earliest unlock time among future ones (or Undefined)
-                future_locks
-                    .iter()
-                    .filter_map(|lock| match lock {
-                        LockedUntil::Time(time) => Some(*time),
-                        LockedUntil::Undefined => None,
-                    })
-                    .min()
-                    .map(LockedUntil::Time)
-                    .or(Some(LockedUntil::Undefined))
-            }
-            UnlockTriggerMode::All => {
-                // Latest unlock time, unless any are undefined
-                if future_locks.iter().any(|l| matches!(l, LockedUntil::Undefined)) {
-                    Some(LockedUntil::Undefined)
-                } else {
-                    future_locks
-                        .iter()
-                        .filter_map(|lock| match lock {
-                            LockedUntil::Time(time) => Some(*time),
-                            #[allow(unreachable_code)]
-                            LockedUntil::Undefined => None,
-                        })
-                        .max()
-                        .map(LockedUntil::Time)
-                }
-            }
-        }
-    }
-}
@@ -1,8 +1,8 @@
 use std::collections::HashSet;
 
 use crate::module::{
-    locked_until,
-    session::instance::{SessionInstance, SessionInstanceStatus},
+    session::instance::SessionInstanceStatus,
+    unlock::{UnlockContext, locked_until},
 };
 use chrono::{DateTime, Utc};
 use hikari_config::{
@@ -110,14 +110,8 @@ pub struct SessionFull<'a> {
 
 impl<'a> SessionFull<'a> {
     #[must_use]
-    pub fn from_config(session: &'a Session, module_id: &'a str, entries: &'a [SessionInstance]) -> Self {
-        let module_session_entries = entries
-            .iter()
-            .filter(|e| e.module == module_id)
-            .cloned()
-            .collect::<Vec<SessionInstance>>();
-
-        let current_entry = module_session_entries.iter().find(|e| e.session == session.id);
+    pub fn from_config(session: &'a Session, module_id: &'a str, unlock_context: &UnlockContext) -> Self {
+        let current_entry = unlock_context.session(module_id, &session.id);
 
         let (status, completion) =
             current_entry.map_or((SessionInstanceStatus::NotStarted, None), |m| (m.status, m.completion));
@@ -129,7 +123,7 @@ impl<'a> SessionFull<'a> {
             .filter(|c| {
                 c.unlock
                     .as_ref()
-                    .is_none_or(|unlock| locked_until(unlock, &module_session_entries).is_none())
+                    .is_none_or(|unlock| locked_until(unlock, module_id, unlock_context).is_none())
             })
             .collect::<Vec<&Content>>();
 
@@ -139,7 +133,7 @@ impl<'a> SessionFull<'a> {
         let locked_until = session
             .unlock
             .as_ref()
-            .and_then(|unlock| locked_until(unlock, &module_session_entries));
+            .and_then(|unlock| locked_until(unlock, module_id, unlock_context));
 
         SessionFull {
             id: &session.id,
@@ -0,0 +1,293 @@
+use crate::module::session::instance::SessionInstance;
+use chrono::{DateTime, TimeZone, Utc};
+use hikari_config::module::unlock::{
+    LockedUntil, Unlock, UnlockTrigger, UnlockTriggerMode, UnlockTriggerTimeFormat, UnlockTriggerWait,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Everything about a user the unlock triggers are evaluated against
+#[derive(Debug, Clone, Default)]
+pub struct UnlockContext {
+    /// Session instances of all modules
+    pub sessions: Vec<SessionInstance>,
+    pub groups: HashSet<String>,
+    /// Scale values of the last finished pre-assessment by module and scale id
+    pub pre_assessment_scales: HashMap<String, HashMap<String, f64>>,
+    /// Best quiz score by module and topic
+    pub quiz_scores: HashMap<String, HashMap<String, f64>>,
+}
+
+impl UnlockContext {
+    #[must_use]
+    pub fn from_sessions(sessions: Vec<SessionInstance>) -> Self {
+        Self {
+            sessions,
+            ..Default::default()
+        }
+    }
+
+    #[must_use]
+    pub fn session(&self, module_id: &str, session_id: &str) -> Option<&SessionInstance> {
+        self.sessions
+            .iter()
+            .find(|instance| instance.module == module_id && instance.session == session_id)
+    }
+}
+
+/// Returns when the content guarded by `unlock` becomes available, or `None` if it is available already.
+///
+/// `module_id` is the module of the guarded content. Triggers without an explicit module refer to it.
+#[must_use]
+pub fn locked_until(unlock: &Unlock, module_id: &str, context: &UnlockContext) -> Option<LockedUntil> {
+    let locks = unlock
+        .triggers
+        .iter()
+        .map(|trigger| trigger_locked_until(trigger, module_id, context));
+    match unlock.trigger_mode {
+        UnlockTriggerMode::All => all(locks),
+        UnlockTriggerMode::Any => any(locks),
+    }
+}
+
+fn trigger_locked_until(trigger: &UnlockTrigger, module_id: &str, context: &UnlockContext) -> Option<LockedUntil> {
+    match trigger {
+        UnlockTrigger::Time { after } => match after {
+            UnlockTriggerTimeFormat::Local(date) => until(Utc.from_utc_datetime(date)),
+            UnlockTriggerTimeFormat::Utc(date) => until(*date),
+        },
+        UnlockTrigger::Completion { after, module, wait } => {
+            let module_id = module.as_deref().unwrap_or(module_id);
+            all(after.iter().map(|session_id| {
+                // If the session status is not found, the session is not started yet
+                let Some(completion_time) = context
+                    .session(module_id, session_id)
+                    .and_then(|instance| instance.completion)
+                else {
+                    return Some(LockedUntil::Undefined);
+                };
+                let completion_time = match wait {
+                    Some(UnlockTriggerWait::Days(days)) => {
+                        // We add the days and set hours, minutes, and seconds to zero
+                        let naive = completion_time.date_naive().and_hms_opt(0, 0, 0).expect("Invalid date")
+                            + chrono::Duration::days(i64::from(*days));
+                        Utc.from_utc_datetime(&naive)
+                    }
+                    Some(UnlockTriggerWait::Seconds(seconds)) => {
+                        completion_time + chrono::Duration::seconds(i64::from(*seconds))
+                    }
+                    None => completion_time,
+                };
+                until(completion_time)
+            }))
+        }
+        UnlockTrigger::PreAssessment {
+            module,
+            scale,
+            min,
+            max,
+        } => {
+            let value = context
+                .pre_assessment_scales
+                .get(module.as_deref().unwrap_or(module_id))
+                .and_then(|scales| scales.get(scale));
+            let unlocked =
+                value.is_some_and(|value| min.is_none_or(|min| *value >= min) && max.is_none_or(|max| *value <= max));
+            (!unlocked).then_some(LockedUntil::Undefined)
+        }
+        UnlockTrigger::Quiz {
+            module,
+            topic,
+            min_score,
+        } => {
+            let score = context
+                .quiz_scores
+                .get(module.as_deref().unwrap_or(module_id))
+                .and_then(|scores| scores.get(topic));
+            let unlocked = score.is_some_and(|score| score >= min_score);
+            (!unlocked).then_some(LockedUntil::Undefined)
+        }
+        UnlockTrigger::Group { group } => (!context.groups.contains(group)).then_some(LockedUntil::Undefined),
+        UnlockTrigger::All(triggers) => all(triggers
+            .iter()
+            .map(|trigger| trigger_locked_until(trigger, module_id, context))),
+        UnlockTrigger::Any(triggers) => any(triggers
+            .iter()
+            .map(|trigger| trigger_locked_until(trigger, module_id, context))),
+    }
+}
+
+fn until(time: DateTime<Utc>) -> Option<LockedUntil> {
+    (time > Utc::now()).then_some(LockedUntil::Time(time))
+}
+
+/// Latest unlock time, unless any are undefined
+fn all(locks: impl Iterator<Item = Option<LockedUntil>>) -> Option<LockedUntil> {
+    locks.flatten().reduce(|latest, lock| match (latest, lock) {
+        (LockedUntil::Time(latest), LockedUntil::Time(time)) => LockedUntil::Time(latest.max(time)),
+        _ => LockedUntil::Undefined,
+    })
+}
+
+/// Earliest unlock time among future ones (or Undefined), unless any are unlocked already
+fn any(locks: impl Iterator<Item = Option<LockedUntil>>) -> Option<LockedUntil> {
+    let mut earliest: Option<DateTime<Utc>> = None;
+    let mut locked = false;
+    for lock in locks {
+        match lock {
+            None => return None,
+            Some(LockedUntil::Time(time)) => earliest = Some(earliest.map_or(time, |earliest| earliest.min(time))),
+            Some(LockedUntil::Undefined) => {}
+        }
+        locked = true;
+    }
+    if !locked {
+        return None;
+    }
+    Some(earliest.map_or(LockedUntil::Undefined, LockedUntil::Time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::module::session::instance::SessionInstanceStatus;
+    use chrono::Duration;
+
+    fn finished(module: &str, session: &str, completion: DateTime<Utc>) -> SessionInstance {
+        SessionInstance {
+            module: module.to_owned(),
+            session: session.to_owned(),
+            status: SessionInstanceStatus::Finished,
+            completion: Some(completion),
+            ..Default::default()
+        }
+    }
+
+    fn unlock(triggers: Vec<UnlockTrigger>, trigger_mode: UnlockTriggerMode) -> Unlock {
+        Unlock { triggers, trigger_mode }
+    }
+
+    fn completion(module: Option<&str>, after: &[&str]) -> UnlockTrigger {
+        UnlockTrigger::Completion {
+            after: after.iter().map(|&session| session.to_owned()).collect(),
+            module: module.map(ToOwned::to_owned),
+            wait: None,
+        }
+    }
+
+    #[test]
+    fn test_completion_of_multiple_sessions_in_other_module() {
+        let yesterday = Utc::now() - Duration::days(1);
+        let context = UnlockContext::from_sessions(vec![finished("other", "a", yesterday)]);
+
+        let trigger = unlock(vec![completion(Some("other"), &["a", "b"])], UnlockTriggerMode::All);
+        assert!(matches!(
+            locked_until(&trigger, "module", &context),
+            Some(LockedUntil::Undefined)
+        ));
+        // Without an explicit module the session is looked up in the module of the content
+        let trigger = unlock(vec![completion(None, &["a"])], UnlockTriggerMode::All);
+        assert!(locked_until(&trigger, "module", &context).is_some());
+
+        let context = UnlockContext::from_sessions(vec![
+            finished("other", "a", yesterday),
+            finished("other", "b", yesterday),
+        ]);
+        let trigger = unlock(vec![completion(Some("other"), &["a", "b"])], UnlockTriggerMode::All);
+        assert!(locked_until(&trigger, "module", &context).is_none());
+    }
+
+    #[test]
+    fn test_completion_wait() {
+        let now = Utc::now();
+        let context = UnlockContext::from_sessions(vec![finished("module", "a", now)]);
+        let trigger = unlock(
+            vec![UnlockTrigger::Completion {
+                after: vec!["a".to_owned()],
+                module: None,
+                wait: Some(UnlockTriggerWait::Seconds(60)),
+            }],
+            UnlockTriggerMode::All,
+        );
+        let Some(LockedUntil::Time(time)) = locked_until(&trigger, "module", &context) else {
+            panic!("expected a time lock");
+        };
+        assert_eq!(time, now + Duration::seconds(60));
+    }
+
+    #[test]
+    fn test_assessment_quiz_and_group_triggers() {
+        let mut context = UnlockContext::default();
+        context
+            .pre_assessment_scales
+            .insert("module".to_owned(), HashMap::from([("motivation".to_owned(), 3.5)]));
+        context
+            .quiz_scores
+            .insert("other".to_owned(), HashMap::from([("basics".to_owned(), 0.5)]));
+        context.groups.insert("pilot".to_owned());
+
+        let scale = |min, max| UnlockTrigger::PreAssessment {
+            module: None,
+            scale: "motivation".to_owned(),
+            min,
+            max,
+        };
+        let quiz = |min_score| UnlockTrigger::Quiz {
+            module: Some("other".to_owned()),
+            topic: "basics".to_owned(),
+            min_score,
+        };
+        let group = |group: &str| UnlockTrigger::Group {
+            group: group.to_owned(),
+        };
+
+        let is_unlocked =
+            |trigger| locked_until(&unlock(vec![trigger], UnlockTriggerMode::All), "module", &context).is_none();
+        assert!(is_unlocked(scale(Some(3.0), None)));
+        assert!(is_unlocked(scale(Some(3.5), Some(3.5))));
+        assert!(!is_unlocked(scale(None, Some(3.0))));
+        assert!(is_unlocked(quiz(0.5)));
+        assert!(!is_unlocked(quiz(0.8)));
+        assert!(is_unlocked(group("pilot")));
+        assert!(!is_unlocked(group("control")));
+    }
+
+    #[test]
+    fn test_nested_triggers() {
+        let mut context = UnlockContext::default();
+        context.groups.insert("pilot".to_owned());
+        let tomorrow = Utc::now() + Duration::days(1);
+        let in_two_days = Utc::now() + Duration::days(2);
+        let time = |time| UnlockTrigger::Time {
+            after: UnlockTriggerTimeFormat::Utc(time),
+        };
+        let group = |group: &str| UnlockTrigger::Group {
+            group: group.to_owned(),
+        };
+
+        let nested = unlock(
+            vec![UnlockTrigger::Any(vec![
+                group("control"),
+                UnlockTrigger::All(vec![group("pilot"), time(tomorrow)]),
+            ])],
+            UnlockTriggerMode::All,
+        );
+        assert!(matches!(
+            locked_until(&nested, "module", &context),
+            Some(LockedUntil::Time(time)) if time == tomorrow
+        ));
+
+        let any = unlock(vec![time(in_two_days), time(tomorrow)], UnlockTriggerMode::Any);
+        assert!(matches!(
+            locked_until(&any, "module", &context),
+            Some(LockedUntil::Time(time)) if time == tomorrow
+        ));
+        let all = unlock(vec![time(in_two_days), time(tomorrow)], UnlockTriggerMode::All);
+        assert!(matches!(
+            locked_until(&all, "module", &context),
+            Some(LockedUntil::Time(time)) if time == in_two_days
+        ));
+
+        let any = unlock(vec![group("control"), group("pilot")], UnlockTriggerMode::Any);
+        assert!(locked_until(&any, "module", &context).is_none());
+    }
+}
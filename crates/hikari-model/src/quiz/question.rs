@@ -18,20 +18,136 @@ pub enum QuestionStatus {
     Skipped,
 }
 
-#[derive(Deserialize, Serialize, ToSchema)]
+#[derive(Deserialize, Serialize, ToSchema, Clone, Copy, PartialEq, Eq, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum QuestionType {
     Text,
     MultipleChoice,
+    Cloze,
+    Ordering,
+    Matching,
+    Numeric,
 }
 
-#[derive(Deserialize, Serialize, ToSchema, Clone)]
+/// Rule which was used to grade the answer of a question.
+#[derive(Deserialize, Serialize, ToSchema, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestionRubric {
+    /// The answer is graded by the LLM
+    Llm,
+    AllOrNothing,
+    PartialCredit,
+}
+
+/// Marker for a gap in the question text of a cloze question.
+pub const CLOZE_GAP_MARKER: &str = "{{gap}}";
+
+#[derive(Deserialize, Serialize, ToSchema, Clone, Debug, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub struct QuestionOption {
     pub option: String,
     pub correct: Option<bool>,
 }
 
+/// Type specific data of the closed question formats.
+///
+/// Solutions are only sent to the client after the question was graded.
+#[derive(Deserialize, Serialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestionDetails {
+    Cloze {
+        /// Number of gaps in the question text
+        gap_count: usize,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        solutions: Option<Vec<String>>,
+    },
+    Ordering {
+        /// Items in the order they are shown to the user
+        items: Vec<String>,
+        /// Indices into `items` in the correct order
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        solution: Option<Vec<usize>>,
+    },
+    Matching {
+        left: Vec<String>,
+        right: Vec<String>,
+        /// Index into `right` for every entry of `left`
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        solution: Option<Vec<usize>>,
+    },
+    Numeric {
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        unit: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        value: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        tolerance: Option<f64>,
+    },
+}
+
+impl QuestionDetails {
+    fn remove_solution(&mut self) {
+        match self {
+            QuestionDetails::Cloze { solutions, .. } => *solutions = None,
+            QuestionDetails::Ordering { solution, .. } | QuestionDetails::Matching { solution, .. } => {
+                *solution = None;
+            }
+            QuestionDetails::Numeric { value, tolerance, .. } => {
+                *value = None;
+                *tolerance = None;
+            }
+        }
+    }
+}
+
+/// Answer of a user to a question.
+///
+/// Text, multiple choice and numeric questions are answered with a plain string,
+/// cloze questions with one string per gap, ordering questions with the indices of the
+/// shown items in the chosen order and matching questions with the chosen index into
+/// `right` for every entry of `left`. Empty lists are rejected.
+#[derive(Deserialize, Serialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(untagged, try_from = "UncheckedQuestionAnswer")]
+pub enum QuestionAnswer {
+    Text(String),
+    Indices(Vec<usize>),
+    Texts(Vec<String>),
+}
+
+/// [`QuestionAnswer`] as it is sent by the client, before it is checked
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum UncheckedQuestionAnswer {
+    Text(String),
+    Indices(Vec<usize>),
+    Texts(Vec<String>),
+}
+
+impl TryFrom<UncheckedQuestionAnswer> for QuestionAnswer {
+    type Error = &'static str;
+
+    fn try_from(answer: UncheckedQuestionAnswer) -> Result<Self, Self::Error> {
+        match answer {
+            UncheckedQuestionAnswer::Text(text) => Ok(QuestionAnswer::Text(text)),
+            UncheckedQuestionAnswer::Indices(indices) if indices.is_empty() => Err("the answer must not be empty"),
+            UncheckedQuestionAnswer::Indices(indices) => Ok(QuestionAnswer::Indices(indices)),
+            UncheckedQuestionAnswer::Texts(texts) if texts.is_empty() => Err("the answer must not be empty"),
+            UncheckedQuestionAnswer::Texts(texts) => Ok(QuestionAnswer::Texts(texts)),
+        }
+    }
+}
+
+impl QuestionAnswer {
+    /// String representation which is stored alongside the question.
+    #[must_use]
+    pub fn to_answer_string(&self) -> String {
+        match self {
+            QuestionAnswer::Text(text) => text.clone(),
+            QuestionAnswer::Indices(_) | QuestionAnswer::Texts(_) => serde_json::to_string(self).unwrap_or_default(),
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct Question {
@@ -44,6 +160,8 @@ pub struct Question {
     pub r#type: QuestionType,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub options: Vec<QuestionOption>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub details: Option<QuestionDetails>,
     pub level: QuestionBloomLevel,
     pub created_at: chrono::NaiveDateTime,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -56,6 +174,9 @@ pub struct Question {
     pub grade: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ai_solution: Option<String>,
+    pub rubric: QuestionRubric,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<String>,
     pub status: QuestionStatus,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub feedback: Option<QuestionFeedback>,
@@ -72,6 +193,31 @@ impl Question {
             for option in &mut self.options {
                 option.correct = None;
             }
+            if let Some(details) = &mut self.details {
+                details.remove_solution();
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_question_answer() {
+        assert_eq!(
+            serde_json::from_str::<QuestionAnswer>("[2, 0]").unwrap(),
+            QuestionAnswer::Indices(vec![2, 0])
+        );
+        assert_eq!(
+            serde_json::from_str::<QuestionAnswer>(r#"["a"]"#).unwrap(),
+            QuestionAnswer::Texts(vec!["a".to_string()])
+        );
+        assert_eq!(
+            serde_json::from_str::<QuestionAnswer>(r#""A, B""#).unwrap(),
+            QuestionAnswer::Text("A, B".to_string())
+        );
+        assert!(serde_json::from_str::<QuestionAnswer>("[]").is_err());
+    }
+}
@@ -0,0 +1,75 @@
+use crate::quiz::question::{QuestionDetails, QuestionOption, QuestionRubric, QuestionType};
+use hikari_config::module::content::{ContentExam, QuestionBloomLevel};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Review state of a question in the question bank.
+///
+/// Only approved questions are served to students.
+#[derive(Deserialize, Serialize, ToSchema, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestionBankStatus {
+    Draft,
+    Approved,
+    Rejected,
+}
+
+/// Curated question which can be served to every student of a module.
+///
+/// Closed question formats are stored with their items in the correct order,
+/// they are shuffled again whenever the question is served.
+#[derive(Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct QuestionBankEntry {
+    pub id: Uuid,
+    pub module_id: String,
+    pub session_id: String,
+    pub topic: String,
+    pub content: String,
+    pub question: String,
+    pub r#type: QuestionType,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub options: Vec<QuestionOption>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub details: Option<QuestionDetails>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub solution: Option<String>,
+    pub level: QuestionBloomLevel,
+    pub rubric: QuestionRubric,
+    pub status: QuestionBankStatus,
+    /// Generated question this entry was promoted from
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub source_question_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub created_by: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub reviewed_by: Option<Uuid>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub reviewed_at: Option<chrono::NaiveDateTime>,
+}
+
+/// Question bank of a module in the format used for YAML import and export.
+///
+/// The questions use the same format as the `exams` of a module content.
+#[derive(Deserialize, Serialize, ToSchema, Clone, Debug)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct QuestionBankDocument {
+    pub module: String,
+    #[serde(default)]
+    pub questions: Vec<QuestionBankDocumentEntry>,
+}
+
+#[derive(Deserialize, Serialize, ToSchema, Clone, Debug)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct QuestionBankDocumentEntry {
+    pub session: String,
+    /// Title of the content the question belongs to
+    pub topic: String,
+    /// Learning content which is used to find sources when grading, defaults to the question text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    pub exam: ContentExam,
+}
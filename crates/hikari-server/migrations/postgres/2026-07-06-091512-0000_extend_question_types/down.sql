@@ -0,0 +1,13 @@
+ALTER TABLE question DROP COLUMN details;
+
+DELETE FROM question WHERE type NOT IN ('text', 'multiplechoice');
+
+ALTER TYPE question_type_enum RENAME TO question_type_enum_old;
+CREATE TYPE question_type_enum AS ENUM ('text', 'multiplechoice');
+
+ALTER TABLE question
+ALTER COLUMN type DROP DEFAULT,
+ALTER COLUMN type TYPE question_type_enum USING type::text::question_type_enum,
+ALTER COLUMN type SET DEFAULT 'text';
+
+DROP TYPE question_type_enum_old;
@@ -0,0 +1,6 @@
+ALTER TYPE question_type_enum ADD VALUE 'cloze';
+ALTER TYPE question_type_enum ADD VALUE 'ordering';
+ALTER TYPE question_type_enum ADD VALUE 'matching';
+ALTER TYPE question_type_enum ADD VALUE 'numeric';
+
+ALTER TABLE question ADD COLUMN details TEXT;
@@ -0,0 +1,3 @@
+ALTER TABLE question DROP COLUMN rubric, DROP COLUMN explanation;
+
+DROP TYPE question_rubric_enum;
@@ -0,0 +1,7 @@
+CREATE TYPE question_rubric_enum AS ENUM ('llm', 'all_or_nothing', 'partial_credit');
+
+ALTER TABLE question
+ADD COLUMN rubric question_rubric_enum NOT NULL DEFAULT 'llm',
+ADD COLUMN explanation TEXT;
+
+UPDATE question SET rubric = 'partial_credit' WHERE type IN ('cloze', 'ordering', 'matching', 'numeric');
@@ -0,0 +1,5 @@
+ALTER TABLE question DROP COLUMN bank_question_id;
+
+DROP TABLE question_bank;
+
+DROP TYPE question_bank_status_enum;
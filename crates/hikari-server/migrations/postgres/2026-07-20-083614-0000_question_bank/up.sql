@@ -0,0 +1,32 @@
+CREATE TYPE question_bank_status_enum AS ENUM ('draft', 'approved', 'rejected');
+
+CREATE TABLE question_bank (
+    id UUID PRIMARY KEY,
+    module_id TEXT NOT NULL,
+    session_id TEXT NOT NULL,
+    topic TEXT NOT NULL,
+    content TEXT NOT NULL,
+    question TEXT NOT NULL,
+    type question_type_enum NOT NULL,
+    options TEXT,
+    details TEXT,
+    solution TEXT,
+    level question_bloom_level_enum NOT NULL,
+    rubric question_rubric_enum NOT NULL DEFAULT 'llm',
+    status question_bank_status_enum NOT NULL DEFAULT 'draft',
+    source_question_id UUID,
+    created_by UUID,
+    reviewed_by UUID,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now(),
+    reviewed_at TIMESTAMP,
+    FOREIGN KEY (source_question_id) REFERENCES question (id) ON DELETE SET NULL,
+    FOREIGN KEY (created_by) REFERENCES users (id) ON DELETE SET NULL,
+    FOREIGN KEY (reviewed_by) REFERENCES users (id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_question_bank_module_id ON question_bank (module_id);
+CREATE INDEX idx_question_bank_lookup ON question_bank (module_id, session_id, topic, level, status);
+
+ALTER TABLE question
+ADD COLUMN bank_question_id UUID REFERENCES question_bank (id) ON DELETE SET NULL;
@@ -0,0 +1,3 @@
+DROP TABLE journal_summary_job;
+
+DROP TYPE journal_summary_job_status_enum;
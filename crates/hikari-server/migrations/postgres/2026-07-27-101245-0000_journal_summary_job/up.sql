@@ -0,0 +1,23 @@
+CREATE TYPE journal_summary_job_status_enum AS ENUM ('pending', 'running', 'completed', 'failed');
+
+CREATE TABLE journal_summary_job (
+    id UUID PRIMARY KEY NOT NULL,
+    user_id UUID NOT NULL,
+    key BYTEA NOT NULL,
+    date TIMESTAMP NOT NULL,
+    timestamp TIMESTAMP NOT NULL,
+    utc_offset INTEGER NOT NULL DEFAULT 0,
+    status journal_summary_job_status_enum NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    lease_owner TEXT,
+    lease_expires_at TIMESTAMP,
+    summary_id UUID,
+    error TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now(),
+    UNIQUE (user_id, key, date),
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (summary_id) REFERENCES journal_summary (id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_journal_summary_job_status ON journal_summary_job (status, lease_expires_at);
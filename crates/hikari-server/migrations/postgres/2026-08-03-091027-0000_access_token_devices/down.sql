@@ -0,0 +1,14 @@
+-- Hashed tokens can not be restored, so every user has to log in again.
+DELETE FROM access_tokens;
+
+DROP INDEX access_tokens_user_id;
+DROP INDEX access_tokens_token_hash;
+ALTER TABLE access_tokens DROP COLUMN expires_at;
+ALTER TABLE access_tokens DROP COLUMN last_used_at;
+ALTER TABLE access_tokens DROP COLUMN created_at;
+ALTER TABLE access_tokens DROP COLUMN device_name;
+ALTER TABLE access_tokens DROP COLUMN token_hash;
+
+ALTER TABLE access_tokens ADD COLUMN access_token varchar(255) NOT NULL;
+CREATE INDEX index_access_tokens ON access_tokens (access_token);
+ALTER TABLE access_tokens ADD CONSTRAINT unique_user_id UNIQUE (user_id);
@@ -0,0 +1,16 @@
+-- Tokens are only stored as SHA-256 hash. Existing tokens are hashed in place so users stay logged in.
+ALTER TABLE access_tokens DROP CONSTRAINT unique_user_id;
+ALTER TABLE access_tokens ADD COLUMN token_hash BYTEA;
+UPDATE access_tokens SET token_hash = sha256(convert_to(access_token, 'UTF8'));
+ALTER TABLE access_tokens ALTER COLUMN token_hash SET NOT NULL;
+DROP INDEX index_access_tokens;
+ALTER TABLE access_tokens DROP COLUMN access_token;
+
+ALTER TABLE access_tokens ADD COLUMN device_name TEXT;
+ALTER TABLE access_tokens ADD COLUMN created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP;
+ALTER TABLE access_tokens ADD COLUMN last_used_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP;
+ALTER TABLE access_tokens ADD COLUMN expires_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP + INTERVAL '30 days';
+ALTER TABLE access_tokens ALTER COLUMN expires_at DROP DEFAULT;
+
+CREATE UNIQUE INDEX access_tokens_token_hash ON access_tokens (token_hash);
+CREATE INDEX access_tokens_user_id ON access_tokens (user_id);
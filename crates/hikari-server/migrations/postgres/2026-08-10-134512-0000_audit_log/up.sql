@@ -0,0 +1,29 @@
+-- Actor and subject are not foreign keys so entries outlive deleted users
+CREATE TABLE audit_log (
+    id UUID PRIMARY KEY NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    actor_id UUID,
+    action TEXT NOT NULL,
+    subject_id UUID,
+    request_id TEXT,
+    details JSONB NOT NULL DEFAULT '{}'
+);
+
+CREATE INDEX idx_audit_log_created_at ON audit_log (created_at DESC);
+CREATE INDEX idx_audit_log_actor_id ON audit_log (actor_id, created_at DESC);
+CREATE INDEX idx_audit_log_subject_id ON audit_log (subject_id, created_at DESC);
+
+-- The audit log is append-only
+CREATE FUNCTION audit_log_prevent_modification() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_log_append_only
+    BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION audit_log_prevent_modification();
+
+CREATE TRIGGER audit_log_no_truncate
+    BEFORE TRUNCATE ON audit_log
+    FOR EACH STATEMENT EXECUTE FUNCTION audit_log_prevent_modification();
@@ -0,0 +1,3 @@
+DROP TABLE export_job;
+
+DROP TYPE export_job_status_enum;
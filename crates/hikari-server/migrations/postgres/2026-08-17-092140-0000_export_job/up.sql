@@ -0,0 +1,20 @@
+CREATE TYPE export_job_status_enum AS ENUM ('pending', 'running', 'completed', 'failed');
+
+CREATE TABLE export_job (
+    id UUID PRIMARY KEY NOT NULL,
+    user_id UUID NOT NULL,
+    status export_job_status_enum NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    lease_owner TEXT,
+    lease_expires_at TIMESTAMP,
+    archive_key TEXT,
+    error TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now(),
+    expires_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_export_job_user_id ON export_job (user_id);
+CREATE INDEX idx_export_job_expires_at ON export_job (expires_at);
+CREATE INDEX idx_export_job_status ON export_job (status, lease_expires_at);
@@ -0,0 +1,23 @@
+CREATE TABLE allocation_sequence (
+    id UUID PRIMARY KEY NOT NULL,
+    token TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    stratum TEXT NOT NULL,
+    seed BIGINT NOT NULL,
+    block_size INTEGER NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    UNIQUE (token, slot, stratum)
+);
+
+CREATE TABLE allocation (
+    sequence_id UUID NOT NULL,
+    position INTEGER NOT NULL,
+    group_name TEXT NOT NULL,
+    user_id UUID,
+    allocated_at TIMESTAMP,
+    PRIMARY KEY (sequence_id, position),
+    FOREIGN KEY (sequence_id) REFERENCES allocation_sequence (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_allocation_user_id ON allocation (user_id);
@@ -0,0 +1,10 @@
+CREATE TABLE llm_memory_summary (
+    conversation_id UUID NOT NULL,
+    scope TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    covered_until INTEGER NOT NULL,
+    tokens INTEGER NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT now(),
+    PRIMARY KEY (conversation_id, scope),
+    FOREIGN KEY (conversation_id) REFERENCES llm_conversation (conversation_id) ON DELETE CASCADE
+);
@@ -0,0 +1,16 @@
+CREATE EXTENSION IF NOT EXISTS vector;
+
+CREATE TABLE llm_learner_memory (
+    id UUID PRIMARY KEY NOT NULL,
+    user_id UUID NOT NULL,
+    module_id TEXT NOT NULL,
+    session_id TEXT NOT NULL,
+    conversation_id UUID,
+    content TEXT NOT NULL,
+    embedding VECTOR (4096) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (conversation_id) REFERENCES llm_conversation (conversation_id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_llm_learner_memory_user_id ON llm_learner_memory (user_id);
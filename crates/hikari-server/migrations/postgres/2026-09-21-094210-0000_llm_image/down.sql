@@ -0,0 +1,13 @@
+DROP INDEX idx_llm_image_user_id;
+
+DROP TABLE llm_image;
+
+DELETE FROM llm_message WHERE content_type = 'image';
+
+ALTER TYPE content_type_enum RENAME TO content_type_enum_old;
+CREATE TYPE content_type_enum AS ENUM ('text', 'payload', 'buttons');
+
+ALTER TABLE llm_message
+ALTER COLUMN content_type TYPE content_type_enum USING content_type::text::content_type_enum;
+
+DROP TYPE content_type_enum_old;
@@ -0,0 +1,12 @@
+ALTER TYPE content_type_enum ADD VALUE 'image';
+
+CREATE TABLE llm_image (
+    id UUID PRIMARY KEY NOT NULL,
+    user_id UUID NOT NULL,
+    mime_type TEXT NOT NULL,
+    data BYTEA NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_llm_image_user_id ON llm_image (user_id);
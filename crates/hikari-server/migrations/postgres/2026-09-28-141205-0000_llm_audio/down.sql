@@ -0,0 +1,14 @@
+DROP INDEX idx_llm_audio_conversation_id;
+DROP INDEX idx_llm_audio_user_id;
+
+DROP TABLE llm_audio;
+
+DELETE FROM llm_message WHERE content_type = 'audio';
+
+ALTER TYPE content_type_enum RENAME TO content_type_enum_old;
+CREATE TYPE content_type_enum AS ENUM ('text', 'payload', 'buttons', 'image');
+
+ALTER TABLE llm_message
+ALTER COLUMN content_type TYPE content_type_enum USING content_type::text::content_type_enum;
+
+DROP TYPE content_type_enum_old;
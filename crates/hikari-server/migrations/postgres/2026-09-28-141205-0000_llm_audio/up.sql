@@ -0,0 +1,17 @@
+ALTER TYPE content_type_enum ADD VALUE 'audio';
+
+CREATE TABLE llm_audio (
+    id UUID PRIMARY KEY NOT NULL,
+    user_id UUID NOT NULL,
+    conversation_id UUID,
+    message_order INTEGER,
+    mime_type TEXT NOT NULL,
+    data BYTEA NOT NULL,
+    transcript TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (conversation_id) REFERENCES llm_conversation (conversation_id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_llm_audio_user_id ON llm_audio (user_id);
+CREATE INDEX idx_llm_audio_conversation_id ON llm_audio (conversation_id, message_order);
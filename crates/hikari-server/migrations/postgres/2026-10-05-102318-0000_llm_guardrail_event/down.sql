@@ -0,0 +1,4 @@
+DROP INDEX idx_llm_guardrail_event_created_at;
+DROP INDEX idx_llm_guardrail_event_conversation_id;
+
+DROP TABLE llm_guardrail_event;
@@ -0,0 +1,19 @@
+CREATE TABLE llm_guardrail_event (
+    id UUID PRIMARY KEY NOT NULL,
+    conversation_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    step TEXT NOT NULL,
+    direction TEXT NOT NULL,
+    categories TEXT NOT NULL,
+    action TEXT NOT NULL,
+    content TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    reviewed_at TIMESTAMP,
+    reviewed_by UUID,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (conversation_id) REFERENCES llm_conversation (conversation_id) ON DELETE CASCADE,
+    FOREIGN KEY (reviewed_by) REFERENCES users (id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_llm_guardrail_event_conversation_id ON llm_guardrail_event (conversation_id);
+CREATE INDEX idx_llm_guardrail_event_created_at ON llm_guardrail_event (created_at);
@@ -0,0 +1,12 @@
+CREATE TABLE crisis_event (
+    id UUID PRIMARY KEY NOT NULL,
+    user_id UUID NOT NULL,
+    source TEXT NOT NULL,
+    reference_id UUID,
+    detector TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    notified_at TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_crisis_event_created_at ON crisis_event (created_at);
@@ -0,0 +1 @@
+ALTER TABLE llm_message DROP COLUMN superseded_by;
@@ -0,0 +1 @@
+DROP TABLE llm_learner_memory_consent;
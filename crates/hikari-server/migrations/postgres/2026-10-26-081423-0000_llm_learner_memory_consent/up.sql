@@ -0,0 +1,6 @@
+-- Users whose facts may be stored and recalled by the learner memory
+CREATE TABLE llm_learner_memory_consent (
+    user_id UUID PRIMARY KEY NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
@@ -0,0 +1,21 @@
+CREATE TABLE journal_summary_job (
+    id BLOB PRIMARY KEY NOT NULL,
+    user_id BLOB NOT NULL,
+    key BLOB NOT NULL,
+    date TIMESTAMP NOT NULL,
+    timestamp TIMESTAMP NOT NULL,
+    utc_offset INTEGER NOT NULL DEFAULT 0,
+    status TEXT NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    lease_owner TEXT,
+    lease_expires_at TIMESTAMP,
+    summary_id BLOB,
+    error TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (user_id, key, date),
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (summary_id) REFERENCES journal_summary (id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_journal_summary_job_status ON journal_summary_job (status, lease_expires_at);
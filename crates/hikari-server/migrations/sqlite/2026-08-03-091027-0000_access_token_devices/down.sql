@@ -0,0 +1,9 @@
+DROP TABLE access_tokens;
+
+CREATE TABLE access_tokens (
+    id INTEGER PRIMARY KEY NOT NULL,
+    user_id BLOB NOT NULL UNIQUE,
+    --  TODO (LOW) shorten the varchar
+    access_token varchar(255) NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
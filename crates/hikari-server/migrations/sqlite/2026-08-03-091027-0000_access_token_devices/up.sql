@@ -0,0 +1,16 @@
+-- SQLite can neither drop the unique constraint nor hash the existing tokens, so the table is recreated and every
+-- user has to log in again.
+DROP TABLE access_tokens;
+
+CREATE TABLE access_tokens (
+    id INTEGER PRIMARY KEY NOT NULL,
+    user_id BLOB NOT NULL,
+    token_hash BLOB NOT NULL UNIQUE,
+    device_name TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE INDEX access_tokens_user_id ON access_tokens (user_id);
@@ -0,0 +1,25 @@
+-- Actor and subject are not foreign keys so entries outlive deleted users
+CREATE TABLE audit_log (
+    id BLOB PRIMARY KEY NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    actor_id BLOB,
+    action TEXT NOT NULL,
+    subject_id BLOB,
+    request_id TEXT,
+    details TEXT NOT NULL DEFAULT '{}'
+);
+
+CREATE INDEX idx_audit_log_created_at ON audit_log (created_at DESC);
+CREATE INDEX idx_audit_log_actor_id ON audit_log (actor_id, created_at DESC);
+CREATE INDEX idx_audit_log_subject_id ON audit_log (subject_id, created_at DESC);
+
+-- The audit log is append-only
+CREATE TRIGGER audit_log_no_update BEFORE UPDATE ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
+
+CREATE TRIGGER audit_log_no_delete BEFORE DELETE ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
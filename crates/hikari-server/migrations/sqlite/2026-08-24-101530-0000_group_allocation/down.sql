@@ -0,0 +1,3 @@
+DROP TABLE allocation;
+
+DROP TABLE allocation_sequence;
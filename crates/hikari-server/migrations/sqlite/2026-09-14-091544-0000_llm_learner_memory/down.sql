@@ -0,0 +1,3 @@
+DROP INDEX idx_llm_learner_memory_user_id;
+
+DROP TABLE llm_learner_memory;
@@ -0,0 +1,14 @@
+-- The embeddings are only stored on postgres, which provides the vector extension
+CREATE TABLE llm_learner_memory (
+    id BLOB PRIMARY KEY NOT NULL,
+    user_id BLOB NOT NULL,
+    module_id TEXT NOT NULL,
+    session_id TEXT NOT NULL,
+    conversation_id BLOB,
+    content TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (conversation_id) REFERENCES llm_conversation (conversation_id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_llm_learner_memory_user_id ON llm_learner_memory (user_id);
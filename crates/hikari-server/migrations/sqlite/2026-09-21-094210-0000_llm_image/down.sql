@@ -0,0 +1,3 @@
+DROP INDEX idx_llm_image_user_id;
+
+DROP TABLE llm_image;
@@ -0,0 +1,4 @@
+DROP INDEX idx_llm_audio_conversation_id;
+DROP INDEX idx_llm_audio_user_id;
+
+DROP TABLE llm_audio;
@@ -0,0 +1,15 @@
+CREATE TABLE llm_audio (
+    id BLOB PRIMARY KEY NOT NULL,
+    user_id BLOB NOT NULL,
+    conversation_id BLOB,
+    message_order INTEGER,
+    mime_type TEXT NOT NULL,
+    data BLOB NOT NULL,
+    transcript TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (conversation_id) REFERENCES llm_conversation (conversation_id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_llm_audio_user_id ON llm_audio (user_id);
+CREATE INDEX idx_llm_audio_conversation_id ON llm_audio (conversation_id, message_order);
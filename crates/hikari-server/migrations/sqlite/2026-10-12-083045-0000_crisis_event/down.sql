@@ -0,0 +1,3 @@
+DROP INDEX idx_crisis_event_created_at;
+
+DROP TABLE crisis_event;
@@ -0,0 +1,12 @@
+CREATE TABLE crisis_event (
+    id BLOB PRIMARY KEY NOT NULL,
+    user_id BLOB NOT NULL,
+    source TEXT NOT NULL,
+    reference_id BLOB,
+    detector TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    notified_at TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_crisis_event_created_at ON crisis_event (created_at);
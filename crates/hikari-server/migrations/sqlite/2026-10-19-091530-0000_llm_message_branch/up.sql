@@ -0,0 +1 @@
+ALTER TABLE llm_message ADD COLUMN superseded_by INTEGER;
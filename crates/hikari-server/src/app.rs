@@ -1,13 +1,16 @@
+use crate::audit::REQUEST_ID_HEADER;
 use crate::opt::Auth;
 use crate::permissions::extract;
+use crate::routes::login::DEVICE_NAME_HEADER;
 use crate::{AppConfig, routes};
 use axum::routing::get;
 use axum::{Extension, Router};
 use axum_prometheus::PrometheusMetricLayerBuilder;
 
+use chrono::TimeDelta;
 use hikari_oidc::{DefaultJwkClient, DefaultOidcConfig, JwkClient};
 use hikari_utils::tower::otel;
-use http::{Method, header};
+use http::{HeaderName, Method, header};
 use protect_axum::GrantsLayer;
 use sea_orm::DatabaseConnection;
 use sentry_tower::NewSentryLayer;
@@ -18,6 +21,7 @@ use std::time::Duration;
 use tokio::{task, time};
 use tower::ServiceBuilder;
 use tower_http::cors::CorsLayer;
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
 
 pub(crate) struct InnerAuthConfig {
     jwk_client: Arc<DefaultJwkClient>,
@@ -25,6 +29,7 @@ pub(crate) struct InnerAuthConfig {
     audience: HashSet<String>,
     groups: HashSet<String>,
     groups_claim: Option<String>,
+    access_token_ttl: TimeDelta,
 }
 
 impl InnerAuthConfig {
@@ -47,6 +52,10 @@ impl InnerAuthConfig {
     pub(crate) fn groups_claim(&self) -> Option<&String> {
         self.groups_claim.as_ref()
     }
+
+    pub(crate) fn access_token_ttl(&self) -> TimeDelta {
+        self.access_token_ttl
+    }
 }
 
 #[derive(Clone)]
@@ -59,6 +68,7 @@ impl AuthConfig {
         audience: HashSet<String>,
         groups: HashSet<String>,
         groups_claim: Option<String>,
+        access_token_ttl: TimeDelta,
     ) -> Self {
         Self(Arc::new(InnerAuthConfig {
             jwk_client,
@@ -66,6 +76,7 @@ impl AuthConfig {
             audience,
             groups,
             groups_claim,
+            access_token_ttl,
         }))
     }
 }
@@ -126,7 +137,8 @@ pub async fn create_app(
             header::ACCEPT,
             header::CONTENT_TYPE,
             header::COOKIE,
-            header::ORIGIN
+            header::ORIGIN,
+            HeaderName::from_static(DEVICE_NAME_HEADER),
         ])
         .allow_methods([
             Method::GET,
@@ -183,6 +195,8 @@ pub async fn create_app(
                 .nest("/planner", routes::api::v0::planner::create_router())
                 .nest("/llm", routes::api::v0::llm::create_router())
                 .nest("/quizzes", routes::api::v0::quiz::create_router())
+                .nest("/question-bank", routes::api::v0::question_bank::create_router())
+                .nest("/admin", routes::api::v0::admin::create_router())
                 .nest("/ws", routes::api::v0::ws::create_router())
                 .layer(api_cors), // Use API-specific CORS for authenticated routes
         )
@@ -191,6 +205,11 @@ pub async fn create_app(
             // Router layers are called bottom to top
             // ServiceBuilder layers are called top to bottom
             ServiceBuilder::new()
+                .layer(SetRequestIdLayer::new(
+                    HeaderName::from_static(REQUEST_ID_HEADER),
+                    MakeRequestUuid,
+                ))
+                .layer(PropagateRequestIdLayer::new(HeaderName::from_static(REQUEST_ID_HEADER)))
                 .layer(NewSentryLayer::new_from_top())
                 .layer(sentry_tower::SentryHttpLayer::new().enable_transaction())
                 .layer(prometheus_layer)
@@ -202,6 +221,7 @@ pub async fn create_app(
                     auth.audience.into_iter().collect(),
                     auth.groups.into_iter().collect(),
                     auth.groups_claim,
+                    TimeDelta::days(i64::from(auth.access_token_ttl_days)),
                 )))
                 .layer(Extension(seaorm_pool))
                 .layer(GrantsLayer::with_extractor(extract)),
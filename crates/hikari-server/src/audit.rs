@@ -0,0 +1,150 @@
+use crate::user::ExtractUserId;
+use axum::extract::FromRequestParts;
+use axum::{Extension, RequestPartsExt};
+use hikari_db::audit_log::{Mutation, NewAuditEntry};
+use http::StatusCode;
+use http::request::Parts;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr};
+use serde_json::Value;
+use serde_json::json;
+use std::error::Error;
+use url::Url;
+use uuid::Uuid;
+
+pub(crate) const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Writes entries to the audit log on behalf of the requesting user.
+///
+/// Sensitive handlers record their action in the transaction which performs it, so an action is never performed
+/// without being logged. Actions which can't be rolled back are recorded before they are performed.
+#[derive(Clone)]
+pub(crate) struct Audit {
+    conn: DatabaseConnection,
+    actor_id: Option<Uuid>,
+    request_id: Option<String>,
+}
+
+impl<S> FromRequestParts<S> for Audit
+where
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Extension::<DatabaseConnection>(conn) = parts.extract().await.map_err(|error| {
+            tracing::error!(
+                error = &error as &dyn Error,
+                "database connection not found in app data"
+            );
+            (StatusCode::INTERNAL_SERVER_ERROR, "Database Connection not found")
+        })?;
+        let actor_id = parts.extract::<Option<ExtractUserId>>().await?.map(|user| user.0);
+        let request_id = parts
+            .headers
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(ToOwned::to_owned);
+        Ok(Self {
+            conn,
+            actor_id,
+            request_id,
+        })
+    }
+}
+
+impl Audit {
+    /// Records an action of the requesting user. `subject` is the user whose data is accessed or changed.
+    pub(crate) async fn record(&self, action: &str, subject: Option<Uuid>, details: Value) -> Result<(), DbErr> {
+        self.append(&self.conn, self.actor_id, action, subject, details).await
+    }
+
+    /// Records an action of the requesting user within the transaction which performs it.
+    pub(crate) async fn record_in<C: ConnectionTrait>(
+        &self,
+        conn: &C,
+        action: &str,
+        subject: Option<Uuid>,
+        details: Value,
+    ) -> Result<(), DbErr> {
+        self.append(conn, self.actor_id, action, subject, details).await
+    }
+
+    /// Records an action of a user which is not authenticated yet within the transaction which performs it, e.g. on
+    /// login.
+    pub(crate) async fn record_as<C: ConnectionTrait>(
+        &self,
+        conn: &C,
+        actor: Uuid,
+        action: &str,
+        subject: Option<Uuid>,
+        details: Value,
+    ) -> Result<(), DbErr> {
+        self.append(conn, Some(actor), action, subject, details).await
+    }
+
+    async fn append<C: ConnectionTrait>(
+        &self,
+        conn: &C,
+        actor_id: Option<Uuid>,
+        action: &str,
+        subject_id: Option<Uuid>,
+        details: Value,
+    ) -> Result<(), DbErr> {
+        tracing::info!(
+            target: "audit",
+            actor = actor_id.map(tracing::field::display),
+            action,
+            subject = subject_id.map(tracing::field::display),
+            request_id = self.request_id.as_deref(),
+            %details,
+            "audited action"
+        );
+        Mutation::append(
+            conn,
+            NewAuditEntry {
+                actor_id,
+                action: action.to_owned(),
+                subject_id,
+                request_id: self.request_id.clone(),
+                details,
+            },
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// Records that the configuration was loaded, which happens whenever the server is (re)started. Credentials in the
+/// source urls are not recorded.
+pub(crate) async fn record_config_load(
+    conn: &DatabaseConnection,
+    sources: &[(&str, Option<&Url>)],
+) -> Result<(), DbErr> {
+    let sources: serde_json::Map<String, Value> = sources
+        .iter()
+        .map(|(name, url)| {
+            let url = url.map(|url| {
+                let mut url = url.clone();
+                // Setting the username only fails for urls which can't contain credentials
+                let _ = url.set_username("");
+                let _ = url.set_password(None);
+                url.to_string()
+            });
+            ((*name).to_owned(), json!(url))
+        })
+        .collect();
+    let details = json!({ "sources": sources });
+    tracing::info!(target: "audit", action = "load_config", %details, "audited action");
+    Mutation::append(
+        conn,
+        NewAuditEntry {
+            actor_id: None,
+            action: "load_config".to_owned(),
+            subject_id: None,
+            request_id: None,
+            details,
+        },
+    )
+    .await?;
+    Ok(())
+}
@@ -283,6 +283,8 @@ pub(crate) async fn create_session_journal_entry<C: ConnectionTrait + Transactio
         focus,
         mood: created_entry.mood,
         prompts: vec![],
+        // Checked by the handler once the transaction is committed
+        crisis_resources: None,
     }))
 }
 
@@ -1,3 +1,5 @@
+use chrono::{TimeDelta, Utc};
+use hikari_db::access_tokens::NewAccessToken;
 use hikari_db::util::FlattenTransactionResultExt;
 use hikari_entity::user::Model as User;
 use sea_orm::prelude::*;
@@ -89,14 +91,24 @@ pub async fn get_or_create_user_and_get_token<C: ConnectionTrait + TransactionTr
     conn: &C,
     sub: &str,
     groups: HashSet<String>,
-) -> Result<hikari_entity::access_tokens::Model, DbErr> {
+    device_name: Option<String>,
+    token_ttl: TimeDelta,
+) -> Result<NewAccessToken, DbErr> {
     let sub = sub.to_string();
 
     conn.transaction(|txn| {
         Box::pin(async move {
             let (user, _) = get_or_create_user(txn, &sub).await?;
             hikari_db::groups::oidc_groups::Mutation::set(txn, user.id, groups).await?;
-            let token = hikari_db::access_tokens::Mutation::create_access_token(txn, user.id).await?;
+            let now = Utc::now().naive_utc();
+            let token = hikari_db::access_tokens::Mutation::create_access_token(
+                txn,
+                user.id,
+                device_name,
+                now,
+                now + token_ttl,
+            )
+            .await?;
 
             Ok(token)
         })
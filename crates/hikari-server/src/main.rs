@@ -8,6 +8,7 @@ use crate::db::migration;
 use crate::opt::{Commands, Db, OpenapiFormat, Run};
 use anyhow::{Result, anyhow};
 use axum::serve;
+use chrono::Utc;
 use clap::Parser;
 
 use hikari_config::assessment::AssessmentConfig;
@@ -15,9 +16,12 @@ use hikari_config::constants::collection::ConstantCollection;
 use hikari_config::documents::collection::DocumentCollection;
 use hikari_config::global::GlobalConfig;
 use hikari_config::module::ModuleConfig;
+use hikari_core::crisis::run_webhook_retry;
+use hikari_core::export::{delete_expired_exports, run_export_job_processor};
+use hikari_core::journal::summarize::job::run_summary_job_processor;
 use hikari_core::llm_config::LlmConfig;
 use hikari_db::sea_orm::{ConnectOptions, Database};
-use hikari_db::tag;
+use hikari_db::{access_tokens, tag};
 use hikari_llm::builder::LlmStructureConfig;
 use hikari_utils::loader::s3::S3Config;
 use hikari_utils::loader::{Loader, LoaderHandler};
@@ -27,9 +31,11 @@ use std::error::Error;
 use std::fmt::Debug;
 use std::net::{IpAddr, Ipv4Addr};
 use std::sync::Arc;
+use std::time::Duration;
 use url::Url;
 
 mod app;
+mod audit;
 mod auth;
 mod data;
 mod db;
@@ -41,6 +47,10 @@ mod user;
 
 const DEFAULT_HOST: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
 const DEFAULT_PORT: u16 = 3030;
+const SUMMARY_JOB_POLL_INTERVAL: Duration = Duration::from_secs(5);
+const EXPORT_JOB_POLL_INTERVAL: Duration = Duration::from_secs(5);
+const EXPIRED_DATA_CLEANUP_INTERVAL: Duration = Duration::from_hours(1);
+const CRISIS_WEBHOOK_RETRY_INTERVAL: Duration = Duration::from_mins(1);
 
 #[derive(Debug)]
 pub(crate) struct InnerAppConfig {
@@ -51,6 +61,7 @@ pub(crate) struct InnerAppConfig {
     worker_url: WorkerUrl,
     llm_config: LlmConfig,
     llm_data: LlmData,
+    export_storage: Option<Loader>,
 }
 
 #[derive(Debug)]
@@ -90,6 +101,7 @@ impl AppConfig {
         worker_url: WorkerUrl,
         llm_config: LlmConfig,
         llm_data: LlmData,
+        export_storage: Option<Loader>,
     ) -> Self {
         Self(Arc::new(InnerAppConfig {
             module_config,
@@ -99,6 +111,7 @@ impl AppConfig {
             worker_url,
             llm_config,
             llm_data,
+            export_storage,
         }))
     }
 
@@ -129,6 +142,11 @@ impl AppConfig {
     pub fn llm_data(&self) -> &LlmData {
         &self.0.llm_data
     }
+
+    /// Storage of data export archives, `None` if export jobs are disabled
+    pub fn export_storage(&self) -> Option<&Loader> {
+        self.0.export_storage.as_ref()
+    }
 }
 
 //noinspection SpellCheckingInspection
@@ -165,6 +183,7 @@ async fn run(opt: Run) -> Result<()> {
     let s3_config: Option<S3Config> = opt.s3.map(Into::into);
     let loader_handler = LoaderHandler::new(s3_config);
     let llm_config: LlmConfig = opt.llm_services.into();
+    hikari_core::openai::pool::configure(llm_config.provider_limits.clone());
     let llm_rag_documents_path = opt.llm_config.llm_collections;
 
     // ---- Load Bots
@@ -190,6 +209,10 @@ async fn run(opt: Run) -> Result<()> {
 
     // ---- Load Global Config
     let global_config = setup::load_config(opt.global_cfg.as_ref(), &loader_handler).await?;
+    let llm_config = llm_config
+        .with_step_defaults(global_config.llm().defaults.clone())
+        .with_learner_memory(global_config.llm().learner_memory.clone())
+        .with_moderation(global_config.llm().moderation.clone());
 
     // ---- Load Modules
     let module_config = setup::load_modules(&opt.config, &loader_handler, &global_config, &document_collection).await?;
@@ -201,6 +224,19 @@ async fn run(opt: Run) -> Result<()> {
         &global_config.module().ids(),
     )?;
 
+    audit::record_config_load(
+        &seaorm_pool,
+        &[
+            ("modules", Some(&opt.config)),
+            ("global", opt.global_cfg.as_ref()),
+            ("assessments", opt.assessment.as_ref()),
+            ("llm_structures", opt.llm_config.llm_structures.as_ref()),
+            ("llm_collections", Some(&llm_rag_documents_path)),
+            ("constants", opt.llm_config.constants.as_ref()),
+        ],
+    )
+    .await?;
+
     let journal_config = global_config.journal().clone();
     for focus in journal_config.focus {
         tag::Mutation::create_or_update_global_focus(&seaorm_pool, focus.name, focus.icon, false).await?;
@@ -229,6 +265,55 @@ async fn run(opt: Run) -> Result<()> {
         .await;
     });
 
+    if opt.process_summary_jobs {
+        let llm_config_clone = llm_config.clone();
+        let seaorm_pool_clone = seaorm_pool.clone();
+        tokio::spawn(async move {
+            run_summary_job_processor(seaorm_pool_clone, &llm_config_clone, SUMMARY_JOB_POLL_INTERVAL).await;
+        });
+    }
+
+    let crisis_config = global_config.crisis().clone();
+    let seaorm_pool_clone = seaorm_pool.clone();
+    tokio::spawn(async move {
+        run_webhook_retry(seaorm_pool_clone, crisis_config, CRISIS_WEBHOOK_RETRY_INTERVAL).await;
+    });
+
+    let export_storage = opt
+        .export_storage
+        .as_ref()
+        .map(|url| loader_handler.loader(url))
+        .transpose()?;
+    if let Some(export_storage) = export_storage.clone() {
+        let seaorm_pool_clone = seaorm_pool.clone();
+        tokio::spawn(async move {
+            run_export_job_processor(seaorm_pool_clone, export_storage, EXPORT_JOB_POLL_INTERVAL).await;
+        });
+    } else {
+        tracing::warn!("no export storage provided, export jobs are disabled");
+    }
+
+    let seaorm_pool_clone = seaorm_pool.clone();
+    let export_storage_clone = export_storage.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(EXPIRED_DATA_CLEANUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            match access_tokens::Mutation::delete_expired_access_tokens(&seaorm_pool_clone, Utc::now().naive_utc())
+                .await
+            {
+                Ok(deleted) => tracing::debug!(deleted, "deleted expired access tokens"),
+                Err(error) => tracing::error!(error = &error as &dyn Error, "failed to delete expired access tokens"),
+            }
+            if let Some(export_storage) = &export_storage_clone {
+                match delete_expired_exports(&seaorm_pool_clone, export_storage, Utc::now().naive_utc()).await {
+                    Ok(deleted) => tracing::debug!(deleted, "deleted expired export jobs"),
+                    Err(error) => tracing::error!(error = &error as &dyn Error, "failed to delete expired export jobs"),
+                }
+            }
+        }
+    });
+
     let Run {
         worker_url,
         host,
@@ -246,6 +331,7 @@ async fn run(opt: Run) -> Result<()> {
         WorkerUrl(worker_url),
         llm_config,
         llm_data,
+        export_storage,
     );
 
     let app = app::create_app(app_config, auth, deletable, seaorm_pool).await?;
@@ -65,6 +65,13 @@ pub(crate) struct Auth {
 
     #[arg(long = "require-claim", help = "Required claim. Value is optional and has to be the json value.", value_parser = NamedOptionalValueParser)]
     pub(crate) required_claims: Vec<NamedOptionalValue>,
+
+    #[arg(
+        long,
+        default_value_t = 30,
+        help = "Days an access token stays valid without being used"
+    )]
+    pub(crate) access_token_ttl_days: u32,
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -102,6 +109,12 @@ pub(crate) struct Run {
     #[arg(long, help = "The url were assessment config is stored")]
     pub(crate) assessment: Option<Url>,
 
+    #[arg(
+        long,
+        help = "The url were data export archives are stored. Export jobs are disabled if not set"
+    )]
+    pub(crate) export_storage: Option<Url>,
+
     #[arg(long, help = "If set it is possible to delete a user and all his data")]
     pub(crate) deletable: bool,
 
@@ -121,6 +134,12 @@ pub(crate) struct Run {
     #[arg(long, help = "The url of the worker")]
     pub(crate) worker_url: Url,
 
+    #[arg(
+        long,
+        help = "If set the server processes queued journal summary jobs in addition to the worker"
+    )]
+    pub(crate) process_summary_jobs: bool,
+
     #[arg(long)]
     pub(crate) otlp_endpoint: Option<String>,
 
@@ -1,19 +1,22 @@
+use crate::AppConfig;
 use crate::user::ExtractUser;
-use axum::RequestExt;
 use axum::extract::{FromRequestParts, Request};
 use axum::response::{IntoResponse, Response};
+use axum::{Extension, RequestExt, RequestPartsExt};
 use axum_extra::extract::Cached;
+use hikari_config::global::roles::RolesConfig;
 use http::StatusCode;
 use http::request::Parts;
 use serde_derive::Serialize;
 use std::collections::HashSet;
-use std::ops::Not;
 
 #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Serialize)]
 pub(crate) enum Permission {
-    Basic,   // like a user
-    Journal, // for journal features
-    Beta,    // for beta features
+    Basic,      // like a user
+    Journal,    // for journal features
+    Beta,       // for beta features
+    Instructor, // for curating module content and inspecting user progress
+    Admin,      // for operators, e.g. to grant groups
 }
 
 #[derive(PartialEq, Eq, Clone, Debug, Default)]
@@ -35,32 +38,34 @@ where
         let Ok(ExtractUser(user)) = user else {
             return Ok(Session::default());
         };
-        let permissions: Permissions = user.groups.into();
+        let Ok(app_config) = parts.extract::<Option<Extension<AppConfig>>>().await;
+        let permissions = match app_config {
+            Some(Extension(app_config)) => Permissions::from_groups(&user.groups, app_config.config().roles()),
+            None => Permissions::from_groups(&user.groups, &RolesConfig::default()),
+        };
         Ok(Session {
             permissions: permissions.0,
         })
     }
 }
 
-const POSITIVE_PERMISSION_MAP: &[(&str, Permission)] = &[("beta", Permission::Beta)];
-
-const NEGATIVE_PERMISSION_MAP: &[(&str, Permission)] = &[("no-journal", Permission::Journal)];
-
-impl From<Vec<String>> for Permissions {
-    fn from(groups: Vec<String>) -> Self {
-        let groups: HashSet<_> = groups.into_iter().collect();
-        let permissions: HashSet<Permission> = std::iter::once(Permission::Basic)
-            .chain(
-                POSITIVE_PERMISSION_MAP
-                    .iter()
-                    .filter_map(|(group, permission)| groups.contains(*group).then_some(*permission)),
-            )
-            .chain(
-                NEGATIVE_PERMISSION_MAP
-                    .iter()
-                    .filter_map(|(group, permission)| groups.contains(*group).not().then_some(*permission)),
-            )
-            .collect();
+impl Permissions {
+    pub(crate) fn from_groups(groups: &[String], roles: &RolesConfig) -> Self {
+        let has_role = |role_groups: &[String]| role_groups.iter().any(|group| groups.contains(group));
+
+        let mut permissions = HashSet::from([Permission::Basic]);
+        if has_role(&roles.beta) {
+            permissions.insert(Permission::Beta);
+        }
+        // Admins have all instructor permissions
+        if has_role(&roles.admin) {
+            permissions.extend([Permission::Admin, Permission::Instructor]);
+        } else if has_role(&roles.instructor) {
+            permissions.insert(Permission::Instructor);
+        }
+        if !has_role(&roles.no_journal) {
+            permissions.insert(Permission::Journal);
+        }
         Self(permissions)
     }
 }
@@ -84,3 +89,42 @@ pub(crate) async fn extract(request: &mut Request) -> Result<HashSet<Permission>
         .map(|permissions| permissions.0)
         .map_err(IntoResponse::into_response)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn groups(groups: &[&str]) -> Vec<String> {
+        groups.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_default_roles() {
+        let roles = RolesConfig::default();
+        let Permissions(permissions) = Permissions::from_groups(&groups(&["wi"]), &roles);
+        assert_eq!(permissions, HashSet::from([Permission::Basic, Permission::Journal]));
+
+        let Permissions(permissions) = Permissions::from_groups(&groups(&["beta", "no-journal"]), &roles);
+        assert_eq!(permissions, HashSet::from([Permission::Basic, Permission::Beta]));
+    }
+
+    #[test]
+    fn test_configured_roles() {
+        let roles = RolesConfig {
+            instructor: groups(&["lecturers"]),
+            admin: groups(&["operators"]),
+            ..RolesConfig::default()
+        };
+        let Permissions(permissions) = Permissions::from_groups(&groups(&["instructor", "lecturers"]), &roles);
+        assert!(permissions.contains(&Permission::Instructor));
+        assert!(!permissions.contains(&Permission::Admin));
+
+        let Permissions(permissions) = Permissions::from_groups(&groups(&["operators"]), &roles);
+        assert!(permissions.contains(&Permission::Instructor));
+        assert!(permissions.contains(&Permission::Admin));
+
+        // The default instructor group is replaced by the configured one
+        let Permissions(permissions) = Permissions::from_groups(&groups(&["instructor"]), &roles);
+        assert!(!permissions.contains(&Permission::Instructor));
+    }
+}
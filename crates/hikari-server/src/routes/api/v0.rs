@@ -1,9 +1,11 @@
+pub(crate) mod admin;
 pub(crate) mod assessment;
 pub(crate) mod bots;
 pub(crate) mod journal;
 pub(crate) mod llm;
 pub(crate) mod modules;
 pub(crate) mod planner;
+pub(crate) mod question_bank;
 pub(crate) mod quiz;
 pub(crate) mod status;
 pub(crate) mod user;
@@ -0,0 +1,707 @@
+use crate::AppConfig;
+use crate::audit::Audit;
+use crate::permissions::Permission;
+use crate::routes::api::v0::admin::error::AdminError;
+use crate::user::ExtractUserId;
+use axum::extract::{Path, Query};
+use axum::response::IntoResponse;
+use axum::routing::{delete, get, post};
+use axum::{Extension, Json, Router};
+use chrono::NaiveDateTime;
+use hikari_core::allocation;
+use hikari_core::assessment::change::{load_cohort_change, load_user_change};
+use hikari_db::audit_log::{self, AuditLogFilter};
+use hikari_db::crisis_event;
+use hikari_db::groups::custom_groups;
+use hikari_db::llm::guardrail_event::{self, GuardrailEventFilter};
+use hikari_db::module::session::status;
+use hikari_db::user;
+use hikari_model::admin::{AllocationReport, GrantGroups, LlmUsage, UserProgress};
+use hikari_model::assessment::change::{CohortAssessmentChange, ModuleAssessmentChange};
+use hikari_model::audit_log::AuditLogEntry;
+use hikari_model::crisis::CrisisEvent;
+use hikari_model::llm::guardrail::GuardrailEvent;
+use hikari_model::user::User;
+use hikari_model_tools::convert::{FromDbModel, TryIntoModel};
+use http::StatusCode;
+use protect_axum::protect;
+use sea_orm::{ConnectionTrait, DatabaseConnection, TransactionTrait};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+mod error;
+
+const DEFAULT_USER_LIMIT: u64 = 50;
+const MAX_USER_LIMIT: u64 = 500;
+const DEFAULT_AUDIT_LOG_LIMIT: u64 = 100;
+const MAX_AUDIT_LOG_LIMIT: u64 = 1000;
+
+pub(crate) fn create_router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/users", get(get_users))
+        .route("/audit-log", get(get_audit_log))
+        .route("/guardrail-events", get(get_guardrail_events))
+        .route("/guardrail-events/{event_id}/review", post(review_guardrail_event))
+        .route("/crisis-events/{event_id}", get(get_crisis_event))
+        .route("/allocations", get(get_allocation_report))
+        .route(
+            "/modules/{module_id}/assessment-change",
+            get(get_cohort_assessment_change),
+        )
+        .nest(
+            "/users/{user_id}",
+            Router::new()
+                .route("/progress", get(get_user_progress))
+                .route("/llm-usage", get(get_user_llm_usage))
+                .route("/groups", post(grant_groups))
+                .route("/groups/{group}", delete(revoke_group))
+                .route(
+                    "/modules/{module_id}/sessions/{session_id}/reset",
+                    post(reset_user_session),
+                )
+                .route("/modules/{module_id}/quiz/reset", post(reset_user_quiz))
+                .route(
+                    "/modules/{module_id}/assessment-change",
+                    get(get_user_assessment_change),
+                ),
+        )
+        .with_state(())
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct UsersQuery {
+    pub group: String,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct AuditLogQuery {
+    pub actor: Option<Uuid>,
+    pub subject: Option<Uuid>,
+    pub action: Option<String>,
+    pub from: Option<NaiveDateTime>,
+    pub to: Option<NaiveDateTime>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct GuardrailEventQuery {
+    pub conversation: Option<Uuid>,
+    pub user: Option<Uuid>,
+    #[serde(default)]
+    pub unreviewed: bool,
+    pub from: Option<NaiveDateTime>,
+    pub to: Option<NaiveDateTime>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct CohortQuery {
+    pub group: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct UsageQuery {
+    pub from: Option<NaiveDateTime>,
+    pub to: Option<NaiveDateTime>,
+}
+
+async fn ensure_user_exists<C: ConnectionTrait>(conn: &C, user_id: Uuid) -> Result<(), AdminError> {
+    user::Query::find_user_by_id(conn, user_id)
+        .await?
+        .ok_or(AdminError::UserNotFound)?;
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v0/admin/users",
+    params(
+        ("group" = String, Query, description = "Only list users in this group"),
+        ("limit" = Option<u64>, Query, description = "Maximum number of users to return (default 50, at most 500)"),
+        ("offset" = Option<u64>, Query, description = "Number of users to skip"),
+    ),
+    responses(
+        (status = OK, body = Vec<User>, description = "Users in the group"),
+    ),
+    tag = "v0/admin",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Instructor", ty = "Permission")]
+pub(crate) async fn get_users(
+    audit: Audit,
+    Extension(conn): Extension<DatabaseConnection>,
+    Query(query): Query<UsersQuery>,
+) -> Result<impl IntoResponse, AdminError> {
+    let limit = query.limit.unwrap_or(DEFAULT_USER_LIMIT).min(MAX_USER_LIMIT);
+    let offset = query.offset.unwrap_or_default();
+    audit
+        .record("list_users", None, json!({ "group": query.group }))
+        .await?;
+
+    let users = user::Query::get_users_by_group(&conn, &query.group, limit, offset).await?;
+    let users = users
+        .into_iter()
+        .map(TryIntoModel::try_into_model)
+        .collect::<Result<Vec<User>, _>>()?;
+    Ok(Json(users))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v0/admin/users/{user_id}/progress",
+    params(
+        ("user_id" = Uuid, Path, description = "The id of the user"),
+    ),
+    responses(
+        (status = OK, body = UserProgress, description = "Module and session progress of the user"),
+        (status = NOT_FOUND, description = "The user does not exist"),
+    ),
+    tag = "v0/admin",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Instructor", ty = "Permission")]
+pub(crate) async fn get_user_progress(
+    audit: Audit,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path(user_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AdminError> {
+    ensure_user_exists(&conn, user_id).await?;
+    audit.record("get_user_progress", Some(user_id), json!({})).await?;
+
+    let modules = hikari_db::module::status::Query::all(&conn, user_id).await?;
+    let sessions = status::Query::all(&conn, user_id).await?;
+    Ok(Json(UserProgress {
+        user_id,
+        modules: modules.into_iter().map(FromDbModel::from_db_model).collect(),
+        sessions: sessions.into_iter().map(FromDbModel::from_db_model).collect(),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v0/admin/users/{user_id}/llm-usage",
+    params(
+        ("user_id" = Uuid, Path, description = "The id of the user"),
+        ("from" = Option<NaiveDateTime>, Query, description = "Only count usage at or after this time (UTC)"),
+        ("to" = Option<NaiveDateTime>, Query, description = "Only count usage before this time (UTC)"),
+    ),
+    responses(
+        (status = OK, body = LlmUsage, description = "Tokens used by the user grouped by llm step"),
+        (status = BAD_REQUEST, description = "`from` is after `to`"),
+        (status = NOT_FOUND, description = "The user does not exist"),
+    ),
+    tag = "v0/admin",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Instructor", ty = "Permission")]
+pub(crate) async fn get_user_llm_usage(
+    audit: Audit,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<UsageQuery>,
+) -> Result<impl IntoResponse, AdminError> {
+    if let (Some(from), Some(to)) = (query.from, query.to)
+        && from > to
+    {
+        return Err(AdminError::InvalidRange);
+    }
+    ensure_user_exists(&conn, user_id).await?;
+    audit
+        .record(
+            "get_user_llm_usage",
+            Some(user_id),
+            json!({ "from": query.from, "to": query.to }),
+        )
+        .await?;
+
+    let usage = hikari_core::usage::get_usage(&conn, user_id, query.from, query.to).await?;
+    Ok(Json(usage))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v0/admin/users/{user_id}/modules/{module_id}/sessions/{session_id}/reset",
+    params(
+        ("user_id" = Uuid, Path, description = "The id of the user"),
+        ("module_id" = String, Path, description = "The id of the module"),
+        ("session_id" = String, Path, description = "The id of the session"),
+    ),
+    responses(
+        (status = NO_CONTENT, description = "The session was reset and can be started again"),
+        (status = NOT_FOUND, description = "The user, module or session does not exist or the session was never started"),
+    ),
+    tag = "v0/admin",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Instructor", ty = "Permission")]
+pub(crate) async fn reset_user_session(
+    audit: Audit,
+    Extension(app_config): Extension<AppConfig>,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path((user_id, module_id, session_id)): Path<(Uuid, String, String)>,
+) -> Result<impl IntoResponse, AdminError> {
+    let module = app_config
+        .module_config()
+        .get(&module_id)
+        .ok_or_else(|| AdminError::ModuleNotFound(module_id.clone()))?;
+    if module.get(&session_id).is_none() {
+        return Err(AdminError::SessionNotFound(session_id));
+    }
+    ensure_user_exists(&conn, user_id).await?;
+
+    let txn = conn.begin().await?;
+    hikari_db::llm::conversation::Mutation::close_open_conversations(&txn, user_id, &module_id, &session_id).await?;
+    if !status::Mutation::reset(&txn, user_id, &module_id, &session_id).await? {
+        return Err(AdminError::SessionNotFound(session_id));
+    }
+    audit
+        .record_in(
+            &txn,
+            "reset_session",
+            Some(user_id),
+            json!({ "module": module_id, "session": session_id }),
+        )
+        .await?;
+    txn.commit().await?;
+
+    tracing::info!(%user_id, %module_id, %session_id, "session reset by admin");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v0/admin/users/{user_id}/modules/{module_id}/quiz/reset",
+    params(
+        ("user_id" = Uuid, Path, description = "The id of the user"),
+        ("module_id" = String, Path, description = "The id of the module"),
+    ),
+    responses(
+        (status = NO_CONTENT, description = "All open quizzes of the module were closed, the next quiz starts fresh"),
+        (status = NOT_FOUND, description = "The user or module does not exist"),
+    ),
+    tag = "v0/admin",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Instructor", ty = "Permission")]
+pub(crate) async fn reset_user_quiz(
+    audit: Audit,
+    Extension(app_config): Extension<AppConfig>,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path((user_id, module_id)): Path<(Uuid, String)>,
+) -> Result<impl IntoResponse, AdminError> {
+    if app_config.module_config().get(&module_id).is_none() {
+        return Err(AdminError::ModuleNotFound(module_id));
+    }
+    ensure_user_exists(&conn, user_id).await?;
+
+    let txn = conn.begin().await?;
+    hikari_db::quiz::quiz::Mutation::close_quizzes_for_module(&txn, &user_id, &module_id).await?;
+    audit
+        .record_in(&txn, "reset_quiz", Some(user_id), json!({ "module": module_id }))
+        .await?;
+    txn.commit().await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v0/admin/users/{user_id}/groups",
+    request_body = GrantGroups,
+    params(
+        ("user_id" = Uuid, Path, description = "The id of the user"),
+    ),
+    responses(
+        (status = NO_CONTENT, description = "The groups were added to the user"),
+        (status = BAD_REQUEST, description = "A group is empty or mapped to a role"),
+        (status = NOT_FOUND, description = "The user does not exist"),
+    ),
+    tag = "v0/admin",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Admin", ty = "Permission")]
+pub(crate) async fn grant_groups(
+    audit: Audit,
+    Extension(app_config): Extension<AppConfig>,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path(user_id): Path<Uuid>,
+    Json(request): Json<GrantGroups>,
+) -> Result<impl IntoResponse, AdminError> {
+    let roles = app_config.config().roles();
+    for group in &request.groups {
+        if group.trim().is_empty() {
+            return Err(AdminError::InvalidGroup);
+        }
+        // Roles must come from the identity provider
+        if roles.is_role_group(group) {
+            return Err(AdminError::RoleGroup(group.clone()));
+        }
+    }
+    ensure_user_exists(&conn, user_id).await?;
+
+    let txn = conn.begin().await?;
+    for group in &request.groups {
+        custom_groups::Mutation::add(&txn, user_id, group.clone()).await?;
+    }
+    audit
+        .record_in(&txn, "grant_groups", Some(user_id), json!({ "groups": request.groups }))
+        .await?;
+    txn.commit().await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v0/admin/users/{user_id}/groups/{group}",
+    params(
+        ("user_id" = Uuid, Path, description = "The id of the user"),
+        ("group" = String, Path, description = "The custom group to remove"),
+    ),
+    responses(
+        (status = NO_CONTENT, description = "The group was removed from the user"),
+        (status = NOT_FOUND, description = "The user is not in the custom group"),
+    ),
+    tag = "v0/admin",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Admin", ty = "Permission")]
+pub(crate) async fn revoke_group(
+    audit: Audit,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path((user_id, group)): Path<(Uuid, String)>,
+) -> Result<impl IntoResponse, AdminError> {
+    let txn = conn.begin().await?;
+    if !custom_groups::Mutation::remove(&txn, user_id, &group).await? {
+        return Err(AdminError::GroupNotFound(group));
+    }
+    audit
+        .record_in(&txn, "revoke_group", Some(user_id), json!({ "group": group }))
+        .await?;
+    txn.commit().await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v0/admin/audit-log",
+    params(
+        ("actor" = Option<Uuid>, Query, description = "Only list actions of this user"),
+        ("subject" = Option<Uuid>, Query, description = "Only list actions on the data of this user"),
+        ("action" = Option<String>, Query, description = "Only list this action"),
+        ("from" = Option<NaiveDateTime>, Query, description = "Only list entries at or after this time (UTC)"),
+        ("to" = Option<NaiveDateTime>, Query, description = "Only list entries before this time (UTC)"),
+        ("limit" = Option<u64>, Query, description = "Maximum number of entries to return (default 100, at most 1000)"),
+        ("offset" = Option<u64>, Query, description = "Number of entries to skip"),
+    ),
+    responses(
+        (status = OK, body = Vec<AuditLogEntry>, description = "Matching audit log entries, newest first"),
+        (status = BAD_REQUEST, description = "`from` is after `to`"),
+    ),
+    tag = "v0/admin",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Admin", ty = "Permission")]
+pub(crate) async fn get_audit_log(
+    audit: Audit,
+    Extension(conn): Extension<DatabaseConnection>,
+    Query(query): Query<AuditLogQuery>,
+) -> Result<impl IntoResponse, AdminError> {
+    if let (Some(from), Some(to)) = (query.from, query.to)
+        && from > to
+    {
+        return Err(AdminError::InvalidRange);
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_AUDIT_LOG_LIMIT).min(MAX_AUDIT_LOG_LIMIT);
+    let offset = query.offset.unwrap_or_default();
+    let filter = AuditLogFilter {
+        actor_id: query.actor,
+        subject_id: query.subject,
+        action: query.action,
+        from: query.from,
+        to: query.to,
+    };
+    // Reading the audit log is an audited action itself
+    audit
+        .record(
+            "read_audit_log",
+            filter.subject_id,
+            json!({ "actor": filter.actor_id, "action": filter.action, "from": filter.from, "to": filter.to }),
+        )
+        .await?;
+
+    let entries: Vec<AuditLogEntry> = audit_log::Query::find(&conn, filter, limit, offset)
+        .await?
+        .into_iter()
+        .map(FromDbModel::from_db_model)
+        .collect();
+    Ok(Json(entries))
+}
+
+/// Lists the messages which triggered a guardrail of the moderation
+#[utoipa::path(
+    get,
+    path = "/api/v0/admin/guardrail-events",
+    params(
+        ("conversation" = Option<Uuid>, Query, description = "Only list events of this conversation"),
+        ("user" = Option<Uuid>, Query, description = "Only list events of this user"),
+        ("unreviewed" = Option<bool>, Query, description = "Only list events which were not reviewed yet"),
+        ("from" = Option<NaiveDateTime>, Query, description = "Only list events at or after this time (UTC)"),
+        ("to" = Option<NaiveDateTime>, Query, description = "Only list events before this time (UTC)"),
+        ("limit" = Option<u64>, Query, description = "Maximum number of events to return (default 100, at most 1000)"),
+        ("offset" = Option<u64>, Query, description = "Number of events to skip"),
+    ),
+    responses(
+        (status = OK, body = Vec<GuardrailEvent>, description = "Matching guardrail events, newest first"),
+        (status = BAD_REQUEST, description = "`from` is after `to`"),
+    ),
+    tag = "v0/admin",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Admin", ty = "Permission")]
+pub(crate) async fn get_guardrail_events(
+    audit: Audit,
+    Extension(conn): Extension<DatabaseConnection>,
+    Query(query): Query<GuardrailEventQuery>,
+) -> Result<impl IntoResponse, AdminError> {
+    if let (Some(from), Some(to)) = (query.from, query.to)
+        && from > to
+    {
+        return Err(AdminError::InvalidRange);
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_AUDIT_LOG_LIMIT).min(MAX_AUDIT_LOG_LIMIT);
+    let offset = query.offset.unwrap_or_default();
+    let filter = GuardrailEventFilter {
+        conversation_id: query.conversation,
+        user_id: query.user,
+        unreviewed: query.unreviewed,
+        from: query.from,
+        to: query.to,
+    };
+    audit
+        .record(
+            "read_guardrail_events",
+            filter.user_id,
+            json!({ "conversation": filter.conversation_id, "unreviewed": filter.unreviewed }),
+        )
+        .await?;
+
+    let events: Vec<GuardrailEvent> = guardrail_event::Query::find(&conn, filter, limit, offset)
+        .await?
+        .into_iter()
+        .map(FromDbModel::from_db_model)
+        .collect();
+    Ok(Json(events))
+}
+
+/// Marks a guardrail event as reviewed by the current user
+#[utoipa::path(
+    post,
+    path = "/api/v0/admin/guardrail-events/{event_id}/review",
+    params(
+        ("event_id" = Uuid, Path, description = "Id of the guardrail event"),
+    ),
+    responses(
+        (status = OK, body = GuardrailEvent, description = "The reviewed event"),
+        (status = NOT_FOUND, description = "Guardrail event not found"),
+    ),
+    tag = "v0/admin",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Admin", ty = "Permission")]
+pub(crate) async fn review_guardrail_event(
+    audit: Audit,
+    ExtractUserId(reviewer): ExtractUserId,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path(event_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AdminError> {
+    audit
+        .record("review_guardrail_event", None, json!({ "event": event_id }))
+        .await?;
+
+    let event = guardrail_event::Mutation::mark_reviewed(&conn, event_id, reviewer)
+        .await?
+        .ok_or(AdminError::GuardrailEventNotFound)?;
+    Ok(Json(GuardrailEvent::from_db_model(event)))
+}
+
+/// Looks up the event a counselor was notified about
+///
+/// The webhook only sends the id of the user if configured, this returns it for the follow-up.
+#[utoipa::path(
+    get,
+    path = "/api/v0/admin/crisis-events/{event_id}",
+    params(
+        ("event_id" = Uuid, Path, description = "Id of the crisis event"),
+    ),
+    responses(
+        (status = OK, body = CrisisEvent, description = "The crisis event"),
+        (status = NOT_FOUND, description = "Crisis event not found"),
+    ),
+    tag = "v0/admin",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Admin", ty = "Permission")]
+pub(crate) async fn get_crisis_event(
+    audit: Audit,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path(event_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AdminError> {
+    let event = crisis_event::Query::find_by_id(&conn, event_id)
+        .await?
+        .ok_or(AdminError::CrisisEventNotFound)?;
+    audit
+        .record("read_crisis_event", Some(event.user_id), json!({ "event": event_id }))
+        .await?;
+    Ok(Json(CrisisEvent::from_db_model(event)))
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct AllocationReportQuery {
+    pub token: Option<String>,
+}
+
+/// Shows how the users of randomized studies were allocated to the groups
+///
+/// Only random group assignments with a block size or stratification keep an allocation sequence.
+#[utoipa::path(
+    get,
+    path = "/api/v0/admin/allocations",
+    params(
+        ("token" = Option<String>, Query, description = "Only report the sequences of this access token"),
+    ),
+    responses(
+        (status = OK, body = Vec<AllocationReport>, description = "One report per access token, group assignment and stratum"),
+    ),
+    tag = "v0/admin",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Admin", ty = "Permission")]
+pub(crate) async fn get_allocation_report(
+    audit: Audit,
+    Extension(conn): Extension<DatabaseConnection>,
+    Query(query): Query<AllocationReportQuery>,
+) -> Result<impl IntoResponse, AdminError> {
+    audit
+        .record("read_allocation_report", None, json!({ "token": query.token }))
+        .await?;
+    let report = allocation::report(&conn, query.token.as_deref()).await?;
+    Ok(Json(report))
+}
+
+fn ensure_module_assessment(app_config: &AppConfig, module_id: &str) -> Result<(), AdminError> {
+    let module = app_config
+        .module_config()
+        .get(module_id)
+        .ok_or_else(|| AdminError::ModuleNotFound(module_id.to_owned()))?;
+    module
+        .assessment()
+        .ok_or_else(|| AdminError::AssessmentNotConfigured(module_id.to_owned()))?;
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v0/admin/users/{user_id}/modules/{module_id}/assessment-change",
+    params(
+        ("user_id" = Uuid, Path, description = "The id of the user"),
+        ("module_id" = String, Path, description = "The id of the module"),
+    ),
+    responses(
+        (status = OK, body = ModuleAssessmentChange, description = "Per-scale change between the pre- and the post-assessment"),
+        (status = NOT_FOUND, description = "The user or module does not exist, the module has no assessment or the user has not finished both assessments"),
+    ),
+    tag = "v0/admin",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Instructor", ty = "Permission")]
+pub(crate) async fn get_user_assessment_change(
+    audit: Audit,
+    Extension(app_config): Extension<AppConfig>,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path((user_id, module_id)): Path<(Uuid, String)>,
+) -> Result<impl IntoResponse, AdminError> {
+    ensure_module_assessment(&app_config, &module_id)?;
+    ensure_user_exists(&conn, user_id).await?;
+    audit
+        .record("read_assessment_change", Some(user_id), json!({ "module": module_id }))
+        .await?;
+
+    let scales = load_user_change(&conn, user_id, &module_id, app_config.assessments())
+        .await?
+        .ok_or(AdminError::AssessmentsNotFinished)?;
+    Ok(Json(ModuleAssessmentChange {
+        module: module_id,
+        scales,
+    }))
+}
+
+/// Summarizes the change between the pre- and the post-assessment of a module over all users of a group
+#[utoipa::path(
+    get,
+    path = "/api/v0/admin/modules/{module_id}/assessment-change",
+    params(
+        ("module_id" = String, Path, description = "The id of the module"),
+        ("group" = String, Query, description = "The group of the cohort"),
+    ),
+    responses(
+        (status = OK, body = CohortAssessmentChange, description = "Per-scale summary over the users who finished both assessments"),
+        (status = NOT_FOUND, description = "The module does not exist or has no assessment"),
+    ),
+    tag = "v0/admin",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Instructor", ty = "Permission")]
+pub(crate) async fn get_cohort_assessment_change(
+    audit: Audit,
+    Extension(app_config): Extension<AppConfig>,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path(module_id): Path<String>,
+    Query(query): Query<CohortQuery>,
+) -> Result<impl IntoResponse, AdminError> {
+    ensure_module_assessment(&app_config, &module_id)?;
+    audit
+        .record(
+            "read_cohort_assessment_change",
+            None,
+            json!({ "module": module_id, "group": query.group }),
+        )
+        .await?;
+
+    let change = load_cohort_change(&conn, &module_id, &query.group, app_config.assessments()).await?;
+    Ok(Json(change))
+}
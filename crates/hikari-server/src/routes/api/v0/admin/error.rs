@@ -0,0 +1,77 @@
+use axum::response::{IntoResponse, Response};
+use hikari_core::assessment::change::ChangeError;
+use http::StatusCode;
+use sea_orm::DbErr;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub(crate) enum AdminError {
+    #[error(transparent)]
+    SeaOrmError(#[from] DbErr),
+
+    #[error(transparent)]
+    Conversion(#[from] hikari_model_tools::error::Error),
+
+    #[error("User not found")]
+    UserNotFound,
+
+    #[error("Module not found: {0}")]
+    ModuleNotFound(String),
+
+    #[error("Session not found: {0}")]
+    SessionNotFound(String),
+
+    #[error("The user is not in the group {0}")]
+    GroupNotFound(String),
+
+    #[error("Invalid group name")]
+    InvalidGroup,
+
+    #[error("The group {0} is mapped to a role and can not be granted")]
+    RoleGroup(String),
+
+    #[error("Guardrail event not found")]
+    GuardrailEventNotFound,
+
+    #[error("Crisis event not found")]
+    CrisisEventNotFound,
+
+    #[error("Invalid time range")]
+    InvalidRange,
+
+    #[error("The module {0} has no assessment")]
+    AssessmentNotConfigured(String),
+
+    #[error("The user has not finished the pre- and post-assessment")]
+    AssessmentsNotFinished,
+
+    #[error(transparent)]
+    AssessmentChange(#[from] ChangeError),
+}
+
+impl IntoResponse for AdminError {
+    fn into_response(self) -> Response {
+        match self {
+            AdminError::SeaOrmError(e) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {e}")).into_response()
+            }
+            AdminError::Conversion(e) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, format!("Conversion error: {e}")).into_response()
+            }
+            AdminError::AssessmentChange(e) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, format!("Assessment error: {e}")).into_response()
+            }
+            AdminError::UserNotFound
+            | AdminError::ModuleNotFound(_)
+            | AdminError::SessionNotFound(_)
+            | AdminError::GroupNotFound(_)
+            | AdminError::GuardrailEventNotFound
+            | AdminError::CrisisEventNotFound
+            | AdminError::AssessmentNotConfigured(_)
+            | AdminError::AssessmentsNotFinished => (StatusCode::NOT_FOUND, self.to_string()).into_response(),
+            AdminError::InvalidGroup | AdminError::RoleGroup(_) | AdminError::InvalidRange => {
+                (StatusCode::BAD_REQUEST, self.to_string()).into_response()
+            }
+        }
+    }
+}
@@ -10,51 +10,26 @@ use chrono::NaiveDateTime;
 use error::Error;
 use hikari_config::assessment::Assessment;
 use hikari_config::assessment::AssessmentConfig;
-use hikari_config::assessment::question::Answer;
 use hikari_config::assessment::question::AnswerValue;
 use hikari_config::assessment::question::Question;
 use hikari_config::assessment::question::QuestionBody;
 use hikari_config::assessment::question::QuestionExt;
-use hikari_config::assessment::scale::Mode;
+use hikari_core::assessment::scale::{build_scale_values, visible_questions};
 use hikari_db::assessment::answer::QuestionAnswer;
 use hikari_model::assessment::scales::ItemValue;
 use hikari_model::assessment::session::AssessmentSession;
 use hikari_model_tools::convert::IntoModel;
 use http::StatusCode;
 use indexmap::IndexMap;
-use num_traits::ToPrimitive;
 use protect_axum::protect;
 use sea_orm::DatabaseConnection;
 use serde_derive::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 pub(crate) mod error;
 
-trait Operation {
-    fn evaluate(&self, data: Vec<u8>) -> Result<f64, Error>;
-}
-
-impl Operation for Mode {
-    fn evaluate(&self, data: Vec<u8>) -> Result<f64, Error> {
-        let length = data.len();
-        if length == 0 {
-            return Err(Error::Other("No data to evaluate".to_owned()));
-        }
-        let sum = data.into_iter().map(f64::from).sum();
-        let res = match self {
-            Self::Sum => sum,
-            Self::Average => {
-                sum / length
-                    .to_f64()
-                    .ok_or_else(|| Error::Other("Failed to evaluate average".to_owned()))?
-            }
-        };
-        Ok(res)
-    }
-}
-
 trait HasSeaOrmAnswerType {
     fn sea_orm_answer_type(&self) -> hikari_entity::assessment::answer::AnswerType;
 }
@@ -290,10 +265,11 @@ pub(crate) async fn update(
     if entry.status != hikari_entity::assessment::session::AssessmentStatus::Running {
         return Err(Error::NotRunning);
     }
-    let (_, question) = app_config
+    let assessment = app_config
         .assessments()
         .get(&entry.assessment)
-        .ok_or(Error::AssessmentConfigNotFound)?
+        .ok_or(Error::AssessmentConfigNotFound)?;
+    let (_, question) = assessment
         .questions
         .iter()
         .find(|(_, q)| q.id.as_str() == question)
@@ -304,6 +280,12 @@ pub(crate) async fn update(
         Error::InvalidAnswer
     })?;
 
+    // The visibility of a question only depends on previous answers, so the stored answers are sufficient
+    let answers = hikari_db::assessment::answer::Query::load_answers(&conn, session).await?;
+    if !visible_questions(assessment, &answers)?.contains(question.id.as_str()) {
+        return Err(Error::QuestionSkipped(question.id.clone()));
+    }
+
     hikari_db::assessment::answer::Mutation::insert_or_update(
         &conn,
         session,
@@ -448,59 +430,7 @@ fn build_scale_answers(
     assessment: &Assessment,
     answers: &[hikari_entity::assessment::answer::Model],
 ) -> Result<Vec<ItemValue>, Error> {
-    let answers: HashMap<_, _> = answers
-        .iter()
-        .map(|answer| (answer.question.as_str(), answer))
-        .collect();
-
-    let result: Result<Vec<_>, Error> = assessment
-        .scales
-        .values()
-        .map(|scale| {
-            let values: Result<Vec<u8>, Error> = scale
-                .items
-                .iter()
-                .map(|item| {
-                    let question = assessment
-                        .questions
-                        .get(item.id.as_str())
-                        .ok_or(Error::QuestionIdDoesNotExist(item.id.clone()))?;
-                    let (min, max) = match &question.body {
-                        QuestionBody::Scale(scale) => (scale.min, scale.max),
-                        scale_type => {
-                            return Err(Error::InvalidScaleType(Into::<&str>::into(scale_type).to_owned()));
-                        }
-                    };
-
-                    match answers.get(item.id.as_str()) {
-                        Some(&answer) => answer
-                            .data
-                            .parse::<u8>()
-                            .map_err(|_| {
-                                tracing::error!(data = answer.data, "Failed to parse data as u8");
-                                Error::InvalidValue(answer.data.clone())
-                            })
-                            .map(|val| if item.reverse { max + min - val } else { val }),
-                        None => Err(Error::AnswerNotFound),
-                    }
-                })
-                .collect();
-            let values = values?;
-            Ok(ItemValue {
-                id: scale.id.clone(),
-                title: scale.title.clone(),
-                value: scale.mode.evaluate(values)?,
-            })
-        })
-        .collect();
-    if let Err(error) = &result {
-        tracing::error!(
-            assessment_id = assessment.assessment_id,
-            error = error as &dyn std::error::Error,
-            "failed to build scale values"
-        );
-    }
-    result
+    build_scale_values(assessment, answers).map_err(Into::into)
 }
 
 pub(crate) fn build_assessment_answers_sea_orm(
@@ -514,22 +444,63 @@ pub(crate) fn build_assessment_answers_sea_orm(
         .into_iter()
         .map(|a| (a.question_id.clone(), a))
         .collect::<HashMap<_, _>>();
-    assessment
+    let visible = assessment.visible_questions(
+        &answers
+            .iter()
+            .map(|(question, answer)| (question.as_str(), answer.answer.clone().into()))
+            .collect(),
+    );
+    check_missing_answers(assessment, &visible, &answers)?;
+
+    // Answers to skipped questions are neither required nor stored, missing scale items are prorated
+    Ok(assessment
         .questions
         .values()
-        .map(|question| {
-            Ok(QuestionAnswer {
+        .filter(|question| visible.contains(question.id.as_str()))
+        .filter_map(|question| {
+            answers.remove(question.id.as_str()).map(|answer| QuestionAnswer {
                 question: question.id.clone(),
                 answer_type: question.sea_orm_answer_type(),
-                data: answer_value_to_string(
-                    answers
-                        .remove(question.id.as_str())
-                        .ok_or(Error::MissingAnswer(question.id.clone()))?
-                        .answer,
-                ),
+                data: answer_value_to_string(answer.answer),
             })
         })
-        .collect::<Result<Vec<_>, Error>>()
+        .collect())
+}
+
+/// Only items of scales may be left unanswered, up to `max_missing` per scale and as long as one item of each scale
+/// was answered.
+fn check_missing_answers(
+    assessment: &Assessment,
+    visible: &HashSet<&str>,
+    answers: &HashMap<String, AnswerRequest>,
+) -> Result<(), Error> {
+    let is_missing = |id: &str| visible.contains(id) && !answers.contains_key(id);
+
+    if let Some(question) = assessment.questions.values().find(|question| {
+        is_missing(&question.id)
+            && !assessment
+                .scales
+                .values()
+                .any(|scale| scale.items.iter().any(|item| item.id == question.id))
+    }) {
+        return Err(Error::MissingAnswer(question.id.clone()));
+    }
+
+    for scale in assessment.scales.values() {
+        let shown: Vec<_> = scale
+            .items
+            .iter()
+            .filter(|item| visible.contains(item.id.as_str()))
+            .collect();
+        let missing: Vec<_> = shown.iter().filter(|item| is_missing(&item.id)).collect();
+        if let Some(item) = missing.get(usize::try_from(scale.max_missing).unwrap_or(usize::MAX)) {
+            return Err(Error::MissingAnswer(item.id.clone()));
+        }
+        if !shown.is_empty() && missing.len() == shown.len() {
+            return Err(Error::NoAnsweredItems(scale.id.clone()));
+        }
+    }
+    Ok(())
 }
 
 async fn load_answered_assessment(
@@ -555,38 +526,33 @@ fn generate_answered_assessment(
         .map(|f| (f.question.as_str(), f))
         .collect::<HashMap<&str, _>>();
 
-    let questions: Result<IndexMap<_, _>, Error> = assessment
+    let parsed: Result<HashMap<_, _>, Error> = assessment
+        .questions
+        .values()
+        .filter_map(|question| {
+            answers.get(question.id.as_str()).map(|&answer| {
+                question
+                    .parse_answer(&answer.data)
+                    .map(|parsed| (question.id.as_str(), parsed))
+                    .map_err(|_| Error::InvalidValue(answer.data.clone()))
+            })
+        })
+        .collect();
+    let mut parsed =
+        parsed.inspect_err(|error| tracing::error!(error = error as &dyn std::error::Error, "failed to parse data"))?;
+    let visible = assessment.visible_questions(&parsed);
+
+    let questions: IndexMap<_, _> = assessment
         .questions
         .values()
         .map(|question| {
             let mut answered_question = question.clone();
-            answered_question.answer = match answers.get(question.id.as_str()) {
-                None => None,
-                Some(&answer) => Some(match answered_question.body {
-                    QuestionBody::Scale(_) => Answer::Scale(
-                        answer
-                            .data
-                            .parse()
-                            .map_err(|_| Error::InvalidValue(answer.data.clone()))?,
-                    ),
-                    QuestionBody::Textfield(_) | QuestionBody::Textarea(_) | QuestionBody::MultiChoice(_) => {
-                        Answer::Text(answer.data.clone())
-                    }
-                    QuestionBody::Select(_) | QuestionBody::SingleChoice(_) => Answer::Bool(
-                        answer
-                            .data
-                            .parse()
-                            .map_err(|_| Error::InvalidValue(answer.data.clone()))?,
-                    ),
-                }),
-            };
-            Ok((question.id.clone(), answered_question))
+            answered_question.answer = parsed.remove(question.id.as_str());
+            answered_question.skipped = !visible.contains(question.id.as_str());
+            (question.id.clone(), answered_question)
         })
         .collect();
 
-    let questions = questions
-        .inspect_err(|error| tracing::error!(error = error as &dyn std::error::Error, "failed to parse data"))?;
-
     let assessment = Assessment {
         assessment_id: assessment.assessment_id.clone(),
         title: assessment.title.clone(),
@@ -608,8 +574,9 @@ mod tests {
     use std::sync::LazyLock;
 
     use hikari_config::assessment::{
+        condition::ShowIf,
         question::{LikertScaleBody, Question, SelectBody},
-        scale::{Item, Scale, ScaleBody},
+        scale::{Band, BandScore, Item, Mode, Norms, PercentileRank, Scale, ScaleBody},
     };
     use hikari_entity::assessment::answer::{AnswerType, Model as Answer};
 
@@ -638,7 +605,9 @@ mod tests {
                                 hint_min: None,
                                 hint_max: None,
                             }),
+                            show_if: None,
                             answer: None,
+                            skipped: false,
                         },
                     ),
                     (
@@ -652,7 +621,9 @@ mod tests {
                                 hint_min: None,
                                 hint_max: None,
                             }),
+                            show_if: None,
                             answer: None,
+                            skipped: false,
                         },
                     ),
                     (
@@ -661,7 +632,9 @@ mod tests {
                             id: QUESTION_ID_3.to_owned(),
                             title: "Test Two".to_owned(),
                             body: QuestionBody::Select(SelectBody { yes: None, no: None }),
+                            show_if: None,
                             answer: None,
+                            skipped: false,
                         },
                     ),
                 ]),
@@ -682,6 +655,10 @@ mod tests {
                                 reverse: false,
                             },
                         ],
+                        max_missing: 0,
+                        bands: vec![],
+                        norms: None,
+                        reliability: None,
                     },
                 )]),
             },
@@ -711,4 +688,221 @@ mod tests {
 
         assert!((answers[0].value - 4.0).abs() < f64::EPSILON);
     }
+
+    #[test]
+    fn test_skipped_questions() {
+        let mut config = ASSESSMENT_CONFIG.clone();
+        let assessment = config.assessments.get_mut(ASSESSMENT_ID).unwrap();
+        // Ask the third question first, the second one is only shown if it was answered with yes
+        assessment.questions.move_index(2, 0);
+        assessment.questions.get_mut(QUESTION_ID_2).unwrap().show_if = Some(ShowIf::Answer {
+            question: QUESTION_ID_3.to_owned(),
+            equals: Some(hikari_config::assessment::question::Answer::Bool(true)),
+            one_of: vec![],
+            min: None,
+            max: None,
+        });
+
+        let request = |question: &str, answer| AnswerRequest {
+            question_id: question.to_owned(),
+            answer,
+        };
+        let question_answers = build_assessment_answers_sea_orm(
+            ASSESSMENT_ID,
+            &config,
+            vec![
+                request(QUESTION_ID_3, AnswerValue::Bool { value: false }),
+                request(QUESTION_ID_1, AnswerValue::SmallInt { value: 3 }),
+                request(QUESTION_ID_2, AnswerValue::SmallInt { value: 5 }),
+            ],
+        )
+        .unwrap();
+        // The answer to the skipped question is dropped
+        assert_eq!(question_answers.len(), 2);
+        assert!(question_answers.iter().all(|answer| answer.question != QUESTION_ID_2));
+
+        assert!(matches!(
+            build_assessment_answers_sea_orm(
+                ASSESSMENT_ID,
+                &config,
+                vec![
+                    request(QUESTION_ID_3, AnswerValue::Bool { value: true }),
+                    request(QUESTION_ID_1, AnswerValue::SmallInt { value: 3 }),
+                ],
+            ),
+            Err(Error::MissingAnswer(question)) if question == QUESTION_ID_2
+        ));
+
+        let answer = |question: &str, answer_type, data: &str| Answer {
+            assessment_session_id: Uuid::new_v4(),
+            answer_type,
+            question: question.to_owned(),
+            data: data.to_owned(),
+        };
+        let scales = build_scale_answers(
+            config.get(ASSESSMENT_ID).unwrap(),
+            &[
+                answer(QUESTION_ID_3, AnswerType::Bool, "false"),
+                answer(QUESTION_ID_1, AnswerType::Int, "3"),
+            ],
+        )
+        .unwrap();
+        assert!((scales[0].value - 3.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_prorated_scale_with_norms_and_bands() {
+        let mut config = ASSESSMENT_CONFIG.clone();
+        let scale = config
+            .assessments
+            .get_mut(ASSESSMENT_ID)
+            .unwrap()
+            .scales
+            .get_mut(SCALE_ID)
+            .unwrap();
+        scale.mode = Mode::Sum;
+        scale.max_missing = 1;
+        scale.norms = Some(Norms {
+            mean: 5.0,
+            sd: 2.0,
+            percentiles: vec![],
+        });
+        scale.bands = vec![
+            Band {
+                label: "low".to_owned(),
+                min: None,
+                max: Some(60.0),
+                feedback: None,
+                score: BandScore::T,
+            },
+            Band {
+                label: "high".to_owned(),
+                min: Some(60.0),
+                max: None,
+                feedback: Some("feedback".to_owned()),
+                score: BandScore::T,
+            },
+        ];
+        let answer = Answer {
+            assessment_session_id: Uuid::new_v4(),
+            answer_type: AnswerType::Int,
+            question: QUESTION_ID_1.to_string(),
+            data: 4.to_string(),
+        };
+
+        let scales = build_scale_answers(config.get(ASSESSMENT_ID).unwrap(), std::slice::from_ref(&answer)).unwrap();
+        // The missing item is prorated from the answered one: 4 * 2 / 1
+        assert!((scales[0].value - 8.0).abs() < f64::EPSILON);
+        assert_eq!(scales[0].prorated, 1);
+        let norm = scales[0].norm.unwrap();
+        assert!((norm.z - 1.5).abs() < f64::EPSILON);
+        assert!((norm.t - 65.0).abs() < f64::EPSILON);
+        assert!((norm.percentile - 93.319).abs() < 0.001);
+        assert_eq!(scales[0].band.as_ref().unwrap().label, "high");
+
+        config
+            .assessments
+            .get_mut(ASSESSMENT_ID)
+            .unwrap()
+            .scales
+            .get_mut(SCALE_ID)
+            .unwrap()
+            .norms
+            .as_mut()
+            .unwrap()
+            .percentiles = vec![
+            PercentileRank {
+                value: 6.0,
+                percentile: 60.0,
+            },
+            PercentileRank {
+                value: 10.0,
+                percentile: 100.0,
+            },
+        ];
+        let scales = build_scale_answers(config.get(ASSESSMENT_ID).unwrap(), std::slice::from_ref(&answer)).unwrap();
+        assert!((scales[0].norm.unwrap().percentile - 80.0).abs() < 1e-9);
+
+        config
+            .assessments
+            .get_mut(ASSESSMENT_ID)
+            .unwrap()
+            .scales
+            .get_mut(SCALE_ID)
+            .unwrap()
+            .max_missing = 0;
+        assert!(matches!(
+            build_scale_answers(config.get(ASSESSMENT_ID).unwrap(), &[answer]),
+            Err(Error::AnswerNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_missing_scale_items() {
+        let mut config = ASSESSMENT_CONFIG.clone();
+        config
+            .assessments
+            .get_mut(ASSESSMENT_ID)
+            .unwrap()
+            .scales
+            .get_mut(SCALE_ID)
+            .unwrap()
+            .max_missing = 1;
+        let request = |question: &str, answer| AnswerRequest {
+            question_id: question.to_owned(),
+            answer,
+        };
+
+        // One missing item of the scale is allowed
+        let question_answers = build_assessment_answers_sea_orm(
+            ASSESSMENT_ID,
+            &config,
+            vec![
+                request(QUESTION_ID_1, AnswerValue::SmallInt { value: 3 }),
+                request(QUESTION_ID_3, AnswerValue::Bool { value: true }),
+            ],
+        )
+        .unwrap();
+        assert_eq!(question_answers.len(), 2);
+
+        // Questions which are not part of a scale have to be answered
+        assert!(matches!(
+            build_assessment_answers_sea_orm(
+                ASSESSMENT_ID,
+                &config,
+                vec![request(QUESTION_ID_1, AnswerValue::SmallInt { value: 3 })],
+            ),
+            Err(Error::MissingAnswer(question)) if question == QUESTION_ID_3
+        ));
+
+        // Without any answered item the scale can't be prorated
+        config
+            .assessments
+            .get_mut(ASSESSMENT_ID)
+            .unwrap()
+            .scales
+            .get_mut(SCALE_ID)
+            .unwrap()
+            .max_missing = 2;
+        assert!(matches!(
+            build_assessment_answers_sea_orm(
+                ASSESSMENT_ID,
+                &config,
+                vec![request(QUESTION_ID_3, AnswerValue::Bool { value: true })],
+            ),
+            Err(Error::NoAnsweredItems(scale)) if scale == SCALE_ID
+        ));
+        assert!(matches!(
+            build_scale_answers(
+                config.get(ASSESSMENT_ID).unwrap(),
+                &[Answer {
+                    assessment_session_id: Uuid::new_v4(),
+                    answer_type: AnswerType::Bool,
+                    question: QUESTION_ID_3.to_string(),
+                    data: "true".to_owned(),
+                }],
+            ),
+            Err(Error::NoAnsweredItems(_))
+        ));
+    }
 }
@@ -1,6 +1,8 @@
 use axum::response::{IntoResponse, Response};
 
 use crate::db;
+use hikari_core::assessment::change::ChangeError;
+use hikari_core::assessment::scale::ScaleError;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -21,6 +23,10 @@ pub(crate) enum Error {
     InvalidAnswer,
     #[error("answer {0} was not found")]
     MissingAnswer(String),
+    #[error("no item of scale \"{0}\" was answered")]
+    NoAnsweredItems(String),
+    #[error("question {0} is skipped because of previous answers")]
+    QuestionSkipped(String),
     #[error("session id doesn't belong to assessment id")]
     UnrelatedSessionId,
     #[error("couldn't parse stored answer value {0}")]
@@ -42,6 +48,29 @@ impl From<db::error::DbError> for Error {
     }
 }
 
+impl From<ScaleError> for Error {
+    fn from(error: ScaleError) -> Self {
+        match error {
+            ScaleError::QuestionIdDoesNotExist(id) => Self::QuestionIdDoesNotExist(id),
+            ScaleError::InvalidScaleType(scale_type) => Self::InvalidScaleType(scale_type),
+            ScaleError::AnswerNotFound => Self::AnswerNotFound,
+            ScaleError::InvalidValue(value) => Self::InvalidValue(value),
+            ScaleError::NoAnsweredItems(scale) => Self::NoAnsweredItems(scale),
+            ScaleError::Other(message) => Self::Other(message),
+        }
+    }
+}
+
+impl From<ChangeError> for Error {
+    fn from(error: ChangeError) -> Self {
+        match error {
+            ChangeError::DbError(error) => error.into(),
+            ChangeError::Scale(error) => error.into(),
+            ChangeError::AssessmentNotConfigured(_) => Self::AssessmentConfigNotFound,
+        }
+    }
+}
+
 impl From<sea_orm::DbErr> for Error {
     fn from(error: sea_orm::DbErr) -> Self {
         Self::DB(error.into())
@@ -57,7 +86,9 @@ impl IntoResponse for Error {
             | Self::NotCompleted
             | Self::NotFound => http::StatusCode::NOT_FOUND.into_response(),
             Self::NotRunning => http::StatusCode::CONFLICT.into_response(),
-            Self::InvalidAnswer | Self::MissingAnswer(_) => http::StatusCode::BAD_REQUEST.into_response(),
+            Self::InvalidAnswer | Self::MissingAnswer(_) | Self::NoAnsweredItems(_) | Self::QuestionSkipped(_) => {
+                http::StatusCode::BAD_REQUEST.into_response()
+            }
             _ => http::StatusCode::INTERNAL_SERVER_ERROR.into_response(),
         }
     }
@@ -1,8 +1,10 @@
 pub(crate) mod assistant;
 pub(crate) mod error;
+pub(crate) mod insights;
 pub(crate) mod journal_entry;
 pub(crate) mod journal_focus;
 
+use crate::AppConfig;
 use crate::permissions::Permission;
 use crate::routes::api::v0::journal::error::JournalError;
 use crate::user::ExtractUserId;
@@ -10,6 +12,7 @@ use axum::Extension;
 use axum::Json;
 use axum::response::IntoResponse;
 use axum::routing::{Router, get, post};
+use hikari_core::crisis::{self, CrisisSource};
 use hikari_db::journal;
 use hikari_db::sea_orm::DatabaseConnection;
 use hikari_model::journal::partial::NewJournalEntryWithData;
@@ -29,6 +32,7 @@ where
                 .route("/empty", post(create_empty_journal_entry))
                 .nest("/{journal_entry}", journal_entry::create_router()),
         )
+        .route("/insights", get(insights::get_journal_insights))
         .nest("/focus", journal_focus::create_router())
         .nest("/assistant", assistant::create_router())
         .with_state(())
@@ -95,18 +99,37 @@ pub(crate) async fn create_empty_journal_entry(
 pub(crate) async fn create_journal_entry(
     ExtractUserId(user): ExtractUserId,
     Extension(conn): Extension<DatabaseConnection>,
+    Extension(app_config): Extension<AppConfig>,
     Json(json): Json<NewJournalEntryWithData>,
 ) -> Result<impl IntoResponse, JournalError> {
+    let contents: Vec<String> = json.content.into_iter().map(|content| content.content).collect();
+    let crisis_config = app_config.config().crisis();
+    let text = crisis_config.checks_journal().then(|| contents.join("\n"));
     let entry = journal::journal_entry::Mutation::create_journal_entry_with_content(
         &conn,
         user,
         json.title,
-        json.content.into_iter().map(|content| content.content).collect(),
+        contents,
         json.focus,
         json.mood,
         json.prompts,
     )
     .await?;
+    let crisis_resources = match text {
+        Some(text) => {
+            crisis::check(
+                crisis_config,
+                app_config.llm_config(),
+                &conn,
+                user,
+                CrisisSource::Journal,
+                Some(entry.id),
+                &text,
+            )
+            .await
+        }
+        None => None,
+    };
     let entry = MetaJournalEntryWithMetaContent {
         id: entry.id,
         user_id: entry.user_id,
@@ -126,6 +149,7 @@ pub(crate) async fn create_journal_entry(
             .collect(),
         focus: entry.focus.into_iter().map(IntoModel::into_model).collect::<Vec<_>>(),
         prompts: vec![],
+        crisis_resources,
     };
     Ok(Json(entry))
 }
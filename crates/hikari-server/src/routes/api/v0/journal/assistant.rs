@@ -1,12 +1,13 @@
 pub(crate) mod error;
 pub(crate) mod summarize;
+pub(crate) mod summary_job;
 use crate::AppConfig;
 use crate::permissions::Permission;
 use crate::routes::api::v0::journal::assistant::error::{AssistantError, AssistantErrorType};
 use crate::routes::error::ErrorData;
 use crate::user::ExtractUserId;
 use axum::response::IntoResponse;
-use axum::routing::{Router, post};
+use axum::routing::{Router, get, post};
 use axum::{Extension, Json};
 use hikari_core::journal::assistant::{
     MergeResponse, PromptResponse, generate_prompt, generate_text_prompt, merge_prompts, text_merge_prompts,
@@ -16,6 +17,7 @@ use protect_axum::protect;
 use sea_orm::DatabaseConnection;
 use serde_derive::Deserialize;
 use summarize::summarize_handler;
+use summary_job::{create_summary_job, get_summary_job_handler};
 use utoipa::ToSchema;
 
 pub(crate) fn create_router<S>() -> Router<S>
@@ -26,6 +28,8 @@ where
         .route("/merge", post(merge))
         .route("/prompt", post(prompt))
         .route("/summarize", post(summarize_handler))
+        .route("/summary-jobs", post(create_summary_job))
+        .route("/summary-jobs/{job_id}", get(get_summary_job_handler))
         .route("/text_merge", post(text_merge))
         .route("/text_prompt", post(text_prompt))
         .with_state(())
@@ -1,5 +1,6 @@
 use crate::routes::error::{ErrorData, ErrorDataProvider, GetStatusCode};
 use axum::response::{IntoResponse, Response};
+use hikari_core::journal::summarize::error::SummarizeError;
 use hikari_core::openai::error::FunctionCallError;
 use http::status::InvalidStatusCode;
 use sea_orm::DbErr;
@@ -16,6 +17,9 @@ pub(crate) enum AssistantError {
     #[error(transparent)]
     FunctionCall(#[from] FunctionCallError),
 
+    #[error(transparent)]
+    Summarize(#[from] SummarizeError),
+
     #[error("Error loading data from db")]
     DbError(#[from] DbErr),
 
@@ -65,6 +69,10 @@ impl ErrorDataProvider<AssistantErrorType> for AssistantError {
                 ErrorData::new(AssistantErrorType::Assistant, "error using the assistant")
             }
             Self::FunctionCall(fc) => fc.error_data()?,
+            Self::Summarize(error) => {
+                tracing::error!(error = &error as &dyn Error, "error handling summary job");
+                ErrorData::new(AssistantErrorType::Assistant, "error handling summary job")
+            }
             Self::DbError(error) => {
                 tracing::error!(error = &error as &dyn Error, "error communicating with database");
                 ErrorData::new(AssistantErrorType::Database, "error communicating with database")
@@ -0,0 +1,105 @@
+use crate::permissions::Permission;
+use crate::routes::api::v0::journal::assistant::error::{AssistantError, AssistantErrorType};
+use crate::routes::error::ErrorData;
+use crate::user::ExtractUserId;
+use axum::extract::{Path, Query};
+use axum::response::IntoResponse;
+use axum::{Extension, Json};
+use hikari_core::journal::summarize::job::{SummaryJob, get_summary_job, request_summary, wait_for_summary_job};
+use http::StatusCode;
+use protect_axum::protect;
+use sea_orm::DatabaseConnection;
+use serde_derive::Deserialize;
+use std::time::Duration;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use super::summarize::SummaryOptions;
+
+/// Upper bound for long polling a summary job
+const MAX_WAIT_SECONDS: u64 = 60;
+
+#[derive(Debug, Clone, Deserialize, IntoParams, ToSchema)]
+pub(crate) struct SummaryJobQuery {
+    /// Seconds to wait for the job to finish before returning its current state (at most 60)
+    pub wait: Option<u64>,
+}
+
+fn job_status_code(job: &SummaryJob) -> StatusCode {
+    if job.status.is_finished() {
+        StatusCode::OK
+    } else {
+        StatusCode::ACCEPTED
+    }
+}
+
+/// Request a summary of the journal entries
+///
+/// Queues a summary job which is processed in the background. If the summary already exists it is returned right
+/// away. Requests for the same entries share one job.
+#[utoipa::path(
+    post,
+    path = "/api/v0/journal/assistant/summary-jobs",
+    request_body = Option<SummaryOptions>,
+    responses(
+        (status = OK, description = "The summary is already available.", body = SummaryJob),
+        (status = ACCEPTED, description = "The summary job was queued. Poll it for the result.", body = SummaryJob),
+        (status = INTERNAL_SERVER_ERROR, description = "Something went wrong. Check response body.", body = ErrorData<AssistantErrorType>),
+    ),
+    tag = "v0/journal",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Journal", ty = "Permission")]
+pub(crate) async fn create_summary_job(
+    ExtractUserId(user_id): ExtractUserId,
+    Extension(conn): Extension<DatabaseConnection>,
+    options: Option<Json<SummaryOptions>>,
+) -> Result<impl IntoResponse, AssistantError> {
+    let time = options.and_then(|Json(options)| options.time);
+    let job = request_summary(&conn, user_id, time).await?;
+    tracing::debug!(%user_id, job_id = ?job.id, status = ?job.status, "requested summary job");
+    Ok((job_status_code(&job), Json(job)))
+}
+
+/// Get the state of a summary job
+///
+/// With `wait` set the request is held open until the job finished or the given number of seconds passed.
+#[utoipa::path(
+    get,
+    path = "/api/v0/journal/assistant/summary-jobs/{job_id}",
+    params(
+        ("job_id" = Uuid, Path, description = "The id of the summary job"),
+        SummaryJobQuery,
+    ),
+    responses(
+        (status = OK, description = "The job is finished.", body = SummaryJob),
+        (status = ACCEPTED, description = "The job is still queued or running.", body = SummaryJob),
+        (status = NOT_FOUND, description = "The job does not exist."),
+        (status = INTERNAL_SERVER_ERROR, description = "Something went wrong. Check response body.", body = ErrorData<AssistantErrorType>),
+    ),
+    tag = "v0/journal",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Journal", ty = "Permission")]
+pub(crate) async fn get_summary_job_handler(
+    ExtractUserId(user_id): ExtractUserId,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path(job_id): Path<Uuid>,
+    Query(query): Query<SummaryJobQuery>,
+) -> Result<impl IntoResponse, AssistantError> {
+    let job = match query.wait {
+        Some(wait) if wait > 0 => {
+            let timeout = Duration::from_secs(wait.min(MAX_WAIT_SECONDS));
+            wait_for_summary_job(&conn, user_id, job_id, timeout).await?
+        }
+        _ => get_summary_job(&conn, user_id, job_id).await?,
+    };
+    let Some(job) = job else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+    Ok((job_status_code(&job), Json(job)).into_response())
+}
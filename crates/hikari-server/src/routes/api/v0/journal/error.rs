@@ -23,6 +23,9 @@ pub(crate) enum JournalError {
 
     #[error("Field {0} data too large")]
     TooLarge(String),
+
+    #[error("Invalid range: {0}")]
+    InvalidRange(String),
 }
 
 impl IntoResponse for JournalError {
@@ -30,6 +33,7 @@ impl IntoResponse for JournalError {
         match self {
             Self::NotFound | Self::SeaOrmError(DbErr::RecordNotFound(_)) => StatusCode::NOT_FOUND.into_response(),
             Self::TooLarge(_) => StatusCode::BAD_REQUEST.into_response(),
+            Self::InvalidRange(message) => (StatusCode::BAD_REQUEST, message).into_response(),
             _ => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
         }
     }
@@ -0,0 +1,79 @@
+use crate::AppConfig;
+use crate::permissions::Permission;
+use crate::routes::api::v0::journal::error::JournalError;
+use crate::user::ExtractUserId;
+use axum::Extension;
+use axum::Json;
+use axum::extract::Query;
+use axum::response::IntoResponse;
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use hikari_core::journal::insights::{InsightsOptions, get_insights};
+use hikari_db::sea_orm::DatabaseConnection;
+use hikari_model::journal::insights::{InsightsGranularity, JournalInsights};
+use protect_axum::protect;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct InsightsFilter {
+    pub from: Option<DateTime<FixedOffset>>,
+    pub to: Option<DateTime<FixedOffset>>,
+    #[serde(default)]
+    pub granularity: InsightsGranularity,
+    pub moving_average_periods: Option<u32>,
+    pub min_topic_occurrences: Option<u32>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v0/journal/insights",
+    params(
+        ("from" = Option<DateTime<FixedOffset>>, Query, description = "Start of the window (inclusive). Defaults to the configured number of days before `to`"),
+        ("to" = Option<DateTime<FixedOffset>>, Query, description = "End of the window (exclusive). Its offset is used to assign entries to days. Defaults to now"),
+        ("granularity" = Option<InsightsGranularity>, Query, description = "Size of the periods of the mood trend"),
+        ("moving-average-periods" = Option<u32>, Query, description = "Number of periods averaged for the mood trend"),
+        ("min-topic-occurrences" = Option<u32>, Query, description = "Number of summaries a topic has to appear in to count as recurring"),
+    ),
+    responses(
+        (status = OK, description = "Insights computed from the journal of the user", body = JournalInsights),
+        (status = BAD_REQUEST, description = "The window is empty or larger than allowed"),
+    ),
+    tag = "v0/journal",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Journal", ty = "Permission")]
+pub(crate) async fn get_journal_insights(
+    ExtractUserId(user): ExtractUserId,
+    Extension(app_config): Extension<AppConfig>,
+    Extension(conn): Extension<DatabaseConnection>,
+    Query(filter): Query<InsightsFilter>,
+) -> Result<impl IntoResponse, JournalError> {
+    let config = &app_config.config().journal().insights;
+    let to = filter.to.unwrap_or_else(|| Utc::now().fixed_offset());
+    let from = filter
+        .from
+        .unwrap_or_else(|| to - Duration::days(i64::from(config.default_window_days)));
+
+    if from >= to {
+        return Err(JournalError::InvalidRange("from has to be before to".to_owned()));
+    }
+    if to - from > Duration::days(i64::from(config.max_window_days)) {
+        return Err(JournalError::InvalidRange(format!(
+            "the window must not exceed {} days",
+            config.max_window_days
+        )));
+    }
+
+    let options = InsightsOptions {
+        from,
+        to,
+        granularity: filter.granularity,
+        moving_average_periods: filter.moving_average_periods.unwrap_or(config.moving_average_periods),
+        min_topic_occurrences: filter.min_topic_occurrences.unwrap_or(config.min_topic_occurrences),
+    };
+    let today = Utc::now().with_timezone(to.offset()).date_naive();
+    let insights = get_insights(&conn, user, &options, today).await?;
+    Ok(Json(insights))
+}
@@ -1,6 +1,7 @@
 pub(crate) mod focus;
 pub(crate) mod mood;
 
+use crate::AppConfig;
 use crate::permissions::Permission;
 use crate::routes::api::v0::journal::error::JournalError;
 use crate::user::ExtractUserId;
@@ -8,6 +9,7 @@ use axum::Extension;
 use axum::extract::Path;
 use axum::response::{IntoResponse, Json};
 use axum::routing::{Router, get};
+use hikari_core::crisis::{self, CrisisSource};
 use hikari_db::journal;
 use hikari_db::sea_orm::DatabaseConnection;
 use hikari_model::journal::JournalEntry;
@@ -101,6 +103,7 @@ pub(crate) async fn list_journal_entry_contents(
 pub(crate) async fn add_journal_entry_content(
     ExtractUserId(user): ExtractUserId,
     Extension(conn): Extension<DatabaseConnection>,
+    Extension(app_config): Extension<AppConfig>,
     Path(journal_entry): Path<Uuid>,
     Json(body): Json<NewJournalContent>,
 ) -> Result<impl IntoResponse, JournalError> {
@@ -109,10 +112,28 @@ pub(crate) async fn add_journal_entry_content(
         .await?
         .ok_or(JournalError::NotFound)?;
 
-    let journal_entry =
+    let crisis_config = app_config.config().crisis();
+    let text = crisis_config.checks_journal().then(|| body.content.clone());
+    let journal_content =
         journal::journal_content::Mutation::add_journal_content(&conn, journal_entry.id, body.content).await?;
+    let crisis_resources = match text {
+        Some(text) => {
+            crisis::check(
+                crisis_config,
+                app_config.llm_config(),
+                &conn,
+                user,
+                CrisisSource::Journal,
+                Some(journal_content.last_insert_id),
+                &text,
+            )
+            .await
+        }
+        None => None,
+    };
     Ok(Json(JournalContentId {
-        id: journal_entry.last_insert_id,
+        id: journal_content.last_insert_id,
+        crisis_resources,
     }))
 }
 
@@ -5,6 +5,7 @@ use crate::db::sea_orm::util::{
     finish_llm_conversation, get_last_or_create_llm_conversation, start_new_llm_conversation,
 };
 use crate::permissions::Permission;
+use crate::routes::api::v0::llm::audio::{SpeechSettings, transcribe_message};
 use crate::routes::api::v0::llm::error::LlmError;
 use crate::routes::api::v0::llm::load_slots::load_slots;
 use crate::routes::api::v0::modules::error::ModuleError;
@@ -18,19 +19,21 @@ use axum::routing::{Router, get};
 use bytes::Bytes;
 use futures_util::stream::SplitSink;
 use futures_util::{SinkExt, StreamExt};
+use hikari_config::global::crisis::CrisisConfig;
 use hikari_config::module::llm_agent::LlmService;
+use hikari_core::crisis::{self, CrisisSource};
 use hikari_core::llm_config::LlmConfig;
+use hikari_core::unlock::load_unlock_context;
 use hikari_llm::builder::LlmStructureBuilder;
 use hikari_llm::execution::agent::LlmAgent;
-use hikari_llm::execution::agent::response::Response;
+use hikari_llm::execution::agent::response::{Response, Transcription};
 use hikari_llm::execution::iterator::LlmStepIterator;
 use hikari_model::chat::TypeSafePayload;
+use hikari_model::llm::audio::AudioMessage;
 use hikari_model::llm::conversation::LlmConversation;
 use hikari_model::llm::state::LlmConversationState;
-use hikari_model::module::locked_until;
-use hikari_model::module::session::instance::SessionInstance;
+use hikari_model::module::unlock::{UnlockContext, locked_until};
 use hikari_model::user::User;
-use hikari_model_tools::convert::IntoModel;
 use protect_axum::protect;
 use sea_orm::DatabaseConnection;
 use serde_derive::{Deserialize, Serialize};
@@ -42,8 +45,10 @@ use tokio::sync::Mutex;
 use tokio::time::interval;
 use utoipa::ToSchema;
 
+pub(crate) mod audio;
 pub(crate) mod error;
 
+pub(crate) mod images;
 mod load_slots;
 
 pub(crate) fn create_router<S>() -> Router<S>
@@ -52,6 +57,8 @@ where
 {
     Router::new()
         .route("/chat/{module_id}/{session_id}/ws", get(handler))
+        .nest("/images", images::create_router())
+        .nest("/audio", audio::create_router())
         .with_state(())
 }
 
@@ -72,22 +79,49 @@ pub(crate) struct ConnectionInfo {
     pub current_sequence: u16, //Fixme: Don't know if u16 makes sense
 }
 
+#[derive(ToSchema, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct SpeechOutput {
+    /// Whether completed messages are additionally sent as audio
+    pub enabled: bool,
+}
+
 #[derive(ToSchema, Serialize, Deserialize, Debug)]
 #[serde(rename_all = "snake_case", tag = "type", content = "value")]
 pub(crate) enum Request {
     Chat(ChatRequest<TypeSafePayload>),
+    Audio(AudioMessage),
+    SpeechOutput(SpeechOutput),
     ConnectionInfo(ConnectionInfo),
+    /// Generates a new answer to the last message, the previous answer is kept as a branch
+    Regenerate,
+    /// Replaces the last message and generates a new answer, the previous exchange is kept as a branch
+    EditMessage(ChatRequest<TypeSafePayload>),
     Abort,
     Restart,
     ControllMessage,
 }
 
+/// What the agent is asked to answer
+#[derive(Debug)]
+enum AgentInput {
+    /// A message of the user or `None` to continue the conversation
+    Message {
+        message: Option<TypeSafePayload>,
+        history_needed: bool,
+    },
+    /// The last message of the user again
+    Regenerate,
+    /// A replacement of the last message of the user
+    Edit(TypeSafePayload),
+}
+
 async fn start_conversation(
     user: &User,
     config: &AppConfig,
     module_id: &str,
     session_id: &str,
-    entries: &[SessionInstance],
+    unlock_context: &UnlockContext,
     conn: &DatabaseConnection,
     force_start: bool,
 ) -> Result<
@@ -118,7 +152,7 @@ async fn start_conversation(
         content
             .unlock
             .as_ref()
-            .is_none_or(|unlock| locked_until(unlock, entries).is_none())
+            .is_none_or(|unlock| locked_until(unlock, module_id, unlock_context).is_none())
     });
 
     let primary_documents = tailored_session
@@ -183,14 +217,10 @@ async fn create_agent(
     constants: &HashMap<String, yaml_serde::Value>,
     force: bool,
 ) -> Result<LlmAgent, LlmError> {
-    let session_instances: Vec<_> = hikari_db::module::session::status::Query::for_module(conn, user.id, module_id)
-        .await?
-        .into_iter()
-        .map(IntoModel::into_model)
-        .collect();
+    let unlock_context = load_unlock_context(conn, user, config.assessments()).await?;
 
     let (llm_conversation, llm_structure, llm_state, primary_documents, secondary_documents, llm_service) =
-        start_conversation(user, config, module_id, session_id, &session_instances, conn, force).await?;
+        start_conversation(user, config, module_id, session_id, &unlock_context, conn, force).await?;
 
     let mut llm_structure = llm_structure;
     llm_structure.with_constants(constants, false);
@@ -266,6 +296,7 @@ async fn process_message(
     llm_config: &LlmConfig,
     constants: &HashMap<String, yaml_serde::Value>,
     llm_agent: &mut Option<Mutex<LlmAgent>>,
+    speech_output: &mut bool,
 ) -> Result<(), LlmError> {
     let request_message = convert_message(message)?;
     handle_message(
@@ -278,6 +309,7 @@ async fn process_message(
         llm_config,
         constants,
         llm_agent,
+        speech_output,
         request_message,
     )
     .await
@@ -335,6 +367,7 @@ pub async fn handle_socket(
         }
     };
 
+    let mut speech_output = false;
     let mut ping_interval = interval(Duration::from_secs(30));
 
     loop {
@@ -362,6 +395,7 @@ pub async fn handle_socket(
                             &llm_config,
                             constants,
                             &mut llm_agent,
+                            &mut speech_output,
                         ).await;
                         if let Err(e) = res {
                             send_error(&mut sender, e).await;
@@ -402,9 +436,20 @@ async fn handle_message(
     llm_config: &LlmConfig,
     constants: &HashMap<String, yaml_serde::Value>,
     llm_agent: &mut Option<Mutex<LlmAgent>>,
+    speech_output: &mut bool,
     message: Request,
 ) -> Result<(), LlmError> {
-    let action = handle_request(llm_agent.as_ref(), sender, message).await?;
+    let action = handle_request(
+        conn,
+        user,
+        llm_config,
+        config.config().crisis(),
+        llm_agent.as_ref(),
+        speech_output,
+        sender,
+        message,
+    )
+    .await?;
     match action {
         ResponseAction::Abort => {
             hikari_db::llm::conversation::Mutation::close_open_conversations(conn, user.id, module_id, session_id)
@@ -415,7 +460,8 @@ async fn handle_message(
             let new_agent =
                 create_agent(user, config, module_id, session_id, conn, llm_config, constants, true).await?;
             *llm_agent = Some(Mutex::new(new_agent));
-            send_connection_info(llm_agent.as_ref(), sender, false).await?;
+            let speech = speech_context(conn, user, llm_config, *speech_output);
+            send_connection_info(llm_agent.as_ref(), speech, sender, false).await?;
             Ok(())
         }
         ResponseAction::SetFinished => {
@@ -431,26 +477,88 @@ async fn handle_message(
     }
 }
 
+/// The settings for the spoken output, if the client enabled it
+fn speech_context<'a>(
+    conn: &'a DatabaseConnection,
+    user: &User,
+    llm_config: &'a LlmConfig,
+    speech_output: bool,
+) -> Option<SpeechSettings<'a>> {
+    speech_output.then(|| SpeechSettings::new(conn, llm_config, user.id))
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn handle_request(
+    conn: &DatabaseConnection,
+    user: &User,
+    llm_config: &LlmConfig,
+    crisis_config: &CrisisConfig,
     llm_agent: Option<&Mutex<LlmAgent>>,
+    speech_output: &mut bool,
     sender: &mut SplitSink<WebSocket, WsMessage>,
     request: Request,
 ) -> Result<ResponseAction, LlmError> {
-    tracing::debug!(request = ?request, "received request");
+    // The audio data is not logged
+    if let Request::Audio(audio) = &request {
+        tracing::debug!(mime_type = %audio.mime_type, size = audio.data.len(), "received audio request");
+    } else {
+        tracing::debug!(request = ?request, "received request");
+    }
     // TODO we currently block the websocket connection until the message is processed
     // TODO consider moving the processing to a separate task
+    let speech = speech_context(conn, user, llm_config, *speech_output);
     match request {
         Request::Chat(chat_message) => {
             tracing::trace!("chat message received");
             let message = chat_message.payload;
-            generate_agent_response(llm_agent, sender, Some(message.clone()), false).await
+            if let Some(text) = message.clone().message_string() {
+                check_crisis(conn, user, llm_config, crisis_config, llm_agent, sender, &text).await?;
+            }
+            let input = AgentInput::Message {
+                message: Some(message),
+                history_needed: false,
+            };
+            generate_agent_response(llm_agent, speech, sender, input).await
+        }
+        Request::Audio(audio) => {
+            tracing::trace!("audio message received");
+            let conversation_id = llm_agent.ok_or(LlmError::NoAgent)?.lock().await.conversation_id();
+            let content = transcribe_message(conn, llm_config, user.id, conversation_id, audio).await?;
+            let transcription = Transcription {
+                audio_id: content.audio_id,
+                text: content.text.clone(),
+            };
+            send_response(sender, &Response::Transcription(transcription)).await?;
+            check_crisis(conn, user, llm_config, crisis_config, llm_agent, sender, &content.text).await?;
+            let input = AgentInput::Message {
+                message: Some(TypeSafePayload::Audio(content)),
+                history_needed: false,
+            };
+            generate_agent_response(llm_agent, speech, sender, input).await
+        }
+        Request::SpeechOutput(SpeechOutput { enabled }) => {
+            tracing::trace!(enabled, "speech output changed");
+            *speech_output = enabled;
+            Ok(ResponseAction::Nothing)
         }
         Request::ConnectionInfo(connection_info) => {
             tracing::trace!(
                 current_sequence = connection_info.current_sequence,
                 "connection info received"
             );
-            send_connection_info(llm_agent, sender, connection_info.history_needed).await
+            send_connection_info(llm_agent, speech, sender, connection_info.history_needed).await
+        }
+        Request::Regenerate => {
+            tracing::trace!("regenerate message received");
+            generate_agent_response(llm_agent, speech, sender, AgentInput::Regenerate).await
+        }
+        Request::EditMessage(chat_message) => {
+            tracing::trace!("edit message received");
+            let message = chat_message.payload;
+            if let Some(text) = message.clone().message_string() {
+                check_crisis(conn, user, llm_config, crisis_config, llm_agent, sender, &text).await?;
+            }
+            generate_agent_response(llm_agent, speech, sender, AgentInput::Edit(message)).await
         }
         Request::Abort => {
             tracing::trace!("abort message received");
@@ -464,30 +572,87 @@ async fn handle_request(
     }
 }
 
+/// Sends the hotlines before the response of the agent if the message contains signals of acute distress
+async fn check_crisis(
+    conn: &DatabaseConnection,
+    user: &User,
+    llm_config: &LlmConfig,
+    crisis_config: &CrisisConfig,
+    llm_agent: Option<&Mutex<LlmAgent>>,
+    sender: &mut SplitSink<WebSocket, WsMessage>,
+    text: &str,
+) -> Result<(), LlmError> {
+    if !crisis_config.checks_chat() {
+        return Ok(());
+    }
+    let conversation_id = match llm_agent {
+        Some(agent) => Some(agent.lock().await.conversation_id()),
+        None => None,
+    };
+    let resources = crisis::check(
+        crisis_config,
+        llm_config,
+        conn,
+        user.id,
+        CrisisSource::Chat,
+        conversation_id,
+        text,
+    )
+    .await;
+    if let Some(resources) = resources {
+        send_response(sender, &Response::CrisisResources(resources)).await?;
+    }
+    Ok(())
+}
+
 async fn send_connection_info(
     llm_agent: Option<&Mutex<LlmAgent>>,
+    speech: Option<SpeechSettings<'_>>,
     sender: &mut SplitSink<WebSocket, Message>,
     history_needed: bool,
 ) -> Result<ResponseAction, LlmError> {
-    generate_agent_response(llm_agent, sender, None, history_needed).await
+    let input = AgentInput::Message {
+        message: None,
+        history_needed,
+    };
+    generate_agent_response(llm_agent, speech, sender, input).await
 }
 
 async fn generate_agent_response(
     llm_agent: Option<&Mutex<LlmAgent>>,
+    speech: Option<SpeechSettings<'_>>,
     sender: &mut SplitSink<WebSocket, WsMessage>,
-    message: Option<TypeSafePayload>,
-    history_needed: bool,
+    input: AgentInput,
 ) -> Result<ResponseAction, LlmError> {
-    tracing::debug!(?message, "sending message to agent");
+    tracing::debug!(?input, "sending message to agent");
     let llm_agent = llm_agent.ok_or(LlmError::NoAgent)?;
     let mut agent_guard = llm_agent.lock().await;
-    let stream = agent_guard.chat(message, history_needed);
+    let conversation_id = agent_guard.conversation_id();
+    let mut speech = speech.map(|settings| settings.speech(conversation_id));
+    // Rewinding always sends the history of the new branch
+    let stream = match input {
+        AgentInput::Message {
+            message,
+            history_needed,
+        } => agent_guard.chat(message, history_needed),
+        AgentInput::Regenerate => agent_guard.regenerate(),
+        AgentInput::Edit(message) => agent_guard.edit_last_message(message),
+    };
     let mut stream = pin!(stream);
     while let Some(response) = stream.next().await {
         match response {
             Ok(response) => {
+                let end = matches!(response, Response::ConversationEnd);
+                if let Some(speech) = &mut speech {
+                    // The audio of all messages is sent before the conversation ends
+                    if end {
+                        speech.finish(sender).await?;
+                    } else {
+                        speech.push(sender, &response).await?;
+                    }
+                }
                 send_response(sender, &response).await?;
-                if matches!(response, Response::ConversationEnd) {
+                if end {
                     return Ok(ResponseAction::SetFinished);
                 }
             }
@@ -496,6 +661,9 @@ async fn generate_agent_response(
             }
         }
     }
+    if let Some(speech) = &mut speech {
+        speech.finish(sender).await?;
+    }
     Ok(ResponseAction::Nothing)
 }
 
@@ -559,4 +727,25 @@ mod tests {
         );
         assert_eq!(serialized, expected);
     }
+
+    #[test]
+    fn test_rewind_request_deserialization() {
+        let request: Request = serde_json::from_value(json!({"type": "regenerate"})).unwrap();
+        assert!(matches!(request, Request::Regenerate));
+
+        let request: Request = serde_json::from_value(json!({
+            "type": "edit_message",
+            "value": {
+                "payload": {
+                    "content_type": "text",
+                    "content": {"text": "Neue Frage"}
+                }
+            }
+        }))
+        .unwrap();
+        let Request::EditMessage(chat_message) = request else {
+            panic!("expected an edit request");
+        };
+        assert_eq!(chat_message.payload.message_string().as_deref(), Some("Neue Frage"));
+    }
 }
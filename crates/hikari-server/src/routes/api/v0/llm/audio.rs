@@ -0,0 +1,358 @@
+use crate::audit::Audit;
+use crate::permissions::Permission;
+use crate::routes::api::v0::llm::error::LlmError;
+use crate::user::ExtractUserId;
+use axum::extract::Path;
+use axum::extract::ws::{Message as WsMessage, WebSocket};
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::{Extension, Router};
+use base64::Engine;
+use futures_util::SinkExt;
+use futures_util::stream::SplitSink;
+use hikari_core::llm_config::LlmConfig;
+use hikari_core::openai::audio::{SPEECH_MIME_TYPE, audio_file_name, synthesize, transcribe};
+use hikari_db::llm::audio::{Mutation, Query};
+use hikari_llm::execution::agent::response::{AudioChunk, ChatChunk, Response};
+use hikari_model::chat::AudioContent;
+use hikari_model::llm::audio::AudioMessage;
+use protect_axum::protect;
+use sea_orm::DatabaseConnection;
+use serde_json::json;
+use std::collections::VecDeque;
+use std::error::Error;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+/// Upload limit of the transcription endpoint
+const MAX_AUDIO_SIZE: usize = 25 * 1024 * 1024;
+
+/// Size of the decoded audio data per chunk, a multiple of 3 so the base64 chunks have no padding in between
+const AUDIO_CHUNK_SIZE: usize = 48 * 1024;
+
+pub(crate) fn create_router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/{audio_id}", get(get_audio).delete(delete_audio))
+        .with_state(())
+}
+
+/// Returns a voice message or the spoken output of a message
+#[utoipa::path(
+    get,
+    path = "/api/v0/llm/audio/{audio_id}",
+    params(
+        ("audio_id" = Uuid, Path, description = "The id of the audio"),
+    ),
+    responses(
+        (status = OK, description = "The audio with its mime type as content type"),
+        (status = NOT_FOUND, description = "The audio does not exist"),
+    ),
+    tag = "v0/llm",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Basic", ty = "Permission")]
+pub(crate) async fn get_audio(
+    ExtractUserId(user_id): ExtractUserId,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path(audio_id): Path<Uuid>,
+) -> Result<impl IntoResponse, LlmError> {
+    let audio = Query::get_audio(&conn, user_id, audio_id)
+        .await?
+        .ok_or(LlmError::AudioNotFound)?;
+    Ok(([(http::header::CONTENT_TYPE, audio.mime_type)], audio.data))
+}
+
+/// Deletes a voice message or the spoken output of a message, the message keeps its transcript
+#[utoipa::path(
+    delete,
+    path = "/api/v0/llm/audio/{audio_id}",
+    params(
+        ("audio_id" = Uuid, Path, description = "The id of the audio"),
+    ),
+    responses(
+        (status = NO_CONTENT, description = "The audio was deleted"),
+        (status = NOT_FOUND, description = "The audio does not exist"),
+    ),
+    tag = "v0/llm",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Basic", ty = "Permission")]
+pub(crate) async fn delete_audio(
+    ExtractUserId(user_id): ExtractUserId,
+    audit: Audit,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path(audio_id): Path<Uuid>,
+) -> Result<impl IntoResponse, LlmError> {
+    audit
+        .record("delete_llm_audio", Some(user_id), json!({ "audio_id": audio_id }))
+        .await?;
+    if !Mutation::delete_audio(&conn, user_id, audio_id).await? {
+        return Err(LlmError::AudioNotFound);
+    }
+    tracing::debug!(%user_id, %audio_id, "audio deleted");
+    Ok(http::StatusCode::NO_CONTENT)
+}
+
+fn decode_audio(message: &AudioMessage) -> Result<(&'static str, Vec<u8>), LlmError> {
+    let file_name = audio_file_name(&message.mime_type)
+        .ok_or_else(|| LlmError::RequestError(format!("unsupported audio type: {}", message.mime_type)))?;
+    let data = base64::engine::general_purpose::STANDARD
+        .decode(&message.data)
+        .map_err(|error| LlmError::RequestError(format!("invalid audio data: {error}")))?;
+    if data.is_empty() || data.len() > MAX_AUDIO_SIZE {
+        return Err(LlmError::RequestError(format!(
+            "the audio has to be between 1 byte and {MAX_AUDIO_SIZE} bytes"
+        )));
+    }
+    Ok((file_name, data))
+}
+
+/// Transcribes the voice message and stores it with its transcript
+pub(crate) async fn transcribe_message(
+    conn: &DatabaseConnection,
+    llm_config: &LlmConfig,
+    user_id: Uuid,
+    conversation_id: Uuid,
+    message: AudioMessage,
+) -> Result<AudioContent, LlmError> {
+    let (file_name, data) = decode_audio(&message)?;
+    let text = transcribe(
+        llm_config,
+        &user_id.to_string(),
+        file_name,
+        data.clone(),
+        message.language.as_deref(),
+    )
+    .await?;
+    if text.is_empty() {
+        return Err(LlmError::RequestError("no speech recognized".to_string()));
+    }
+    let audio =
+        Mutation::insert_voice_message(conn, user_id, conversation_id, message.mime_type, data, text.clone()).await?;
+    tracing::debug!(%user_id, audio_id = %audio.id, "voice message transcribed");
+    Ok(AudioContent {
+        audio_id: audio.id,
+        text,
+    })
+}
+
+/// Splits the audio into the chunks sent to the client
+fn audio_chunks(id: i32, audio_id: Uuid, mime_type: &str, data: &[u8]) -> Vec<AudioChunk> {
+    let count = data.chunks(AUDIO_CHUNK_SIZE).count();
+    data.chunks(AUDIO_CHUNK_SIZE)
+        .enumerate()
+        .map(|(index, chunk)| AudioChunk {
+            id,
+            audio_id,
+            mime_type: mime_type.to_string(),
+            data: base64::engine::general_purpose::STANDARD.encode(chunk),
+            sequence: u32::try_from(index).unwrap_or(u32::MAX),
+            last: index + 1 == count,
+        })
+        .collect()
+}
+
+/// Collects the streamed chunks of the messages, a message is complete once the next one starts
+#[derive(Default)]
+struct BubbleCollector {
+    current: Option<(i32, String)>,
+}
+
+impl BubbleCollector {
+    /// Adds the chunk and returns the previous message if it is complete
+    fn push(&mut self, chunk: &ChatChunk) -> Option<(i32, String)> {
+        match &mut self.current {
+            Some((id, content)) if *id == chunk.id => {
+                content.push_str(&chunk.content);
+                None
+            }
+            _ => self.current.replace((chunk.id, chunk.content.clone())),
+        }
+    }
+
+    fn finish(&mut self) -> Option<(i32, String)> {
+        self.current.take()
+    }
+}
+
+/// What is needed for the spoken output of a connection
+#[derive(Clone, Copy)]
+pub(crate) struct SpeechSettings<'a> {
+    conn: &'a DatabaseConnection,
+    llm_config: &'a LlmConfig,
+    user_id: Uuid,
+}
+
+impl<'a> SpeechSettings<'a> {
+    pub(crate) fn new(conn: &'a DatabaseConnection, llm_config: &'a LlmConfig, user_id: Uuid) -> Self {
+        Self {
+            conn,
+            llm_config,
+            user_id,
+        }
+    }
+
+    pub(crate) fn speech(self, conversation_id: Uuid) -> Speech<'a> {
+        Speech {
+            settings: self,
+            conversation_id,
+            bubbles: BubbleCollector::default(),
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+/// Spoken output of the completed messages of a response.
+/// Speech is synthesized next to the agent, the audio is sent in the order of the messages once it is ready.
+pub(crate) struct Speech<'a> {
+    settings: SpeechSettings<'a>,
+    conversation_id: Uuid,
+    bubbles: BubbleCollector,
+    pending: VecDeque<JoinHandle<Result<Vec<AudioChunk>, LlmError>>>,
+}
+
+impl Speech<'_> {
+    /// Starts speaking the previous message if the response starts a new one and sends the audio which is ready
+    pub(crate) async fn push(
+        &mut self,
+        sender: &mut SplitSink<WebSocket, WsMessage>,
+        response: &Response,
+    ) -> Result<(), LlmError> {
+        let completed = match response {
+            Response::Chat(chunk) => self.bubbles.push(chunk),
+            _ => self.bubbles.finish(),
+        };
+        if let Some((id, text)) = completed {
+            self.speak(id, text);
+        }
+        while self.pending.front().is_some_and(JoinHandle::is_finished) {
+            if let Some(task) = self.pending.pop_front() {
+                send_audio(sender, task).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Speaks the last message and waits until the audio of all messages is sent
+    pub(crate) async fn finish(&mut self, sender: &mut SplitSink<WebSocket, WsMessage>) -> Result<(), LlmError> {
+        if let Some((id, text)) = self.bubbles.finish() {
+            self.speak(id, text);
+        }
+        while let Some(task) = self.pending.pop_front() {
+            send_audio(sender, task).await?;
+        }
+        Ok(())
+    }
+
+    fn speak(&mut self, id: i32, text: String) {
+        let text = text.trim().to_owned();
+        if text.is_empty() {
+            return;
+        }
+        let conn = self.settings.conn.clone();
+        let llm_config = self.settings.llm_config.clone();
+        let user_id = self.settings.user_id;
+        let conversation_id = self.conversation_id;
+        self.pending.push_back(tokio::spawn(async move {
+            // Failing speech output does not interrupt the conversation, the message is only shown as text
+            let data = match synthesize(&llm_config, &user_id.to_string(), &text).await {
+                Ok(data) => data,
+                Err(error) => {
+                    tracing::warn!(error = &error as &dyn Error, id, "failed to synthesize speech");
+                    return Ok(vec![]);
+                }
+            };
+            let audio = Mutation::insert_message_speech(
+                &conn,
+                user_id,
+                conversation_id,
+                id,
+                SPEECH_MIME_TYPE.to_string(),
+                data,
+            )
+            .await?;
+            Ok(audio_chunks(id, audio.id, &audio.mime_type, &audio.data))
+        }));
+    }
+}
+
+impl Drop for Speech<'_> {
+    fn drop(&mut self) {
+        // Nobody receives the audio once the response is aborted
+        for task in &self.pending {
+            task.abort();
+        }
+    }
+}
+
+async fn send_audio(
+    sender: &mut SplitSink<WebSocket, WsMessage>,
+    task: JoinHandle<Result<Vec<AudioChunk>, LlmError>>,
+) -> Result<(), LlmError> {
+    let chunks = match task.await {
+        Ok(chunks) => chunks?,
+        Err(error) => {
+            tracing::error!(error = &error as &dyn Error, "speech synthesis task failed");
+            return Ok(());
+        }
+    };
+    for chunk in chunks {
+        let message = serde_json::to_string(&Response::Audio(chunk))?;
+        sender
+            .send(WsMessage::Text(message.into()))
+            .await
+            .map_err(LlmError::SendError)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bubble_collector() {
+        let chunk = |id: i32, content: &str| ChatChunk::new(content.to_owned(), id, "step".to_owned());
+        let mut bubbles = BubbleCollector::default();
+        assert_eq!(bubbles.push(&chunk(1, "Hal")), None);
+        assert_eq!(bubbles.push(&chunk(1, "lo")), None);
+        assert_eq!(bubbles.push(&chunk(2, "Wie")), Some((1, "Hallo".to_owned())));
+        assert_eq!(bubbles.finish(), Some((2, "Wie".to_owned())));
+        assert_eq!(bubbles.finish(), None);
+    }
+
+    #[test]
+    fn test_audio_chunks() {
+        let data = vec![0; AUDIO_CHUNK_SIZE * 2 + 1];
+        let chunks = audio_chunks(1, Uuid::nil(), SPEECH_MIME_TYPE, &data);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks.iter().filter(|chunk| chunk.last).count(), 1);
+        assert!(chunks.last().unwrap().last);
+        assert_eq!(chunks.last().unwrap().sequence, 2);
+
+        let decoded: Vec<u8> = chunks
+            .iter()
+            .flat_map(|chunk| base64::engine::general_purpose::STANDARD.decode(&chunk.data).unwrap())
+            .collect();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_decode_audio() {
+        let message = |mime_type: &str, data: &str| AudioMessage {
+            mime_type: mime_type.to_owned(),
+            data: data.to_owned(),
+            language: None,
+        };
+        assert_eq!(decode_audio(&message("audio/webm", "AQID")).unwrap().0, "audio.webm");
+        assert!(decode_audio(&message("text/plain", "AQID")).is_err());
+        assert!(decode_audio(&message("audio/webm", "")).is_err());
+    }
+}
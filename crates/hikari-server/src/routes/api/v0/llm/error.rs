@@ -28,6 +28,14 @@ pub(crate) enum LlmError {
     AgentUnspecified,
     #[error("No agent found")]
     NoAgent,
+    #[error("Image not found")]
+    ImageNotFound,
+    #[error("The images of the user exceed the storage quota")]
+    ImageQuotaExceeded,
+    #[error("Audio not found")]
+    AudioNotFound,
+    #[error(transparent)]
+    OpenAiError(#[from] hikari_core::openai::error::OpenAiError),
     #[error(transparent)]
     LlmExecutionError(#[from] LlmExecutionError),
     #[error(transparent)]
@@ -66,6 +74,26 @@ impl LlmError {
                 error: self.to_string(),
                 status_code: 502,
             },
+            LlmError::ImageNotFound
+            | LlmError::AudioNotFound
+            | LlmError::LlmExecutionError(
+                hikari_llm::execution::error::LlmExecutionError::ImageNotFound(_)
+                | hikari_llm::execution::error::LlmExecutionError::AudioNotFound(_),
+            ) => ErrorResponse {
+                error: self.to_string(),
+                status_code: 404,
+            },
+            LlmError::ImageQuotaExceeded => ErrorResponse {
+                error: self.to_string(),
+                status_code: 413,
+            },
+            LlmError::LlmExecutionError(
+                hikari_llm::execution::error::LlmExecutionError::NoUserMessage
+                | hikari_llm::execution::error::LlmExecutionError::ConversationEnded,
+            ) => ErrorResponse {
+                error: self.to_string(),
+                status_code: 409,
+            },
             other => ErrorResponse {
                 error: other.to_string(),
                 status_code: 500,
@@ -80,6 +108,8 @@ impl IntoResponse for LlmError {
             LlmError::RequestError(_) | LlmError::ModuleError(_) => {
                 http::status::StatusCode::BAD_REQUEST.into_response()
             }
+            LlmError::ImageNotFound | LlmError::AudioNotFound => http::status::StatusCode::NOT_FOUND.into_response(),
+            LlmError::ImageQuotaExceeded => http::status::StatusCode::PAYLOAD_TOO_LARGE.into_response(),
             _ => http::status::StatusCode::INTERNAL_SERVER_ERROR.into_response(),
         }
     }
@@ -0,0 +1,174 @@
+use crate::audit::Audit;
+use crate::permissions::Permission;
+use crate::routes::api::v0::llm::error::LlmError;
+use crate::user::ExtractUserId;
+use axum::extract::{DefaultBodyLimit, Path};
+use axum::response::IntoResponse;
+use axum::routing::{get, post};
+use axum::{Extension, Json, Router};
+use base64::Engine;
+use hikari_db::llm::image::{Mutation, Query};
+use hikari_db::util::FlattenTransactionResultExt;
+use hikari_model::llm::image::{ImageUpload, UploadedImage};
+use hikari_model_tools::convert::IntoModel;
+use protect_axum::protect;
+use sea_orm::{DatabaseConnection, TransactionTrait};
+use serde_json::json;
+use uuid::Uuid;
+
+/// The image types vision-capable models accept
+const SUPPORTED_MIME_TYPES: [&str; 4] = ["image/png", "image/jpeg", "image/webp", "image/gif"];
+
+const MAX_IMAGE_SIZE: usize = 10 * 1024 * 1024;
+/// The image is sent base64 encoded in a JSON body, which is a third larger than the image itself
+const MAX_UPLOAD_SIZE: usize = MAX_IMAGE_SIZE.div_ceil(3) * 4 + 1024;
+/// Total size of the stored images of a user
+const MAX_STORAGE_PER_USER: i64 = 100 * 1024 * 1024;
+
+pub(crate) fn create_router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/", post(upload_image).layer(DefaultBodyLimit::max(MAX_UPLOAD_SIZE)))
+        .route("/{image_id}", get(get_image).delete(delete_image))
+        .with_state(())
+}
+
+fn decode_upload(upload: ImageUpload) -> Result<(String, Vec<u8>), LlmError> {
+    let ImageUpload { mime_type, data } = upload;
+    if !SUPPORTED_MIME_TYPES.contains(&mime_type.as_str()) {
+        return Err(LlmError::RequestError(format!("unsupported image type: {mime_type}")));
+    }
+    let data = base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|error| LlmError::RequestError(format!("invalid image data: {error}")))?;
+    if data.is_empty() || data.len() > MAX_IMAGE_SIZE {
+        return Err(LlmError::RequestError(format!(
+            "the image has to be between 1 byte and {MAX_IMAGE_SIZE} bytes"
+        )));
+    }
+    Ok((mime_type, data))
+}
+
+/// Stores an image which can be sent in agent conversations as image message
+#[utoipa::path(
+    post,
+    path = "/api/v0/llm/images",
+    request_body = ImageUpload,
+    responses(
+        (status = OK, body = UploadedImage, description = "The stored image"),
+        (status = BAD_REQUEST, description = "The image type is not supported or the data is invalid"),
+        (status = PAYLOAD_TOO_LARGE, description = "The images of the user exceed the storage quota"),
+    ),
+    tag = "v0/llm",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Basic", ty = "Permission")]
+pub(crate) async fn upload_image(
+    ExtractUserId(user_id): ExtractUserId,
+    Extension(conn): Extension<DatabaseConnection>,
+    Json(upload): Json<ImageUpload>,
+) -> Result<impl IntoResponse, LlmError> {
+    let (mime_type, data) = decode_upload(upload)?;
+    let size = i64::try_from(data.len()).unwrap_or(i64::MAX);
+    let image: UploadedImage = conn
+        .transaction::<_, _, LlmError>(|txn| {
+            Box::pin(async move {
+                let used = Query::get_user_storage(txn, user_id).await?;
+                if used.saturating_add(size) > MAX_STORAGE_PER_USER {
+                    tracing::info!(%user_id, used, size, "image storage quota exceeded");
+                    return Err(LlmError::ImageQuotaExceeded);
+                }
+                Ok(Mutation::insert_image(txn, user_id, mime_type, data).await?)
+            })
+        })
+        .await
+        .flatten_res()?
+        .into_model();
+    tracing::debug!(%user_id, image_id = %image.id, "image uploaded");
+    Ok(Json(image))
+}
+
+/// Returns the raw image
+#[utoipa::path(
+    get,
+    path = "/api/v0/llm/images/{image_id}",
+    params(
+        ("image_id" = Uuid, Path, description = "The id of the image"),
+    ),
+    responses(
+        (status = OK, description = "The image with its mime type as content type"),
+        (status = NOT_FOUND, description = "The image does not exist"),
+    ),
+    tag = "v0/llm",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Basic", ty = "Permission")]
+pub(crate) async fn get_image(
+    ExtractUserId(user_id): ExtractUserId,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path(image_id): Path<Uuid>,
+) -> Result<impl IntoResponse, LlmError> {
+    let image = Query::get_image(&conn, user_id, image_id)
+        .await?
+        .ok_or(LlmError::ImageNotFound)?;
+    Ok(([(http::header::CONTENT_TYPE, image.mime_type)], image.data))
+}
+
+/// Deletes an image, messages referencing it are shown without the image
+#[utoipa::path(
+    delete,
+    path = "/api/v0/llm/images/{image_id}",
+    params(
+        ("image_id" = Uuid, Path, description = "The id of the image"),
+    ),
+    responses(
+        (status = NO_CONTENT, description = "The image was deleted"),
+        (status = NOT_FOUND, description = "The image does not exist"),
+    ),
+    tag = "v0/llm",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Basic", ty = "Permission")]
+pub(crate) async fn delete_image(
+    ExtractUserId(user_id): ExtractUserId,
+    audit: Audit,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path(image_id): Path<Uuid>,
+) -> Result<impl IntoResponse, LlmError> {
+    audit
+        .record("delete_llm_image", Some(user_id), json!({ "image_id": image_id }))
+        .await?;
+    if !Mutation::delete_image(&conn, user_id, image_id).await? {
+        return Err(LlmError::ImageNotFound);
+    }
+    tracing::debug!(%user_id, %image_id, "image deleted");
+    Ok(http::StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_upload() {
+        let upload = |mime_type: &str, data: &str| ImageUpload {
+            mime_type: mime_type.to_owned(),
+            data: data.to_owned(),
+        };
+        let (mime_type, data) = decode_upload(upload("image/png", "AQID")).unwrap();
+        assert_eq!(mime_type, "image/png");
+        assert_eq!(data, vec![1, 2, 3]);
+
+        assert!(decode_upload(upload("application/pdf", "AQID")).is_err());
+        assert!(decode_upload(upload("image/png", "not base64!")).is_err());
+        assert!(decode_upload(upload("image/png", "")).is_err());
+    }
+}
@@ -12,6 +12,7 @@ use error::ModuleError;
 use futures::future::try_join_all;
 use futures::future::try_join3;
 use hikari_config::module::next_session::Next;
+use hikari_core::unlock::load_unlock_context;
 use hikari_db::module::session::status;
 use hikari_db::util::{FlattenTransactionResultExt, InspectTransactionError};
 use hikari_model::history::{HistoryEntry, HistoryEntryType};
@@ -59,6 +60,7 @@ where
             "/{module}",
             Router::new()
                 .route("/", get(get_module))
+                .route("/assessments/change", get(assessment::get_assessment_change))
                 .nest("/assessments/{pre_post}", assessment::create_router())
                 .nest("/quizzes", quiz::create_router())
                 .nest(
@@ -107,13 +109,12 @@ pub(crate) async fn list_modules(
     Query(deep): Query<ModuleFlags>,
 ) -> Result<Response, ModuleError> {
     let conn = &conn;
-    let (session_instances, module_status, assessments) = try_join3(
-        status::Query::all(conn, user.id),
+    let (unlock_context, module_status, assessments) = try_join3(
+        load_unlock_context(conn, &user, app_config.assessments()),
         hikari_db::module::status::Query::all(conn, user.id),
         hikari_db::module::assessment::Query::all(conn, user.id),
     )
     .await?;
-    let session_instances: Vec<_> = session_instances.into_iter().map(IntoModel::into_model).collect();
     let module_completion: HashMap<_, _> = module_status.into_iter().map(|m| (m.module, m.completion)).collect();
 
     let module_cfg = app_config.module_config();
@@ -131,7 +132,7 @@ pub(crate) async fn list_modules(
             ModuleFull::from_config(
                 module,
                 deep,
-                &session_instances,
+                &unlock_context,
                 assessments.get(&module.id),
                 module_completion
                     .get(module.id.as_str())
@@ -229,11 +230,8 @@ pub(crate) async fn get_module(
     Extension(conn): Extension<DatabaseConnection>,
     Path(module_id): Path<String>,
 ) -> Result<impl IntoResponse, ModuleError> {
-    let session_instances: Vec<_> = status::Query::for_module(&conn, user.id, &module_id)
-        .await?
-        .into_iter()
-        .map(IntoModel::into_model)
-        .collect();
+    // Unlock triggers can depend on other modules, so the context covers all of them
+    let unlock_context = load_unlock_context(&conn, &user, app_config.assessments()).await?;
 
     let assessment = hikari_db::module::assessment::Query::get_for_module(&conn, user.id, &module_id)
         .await?
@@ -248,7 +246,7 @@ pub(crate) async fn get_module(
     let res: ModuleFull = ModuleFull::from_config(
         &module,
         true,
-        &session_instances,
+        &unlock_context,
         assessment.as_ref(),
         module_status.and_then(|status| status.completion).map(|c| c.and_utc()),
     );
@@ -335,11 +333,7 @@ pub(crate) async fn get_session_data(
 ) -> Result<impl IntoResponse, ModuleError> {
     let (_, session) = get_session(&module_id, &session_id, app_config.module_config(), &user.groups)?;
 
-    let session_instances: Vec<_> = status::Query::for_module(&conn, user.id, &module_id)
-        .await?
-        .into_iter()
-        .map(IntoModel::into_model)
-        .collect();
+    let unlock_context = load_unlock_context(&conn, &user, app_config.assessments()).await?;
 
     // TODO why should we create a new entry here?
 
@@ -355,7 +349,7 @@ pub(crate) async fn get_session_data(
 
     // tracing::debug!("Received User Module {:?} ", entry);
 
-    let res = SessionFull::from_config(session, &module_id, &session_instances);
+    let res = SessionFull::from_config(session, &module_id, &unlock_context);
 
     Ok(Json(res).into_response())
 }
@@ -12,7 +12,9 @@ use axum::routing::{get, post};
 use axum::{Extension, Router};
 use chrono::NaiveDateTime;
 use hikari_config::module::assessment::ModuleAssessment;
+use hikari_core::assessment::change::load_user_change;
 use hikari_entity::assessment::session::Model as AssessmentSession;
+use hikari_model::assessment::change::ModuleAssessmentChange;
 use http::StatusCode;
 use protect_axum::protect;
 use sea_orm::DatabaseConnection;
@@ -250,3 +252,45 @@ pub(crate) async fn pre_post_assessment(
 
     Ok(Json(res))
 }
+
+#[utoipa::path(
+    get,
+    path = "/api/v0/modules/{module}/assessments/change",
+    responses(
+        (status = OK, body = ModuleAssessmentChange, description = "Per-scale change between the pre- and the post-assessment"),
+        (status = NOT_FOUND, description = "The module has no assessment or the user has not finished both assessments"),
+    ),
+    params(
+        ("module" = String, Path, description = "module id of which the assessments should be compared"),
+    ),
+    tag = "v0/modules",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect(
+    "Permission::Basic
+",
+    ty = "Permission"
+)]
+pub(crate) async fn get_assessment_change(
+    ExtractUser(user): ExtractUser,
+    Extension(conn): Extension<DatabaseConnection>,
+    Extension(app_config): Extension<AppConfig>,
+    Path(module_id): Path<String>,
+) -> Result<impl IntoResponse, ModuleError> {
+    let module = app_config
+        .module_config()
+        .get_for_group(&module_id, &user.groups)
+        .ok_or(modules::error::ModuleError::ModuleNotFound)?;
+    module.assessment().ok_or(ModuleError::AssessmentNotConfigured)?;
+
+    let scales = load_user_change(&conn, user.id, &module_id, app_config.assessments())
+        .await
+        .map_err(Error::from)?
+        .ok_or(Error::NotCompleted)?;
+    Ok(Json(ModuleAssessmentChange {
+        module: module_id,
+        scales,
+    }))
+}
@@ -4,6 +4,8 @@ use crate::routes::api::v0::{assessment, bots};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use csml_engine::data::EngineError;
+use hikari_core::allocation::AllocationError;
+use hikari_core::export::error::ExportError;
 use sea_orm::DbErr;
 use std::str::Utf8Error;
 use thiserror::Error;
@@ -68,6 +70,15 @@ pub(crate) enum UserError {
 
     #[error("No groups to select")]
     NoGroupsToSelect,
+
+    #[error(transparent)]
+    Allocation(#[from] AllocationError),
+
+    #[error(transparent)]
+    Export(#[from] ExportError),
+
+    #[error("Export jobs are disabled")]
+    ExportsDisabled,
 }
 
 #[derive(Error, Debug)]
@@ -166,6 +177,8 @@ fn user_error_status(error: &UserError) -> StatusCode {
         UserError::InvalidKey => StatusCode::BAD_REQUEST,
         UserError::NotFound => StatusCode::NOT_FOUND,
         UserError::InvalidToken => StatusCode::NOT_ACCEPTABLE,
+        UserError::Export(ExportError::TooManyJobs) => StatusCode::TOO_MANY_REQUESTS,
+        UserError::ExportsDisabled => StatusCode::SERVICE_UNAVAILABLE,
         _ => StatusCode::INTERNAL_SERVER_ERROR,
     }
 }
@@ -12,12 +12,16 @@ use axum::response::IntoResponse;
 use axum::routing::{get, post};
 use axum::{Extension, Router};
 use csml_model::FlowTrigger;
+use hikari_core::crisis::{self, CrisisSource};
 use hikari_db::config;
+use hikari_db::journal::journal_content;
 use hikari_db::module::session::status;
 use hikari_db::sea_orm::DatabaseConnection;
 use hikari_db::util::FlattenTransactionResultExt;
 use hikari_entity::module::session::status::Status;
-use hikari_model::chat::{Client, Direction, Message, MessageResponse, Payload, RequestMetadata, TypeSafePayload};
+use hikari_model::chat::{
+    Client, CreatableEntity, Direction, Message, MessageResponse, Payload, RequestMetadata, TypeSafePayload,
+};
 use hikari_model::module::session::instance::SessionInstanceStatus;
 use hikari_model::user::User;
 use hikari_model_tools::convert::{FromDbModel, IntoDbModel};
@@ -111,6 +115,8 @@ pub(crate) async fn start_session(
     let configs = configs_into_map(configs)?;
     check_exclusivity(request.as_ref(), user.id, &conn, &module_id, &session_id).await?;
 
+    let user_id = user.id;
+    let app_config_clone = app_config.clone();
     let res = conn
         .transaction(|txn| {
             Box::pin(async move {
@@ -174,7 +180,8 @@ pub(crate) async fn start_session(
         })
         .await;
 
-    let res = res.flatten_res()?;
+    let mut res = res.flatten_res()?;
+    check_created_journal_entries(&app_config_clone, &conn, user_id, &mut res).await?;
 
     Ok(Json(res))
 }
@@ -232,6 +239,8 @@ pub(crate) async fn reset_session(
 
     check_exclusivity(request.as_ref(), user.id, &conn, &module_id, &session_id).await?;
 
+    let user_id = user.id;
+    let app_config_clone = app_config.clone();
     let res = conn
         .transaction(|txn| {
             Box::pin(async move {
@@ -289,7 +298,10 @@ pub(crate) async fn reset_session(
         })
         .await;
 
-    let res = res.flatten_res()?;
+    let mut res = res.flatten_res()?;
+    if let Some(res) = &mut res {
+        check_created_journal_entries(&app_config_clone, &conn, user_id, res).await?;
+    }
 
     Ok(if res.is_some() {
         Json(res).into_response()
@@ -425,6 +437,8 @@ pub(crate) async fn chat_session_inner(
     let configs = config::Query::get_user_config(&conn, user.id).await?;
     let configs = configs_into_map(configs)?;
 
+    let user_id = user.id;
+    let app_config_clone = app_config.clone();
     let res = conn
         .transaction::<_, MessageResponse<Payload>, MessagingError>(|txn| {
             Box::pin(async move {
@@ -472,7 +486,46 @@ pub(crate) async fn chat_session_inner(
         })
         .await;
 
-    res.flatten_res().map(Json)
+    let mut res = res.flatten_res()?;
+    check_created_journal_entries(&app_config_clone, &conn, user_id, &mut res).await?;
+    Ok(Json(res))
+}
+
+/// Checks the journal entries created at the end of a session for signals of acute distress. This happens after the
+/// transaction, so the classifier never holds it open.
+async fn check_created_journal_entries(
+    app_config: &AppConfig,
+    conn: &DatabaseConnection,
+    user_id: Uuid,
+    response: &mut MessageResponse<Payload>,
+) -> Result<(), MessagingError> {
+    let crisis_config = app_config.config().crisis();
+    if !crisis_config.checks_journal() {
+        return Ok(());
+    }
+    for entity in &mut response.created_entities {
+        let CreatableEntity::JournalEntry(entry) = entity;
+        let contents = journal_content::Query::get_user_journal_entry_contents(conn, user_id, entry.id).await?;
+        let text = contents
+            .into_iter()
+            .map(|content| content.content)
+            .collect::<Vec<_>>()
+            .join("\n");
+        if text.trim().is_empty() {
+            continue;
+        }
+        entry.crisis_resources = crisis::check(
+            crisis_config,
+            app_config.llm_config(),
+            conn,
+            user_id,
+            CrisisSource::Journal,
+            Some(entry.id),
+            &text,
+        )
+        .await;
+    }
+    Ok(())
 }
 
 #[utoipa::path(
@@ -0,0 +1,427 @@
+use crate::AppConfig;
+use crate::permissions::Permission;
+use crate::routes::api::v0::question_bank::error::QuestionBankError;
+use crate::user::ExtractUserId;
+use axum::extract::{Path, Query};
+use axum::response::IntoResponse;
+use axum::routing::{get, post, put};
+use axum::{Extension, Json, Router};
+use hikari_config::module::Module;
+use hikari_config::module::content::ContentExam;
+use hikari_core::quiz::bank::{MIN_PROMOTION_GRADE, entry_input_from_exam, entry_input_from_question, exam_from_entry};
+use hikari_db::quiz::question_bank::{Mutation, QuestionBankEntryInput};
+use hikari_entity::quiz::question_bank::Status;
+use hikari_model::quiz::question::Question;
+use hikari_model::quiz::question_bank::{
+    QuestionBankDocument, QuestionBankDocumentEntry, QuestionBankEntry, QuestionBankStatus,
+};
+use hikari_model_tools::convert::{IntoDbModel, IntoModel};
+use http::{HeaderValue, StatusCode, header};
+use protect_axum::protect;
+use sea_orm::{ActiveValue, DatabaseConnection, TransactionTrait};
+use serde::Deserialize;
+use std::collections::HashSet;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+mod error;
+
+pub(crate) fn create_router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .nest(
+            "/{module_id}",
+            Router::new()
+                .route("/", get(get_entries).post(promote_question))
+                .route("/candidates", get(get_candidates))
+                .route("/export", get(export_entries))
+                .route("/import", post(import_entries))
+                .route("/{entry_id}", put(update_entry).delete(delete_entry))
+                .route("/{entry_id}/review", post(review_entry)),
+        )
+        .with_state(())
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct StatusFilter {
+    pub status: Option<QuestionBankStatus>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v0/question-bank/{module_id}",
+    params(
+        ("status" = Option<QuestionBankStatus>, Query, description = "Only list entries with this review status"),
+    ),
+    responses(
+        (status = OK, body = Vec<QuestionBankEntry>, description = "Question bank of the module"),
+    ),
+    tag = "v0/question-bank",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Instructor", ty = "Permission")]
+pub(crate) async fn get_entries(
+    Extension(app_config): Extension<AppConfig>,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path(module_id): Path<String>,
+    Query(filter): Query<StatusFilter>,
+) -> Result<impl IntoResponse, QuestionBankError> {
+    get_module(&app_config, &module_id)?;
+
+    let entries: Vec<QuestionBankEntry> = hikari_db::quiz::question_bank::Query::get_entries_by_module(
+        &conn,
+        &module_id,
+        filter.status.map(IntoDbModel::into_db_model),
+    )
+    .await?
+    .into_iter()
+    .map(IntoModel::into_model)
+    .collect();
+
+    Ok(Json(entries))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v0/question-bank/{module_id}/candidates",
+    responses(
+        (status = OK, body = Vec<Question>, description = "Well rated generated questions which are not part of the question bank yet"),
+    ),
+    tag = "v0/question-bank",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Instructor", ty = "Permission")]
+pub(crate) async fn get_candidates(
+    Extension(app_config): Extension<AppConfig>,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path(module_id): Path<String>,
+) -> Result<impl IntoResponse, QuestionBankError> {
+    get_module(&app_config, &module_id)?;
+
+    let promoted: HashSet<Uuid> = hikari_db::quiz::question_bank::Query::get_promoted_question_ids(&conn, &module_id)
+        .await?
+        .into_iter()
+        .collect();
+    let candidates: Vec<Question> =
+        hikari_db::quiz::question::Query::get_promotion_candidates(&conn, &module_id, MIN_PROMOTION_GRADE)
+            .await?
+            .into_iter()
+            .filter(|question| !promoted.contains(&question.id))
+            .map(IntoModel::into_model)
+            .collect();
+
+    Ok(Json(candidates))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct PromoteRequest {
+    question_id: Uuid,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v0/question-bank/{module_id}",
+    request_body = PromoteRequest,
+    responses(
+        (status = CREATED, body = QuestionBankEntry, description = "Draft entry created from the generated question"),
+        (status = NOT_FOUND, description = "The question does not belong to the module"),
+    ),
+    tag = "v0/question-bank",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Instructor", ty = "Permission")]
+pub(crate) async fn promote_question(
+    ExtractUserId(user_id): ExtractUserId,
+    Extension(app_config): Extension<AppConfig>,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path(module_id): Path<String>,
+    Json(payload): Json<PromoteRequest>,
+) -> Result<impl IntoResponse, QuestionBankError> {
+    get_module(&app_config, &module_id)?;
+
+    let question = hikari_db::quiz::question::Query::get_question_by_id(&conn, &payload.question_id)
+        .await?
+        .ok_or(QuestionBankError::QuestionNotFound)?;
+    let quiz = hikari_db::quiz::quiz::Query::get_quiz(&conn, &question.quiz_id)
+        .await?
+        .ok_or(QuestionBankError::QuestionNotFound)?;
+    if quiz.module_id != module_id {
+        return Err(QuestionBankError::QuestionNotFound);
+    }
+
+    let question_id = question.id;
+    let input = entry_input_from_question(question.into_model())?;
+    let entry: QuestionBankEntry = Mutation::create_entry(
+        &conn,
+        &module_id,
+        input,
+        Status::Draft,
+        Some(question_id),
+        Some(user_id),
+    )
+    .await?
+    .into_model();
+
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v0/question-bank/{module_id}/{entry_id}",
+    request_body = ContentExam,
+    responses(
+        (status = OK, body = QuestionBankEntry, description = "Updated entry"),
+        (status = BAD_REQUEST, description = "The question is not valid"),
+    ),
+    tag = "v0/question-bank",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Instructor", ty = "Permission")]
+pub(crate) async fn update_entry(
+    Extension(app_config): Extension<AppConfig>,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path((module_id, entry_id)): Path<(String, Uuid)>,
+    Json(exam): Json<ContentExam>,
+) -> Result<impl IntoResponse, QuestionBankError> {
+    let module = get_module(&app_config, &module_id)?;
+
+    let entry = hikari_db::quiz::question_bank::Query::get_entry_by_id(&conn, &module_id, &entry_id)
+        .await?
+        .ok_or(QuestionBankError::EntryNotFound)?;
+
+    let input = entry_input_from_exam(
+        entry.session_id.clone(),
+        entry.topic.clone(),
+        Some(entry.content.clone()),
+        exam,
+        module.quiz_rubric.into_db_model(),
+    )?;
+
+    let changed = entry.question != input.question
+        || entry.r#type != input.question_type
+        || entry.options != input.options
+        || entry.details != input.details
+        || entry.solution != input.solution
+        || entry.level != input.level
+        || entry.rubric != input.rubric;
+
+    let mut active_model: hikari_entity::quiz::question_bank::ActiveModel = entry.into();
+    if changed {
+        // Changed questions have to be reviewed again before they are served to students
+        active_model.status = ActiveValue::Set(Status::Draft);
+        active_model.reviewed_by = ActiveValue::Set(None);
+        active_model.reviewed_at = ActiveValue::Set(None);
+    }
+    active_model.question = ActiveValue::Set(input.question);
+    active_model.r#type = ActiveValue::Set(input.question_type);
+    active_model.options = ActiveValue::Set(input.options);
+    active_model.details = ActiveValue::Set(input.details);
+    active_model.solution = ActiveValue::Set(input.solution);
+    active_model.level = ActiveValue::Set(input.level);
+    active_model.rubric = ActiveValue::Set(input.rubric);
+
+    let entry: QuestionBankEntry = Mutation::update_entry(&conn, active_model).await?.into_model();
+
+    Ok(Json(entry))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct ReviewRequest {
+    /// Approved entries are served to students, rejected ones are kept so they are not promoted again
+    approved: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v0/question-bank/{module_id}/{entry_id}/review",
+    request_body = ReviewRequest,
+    responses(
+        (status = OK, body = QuestionBankEntry, description = "Reviewed entry"),
+    ),
+    tag = "v0/question-bank",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Instructor", ty = "Permission")]
+pub(crate) async fn review_entry(
+    ExtractUserId(user_id): ExtractUserId,
+    Extension(app_config): Extension<AppConfig>,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path((module_id, entry_id)): Path<(String, Uuid)>,
+    Json(payload): Json<ReviewRequest>,
+) -> Result<impl IntoResponse, QuestionBankError> {
+    get_module(&app_config, &module_id)?;
+
+    let entry = hikari_db::quiz::question_bank::Query::get_entry_by_id(&conn, &module_id, &entry_id)
+        .await?
+        .ok_or(QuestionBankError::EntryNotFound)?;
+
+    let status = if payload.approved {
+        Status::Approved
+    } else {
+        Status::Rejected
+    };
+    let entry: QuestionBankEntry = Mutation::review_entry(&conn, entry, status, &user_id)
+        .await?
+        .into_model();
+
+    Ok(Json(entry))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v0/question-bank/{module_id}/{entry_id}",
+    responses(
+        (status = NO_CONTENT, description = "Entry deleted"),
+        (status = NOT_FOUND, description = "Entry not found"),
+    ),
+    tag = "v0/question-bank",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Instructor", ty = "Permission")]
+pub(crate) async fn delete_entry(
+    Extension(app_config): Extension<AppConfig>,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path((module_id, entry_id)): Path<(String, Uuid)>,
+) -> Result<impl IntoResponse, QuestionBankError> {
+    get_module(&app_config, &module_id)?;
+
+    let rows_affected = Mutation::delete_entry(&conn, &module_id, &entry_id).await?;
+    if rows_affected == 0 {
+        return Err(QuestionBankError::EntryNotFound);
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v0/question-bank/{module_id}/export",
+    responses(
+        (status = OK, description = "Approved questions of the module as YAML", content_type = "application/yaml"),
+    ),
+    tag = "v0/question-bank",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Instructor", ty = "Permission")]
+pub(crate) async fn export_entries(
+    Extension(app_config): Extension<AppConfig>,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path(module_id): Path<String>,
+) -> Result<impl IntoResponse, QuestionBankError> {
+    get_module(&app_config, &module_id)?;
+
+    let entries =
+        hikari_db::quiz::question_bank::Query::get_entries_by_module(&conn, &module_id, Some(Status::Approved)).await?;
+
+    let questions = entries
+        .into_iter()
+        .map(|entry| {
+            let entry: QuestionBankEntry = entry.into_model();
+            QuestionBankDocumentEntry {
+                session: entry.session_id.clone(),
+                topic: entry.topic.clone(),
+                content: Some(entry.content.clone()),
+                exam: exam_from_entry(entry),
+            }
+        })
+        .collect();
+    let document = QuestionBankDocument {
+        module: module_id,
+        questions,
+    };
+    let body = yaml_serde::to_string(&document)?;
+
+    Ok((
+        [(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/yaml; charset=utf-8"),
+        )],
+        body,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v0/question-bank/{module_id}/import",
+    request_body(content = QuestionBankDocument, content_type = "application/yaml"),
+    responses(
+        (status = CREATED, body = Vec<QuestionBankEntry>, description = "Imported entries, which are approved right away"),
+        (status = BAD_REQUEST, description = "The document is not valid"),
+    ),
+    tag = "v0/question-bank",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Instructor", ty = "Permission")]
+pub(crate) async fn import_entries(
+    ExtractUserId(user_id): ExtractUserId,
+    Extension(app_config): Extension<AppConfig>,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path(module_id): Path<String>,
+    body: String,
+) -> Result<impl IntoResponse, QuestionBankError> {
+    let module = get_module(&app_config, &module_id)?;
+
+    let document: QuestionBankDocument = yaml_serde::from_str(&body).map_err(QuestionBankError::InvalidDocument)?;
+    if document.module != module_id {
+        return Err(QuestionBankError::ModuleMismatch(document.module));
+    }
+
+    // Validate the whole document before anything is stored
+    let inputs = document
+        .questions
+        .into_iter()
+        .map(|question| {
+            let session = module
+                .sessions
+                .get(&question.session)
+                .ok_or_else(|| QuestionBankError::SessionNotFound(question.session.clone()))?;
+            if !session.contents.iter().any(|content| content.title == question.topic) {
+                return Err(QuestionBankError::TopicNotFound(question.topic));
+            }
+            Ok(entry_input_from_exam(
+                question.session,
+                question.topic,
+                question.content,
+                question.exam,
+                module.quiz_rubric.into_db_model(),
+            )?)
+        })
+        .collect::<Result<Vec<QuestionBankEntryInput>, QuestionBankError>>()?;
+
+    let txn = conn.begin().await?;
+    let mut entries = Vec::with_capacity(inputs.len());
+    for input in inputs {
+        let entry: QuestionBankEntry =
+            Mutation::create_entry(&txn, &module_id, input, Status::Approved, None, Some(user_id))
+                .await?
+                .into_model();
+        entries.push(entry);
+    }
+    txn.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(entries)))
+}
+
+fn get_module<'a>(app_config: &'a AppConfig, module_id: &str) -> Result<&'a Module<'a>, QuestionBankError> {
+    app_config
+        .module_config()
+        .get(module_id)
+        .ok_or_else(|| QuestionBankError::ModuleNotFound(module_id.to_string()))
+}
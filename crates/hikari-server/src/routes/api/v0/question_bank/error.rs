@@ -0,0 +1,66 @@
+use axum::response::{IntoResponse, Response};
+use hikari_core::quiz::error::QuizError;
+use http::StatusCode;
+use sea_orm::DbErr;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub(crate) enum QuestionBankError {
+    #[error(transparent)]
+    SeaOrmError(#[from] DbErr),
+
+    #[error("Module not found: {0}")]
+    ModuleNotFound(String),
+
+    #[error("Session not found: {0}")]
+    SessionNotFound(String),
+
+    #[error("Topic not found: {0}")]
+    TopicNotFound(String),
+
+    #[error("The document belongs to module {0}")]
+    ModuleMismatch(String),
+
+    #[error("The question bank entry was not found.")]
+    EntryNotFound,
+
+    #[error("The requested question was not found.")]
+    QuestionNotFound,
+
+    #[error("Invalid question bank document: {0}")]
+    InvalidDocument(yaml_serde::Error),
+
+    #[error(transparent)]
+    SerializeError(#[from] yaml_serde::Error),
+
+    #[error(transparent)]
+    QuizError(#[from] QuizError),
+}
+
+impl IntoResponse for QuestionBankError {
+    fn into_response(self) -> Response {
+        match self {
+            QuestionBankError::SeaOrmError(e) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {e}")).into_response()
+            }
+            QuestionBankError::ModuleNotFound(_)
+            | QuestionBankError::EntryNotFound
+            | QuestionBankError::QuestionNotFound => (StatusCode::NOT_FOUND, self.to_string()).into_response(),
+            QuestionBankError::SessionNotFound(_)
+            | QuestionBankError::TopicNotFound(_)
+            | QuestionBankError::ModuleMismatch(_)
+            | QuestionBankError::InvalidDocument(_)
+            | QuestionBankError::QuizError(QuizError::InvalidBankEntry(_)) => {
+                (StatusCode::BAD_REQUEST, self.to_string()).into_response()
+            }
+            QuestionBankError::SerializeError(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to serialize question bank: {e}"),
+            )
+                .into_response(),
+            QuestionBankError::QuizError(e) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, format!("Quiz error: {e}")).into_response()
+            }
+        }
+    }
+}
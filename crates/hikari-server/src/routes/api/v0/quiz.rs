@@ -15,9 +15,9 @@ use futures::TryFutureExt;
 use hikari_config::module::content::{Content, ContentExam};
 use hikari_config::module::session::Session;
 use hikari_core::llm_config::LlmConfig;
-use hikari_core::quiz::evaluation::evaluate_answer;
+use hikari_core::quiz::evaluation::{evaluate_answer, explain_answer};
 use hikari_core::quiz::question::create_question;
-use hikari_model::quiz::question::{Question, QuestionFeedback};
+use hikari_model::quiz::question::{Question, QuestionAnswer, QuestionFeedback};
 use hikari_model::quiz::quiz::{Quiz, QuizFull};
 use hikari_model::quiz::score::Score;
 use hikari_model_tools::convert::{IntoDbModel, IntoModel};
@@ -52,7 +52,8 @@ where
                             .route("/", get(get_question))
                             .route("/feedback", post(add_feedback))
                             .route("/skip", post(skip_question))
-                            .route("/answer", post(submit_answer)),
+                            .route("/answer", post(submit_answer))
+                            .route("/explanation", post(explain_question)),
                     ),
             ),
         )
@@ -285,10 +286,12 @@ async fn get_next_question(
 
     let selected_session_id = pick_random_session(&session_ids).ok_or(QuizError::NoSessionIds)?;
 
-    let session = app_config
+    let module = app_config
         .module_config()
         .get(&module_id)
-        .ok_or(QuizError::ModuleNotFound(module_id.clone()))?
+        .ok_or(QuizError::ModuleNotFound(module_id.clone()))?;
+
+    let session = module
         .sessions
         .get(&selected_session_id)
         .ok_or(QuizError::SessionNotFound(selected_session_id.clone()))?;
@@ -324,7 +327,9 @@ async fn get_next_question(
         llm_config,
         &conn,
         &quiz_id,
+        &module_id,
         &sources,
+        module.quiz_rubric,
     )
     .await?;
 
@@ -335,7 +340,7 @@ async fn get_next_question(
 
 #[derive(Deserialize, ToSchema)]
 struct EvaluationRequest {
-    answer: String,
+    answer: QuestionAnswer,
 }
 
 #[utoipa::path(
@@ -361,9 +366,14 @@ async fn submit_answer(
     let (quiz, quiz_question) = try_join!(
         // To ensure the user has access to the quiz, we first fetch the quiz
         get_quiz_by_id(&conn, &user_id, &quiz_id),
-        get_question_by_id(&conn, &question_id)
+        // The solution is needed for grading, so the question is not sanitized here
+        get_unsanitized_question_by_id(&conn, &question_id)
     )?;
 
+    if quiz_question.quiz_id != quiz_id {
+        return Err(QuizError::QuestionNotFound);
+    }
+
     let module_id = quiz.module_id;
     let session_id = quiz_question.session_id.as_str();
 
@@ -407,6 +417,68 @@ async fn submit_answer(
     Ok(Json(evaluated_question).into_response())
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v0/quizzes/{quiz_id}/questions/{question_id}/explanation",
+    responses(
+        (status = OK, body = Question, description = "Graded question with an explanation of the solution"),
+        (status = CONFLICT, description = "The question has not been answered yet"),
+    ),
+    tag = "v0/quizzes",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Basic", ty = "Permission")]
+async fn explain_question(
+    ExtractUserId(user_id): ExtractUserId,
+    Extension(app_config): Extension<AppConfig>,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path((quiz_id, question_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, QuizError> {
+    let (quiz, quiz_question) = try_join!(
+        // To ensure the user has access to the quiz, we first fetch the quiz
+        get_quiz_by_id(&conn, &user_id, &quiz_id),
+        get_unsanitized_question_by_id(&conn, &question_id)
+    )?;
+
+    if quiz_question.quiz_id != quiz_id {
+        return Err(QuizError::QuestionNotFound);
+    }
+
+    if quiz_question.explanation.is_some() {
+        return Ok(Json(quiz_question).into_response());
+    }
+
+    let module_id = quiz.module_id;
+    let session_id = quiz_question.session_id.as_str();
+
+    let session = app_config
+        .module_config()
+        .get(&module_id)
+        .ok_or(QuizError::ModuleNotFound(module_id.clone()))?
+        .sessions
+        .get(session_id)
+        .ok_or(QuizError::SessionNotFound(session_id.to_string()))?;
+
+    let session_sources: Vec<String> = session
+        .contents
+        .iter()
+        .flat_map(|c| c.sources.primary().iter().map(|s| s.file_id.clone()))
+        .collect();
+
+    let explained_question = explain_answer(
+        &user_id,
+        &quiz_question,
+        app_config.llm_config(),
+        &conn,
+        session_sources,
+    )
+    .await?;
+
+    Ok(Json(explained_question).into_response())
+}
+
 #[utoipa::path(
     post,
     path = "/api/v0/quizzes/{quiz_id}/questions/{question_id}/skip",
@@ -495,11 +567,16 @@ async fn get_quiz_by_id(conn: &DatabaseConnection, user_id: &Uuid, quiz_id: &Uui
 }
 
 async fn get_question_by_id(conn: &DatabaseConnection, question_id: &Uuid) -> Result<Question, QuizError> {
-    let mut result: Question = hikari_db::quiz::question::Query::get_question_by_id(conn, question_id)
+    let mut result = get_unsanitized_question_by_id(conn, question_id).await?;
+    result.sanitize_for_client();
+    Ok(result)
+}
+
+async fn get_unsanitized_question_by_id(conn: &DatabaseConnection, question_id: &Uuid) -> Result<Question, QuizError> {
+    let result: Question = hikari_db::quiz::question::Query::get_question_by_id(conn, question_id)
         .await?
         .ok_or(QuizError::QuestionNotFound)?
         .into_model();
-    result.sanitize_for_client();
     Ok(result)
 }
 
@@ -70,6 +70,12 @@ impl IntoResponse for QuizError {
             QuizError::NoContentProvided => {
                 (StatusCode::BAD_REQUEST, "No content provided for question generation").into_response()
             }
+            QuizError::QuizError(hikari_core::quiz::error::QuizError::InvalidAnswer(e)) => {
+                (StatusCode::BAD_REQUEST, format!("Invalid answer: {e}")).into_response()
+            }
+            QuizError::QuizError(hikari_core::quiz::error::QuizError::NotAnswered) => {
+                (StatusCode::CONFLICT, "Question not answered yet").into_response()
+            }
             QuizError::QuizError(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Quiz error: {e}")).into_response(),
             QuizError::SerializeError(e) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
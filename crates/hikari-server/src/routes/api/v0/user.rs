@@ -1,3 +1,5 @@
+use crate::AppConfig;
+use crate::audit::Audit;
 use crate::permissions::Permission;
 use crate::routes::api::v0::modules::error::UserError;
 use crate::user::{ExtractUser, ExtractUserId};
@@ -5,20 +7,25 @@ use axum::response::IntoResponse;
 use axum::routing::{delete, get};
 use axum::{Extension, Json, Router};
 use chrono::NaiveDate;
+use hikari_core::export::delete_user_exports;
 use hikari_db::sea_orm::DatabaseConnection;
 use hikari_db::user;
 use hikari_model::user::{Gender, User};
 use hikari_model_tools::convert::IntoDbModel;
 use protect_axum::protect;
-use sea_orm::ActiveValue;
+use sea_orm::{ActiveValue, TransactionTrait};
 use serde_derive::Deserialize;
+use serde_json::json;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 pub(crate) mod access;
 pub(crate) mod config;
 pub(crate) mod context_log;
+pub(crate) mod devices;
+pub(crate) mod export;
 pub(crate) mod handle;
+pub(crate) mod memory;
 
 pub(crate) fn create_router<S>(deletable: bool) -> Router<S>
 where
@@ -29,7 +36,10 @@ where
         .nest("/handle", handle::create_router())
         .nest("/config", config::create_router())
         .nest("/access", access::create_router())
-        .nest("/context_log", context_log::create_router());
+        .nest("/context_log", context_log::create_router())
+        .nest("/devices", devices::create_router())
+        .nest("/export", export::create_router())
+        .nest("/memory", memory::create_router());
 
     if deletable {
         router = router.route("/delete", delete(delete_user));
@@ -40,9 +50,18 @@ where
 
 pub(crate) async fn delete_user(
     ExtractUserId(user_id): ExtractUserId,
+    audit: Audit,
+    Extension(app_config): Extension<AppConfig>,
     Extension(conn): Extension<DatabaseConnection>,
 ) -> Result<impl IntoResponse, UserError> {
-    user::Mutation::delete(&conn, user_id).await?;
+    let txn = conn.begin().await?;
+    // The archives are not removed by the cascading delete of the jobs
+    if let Some(storage) = app_config.export_storage() {
+        delete_user_exports(&txn, storage, user_id).await?;
+    }
+    user::Mutation::delete(&txn, user_id).await?;
+    audit.record_in(&txn, "delete_user", Some(user_id), json!({})).await?;
+    txn.commit().await?;
 
     tracing::debug!(%user_id, "user deleted!");
 
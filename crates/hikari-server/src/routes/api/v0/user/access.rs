@@ -1,4 +1,5 @@
 use crate::AppConfig;
+use crate::audit::Audit;
 use crate::permissions::Permission;
 use crate::routes::api::v0::modules::error::UserError;
 use crate::user::ExtractUser;
@@ -7,6 +8,7 @@ use axum::response::IntoResponse;
 use axum::routing::{get, post};
 use axum::{Extension, Json, Router};
 use hikari_config::global::access::GroupAccess;
+use hikari_core::allocation::BlockRandomization;
 use hikari_db::groups::{self, groups_token};
 use hikari_db::sea_orm::DatabaseConnection;
 use hikari_db::util::FlattenTransactionResultExt;
@@ -16,6 +18,7 @@ use rand::seq::IndexedRandom;
 use sea_orm::{DbErr, TransactionTrait};
 use serde::Serialize;
 use serde_derive::Deserialize;
+use serde_json::json;
 use std::error::Error;
 use utoipa::ToSchema;
 
@@ -88,6 +91,7 @@ pub(crate) async fn access_approvals(
 #[protect("Permission::Basic", ty = "Permission")]
 pub(crate) async fn add_access(
     ExtractUser(user): ExtractUser,
+    audit: Audit,
     Extension(conn): Extension<DatabaseConnection>,
     Extension(config): Extension<AppConfig>,
     Json(GroupToken { token }): Json<GroupToken>,
@@ -97,7 +101,6 @@ pub(crate) async fn add_access(
         tracing::warn!(token = %token, "invalid token");
         return Err(UserError::InvalidToken);
     };
-
     let res = conn
         .transaction::<_, http::status::StatusCode, UserError>(|txn| {
             Box::pin(async move {
@@ -120,13 +123,40 @@ pub(crate) async fn add_access(
                     return Err(UserError::from(error));
                 }
 
-                for group in groups {
+                for (slot, group) in groups.iter().enumerate() {
                     let name = match group {
-                        GroupAccess::Single(value) => value,
-                        GroupAccess::Random { random } => select_group(random.as_slice())?,
+                        GroupAccess::Single(value) => value.clone(),
+                        GroupAccess::Random {
+                            random,
+                            block_size: None,
+                            stratify_by,
+                        } if stratify_by.is_empty() => select_group(random.as_slice())?.to_owned(),
+                        GroupAccess::Random {
+                            random,
+                            block_size,
+                            stratify_by,
+                        } => {
+                            let randomization = BlockRandomization {
+                                token: &token,
+                                slot: i32::try_from(slot).expect("too many groups for an access token"),
+                                groups: random,
+                                block_size: *block_size,
+                                stratify_by,
+                            };
+                            randomization.allocate(txn, &user).await?
+                        }
                     };
-                    groups::custom_groups::Mutation::add(txn, user.id, name.to_owned()).await?;
+                    groups::custom_groups::Mutation::add(txn, user.id, name).await?;
                 }
+                // The token is a shared secret, so only the matching access config is recorded
+                audit
+                    .record_in(
+                        txn,
+                        "add_group_token",
+                        Some(user.id),
+                        json!({ "access_index": access_index }),
+                    )
+                    .await?;
                 Ok(http::status::StatusCode::OK)
             })
         })
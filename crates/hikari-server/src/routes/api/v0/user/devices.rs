@@ -0,0 +1,91 @@
+use crate::audit::Audit;
+use crate::permissions::Permission;
+use crate::routes::api::v0::modules::error::UserError;
+use crate::user::{ExtractAccessTokenId, ExtractUserId};
+use axum::extract::Path;
+use axum::response::IntoResponse;
+use axum::routing::{delete, get};
+use axum::{Extension, Json, Router};
+use chrono::Utc;
+use hikari_db::access_tokens::{Mutation, Query};
+use hikari_model::login::Device;
+use protect_axum::protect;
+use sea_orm::{DatabaseConnection, TransactionTrait};
+use serde_json::json;
+
+pub(crate) fn create_router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/", get(get_devices))
+        .route("/{device_id}", delete(revoke_device))
+        .with_state(())
+}
+
+/// Lists the devices the user is currently logged in on
+#[utoipa::path(
+    get,
+    path = "/api/v0/user/devices",
+    responses(
+        (status = OK, body = Vec<Device>, description = "The devices with a valid access token"),
+    ),
+    tag = "v0/user",
+)]
+#[protect("Permission::Basic", ty = "Permission")]
+pub(crate) async fn get_devices(
+    ExtractUserId(user_id): ExtractUserId,
+    ExtractAccessTokenId(current_token_id): ExtractAccessTokenId,
+    Extension(conn): Extension<DatabaseConnection>,
+) -> Result<impl IntoResponse, UserError> {
+    let tokens = Query::get_user_access_tokens(&conn, user_id, Utc::now().naive_utc()).await?;
+    let devices: Vec<_> = tokens
+        .into_iter()
+        .map(|token| Device {
+            id: token.id,
+            name: token.device_name,
+            created_at: token.created_at,
+            last_used_at: token.last_used_at,
+            expires_at: token.expires_at,
+            current: current_token_id == Some(token.id),
+        })
+        .collect();
+    Ok(Json(devices))
+}
+
+/// Logs the user out on the given device by revoking its access token
+#[utoipa::path(
+    delete,
+    path = "/api/v0/user/devices/{device_id}",
+    params(
+        ("device_id" = i32, Path, description = "The id of the device"),
+    ),
+    responses(
+        (status = NO_CONTENT, description = "The device was logged out"),
+        (status = NOT_FOUND, description = "The device does not exist"),
+    ),
+    tag = "v0/user",
+)]
+#[protect("Permission::Basic", ty = "Permission")]
+pub(crate) async fn revoke_device(
+    ExtractUserId(user_id): ExtractUserId,
+    audit: Audit,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path(device_id): Path<i32>,
+) -> Result<impl IntoResponse, UserError> {
+    let txn = conn.begin().await?;
+    if !Mutation::delete_access_token(&txn, user_id, device_id).await? {
+        return Err(UserError::NotFound);
+    }
+    audit
+        .record_in(
+            &txn,
+            "revoke_access_token",
+            Some(user_id),
+            json!({ "token_id": device_id }),
+        )
+        .await?;
+    txn.commit().await?;
+    tracing::debug!(%user_id, device_id, "device logged out");
+    Ok(http::StatusCode::NO_CONTENT)
+}
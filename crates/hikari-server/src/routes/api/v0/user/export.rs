@@ -0,0 +1,196 @@
+use crate::AppConfig;
+use crate::audit::Audit;
+use crate::permissions::Permission;
+use crate::routes::api::v0::modules::error::UserError;
+use crate::user::ExtractUserId;
+use axum::body::Body;
+use axum::extract::Path;
+use axum::response::IntoResponse;
+use axum::routing::{get, post};
+use axum::{Extension, Json, Router};
+use futures::StreamExt;
+use hikari_core::export::{ExportJob, get_export_job, open_export_archive, request_export, write_archive};
+use http::{StatusCode, header};
+use protect_axum::protect;
+use sea_orm::DatabaseConnection;
+use serde_json::json;
+use std::error::Error;
+use tokio_util::io::ReaderStream;
+use uuid::Uuid;
+
+/// Size of the buffer between the archive writer and the response body
+const EXPORT_BUFFER_SIZE: usize = 64 * 1024;
+const EXPORT_FILE_NAME: &str = "hikari-export.zip";
+
+pub(crate) fn create_router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/", get(export_user_data))
+        .route("/jobs", post(create_export_job))
+        .route("/jobs/{job_id}", get(get_export_job_handler))
+        .route("/jobs/{job_id}/archive", get(download_export_archive))
+        .with_state(())
+}
+
+fn zip_headers() -> [(header::HeaderName, String); 2] {
+    [
+        (header::CONTENT_TYPE, "application/zip".to_owned()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{EXPORT_FILE_NAME}\""),
+        ),
+    ]
+}
+
+/// Export all data stored about the user
+///
+/// The archive contains one JSON document per domain (profile, configs, context logs, journal, conversations, slots,
+/// quizzes, assessments, planner and history). It is streamed while it is created. For large histories use the
+/// export jobs instead.
+#[utoipa::path(
+    get,
+    path = "/api/v0/user/export",
+    responses(
+        (status = OK, description = "ZIP archive with the data of the user", content_type = "application/zip", body = Vec<u8>),
+    ),
+    tag = "v0/user",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Basic", ty = "Permission")]
+pub(crate) async fn export_user_data(
+    ExtractUserId(user_id): ExtractUserId,
+    audit: Audit,
+    Extension(conn): Extension<DatabaseConnection>,
+) -> Result<impl IntoResponse, UserError> {
+    audit
+        .record("export_user_data", Some(user_id), json!({ "mode": "stream" }))
+        .await?;
+
+    let (writer, reader) = tokio::io::duplex(EXPORT_BUFFER_SIZE);
+    let archive = tokio::spawn(async move { write_archive(&conn, user_id, writer).await.map(drop) });
+
+    // The status is already sent when the archive fails, so the body ends with an error instead. This aborts the
+    // response and the client never receives an incomplete archive as a complete one.
+    let result = futures::stream::once(async move {
+        let error = match archive.await {
+            Ok(Ok(())) => return None,
+            Ok(Err(error)) => std::io::Error::other(error),
+            Err(error) => std::io::Error::other(error),
+        };
+        tracing::error!(error = &error as &dyn Error, %user_id, "failed to stream export archive");
+        Some(Err(error))
+    })
+    .filter_map(std::future::ready);
+
+    Ok((
+        zip_headers(),
+        Body::from_stream(ReaderStream::new(reader).chain(result)),
+    ))
+}
+
+/// Request an export of all data stored about the user
+///
+/// The archive is created in the background and can be downloaded for seven days once the job completed. Only one
+/// job per user can be queued or running at a time.
+#[utoipa::path(
+    post,
+    path = "/api/v0/user/export/jobs",
+    responses(
+        (status = ACCEPTED, description = "The export job was queued. Poll it for the result.", body = ExportJob),
+        (status = TOO_MANY_REQUESTS, description = "Another export job of the user is still queued or running."),
+        (status = SERVICE_UNAVAILABLE, description = "Export jobs are not enabled on this server."),
+    ),
+    tag = "v0/user",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Basic", ty = "Permission")]
+pub(crate) async fn create_export_job(
+    ExtractUserId(user_id): ExtractUserId,
+    audit: Audit,
+    Extension(app_config): Extension<AppConfig>,
+    Extension(conn): Extension<DatabaseConnection>,
+) -> Result<impl IntoResponse, UserError> {
+    if app_config.export_storage().is_none() {
+        return Err(UserError::ExportsDisabled);
+    }
+    audit
+        .record("export_user_data", Some(user_id), json!({ "mode": "job" }))
+        .await?;
+    let job = request_export(&conn, user_id).await?;
+    tracing::debug!(%user_id, job_id = %job.id, "requested export job");
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+/// Get the state of an export job
+#[utoipa::path(
+    get,
+    path = "/api/v0/user/export/jobs/{job_id}",
+    params(
+        ("job_id" = Uuid, Path, description = "The id of the export job"),
+    ),
+    responses(
+        (status = OK, description = "The job is finished.", body = ExportJob),
+        (status = ACCEPTED, description = "The job is still queued or running.", body = ExportJob),
+        (status = NOT_FOUND, description = "The job does not exist."),
+    ),
+    tag = "v0/user",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Basic", ty = "Permission")]
+pub(crate) async fn get_export_job_handler(
+    ExtractUserId(user_id): ExtractUserId,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path(job_id): Path<Uuid>,
+) -> Result<impl IntoResponse, UserError> {
+    let job = get_export_job(&conn, user_id, job_id)
+        .await?
+        .ok_or(UserError::NotFound)?;
+    let status = if job.status.is_finished() {
+        StatusCode::OK
+    } else {
+        StatusCode::ACCEPTED
+    };
+    Ok((status, Json(job)))
+}
+
+/// Download the archive of a completed export job
+#[utoipa::path(
+    get,
+    path = "/api/v0/user/export/jobs/{job_id}/archive",
+    params(
+        ("job_id" = Uuid, Path, description = "The id of the export job"),
+    ),
+    responses(
+        (status = OK, description = "ZIP archive with the data of the user", content_type = "application/zip", body = Vec<u8>),
+        (status = NOT_FOUND, description = "The job does not exist or is not completed."),
+    ),
+    tag = "v0/user",
+    security(
+        ("token" = [])
+    )
+)]
+#[protect("Permission::Basic", ty = "Permission")]
+pub(crate) async fn download_export_archive(
+    ExtractUserId(user_id): ExtractUserId,
+    audit: Audit,
+    Extension(app_config): Extension<AppConfig>,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path(job_id): Path<Uuid>,
+) -> Result<impl IntoResponse, UserError> {
+    let storage = app_config.export_storage().ok_or(UserError::ExportsDisabled)?;
+    audit
+        .record("download_export", Some(user_id), json!({ "job_id": job_id }))
+        .await?;
+    let archive = open_export_archive(&conn, storage, user_id, job_id)
+        .await?
+        .ok_or(UserError::NotFound)?;
+    Ok((zip_headers(), Body::from_stream(ReaderStream::new(archive))))
+}
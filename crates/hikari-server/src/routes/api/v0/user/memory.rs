@@ -0,0 +1,166 @@
+use crate::audit::Audit;
+use crate::permissions::Permission;
+use crate::routes::api::v0::modules::error::UserError;
+use crate::user::ExtractUserId;
+use axum::extract::Path;
+use axum::response::IntoResponse;
+use axum::routing::{delete, get};
+use axum::{Extension, Json, Router};
+use hikari_db::llm::learner_memory::{Mutation, Query};
+use hikari_db::util::FlattenTransactionResultExt;
+use hikari_model::llm::learner_memory::{LearnerMemory, LearnerMemoryConsent};
+use hikari_model_tools::convert::IntoModel;
+use protect_axum::protect;
+use sea_orm::{DatabaseConnection, TransactionTrait};
+use serde_json::json;
+use uuid::Uuid;
+
+pub(crate) fn create_router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/", get(get_memories).delete(delete_memories))
+        .route("/consent", get(get_consent).put(set_consent))
+        .route("/{memory_id}", delete(delete_memory))
+        .with_state(())
+}
+
+/// Lists the facts agents remember about the user across sessions
+#[utoipa::path(
+    get,
+    path = "/api/v0/user/memory",
+    responses(
+        (status = OK, body = Vec<LearnerMemory>, description = "The stored memories, newest first"),
+    ),
+    tag = "v0/user",
+)]
+#[protect("Permission::Basic", ty = "Permission")]
+pub(crate) async fn get_memories(
+    ExtractUserId(user_id): ExtractUserId,
+    Extension(conn): Extension<DatabaseConnection>,
+) -> Result<impl IntoResponse, UserError> {
+    let memories: Vec<LearnerMemory> = Query::get_user_memories(&conn, user_id)
+        .await?
+        .into_iter()
+        .map(IntoModel::into_model)
+        .collect();
+    Ok(Json(memories))
+}
+
+/// Deletes a stored memory, agents will no longer recall it
+#[utoipa::path(
+    delete,
+    path = "/api/v0/user/memory/{memory_id}",
+    params(
+        ("memory_id" = Uuid, Path, description = "The id of the memory"),
+    ),
+    responses(
+        (status = NO_CONTENT, description = "The memory was deleted"),
+        (status = NOT_FOUND, description = "The memory does not exist"),
+    ),
+    tag = "v0/user",
+)]
+#[protect("Permission::Basic", ty = "Permission")]
+pub(crate) async fn delete_memory(
+    ExtractUserId(user_id): ExtractUserId,
+    audit: Audit,
+    Extension(conn): Extension<DatabaseConnection>,
+    Path(memory_id): Path<Uuid>,
+) -> Result<impl IntoResponse, UserError> {
+    audit
+        .record(
+            "delete_learner_memory",
+            Some(user_id),
+            json!({ "memory_id": memory_id }),
+        )
+        .await?;
+    if !Mutation::delete_memory(&conn, user_id, memory_id).await? {
+        return Err(UserError::NotFound);
+    }
+    tracing::debug!(%user_id, %memory_id, "learner memory deleted");
+    Ok(http::StatusCode::NO_CONTENT)
+}
+
+/// Deletes all stored memories of the user
+#[utoipa::path(
+    delete,
+    path = "/api/v0/user/memory",
+    responses(
+        (status = NO_CONTENT, description = "All memories were deleted"),
+    ),
+    tag = "v0/user",
+)]
+#[protect("Permission::Basic", ty = "Permission")]
+pub(crate) async fn delete_memories(
+    ExtractUserId(user_id): ExtractUserId,
+    audit: Audit,
+    Extension(conn): Extension<DatabaseConnection>,
+) -> Result<impl IntoResponse, UserError> {
+    audit
+        .record("delete_learner_memories", Some(user_id), json!({}))
+        .await?;
+    let deleted = Mutation::delete_user_memories(&conn, user_id).await?;
+    tracing::debug!(%user_id, deleted, "learner memories deleted");
+    Ok(http::StatusCode::NO_CONTENT)
+}
+
+/// Whether the user allows agents to remember facts about them across sessions
+#[utoipa::path(
+    get,
+    path = "/api/v0/user/memory/consent",
+    responses(
+        (status = OK, body = LearnerMemoryConsent, description = "The consent of the user"),
+    ),
+    tag = "v0/user",
+)]
+#[protect("Permission::Basic", ty = "Permission")]
+pub(crate) async fn get_consent(
+    ExtractUserId(user_id): ExtractUserId,
+    Extension(conn): Extension<DatabaseConnection>,
+) -> Result<impl IntoResponse, UserError> {
+    let enabled = Query::has_consent(&conn, user_id).await?;
+    Ok(Json(LearnerMemoryConsent { enabled }))
+}
+
+/// Opts in to or out of the learner memory. Opting out deletes all stored memories.
+#[utoipa::path(
+    put,
+    path = "/api/v0/user/memory/consent",
+    request_body = LearnerMemoryConsent,
+    responses(
+        (status = NO_CONTENT, description = "The consent was updated"),
+    ),
+    tag = "v0/user",
+)]
+#[protect("Permission::Basic", ty = "Permission")]
+pub(crate) async fn set_consent(
+    ExtractUserId(user_id): ExtractUserId,
+    audit: Audit,
+    Extension(conn): Extension<DatabaseConnection>,
+    Json(LearnerMemoryConsent { enabled }): Json<LearnerMemoryConsent>,
+) -> Result<impl IntoResponse, UserError> {
+    conn.transaction::<_, (), UserError>(|txn| {
+        Box::pin(async move {
+            Mutation::set_consent(txn, user_id, enabled).await?;
+            let deleted = if enabled {
+                0
+            } else {
+                Mutation::delete_user_memories(txn, user_id).await?
+            };
+            audit
+                .record_in(
+                    txn,
+                    "set_learner_memory_consent",
+                    Some(user_id),
+                    json!({ "enabled": enabled, "deleted": deleted }),
+                )
+                .await?;
+            Ok(())
+        })
+    })
+    .await
+    .flatten_res()?;
+    tracing::debug!(%user_id, enabled, "learner memory consent updated");
+    Ok(http::StatusCode::NO_CONTENT)
+}
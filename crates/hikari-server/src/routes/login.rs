@@ -1,24 +1,30 @@
 use crate::app::AuthConfig;
+use crate::audit::Audit;
 use crate::auth::validate_jwt;
 use crate::db;
 use crate::routes::error::{ErrorData, LoginError, LoginErrorType};
-use crate::user::ExtractUserId;
+use crate::user::{ExtractAccessTokenId, ExtractUserId};
 use anyhow::Result;
 use axum::body::{Body, Bytes};
 use axum::response::{IntoResponse, Response};
 use axum::routing::{get, post};
 use axum::{Extension, Router};
 use hikari_db::access_tokens;
+use hikari_db::util::FlattenTransactionResultExt;
 use hikari_model::login::Token;
-use http::StatusCode;
 use http::header;
-use sea_orm::DatabaseConnection;
+use http::{HeaderMap, StatusCode};
+use sea_orm::{DatabaseConnection, TransactionTrait};
+use serde_json::json;
 use std::borrow::Cow;
 use std::error::Error;
 use std::str;
 use std::str::from_utf8;
 use tracing;
 
+pub(crate) const DEVICE_NAME_HEADER: &str = "x-device-name";
+const MAX_DEVICE_NAME_LENGTH: usize = 255;
+
 #[allow(clippy::too_many_arguments)]
 pub fn create_router<S: Clone + Send + Sync + 'static>() -> Router<S> {
     Router::new()
@@ -32,8 +38,11 @@ pub fn create_router<S: Clone + Send + Sync + 'static>() -> Router<S> {
     post,
     path = "/login/token",
     request_body(content = String, description = "The plain jwt token received from auth-server", content_type = "text/plain"),
+    params(
+        ("X-Device-Name" = Option<String>, Header, description = "Name of the device shown in the device list. Defaults to the user agent"),
+    ),
     responses(
-        (status = OK, description = "Successful login, returns Bearer token", body = Token, example = json!( Token { access_token: "abcToken12345678".into() })),
+        (status = OK, description = "Successful login, returns Bearer token", body = Token, example = json!( Token { access_token: "abcToken12345678".into(), expires_at: None })),
         (status = UNAUTHORIZED, description = "Authentication failed. Possible reason may be that the token is expired.", body = ErrorData<LoginErrorType>),
         (status = FORBIDDEN, description = "Authentication succeeded but access was denied. See body for reason", body = ErrorData<LoginErrorType>),
     ),
@@ -42,6 +51,8 @@ pub fn create_router<S: Clone + Send + Sync + 'static>() -> Router<S> {
 pub(crate) async fn login_token(
     Extension(state): Extension<AuthConfig>,
     Extension(conn): Extension<DatabaseConnection>,
+    audit: Audit,
+    headers: HeaderMap,
     token: Bytes,
 ) -> Result<Response, LoginError> {
     let state = state.as_ref();
@@ -62,7 +73,29 @@ pub(crate) async fn login_token(
         LoginError::Invalid
     })?;
 
-    let access_token = db::sea_orm::user::get_or_create_user_and_get_token(&conn, &sub, groups).await?;
+    let device_name = device_name(&headers);
+    let token_ttl = state.access_token_ttl();
+    let access_token = conn
+        .transaction::<_, _, LoginError>(|txn| {
+            Box::pin(async move {
+                let access_token =
+                    db::sea_orm::user::get_or_create_user_and_get_token(txn, &sub, groups, device_name, token_ttl)
+                        .await?;
+                let user_id = access_token.model.user_id;
+                audit
+                    .record_as(
+                        txn,
+                        user_id,
+                        "create_access_token",
+                        Some(user_id),
+                        json!({ "token_id": access_token.model.id, "device_name": access_token.model.device_name }),
+                    )
+                    .await?;
+                Ok(access_token)
+            })
+        })
+        .await
+        .flatten_res()?;
 
     let mut response = Response::builder();
     response = response.status(StatusCode::OK);
@@ -71,7 +104,8 @@ pub(crate) async fn login_token(
     let res = response
         .body(Body::from(
             serde_json::to_string(&Token {
-                access_token: access_token.access_token,
+                access_token: access_token.token,
+                expires_at: Some(access_token.model.expires_at),
             })
             .map_err(|error| {
                 tracing::error!(error = &error as &dyn Error, "could not serialize token");
@@ -86,6 +120,19 @@ pub(crate) async fn login_token(
     Ok(res)
 }
 
+fn device_name(headers: &HeaderMap) -> Option<String> {
+    let name = headers
+        .get(DEVICE_NAME_HEADER)
+        .or_else(|| headers.get(header::USER_AGENT))?
+        .to_str()
+        .ok()?
+        .trim();
+    if name.is_empty() {
+        return None;
+    }
+    Some(name.chars().take(MAX_DEVICE_NAME_LENGTH).collect())
+}
+
 async fn whoami(user: Option<ExtractUserId>) -> impl IntoResponse {
     match user {
         None => {
@@ -100,7 +147,7 @@ async fn whoami(user: Option<ExtractUserId>) -> impl IntoResponse {
     post,
     path = "/logout",
     responses(
-        (status = NO_CONTENT, description = "User Logged out successfully"),
+        (status = NO_CONTENT, description = "The access token of the current device was revoked"),
         (status = INTERNAL_SERVER_ERROR, description = "Failed to delete access token")
     ),
     tag = "oidc",
@@ -110,9 +157,15 @@ async fn whoami(user: Option<ExtractUserId>) -> impl IntoResponse {
 )]
 pub(crate) async fn logout(
     ExtractUserId(user_id): ExtractUserId,
+    ExtractAccessTokenId(token_id): ExtractAccessTokenId,
     Extension(conn): Extension<DatabaseConnection>,
 ) -> impl IntoResponse {
-    if let Err(error) = access_tokens::Mutation::delete_access_token(&conn, user_id).await {
+    // Users authenticated with their jwt directly have no access token to revoke
+    let Some(token_id) = token_id else {
+        tracing::debug!(user = %user_id, "no access token to revoke");
+        return StatusCode::NO_CONTENT;
+    };
+    if let Err(error) = access_tokens::Mutation::delete_access_token(&conn, user_id, token_id).await {
         tracing::error!(
             user = %user_id,
             error = &error as &dyn Error,
@@ -120,6 +173,6 @@ pub(crate) async fn logout(
         );
         return StatusCode::INTERNAL_SERVER_ERROR;
     }
-    tracing::debug!(user = %user_id, "user logged out");
+    tracing::debug!(user = %user_id, token_id, "user logged out");
     StatusCode::NO_CONTENT
 }
@@ -30,8 +30,17 @@ struct SecurityAddon;
         api::v0::quiz::get_question,
         api::v0::quiz::get_next_question,
         api::v0::quiz::submit_answer,
+        api::v0::quiz::explain_question,
         api::v0::quiz::add_feedback,
         api::v0::quiz::skip_question,
+        api::v0::question_bank::get_entries,
+        api::v0::question_bank::get_candidates,
+        api::v0::question_bank::promote_question,
+        api::v0::question_bank::update_entry,
+        api::v0::question_bank::review_entry,
+        api::v0::question_bank::delete_entry,
+        api::v0::question_bank::export_entries,
+        api::v0::question_bank::import_entries,
         api::v0::bots::message::message,
         api::v0::bots::list_bots,
         api::v0::bots::flows::list_flows,
@@ -52,6 +61,7 @@ struct SecurityAddon;
         api::v0::modules::assessment::pre_post_assessment,
         api::v0::modules::assessment::start_module_assessment,
         api::v0::modules::assessment::submit_module_assessment,
+        api::v0::modules::assessment::get_assessment_change,
         api::v0::modules::quiz::start_quiz,
         api::v0::modules::quiz::get_module_scores,
         api::v0::modules::quiz::get_module_quizzes,
@@ -69,6 +79,22 @@ struct SecurityAddon;
         api::v0::user::context_log::get_user_context_logs_by_type,
         api::v0::user::context_log::add_user_context_log,
         api::v0::user::context_log::get_latest_user_context_log_by_type,
+        api::v0::user::devices::get_devices,
+        api::v0::user::devices::revoke_device,
+        api::v0::user::export::export_user_data,
+        api::v0::user::export::create_export_job,
+        api::v0::user::export::get_export_job_handler,
+        api::v0::user::export::download_export_archive,
+        api::v0::user::memory::get_memories,
+        api::v0::user::memory::delete_memory,
+        api::v0::user::memory::delete_memories,
+        api::v0::user::memory::get_consent,
+        api::v0::user::memory::set_consent,
+        api::v0::llm::images::upload_image,
+        api::v0::llm::images::get_image,
+        api::v0::llm::images::delete_image,
+        api::v0::llm::audio::get_audio,
+        api::v0::llm::audio::delete_audio,
         api::v0::modules::messaging::start_session,
         api::v0::modules::messaging::reset_session,
         api::v0::modules::messaging::chat_session,
@@ -101,11 +127,28 @@ struct SecurityAddon;
         api::v0::journal::journal_focus::create_user_focus,
         api::v0::journal::journal_focus::update_user_focus,
         api::v0::journal::journal_focus::get_focus_incl_global,
+        api::v0::journal::insights::get_journal_insights,
         api::v0::journal::assistant::prompt,
         api::v0::journal::assistant::merge,
         api::v0::journal::assistant::text_prompt,
         api::v0::journal::assistant::text_merge,
         api::v0::journal::assistant::summarize::summarize_handler,
+        api::v0::journal::assistant::summary_job::create_summary_job,
+        api::v0::journal::assistant::summary_job::get_summary_job_handler,
+        api::v0::admin::get_users,
+        api::v0::admin::get_user_progress,
+        api::v0::admin::get_user_llm_usage,
+        api::v0::admin::reset_user_session,
+        api::v0::admin::reset_user_quiz,
+        api::v0::admin::grant_groups,
+        api::v0::admin::revoke_group,
+        api::v0::admin::get_audit_log,
+        api::v0::admin::get_guardrail_events,
+        api::v0::admin::review_guardrail_event,
+        api::v0::admin::get_crisis_event,
+        api::v0::admin::get_allocation_report,
+        api::v0::admin::get_user_assessment_change,
+        api::v0::admin::get_cohort_assessment_change,
         login::login_token,
         login::logout,
         global::frontend_version,
@@ -5,7 +5,8 @@ use axum::extract::{FromRequestParts, OptionalFromRequestParts};
 use axum::{Extension, RequestPartsExt};
 use axum_auth::AuthBearer;
 use axum_extra::extract::Cached;
-use hikari_db::user;
+use chrono::{TimeDelta, Utc};
+use hikari_db::{access_tokens, user};
 use hikari_model::user::User;
 use hikari_model_tools::convert::{TryFromDbModel, TryIntoModel};
 use http::StatusCode;
@@ -29,9 +30,16 @@ pub fn extract_auth_token_from_params(parts: &mut Parts) -> Option<String> {
 
 type Rejection = (StatusCode, &'static str);
 
+/// Token usage is only written back after this interval to avoid a write on every request
+const ACCESS_TOKEN_REFRESH_INTERVAL: TimeDelta = TimeDelta::minutes(5);
+/// Token lifetime used if no auth config is available
+const DEFAULT_ACCESS_TOKEN_TTL: TimeDelta = TimeDelta::days(30);
+
 #[derive(Clone)]
 struct Session {
     user: User,
+    /// Set if the user authenticated with an access token instead of a jwt
+    access_token_id: Option<i32>,
 }
 
 #[derive(Clone)]
@@ -40,6 +48,10 @@ pub(crate) struct ExtractUser(pub User);
 #[derive(Clone)]
 pub(crate) struct ExtractUserId(pub Uuid);
 
+/// The id of the access token used for the request, `None` if the user authenticated with a jwt
+#[derive(Clone)]
+pub(crate) struct ExtractAccessTokenId(pub Option<i32>);
+
 impl<S> FromRequestParts<S> for Session
 where
     S: Send + Sync,
@@ -69,6 +81,11 @@ where
                     );
                     (StatusCode::INTERNAL_SERVER_ERROR, "Database Connection not found")
                 })?;
+        let access_token_ttl = app_state
+            .as_ref()
+            .map_or(DEFAULT_ACCESS_TOKEN_TTL, |Extension(app_state)| {
+                app_state.as_ref().access_token_ttl()
+            });
         if let Some(Extension(app_state)) = app_state {
             let state = app_state.as_ref();
 
@@ -94,7 +111,10 @@ where
                         tracing::error!(error = &error as &dyn Error, %user_id, "failed to decode db user");
                         (StatusCode::INTERNAL_SERVER_ERROR, "Error to decode db user")
                     })?;
-                    return Ok(Self { user });
+                    return Ok(Self {
+                        user,
+                        access_token_id: None,
+                    });
                 }
                 Err(err) => {
                     tracing::error!(error = &err as &dyn Error, "error validating token");
@@ -111,16 +131,25 @@ where
             tracing::warn!("No app state found for JWT authentication");
         }
 
-        Self::from_db(&conn, &token).await.map(|user| Self { user })
+        Self::from_db(&conn, &token, access_token_ttl).await
     }
 }
 
 impl Session {
-    async fn from_db(conn: &DatabaseConnection, token: &str) -> Result<User, Rejection> {
-        let Ok(Some(user)) = user::Query::find_by_token(conn, token).await else {
+    async fn from_db(conn: &DatabaseConnection, token: &str, access_token_ttl: TimeDelta) -> Result<Self, Rejection> {
+        let now = Utc::now().naive_utc();
+        let Ok(Some((access_token, user))) = user::Query::find_by_token(conn, token, now).await else {
             return Err((StatusCode::UNAUTHORIZED, "Authentication failed."));
         };
 
+        // Sliding expiry: every use keeps the token alive for another ttl
+        if now - access_token.last_used_at >= ACCESS_TOKEN_REFRESH_INTERVAL
+            && let Err(error) =
+                access_tokens::Mutation::refresh_access_token(conn, access_token.id, now, now + access_token_ttl).await
+        {
+            tracing::warn!(error = &error as &dyn Error, "failed to refresh access token");
+        }
+
         sentry::configure_scope(|scope| {
             scope.set_user(Some(sentry::User {
                 id: Some(user.0.id.as_hyphenated().to_string()),
@@ -128,9 +157,13 @@ impl Session {
             }));
         });
 
-        user.try_into_model().map_err(|error| {
+        let user = user.try_into_model().map_err(|error| {
             tracing::error!(error = &error as &dyn Error, "error converting user");
             (StatusCode::INTERNAL_SERVER_ERROR, "Error loading user")
+        })?;
+        Ok(Self {
+            user,
+            access_token_id: Some(access_token.id),
         })
     }
 }
@@ -189,3 +222,15 @@ where
         Ok(Self(session.user.id))
     }
 }
+
+impl<S> FromRequestParts<S> for ExtractAccessTokenId
+where
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let session: Session = Cached::<Session>::from_request_parts(parts, state).await?.0;
+        Ok(Self(session.access_token_id))
+    }
+}
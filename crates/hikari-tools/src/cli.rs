@@ -1,11 +1,13 @@
 pub(crate) mod opt;
+pub(crate) mod research_export;
 pub(crate) mod schema;
 
 use crate::opt::Commands;
 use anyhow::Error;
 
-pub(crate) fn exec(command: Commands) -> Result<(), Error> {
+pub(crate) async fn exec(command: Commands) -> Result<(), Error> {
     match command {
         Commands::Schema(schema) => schema::exec(schema),
+        Commands::ResearchExport(export) => research_export::exec(export).await,
     }
 }
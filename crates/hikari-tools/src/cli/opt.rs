@@ -1,4 +1,5 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use url::Url;
 
 #[derive(Debug, Parser)]
 #[command(name = "hikari", about = "Cli for a bright bot")]
@@ -10,6 +11,8 @@ pub(crate) struct Cli {
 #[derive(Debug, Subcommand)]
 pub(crate) enum Commands {
     Schema(Schema),
+    /// Export pseudonymized study data for researchers
+    ResearchExport(ResearchExport),
 }
 
 #[derive(Debug, Parser)]
@@ -17,3 +20,45 @@ pub(crate) struct Schema {
     #[arg(required = true)]
     pub(crate) output_folder: String,
 }
+
+#[derive(Debug, Clone, Copy, ValueEnum, Eq, PartialEq)]
+pub(crate) enum ExportFormat {
+    Csv,
+    Parquet,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct ResearchExport {
+    #[arg(long, env = "DATABASE_URL", help = "Url of the database to export from")]
+    pub(crate) db_url: String,
+
+    #[arg(short, long, help = "The url were the global config is stored (file:// only)")]
+    pub(crate) global_cfg: Url,
+
+    #[arg(long, help = "The url were assessment config is stored (file:// only)")]
+    pub(crate) assessment: Option<Url>,
+
+    #[arg(
+        long = "token",
+        value_delimiter = ',',
+        required = true,
+        help = "Access tokens of the study, every user who redeemed one of them is exported"
+    )]
+    pub(crate) tokens: Vec<String>,
+
+    #[arg(
+        long,
+        env = "RESEARCH_EXPORT_SECRET",
+        help = "Secret used to derive the pseudonyms. Keep it to get the same pseudonyms in later exports"
+    )]
+    pub(crate) secret: String,
+
+    #[arg(long, value_enum, default_value = "csv")]
+    pub(crate) format: ExportFormat,
+
+    #[arg(long, help = "Replace free text answers with a placeholder")]
+    pub(crate) redact_free_text: bool,
+
+    #[arg(required = true)]
+    pub(crate) output_folder: String,
+}
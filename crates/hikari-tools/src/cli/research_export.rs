@@ -0,0 +1,398 @@
+mod table;
+
+use crate::cli::opt;
+use anyhow::Context;
+use chrono::NaiveDateTime;
+use hikari_config::assessment::AssessmentConfig;
+use hikari_config::assessment::question::QuestionBody;
+use hikari_config::global::GlobalConfig;
+use hikari_config::global::access::GroupAccess;
+use hikari_core::assessment::scale::build_scale_values;
+use hikari_db::sea_orm::{Database, DatabaseConnection};
+use hikari_db::{assessment, groups, llm, module, quiz};
+use hikari_entity::assessment::answer::AnswerType;
+use hikari_entity::assessment::session::AssessmentStatus;
+use hikari_entity::module::session::status::Status as SessionStatus;
+use hikari_utils::loader::LoaderHandler;
+use num_traits::ToPrimitive;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use table::{Cell, Kind, Table};
+use uuid::Uuid;
+
+const REDACTED: &str = "[redacted]";
+
+pub(crate) async fn exec(export: opt::ResearchExport) -> Result<(), anyhow::Error> {
+    let opt::ResearchExport {
+        db_url,
+        global_cfg,
+        assessment,
+        tokens,
+        secret,
+        format,
+        redact_free_text,
+        output_folder,
+    } = export;
+
+    let loader_handler = LoaderHandler::new(None);
+    let global_config = hikari_config::global::load(loader_handler.loader(&global_cfg)?)
+        .await
+        .context("failed to load global config")?;
+    let assessment_config = match assessment {
+        Some(url) => hikari_config::assessment::load(loader_handler.loader(&url)?)
+            .await
+            .context("failed to load assessment config")?,
+        None => AssessmentConfig::default(),
+    };
+    let conn = Database::connect(&db_url).await?;
+
+    let exporter = Exporter {
+        conn: &conn,
+        assessments: &assessment_config,
+        pseudonyms: Pseudonyms::new(&secret),
+        redact_free_text,
+    };
+    let participants = exporter.participants(&global_config, &tokens).await?;
+
+    let folder = Path::new(&output_folder);
+    if !folder.exists() {
+        std::fs::create_dir_all(folder)?;
+    }
+    let (answers, scales) = exporter.assessment_tables(&participants).await?;
+    let tables = [
+        exporter.participant_table(&participants),
+        answers,
+        scales,
+        exporter.session_table(&participants).await?,
+        exporter.quiz_score_table(&participants).await?,
+        exporter.usage_table(&participants).await?,
+    ];
+    for table in tables {
+        table.write(folder, format)?;
+    }
+
+    println!("Exported {} participants to {output_folder}", participants.len());
+    Ok(())
+}
+
+/// Derives stable pseudonyms from ids. The same secret always yields the same pseudonym for an id, without the
+/// secret the id can't be recovered.
+struct Pseudonyms {
+    key: Vec<u8>,
+}
+
+impl Pseudonyms {
+    fn new(secret: &str) -> Self {
+        let mut key = (secret.len() as u64).to_be_bytes().to_vec();
+        key.extend_from_slice(secret.as_bytes());
+        Self { key }
+    }
+
+    fn get(&self, id: Uuid) -> String {
+        let digest = Sha256::new()
+            .chain_update(&self.key)
+            .chain_update(id.as_bytes())
+            .finalize();
+        hex::encode(digest.get(..8).unwrap_or_default())
+    }
+}
+
+struct Participant {
+    user_id: Uuid,
+    pseudonym: String,
+    tokens: Vec<String>,
+    conditions: Vec<String>,
+    enrolled_at: NaiveDateTime,
+}
+
+struct Exporter<'a> {
+    conn: &'a DatabaseConnection,
+    assessments: &'a AssessmentConfig,
+    pseudonyms: Pseudonyms,
+    redact_free_text: bool,
+}
+
+fn timestamp(time: NaiveDateTime) -> Cell {
+    time.and_utc().to_rfc3339().into()
+}
+
+fn optional_timestamp(time: Option<NaiveDateTime>) -> Cell {
+    time.map(|time| time.and_utc().to_rfc3339()).into()
+}
+
+impl Exporter<'_> {
+    /// Finds all users who redeemed one of the study tokens. The condition of a participant are the groups they were
+    /// randomly assigned to by these tokens.
+    async fn participants(&self, config: &GlobalConfig, tokens: &[String]) -> Result<Vec<Participant>, anyhow::Error> {
+        let random_groups: HashMap<&str, HashSet<&str>> = config
+            .access()
+            .iter()
+            .filter(|access| tokens.contains(&access.token))
+            .map(|access| {
+                let groups = access
+                    .groups
+                    .iter()
+                    .filter_map(|group| match group {
+                        GroupAccess::Random { random, .. } => Some(random.iter().map(String::as_str)),
+                        GroupAccess::Single(_) => None,
+                    })
+                    .flatten()
+                    .collect();
+                (access.token.as_str(), groups)
+            })
+            .collect();
+        for token in tokens {
+            if !random_groups.contains_key(token.as_str()) {
+                anyhow::bail!("access token {token} is not configured");
+            }
+        }
+
+        let mut participants: BTreeMap<Uuid, Participant> = BTreeMap::new();
+        for redemption in groups::groups_token::Query::get_by_tokens(self.conn, tokens).await? {
+            let participant = participants.entry(redemption.user_id).or_insert_with(|| Participant {
+                user_id: redemption.user_id,
+                pseudonym: self.pseudonyms.get(redemption.user_id),
+                tokens: vec![],
+                conditions: vec![],
+                enrolled_at: redemption.added_at,
+            });
+            participant.tokens.push(redemption.token);
+        }
+
+        for participant in participants.values_mut() {
+            let user_groups = groups::custom_groups::Query::get_for_user(self.conn, participant.user_id).await?;
+            participant.conditions = user_groups
+                .into_iter()
+                .filter(|group| {
+                    participant.tokens.iter().any(|token| {
+                        random_groups
+                            .get(token.as_str())
+                            .is_some_and(|groups| groups.contains(group.as_str()))
+                    })
+                })
+                .collect();
+        }
+
+        // Sorting by pseudonym hides the order in which users registered
+        let mut participants: Vec<_> = participants.into_values().collect();
+        participants.sort_by(|a, b| a.pseudonym.cmp(&b.pseudonym));
+        Ok(participants)
+    }
+
+    fn participant_table(&self, participants: &[Participant]) -> Table {
+        let mut table = Table::new(
+            "participants",
+            vec![
+                ("participant", Kind::Text),
+                ("conditions", Kind::Text),
+                ("tokens", Kind::Text),
+                ("enrolled_at", Kind::Text),
+            ],
+        );
+        for participant in participants {
+            table.push(vec![
+                participant.pseudonym.as_str().into(),
+                participant.conditions.join(";").into(),
+                participant.tokens.join(";").into(),
+                timestamp(participant.enrolled_at),
+            ]);
+        }
+        table
+    }
+
+    /// Exports the answers of all assessment sessions and the scale values calculated from them.
+    async fn assessment_tables(&self, participants: &[Participant]) -> Result<(Table, Table), anyhow::Error> {
+        let columns = vec![
+            ("participant", Kind::Text),
+            ("assessment_session", Kind::Text),
+            ("assessment", Kind::Text),
+            ("module", Kind::Text),
+            ("timepoint", Kind::Text),
+            ("status", Kind::Text),
+            ("completed", Kind::Text),
+        ];
+        let mut answers_table = Table::new(
+            "assessment_answers",
+            [columns.clone(), vec![("question", Kind::Text), ("value", Kind::Text)]].concat(),
+        );
+        let mut scales_table = Table::new(
+            "assessment_scales",
+            [columns, vec![("scale", Kind::Text), ("value", Kind::Number)]].concat(),
+        );
+
+        for participant in participants {
+            let mut timepoints = HashMap::new();
+            for module_assessment in module::assessment::Query::all(self.conn, participant.user_id).await? {
+                if let Some(pre) = module_assessment.last_pre {
+                    timepoints.insert(pre, (module_assessment.module.clone(), "pre"));
+                }
+                if let Some(post) = module_assessment.last_post {
+                    timepoints.insert(post, (module_assessment.module.clone(), "post"));
+                }
+            }
+
+            for session in assessment::session::Query::load_sessions(self.conn, participant.user_id).await? {
+                let (module, timepoint) = timepoints.get(&session.id).map_or((None, None), |(module, timepoint)| {
+                    (Some(module.clone()), Some((*timepoint).to_owned()))
+                });
+                let status = match session.status {
+                    AssessmentStatus::NotStarted => "not_started",
+                    AssessmentStatus::Running => "running",
+                    AssessmentStatus::Finished => "finished",
+                };
+                let session_cells = vec![
+                    participant.pseudonym.as_str().into(),
+                    self.pseudonyms.get(session.id).into(),
+                    session.assessment.as_str().into(),
+                    module.into(),
+                    timepoint.into(),
+                    status.into(),
+                    optional_timestamp(session.completed),
+                ];
+
+                let config = self.assessments.get(&session.assessment);
+                let answers = assessment::answer::Query::load_answers(self.conn, session.id).await?;
+                for answer in &answers {
+                    let free_text = match config.and_then(|config| config.questions.get(&answer.question)) {
+                        Some(question) => {
+                            matches!(question.body, QuestionBody::Textfield(_) | QuestionBody::Textarea(_))
+                        }
+                        None => answer.answer_type == AnswerType::Text,
+                    };
+                    let value = if free_text && self.redact_free_text {
+                        REDACTED.to_owned()
+                    } else {
+                        answer.data.clone()
+                    };
+                    let mut row = session_cells.clone();
+                    row.extend([answer.question.as_str().into(), value.into()]);
+                    answers_table.push(row);
+                }
+
+                if session.status != AssessmentStatus::Finished {
+                    continue;
+                }
+                let Some(config) = config else {
+                    eprintln!("Skipping scales of unknown assessment {}", session.assessment);
+                    continue;
+                };
+                match build_scale_values(config, &answers) {
+                    Ok(values) => {
+                        for value in values {
+                            let mut row = session_cells.clone();
+                            row.extend([value.id.into(), value.value.into()]);
+                            scales_table.push(row);
+                        }
+                    }
+                    Err(error) => eprintln!("Skipping scales of assessment session: {error}"),
+                }
+            }
+        }
+
+        Ok((answers_table, scales_table))
+    }
+
+    async fn session_table(&self, participants: &[Participant]) -> Result<Table, anyhow::Error> {
+        let mut table = Table::new(
+            "sessions",
+            vec![
+                ("participant", Kind::Text),
+                ("module", Kind::Text),
+                ("session", Kind::Text),
+                ("status", Kind::Text),
+                ("completed", Kind::Text),
+            ],
+        );
+        for participant in participants {
+            for session in module::session::status::Query::all(self.conn, participant.user_id).await? {
+                let status = match session.status {
+                    SessionStatus::NotStarted => "not_started",
+                    SessionStatus::Started => "started",
+                    SessionStatus::Finished => "finished",
+                };
+                table.push(vec![
+                    participant.pseudonym.as_str().into(),
+                    session.module.into(),
+                    session.session.into(),
+                    status.into(),
+                    optional_timestamp(session.completion),
+                ]);
+            }
+        }
+        Ok(table)
+    }
+
+    async fn quiz_score_table(&self, participants: &[Participant]) -> Result<Table, anyhow::Error> {
+        let mut table = Table::new(
+            "quiz_scores",
+            vec![
+                ("participant", Kind::Text),
+                ("module", Kind::Text),
+                ("session", Kind::Text),
+                ("topic", Kind::Text),
+                ("score", Kind::Number),
+            ],
+        );
+        for participant in participants {
+            for score in quiz::score::Query::get_scores(self.conn, &participant.user_id).await? {
+                table.push(vec![
+                    participant.pseudonym.as_str().into(),
+                    score.module_id.into(),
+                    score.session_id.into(),
+                    score.topic.into(),
+                    score.score.into(),
+                ]);
+            }
+        }
+        Ok(table)
+    }
+
+    /// Exports the LLM usage per participant, day and step.
+    async fn usage_table(&self, participants: &[Participant]) -> Result<Table, anyhow::Error> {
+        let mut table = Table::new(
+            "usage",
+            vec![
+                ("participant", Kind::Text),
+                ("date", Kind::Text),
+                ("step", Kind::Text),
+                ("requests", Kind::Number),
+                ("tokens", Kind::Number),
+            ],
+        );
+        for participant in participants {
+            let mut usage: BTreeMap<(String, String), (u32, u64)> = BTreeMap::new();
+            for record in llm::usage::Query::get_user_usage(self.conn, participant.user_id, None, None).await? {
+                let entry = usage.entry((record.time.date().to_string(), record.step)).or_default();
+                entry.0 += 1;
+                entry.1 += u64::from(record.tokens);
+            }
+            for ((date, step), (requests, tokens)) in usage {
+                table.push(vec![
+                    participant.pseudonym.as_str().into(),
+                    date.into(),
+                    step.into(),
+                    f64::from(requests).into(),
+                    Cell::Number(tokens.to_f64()),
+                ]);
+            }
+        }
+        Ok(table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pseudonyms_are_stable() {
+        let id = Uuid::new_v4();
+        let pseudonyms = Pseudonyms::new("secret");
+
+        assert_eq!(pseudonyms.get(id), Pseudonyms::new("secret").get(id));
+        assert_eq!(pseudonyms.get(id).len(), 16);
+        assert_ne!(pseudonyms.get(id), Pseudonyms::new("other").get(id));
+        assert_ne!(pseudonyms.get(id), pseudonyms.get(Uuid::new_v4()));
+    }
+}
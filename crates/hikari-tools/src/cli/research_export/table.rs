@@ -0,0 +1,147 @@
+use crate::cli::opt::ExportFormat;
+use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Kind {
+    Text,
+    Number,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Cell {
+    Text(Option<String>),
+    Number(Option<f64>),
+}
+
+impl From<String> for Cell {
+    fn from(value: String) -> Self {
+        Self::Text(Some(value))
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(value: &str) -> Self {
+        Self::Text(Some(value.to_owned()))
+    }
+}
+
+impl From<Option<String>> for Cell {
+    fn from(value: Option<String>) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<f64> for Cell {
+    fn from(value: f64) -> Self {
+        Self::Number(Some(value))
+    }
+}
+
+impl Cell {
+    fn into_text(self) -> Option<String> {
+        match self {
+            Self::Text(value) => value,
+            Self::Number(value) => value.map(|value| value.to_string()),
+        }
+    }
+
+    fn into_number(self) -> Option<f64> {
+        match self {
+            Self::Text(value) => value.and_then(|value| value.parse().ok()),
+            Self::Number(value) => value,
+        }
+    }
+}
+
+/// One dataset of the export, written as one file.
+pub(crate) struct Table {
+    name: &'static str,
+    columns: Vec<(&'static str, Kind)>,
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+    pub(crate) fn new(name: &'static str, columns: Vec<(&'static str, Kind)>) -> Self {
+        Self {
+            name,
+            columns,
+            rows: vec![],
+        }
+    }
+
+    /// Adds a row, its cells must be in the order of the columns.
+    pub(crate) fn push(&mut self, row: Vec<Cell>) {
+        debug_assert_eq!(
+            row.len(),
+            self.columns.len(),
+            "row does not match columns of {}",
+            self.name
+        );
+        self.rows.push(row);
+    }
+
+    pub(crate) fn write(self, folder: &Path, format: ExportFormat) -> Result<(), anyhow::Error> {
+        match format {
+            ExportFormat::Csv => self.write_csv(&folder.join(format!("{}.csv", self.name))),
+            ExportFormat::Parquet => self.write_parquet(&folder.join(format!("{}.parquet", self.name))),
+        }
+    }
+
+    fn write_csv(self, path: &Path) -> Result<(), anyhow::Error> {
+        let mut writer = csv::Writer::from_path(path)?;
+        writer.write_record(self.columns.iter().map(|(name, _)| *name))?;
+        for row in self.rows {
+            writer.write_record(row.into_iter().map(|cell| cell.into_text().unwrap_or_default()))?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn write_parquet(self, path: &Path) -> Result<(), anyhow::Error> {
+        let schema = Arc::new(Schema::new(
+            self.columns
+                .iter()
+                .map(|(name, kind)| {
+                    let data_type = match kind {
+                        Kind::Text => DataType::Utf8,
+                        Kind::Number => DataType::Float64,
+                    };
+                    Field::new(*name, data_type, true)
+                })
+                .collect::<Vec<_>>(),
+        ));
+
+        let mut columns: Vec<Vec<Cell>> = self
+            .columns
+            .iter()
+            .map(|_| Vec::with_capacity(self.rows.len()))
+            .collect();
+        for row in self.rows {
+            for (column, cell) in columns.iter_mut().zip(row) {
+                column.push(cell);
+            }
+        }
+        let arrays = self
+            .columns
+            .iter()
+            .zip(columns)
+            .map(|((_, kind), cells)| -> ArrayRef {
+                match kind {
+                    Kind::Text => Arc::new(cells.into_iter().map(Cell::into_text).collect::<StringArray>()),
+                    Kind::Number => Arc::new(cells.into_iter().map(Cell::into_number).collect::<Float64Array>()),
+                }
+            })
+            .collect();
+
+        let batch = RecordBatch::try_new(Arc::clone(&schema), arrays)?;
+        let mut writer = ArrowWriter::try_new(File::create(path)?, schema, None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(())
+    }
+}
@@ -7,5 +7,5 @@ use cli::opt;
 #[tokio::main]
 async fn main() -> Result<()> {
     let opt = opt::Cli::parse();
-    cli::exec(opt.command)
+    cli::exec(opt.command).await
 }
@@ -31,6 +31,38 @@ pub struct LlmServices {
     pub planner_model: Option<String>,
     #[arg(long, required = false)]
     pub planner_service: Option<String>,
+    #[arg(long, required = false, help = "Model for the transcription of audio messages")]
+    pub transcription_model: Option<String>,
+    #[arg(
+        long,
+        required = false,
+        help = "Service for the transcription of audio messages, e.g. the url of a local whisper server"
+    )]
+    pub transcription_service: Option<String>,
+    #[arg(long, required = false, help = "Model for the spoken output of messages")]
+    pub speech_model: Option<String>,
+    #[arg(long, required = false, help = "Service for the spoken output of messages")]
+    pub speech_service: Option<String>,
+    #[arg(long, default_value = "alloy", help = "Voice of the spoken output of messages")]
+    pub speech_voice: String,
+    #[arg(long, default_value_t = 8, help = "Maximum concurrent requests per LLM provider")]
+    pub llm_max_concurrency: usize,
+    #[arg(long, required = false, help = "Maximum requests per minute per LLM provider")]
+    pub llm_requests_per_minute: Option<u32>,
+    #[arg(long, required = false, help = "Maximum tokens per minute per LLM provider")]
+    pub llm_tokens_per_minute: Option<u32>,
+    #[arg(
+        long,
+        default_value_t = 5,
+        help = "Consecutive failures after which requests to a LLM provider fail fast"
+    )]
+    pub llm_circuit_breaker_failures: u32,
+    #[arg(
+        long,
+        default_value_t = 30,
+        help = "Seconds until a failing LLM provider is tried again"
+    )]
+    pub llm_circuit_breaker_cooldown: u64,
 }
 
 #[derive(Debug, Clone, Args)]
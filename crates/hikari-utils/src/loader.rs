@@ -6,6 +6,7 @@ use futures::Stream;
 use s3::S3Config;
 use std::path::Path;
 use std::pin::Pin;
+use tokio::io::AsyncRead;
 use url::Url;
 
 pub mod error;
@@ -75,6 +76,9 @@ impl LoaderHandler {
     }
 }
 
+/// Reads the content of a file while it is downloaded
+pub type FileReader = Pin<Box<dyn AsyncRead + Send>>;
+
 #[derive(Clone, Debug)]
 pub enum Loader {
     S3(S3Loader),
@@ -106,6 +110,27 @@ impl LoaderTrait for Loader {
             Loader::FileSystem(loader) => loader.get_file_metadata(path).await,
         }
     }
+
+    async fn open_file<P: AsRef<Path>>(&self, path: P) -> Result<FileReader, LoadingError> {
+        match self {
+            Loader::S3(loader) => loader.open_file(path).await,
+            Loader::FileSystem(loader) => loader.open_file(path).await,
+        }
+    }
+
+    async fn store_file<P: AsRef<Path>>(&self, path: P, source: &Path) -> Result<(), LoadingError> {
+        match self {
+            Loader::S3(loader) => loader.store_file(path, source).await,
+            Loader::FileSystem(loader) => loader.store_file(path, source).await,
+        }
+    }
+
+    async fn delete_file<P: AsRef<Path>>(&self, path: P) -> Result<(), LoadingError> {
+        match self {
+            Loader::S3(loader) => loader.delete_file(path).await,
+            Loader::FileSystem(loader) => loader.delete_file(path).await,
+        }
+    }
 }
 
 pub trait LoaderTrait {
@@ -116,4 +141,10 @@ pub trait LoaderTrait {
     ) -> Pin<Box<dyn Stream<Item = Result<File, LoadingError>> + Send + 'a>>;
     fn load_file<P: AsRef<Path>>(&self, path: P) -> impl Future<Output = Result<File, LoadingError>>;
     fn get_file_metadata<P: AsRef<Path>>(&self, path: P) -> impl Future<Output = Result<FileMetadata, LoadingError>>;
+    /// Opens the file for reading without loading it into memory.
+    fn open_file<P: AsRef<Path>>(&self, path: P) -> impl Future<Output = Result<FileReader, LoadingError>>;
+    /// Stores the local file `source` at `path`, an existing file is replaced.
+    fn store_file<P: AsRef<Path>>(&self, path: P, source: &Path) -> impl Future<Output = Result<(), LoadingError>>;
+    /// Deletes the file, a missing file is not an error.
+    fn delete_file<P: AsRef<Path>>(&self, path: P) -> impl Future<Output = Result<(), LoadingError>>;
 }
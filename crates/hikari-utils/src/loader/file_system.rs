@@ -1,6 +1,6 @@
 use crate::loader::error::LoadingError;
 use crate::loader::file::{File, FileHash, FileMetadata};
-use crate::loader::{Filter, LoaderTrait};
+use crate::loader::{FileReader, Filter, LoaderTrait};
 use async_stream::try_stream;
 use async_walkdir::{DirEntry, Filtering, WalkDir};
 use chrono::{DateTime, Utc};
@@ -92,6 +92,31 @@ impl LoaderTrait for FileSystemLoader {
             None,
         ))
     }
+
+    async fn open_file<P: AsRef<Path>>(&self, path: P) -> Result<FileReader, LoadingError> {
+        let path = self.sub_path(path);
+        tracing::trace!(?path, "Opening file");
+        Ok(Box::pin(fs::File::open(&path).await?))
+    }
+
+    async fn store_file<P: AsRef<Path>>(&self, path: P, source: &Path) -> Result<(), LoadingError> {
+        let path = self.sub_path(path);
+        tracing::trace!(?path, ?source, "Storing file");
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::copy(source, &path).await?;
+        Ok(())
+    }
+
+    async fn delete_file<P: AsRef<Path>>(&self, path: P) -> Result<(), LoadingError> {
+        let path = self.sub_path(path);
+        tracing::trace!(?path, "Deleting file");
+        match fs::remove_file(&path).await {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
 }
 
 async fn filter(entry: DirEntry, filter: Filter) -> Filtering {
@@ -1,10 +1,11 @@
 use crate::args::s3::S3;
 use crate::loader::error::LoadingError;
 use crate::loader::file::{File, FileHash};
-use crate::loader::{FileMetadata, Filter, LoaderTrait};
+use crate::loader::{FileMetadata, FileReader, Filter, LoaderTrait};
 use async_stream::try_stream;
 use aws_sdk_s3::config::{BehaviorVersion, Credentials, SharedCredentialsProvider};
 use aws_sdk_s3::operation::get_object::GetObjectOutput;
+use aws_sdk_s3::primitives::ByteStream;
 use aws_sdk_s3::types::Object;
 use aws_sdk_s3::{Client, config::Region};
 use chrono::{DateTime, Utc};
@@ -235,6 +236,48 @@ impl LoaderTrait for S3Loader {
 
         Ok(metadata)
     }
+
+    async fn open_file<P: AsRef<Path>>(&self, path: P) -> Result<FileReader, LoadingError> {
+        let key = self.sub_key(path);
+        tracing::trace!(?key, "opening object");
+        let object = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(&key)
+            .send()
+            .await
+            .inspect_err(|error| tracing::error!(error = error as &dyn Error, key, "failed to get object"))?;
+        Ok(Box::pin(object.body.into_async_read()))
+    }
+
+    async fn store_file<P: AsRef<Path>>(&self, path: P, source: &Path) -> Result<(), LoadingError> {
+        let key = self.sub_key(path);
+        tracing::trace!(?key, ?source, "storing object");
+        let body = ByteStream::from_path(source).await?;
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(&key)
+            .body(body)
+            .send()
+            .await
+            .inspect_err(|error| tracing::error!(error = error as &dyn Error, key, "failed to put object"))?;
+        Ok(())
+    }
+
+    async fn delete_file<P: AsRef<Path>>(&self, path: P) -> Result<(), LoadingError> {
+        let key = self.sub_key(path);
+        tracing::trace!(?key, "deleting object");
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(&key)
+            .send()
+            .await
+            .inspect_err(|error| tracing::error!(error = error as &dyn Error, key, "failed to delete object"))?;
+        Ok(())
+    }
 }
 
 pub(crate) fn build_client(config: S3Config) -> Client {
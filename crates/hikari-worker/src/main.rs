@@ -1,5 +1,6 @@
 use std::net::{IpAddr, Ipv4Addr};
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Result;
 use axum::routing::get;
@@ -7,6 +8,7 @@ use axum::{Extension, Router, serve};
 use axum_prometheus::PrometheusMetricLayer;
 use clap::Parser;
 use hikari_config::global::GlobalConfig;
+use hikari_core::journal::summarize::job::run_summary_job_processor;
 use hikari_core::llm_config::LlmConfig;
 use sea_orm::{ConnectOptions, Database};
 use sentry_tower::{NewSentryLayer, SentryHttpLayer};
@@ -58,13 +60,22 @@ async fn run(opt: Run) -> Result<()> {
     };
 
     let llm_config: LlmConfig = opt.llm_services.into();
+    hikari_core::openai::pool::configure(llm_config.provider_limits.clone());
     tracing::info!("connecting to database");
     let db = Database::connect(seaorm_pool_options).await?;
 
+    let llm_config = Arc::new(llm_config);
+    let job_db = db.clone();
+    let job_llm_config = Arc::clone(&llm_config);
+    let poll_interval = Duration::from_secs(opt.summary_job_poll_interval);
+    tokio::spawn(async move {
+        run_summary_job_processor(job_db, &job_llm_config, poll_interval).await;
+    });
+
     let mut app = Router::new()
         .merge(routes::swagger::create_router())
-        .nest("/api/v0/csml", v0::csml::create_router(config, llm_config.clone()))
-        .nest("/api/v0/journal", v0::journal::create_router(Arc::new(llm_config)))
+        .nest("/api/v0/csml", v0::csml::create_router(config, (*llm_config).clone()))
+        .nest("/api/v0/journal", v0::journal::create_router(llm_config))
         .route("/api/v0/health", get(v0::get_health));
 
     app = app
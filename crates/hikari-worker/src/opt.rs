@@ -53,6 +53,13 @@ pub(crate) struct Run {
     #[arg(long, help = "Max connections")]
     pub(crate) db_max_connections: Option<u32>,
 
+    #[arg(
+        long,
+        default_value_t = 5,
+        help = "Seconds to wait before polling for new journal summary jobs when the queue is empty"
+    )]
+    pub(crate) summary_job_poll_interval: u64,
+
     #[arg(long)]
     pub(crate) otlp_endpoint: Option<String>,
 